@@ -0,0 +1,93 @@
+//! Micro-benchmarks of the core primitives and of the full algorithms on fixed fixtures.
+//! Run them with `cargo bench --features bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use petgraph::visit::NodeIndexable;
+use Counting_Homomorphisms::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_algorithm, DPData};
+use Counting_Homomorphisms::file_handler::graph_handler::import_metis;
+use Counting_Homomorphisms::file_handler::tree_decomposition_handler::import_ntd;
+use Counting_Homomorphisms::integer_functions::integer_functions_methods;
+use Counting_Homomorphisms::integer_functions::integer_functions_methods::Mapping;
+use Counting_Homomorphisms::modified_dp::algorithm::modified_dp;
+
+const NTD: &str = "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd";
+const FROM_GRAPH: &str = "data/metis_graphs/handmade/from_2.graph";
+const TO_GRAPH: &str = "data/metis_graphs/handmade/to_2.graph";
+
+/// apply, extend and reduce on all mappings of a bag of size 3 into 10 vertices
+fn integer_functions(c : &mut Criterion){
+    let n : Mapping = 10;
+    let mappings = integer_functions_methods::max_mappings(3, n);
+
+    c.bench_function("integer_functions/apply", |b| b.iter(|| {
+        (0..mappings).map(|f| integer_functions_methods::apply(n, black_box(f), 1)).sum::<Mapping>()
+    }));
+    c.bench_function("integer_functions/extend", |b| b.iter(|| {
+        (0..mappings).map(|f| integer_functions_methods::extend(n, black_box(f), 1, 7)).sum::<Mapping>()
+    }));
+    c.bench_function("integer_functions/reduce", |b| b.iter(|| {
+        (0..mappings).map(|f| integer_functions_methods::reduce(n, black_box(f), 1)).sum::<Mapping>()
+    }));
+}
+
+/// writing and reading 10^4 entries of the table of the algorithm of diaz et all
+fn table_get_set(c : &mut Criterion){
+    let from_graph = import_metis(FROM_GRAPH).unwrap();
+    let to_graph = import_metis(TO_GRAPH).unwrap();
+    let ntd = import_ntd(NTD).unwrap();
+
+    c.bench_function("table/set", |b| b.iter(|| {
+        let mut table = DPData::new(&from_graph, &to_graph, &ntd);
+        for f in 0..10_000{
+            table.set(0, black_box(f), 1);
+        }
+        table
+    }));
+
+    let mut table = DPData::new(&from_graph, &to_graph, &ntd);
+    for f in 0..10_000{
+        table.set(0, f, f + 1);
+    }
+    c.bench_function("table/get", |b| b.iter(|| {
+        (0..10_000).map(|f| table.value(&0, &black_box(f))).sum::<u64>()
+    }));
+}
+
+/// the inner loop of an introduce node: extending all mappings of the child by all images of the
+/// introduced vertex and checking the edge to one of its neighbours
+fn introduce_inner_loop(c : &mut Criterion){
+    let to_graph = import_metis(TO_GRAPH).unwrap();
+    let n = to_graph.node_count() as Mapping;
+    let child_mappings = integer_functions_methods::max_mappings(2, n);
+
+    c.bench_function("introduce/inner_loop", |b| b.iter(|| {
+        let mut non_zero = 0;
+        for f_q in 0..child_mappings{
+            for a in 0..n{
+                let f_prime = integer_functions_methods::extend(n, black_box(f_q), 1, a);
+                let image_of_u = integer_functions_methods::apply(n, f_prime, 0);
+                if to_graph.has_edge(to_graph.from_index(a as usize), to_graph.from_index(image_of_u as usize)) {
+                    non_zero += 1;
+                }
+            }
+        }
+        non_zero
+    }));
+}
+
+/// the full algorithms on the fixtures of the unit tests
+fn full_algorithms(c : &mut Criterion){
+    let from_graph = import_metis(FROM_GRAPH).unwrap();
+    let to_graph = import_metis(TO_GRAPH).unwrap();
+    let ntd = import_ntd(NTD).unwrap();
+
+    c.bench_function("algorithms/diaz_serna_thilikos", |b| b.iter(|| {
+        diaz_serna_thilikos_algorithm(black_box(&from_graph), &ntd, &to_graph)
+    }));
+    c.bench_function("algorithms/modified_dp", |b| b.iter(|| {
+        modified_dp(black_box(&ntd), &to_graph)
+    }));
+}
+
+criterion_group!(benches, integer_functions, table_get_set, introduce_inner_loop, full_algorithms);
+criterion_main!(benches);
@@ -0,0 +1,164 @@
+/// A module containing an A/B harness for the individual optimizations of the algorithm of diaz et all. Each of
+/// the optimizations, i.e. dense tables, bitset adjacency, the early exit at empty tables and the pruning of
+/// images by color refinement, is toggled separately in DiazOptions, and the harness measures every combination
+/// of them on the same instance. The speedup of a single optimization is the geometric mean of the speedups of
+/// switching it on over all combinations of the other optimizations, such that speedups can be attributed to
+/// specific changes instead of to the whole configuration.
+pub mod optimization_ablation {
+    use std::fs::File;
+    use std::io;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+    use itertools::Itertools;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_with_options, DiazOptions};
+    use crate::tables::table_backends::TableBackend;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The names of the toggles in the order of their bits in Toggles::index.
+    pub const TOGGLE_NAMES: [&str; 4] = ["dense_tables", "bitset_adjacency", "zero_pruning", "wl_pruning"];
+
+    /// A combination of switched on optimizations.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+    pub struct Toggles {
+        /// stores the tables densely indexed by the mappings instead of in hash maps
+        pub dense_tables: bool,
+        /// intersects the neighbourhoods of the target as bitsets at introduce nodes
+        pub bitset_adjacency: bool,
+        /// returns 0 at the first empty table
+        pub zero_pruning: bool,
+        /// restricts the images of each vertex by color refinement
+        pub wl_pruning: bool,
+    }
+
+    impl Toggles {
+        /// Returns all 16 combinations ordered by their index, starting with no optimization.
+        pub fn all() -> Vec<Toggles> {
+            (0..1 << TOGGLE_NAMES.len()).map(Toggles::from_index).collect()
+        }
+
+        /// Returns the combination whose i-th optimization is switched on iff the i-th bit of the index is set.
+        pub fn from_index(index : usize) -> Toggles {
+            Toggles {
+                dense_tables: index & 1 != 0,
+                bitset_adjacency: index & 2 != 0,
+                zero_pruning: index & 4 != 0,
+                wl_pruning: index & 8 != 0,
+            }
+        }
+
+        /// Returns the index of the combination, see from_index.
+        pub fn index(&self) -> usize {
+            self.flags().iter().enumerate().map(|(i, &flag)| (flag as usize) << i).sum()
+        }
+
+        /// Returns whether each optimization is switched on in the order of TOGGLE_NAMES.
+        pub fn flags(&self) -> [bool; 4] {
+            [self.dense_tables, self.bitset_adjacency, self.zero_pruning, self.wl_pruning]
+        }
+
+        /// Returns the names of the switched on optimizations joined by "+", or "none".
+        pub fn name(&self) -> String {
+            let names : Vec<&str> = TOGGLE_NAMES.iter().zip(self.flags()).filter(|(_, flag)| *flag).map(|(name, _)| *name).collect();
+            if names.is_empty() { "none".to_string() } else { names.join("+") }
+        }
+
+        /// Returns the options of the algorithm of diaz et all with exactly these optimizations.
+        pub fn options(&self) -> DiazOptions {
+            DiazOptions {
+                image_pruning: self.wl_pruning,
+                backend: if self.dense_tables { TableBackend::Dense } else { TableBackend::Hash },
+                scalar_adjacency: !self.bitset_adjacency,
+                keep_zero_tables: !self.zero_pruning,
+                ..DiazOptions::default()
+            }
+        }
+    }
+
+    /// The measurements of a single combination of optimizations.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct AblationRecord {
+        pub toggles: Toggles,
+        /// the number of homomorphisms computed by the last run
+        pub count: u64,
+        /// the running time of each run
+        pub durations: Vec<Duration>,
+    }
+
+    impl AblationRecord {
+        /// Returns the fastest running time over all runs, which is least affected by noise.
+        pub fn minimum(&self) -> Duration { self.durations.iter().min().copied().unwrap_or_default() }
+
+        /// Returns the average running time over all runs.
+        pub fn average(&self) -> Duration {
+            let sum : Duration = self.durations.iter().sum();
+            sum.div_f64(self.durations.len().max(1) as f64)
+        }
+    }
+
+    /// Counts the homomorphisms from "from_graph" to "to_graph" with every combination of optimizations the given
+    /// number of times and returns the records ordered by the index of the combinations. The runs are interleaved,
+    /// i.e. each repetition runs all combinations once, such that a drift of the machine affects all alike.
+    pub fn run_ablation(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, repetitions : usize) -> Vec<AblationRecord> {
+        let mut records : Vec<AblationRecord> = Toggles::all().into_iter()
+            .map(|toggles| AblationRecord { toggles, count: 0, durations: vec![] })
+            .collect();
+
+        for _ in 0..repetitions.max(1){
+            for record in records.iter_mut(){
+                let options = record.toggles.options();
+                let start = Instant::now();
+                record.count = diaz_serna_thilikos_with_options(from_graph, ntd, to_graph, &options);
+                record.durations.push(start.elapsed());
+            }
+        }
+        records
+    }
+
+    /// Returns the speedup of each optimization in the order of TOGGLE_NAMES, i.e. the geometric mean of the
+    /// minimal running time without it divided by the minimal running time with it over all combinations of the
+    /// other optimizations. Pairs with a missing record are skipped, an optimization without pairs has speedup 1.
+    pub fn speedups(records : &[AblationRecord]) -> Vec<(&'static str, f64)> {
+        let time = |index : usize| records.iter().find(|record| record.toggles.index() == index).map(|record| record.minimum().as_secs_f64().max(1e-9));
+
+        TOGGLE_NAMES.iter().enumerate()
+            .map(|(i, &name)| {
+                let ratios : Vec<f64> = (0..1 << TOGGLE_NAMES.len())
+                    .filter(|index| index & (1 << i) == 0)
+                    .filter_map(|index| Some(time(index)?.ln() - time(index | 1 << i)?.ln()))
+                    .collect();
+                let speedup = if ratios.is_empty() { 1.0 } else { (ratios.iter().sum::<f64>() / ratios.len() as f64).exp() };
+                (name, speedup)
+            })
+            .collect()
+    }
+
+    /// The header of the csv files written by write_ablation_csv.
+    pub const ABLATION_HEADER: [&str; 9] = ["dense_tables", "bitset_adjacency", "zero_pruning", "wl_pruning", "configuration",
+        "count", "durations", "minimum", "speedup"];
+
+    /// Writes the records into a csv file with one row per combination, containing the toggles, the count, the space
+    /// separated running times of all runs and the minimal running time in microseconds, and the speedup of the
+    /// minimal running time over the combination without any optimization.
+    pub fn write_ablation_csv<P>(records : &[AblationRecord], path : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let baseline = records.iter().find(|record| record.toggles == Toggles::default()).map(|record| record.minimum().as_secs_f64());
+
+        let mut wtr = csv::Writer::from_writer(File::create(path)?);
+        wtr.write_record(ABLATION_HEADER)?;
+        for record in records{
+            let speedup = baseline.map_or(String::new(), |baseline| format!("{:.3}", baseline / record.minimum().as_secs_f64().max(1e-9)));
+            let mut row : Vec<String> = record.toggles.flags().iter().map(|flag| flag.to_string()).collect();
+            row.extend([
+                record.toggles.name(),
+                record.count.to_string(),
+                record.durations.iter().map(|duration| duration.as_micros().to_string()).join(" "),
+                record.minimum().as_micros().to_string(),
+                speedup,
+            ]);
+            wtr.write_record(row)?;
+        }
+        wtr.flush()
+    }
+}
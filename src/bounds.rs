@@ -0,0 +1,170 @@
+/// A module containing an interval variant of the algorithm of diaz et all for users who need the order of
+/// magnitude of a number of homomorphisms rather than the exact number. Every table entry is an interval
+/// [lower, upper] containing I[p,f], where the upper bound u64::MAX stands for an unbounded interval, such that
+/// saturating arithmetic keeps both bounds valid instead of overflowing.
+///
+/// The tables can be pruned aggressively: after a node has been processed, entries whose upper bound is below a
+/// threshold are dropped and only the entries with the largest upper bounds are kept. A dropped entry of p
+/// contributes at most upper * |V(G)|^r to the number of homomorphisms, where r is the number of pattern vertices
+/// outside of the subtree rooted at p, since every homomorphism extends the mapping of the subtree to these
+/// vertices. The lower bound simply ignores dropped entries, the upper bound adds their contributions, hence the
+/// final bounds are certified.
+pub mod interval_counting {
+    use std::collections::{HashMap, HashSet};
+    use crate::diaz_serna_thilikos::diaz_algorithm::{forget_entries, introduce_entries, join_entries, leaf_entries, AdjacentImages, NeighbourhoodSets};
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::tables::table_backends::Semiring;
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
+
+    /// The message of the panic if a step of the dynamic program fails, which the saturating arithmetic rules out.
+    const SATURATING: &str = "The interval arithmetic saturates instead of overflowing!";
+
+    /// An interval of non-negative integers, where the upper bound u64::MAX stands for infinity.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub struct Interval {
+        pub lower: u64,
+        pub upper: u64,
+    }
+
+    impl Interval {
+        /// Returns the interval containing only the given value.
+        pub fn exact(value : u64) -> Interval { Interval { lower: value, upper: value } }
+
+        /// Returns true if the interval contains a single value.
+        pub fn is_exact(&self) -> bool { self.lower == self.upper }
+
+        /// Returns true if the upper bound is infinite.
+        pub fn is_unbounded(&self) -> bool { self.upper == u64::MAX }
+
+        /// Returns the interval of the sums.
+        pub fn add(&self, other : &Interval) -> Interval {
+            Interval { lower: self.lower.saturating_add(other.lower), upper: self.upper.saturating_add(other.upper) }
+        }
+
+        /// Returns the interval of the products.
+        pub fn mul(&self, other : &Interval) -> Interval {
+            Interval { lower: self.lower.saturating_mul(other.lower), upper: self.upper.saturating_mul(other.upper) }
+        }
+
+        /// Returns true if the value lies within the interval.
+        pub fn contains(&self, value : u64) -> bool { self.lower <= value && value <= self.upper }
+    }
+
+    /// The intervals with saturating arithmetic, such that the shared steps of the dynamic program never fail.
+    impl Semiring for Interval {
+        fn one() -> Interval { Interval::exact(1) }
+
+        fn is_zero(&self) -> bool { self.upper == 0 }
+
+        fn add(self, other : Interval) -> Option<Interval> { Some(Interval::add(&self, &other)) }
+
+        fn mul(self, other : Interval) -> Option<Interval> { Some(Interval::mul(&self, &other)) }
+    }
+
+    /// The pruning of the tables, by default nothing is pruned and the result is exact unless it exceeds u64.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+    pub struct PruningOptions {
+        /// the entries whose upper bound is smaller are dropped
+        pub drop_below: u64,
+        /// the maximal number of entries of a table, the entries with the smallest upper bounds are dropped
+        pub max_entries: Option<usize>,
+    }
+
+    /// The certified bounds on a number of homomorphisms.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub struct CountBounds {
+        pub bounds: Interval,
+        /// the number of dropped table entries
+        pub dropped_entries: usize,
+    }
+
+    /// Computes bounds on the number of homomorphisms from "from_graph" to "to_graph" by the algorithm of diaz et
+    /// all on interval tables, which are pruned after each node according to the options.
+    pub fn interval_hom_count(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, options : &PruningOptions) -> CountBounds {
+        let n = to_graph.node_count() as Mapping;
+        let h = from_graph.node_count();
+        let neighbourhoods = NeighbourhoodSets::new(to_graph);
+        // every image is feasible, the candidates are only pruned by the adjacency of the target
+        let feasible = vec![true; n as usize];
+
+        let mut table : HashMap<TreeNode, HashMap<Mapping, Interval>> = HashMap::new();
+        // the vertices of the bags of the subtree rooted at each node, as long as its parent is not processed
+        let mut subtree_vertices : HashMap<TreeNode, HashSet<Vertex>> = HashMap::new();
+        // the upper bound on the contributions of all dropped entries
+        let mut slack : u64 = 0;
+        let mut dropped_entries = 0;
+
+        for p in ntd.stingy_ordering(){
+            let mut entries : HashMap<Mapping, Interval> = HashMap::new();
+            let mut vertices : HashSet<Vertex> = ntd.bag(p).unwrap().iter().copied().collect();
+            for q in ntd.children(p).into_iter().flatten(){
+                vertices.extend(subtree_vertices.remove(q).unwrap());
+            }
+
+            match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    leaf_entries(from_graph, to_graph, *ntd.unique_vertex(p).unwrap(), &feasible, &mut entries);
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+
+                    let mut images = AdjacentImages::new(&neighbourhoods, from_graph, to_graph, &indexer_q, v, &feasible, false);
+                    introduce_entries(&indexer_q, v, &table.remove(&q).unwrap(), |f_q, buffer| images.fill(f_q, buffer), &mut entries).expect(SATURATING);
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    forget_entries(&BagIndexer::of_node(ntd, q, n), v, &table.remove(&q).unwrap(), &mut entries).expect(SATURATING);
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    let entries_q1 = table.remove(&children[0]).unwrap();
+                    let entries_q2 = table.remove(&children[1]).unwrap();
+                    join_entries(&entries_q1, &entries_q2, &mut entries).expect(SATURATING);
+                }
+                None => {}
+            }
+
+            // the pattern vertices outside of the subtree can be mapped anywhere
+            let outside = h - vertices.len();
+            let extensions = (0..outside).fold(1u64, |product, _| product.saturating_mul(n));
+            for (_, interval) in prune(&mut entries, options){
+                slack = slack.saturating_add(interval.upper.saturating_mul(extensions));
+                dropped_entries += 1;
+            }
+
+            table.insert(p, entries);
+            subtree_vertices.insert(p, vertices);
+        }
+
+        let root_entries = table.remove(&ntd.root()).unwrap();
+        let total = root_entries.values().fold(Interval::exact(0), |sum, interval| Interval::add(&sum, interval));
+        CountBounds { bounds: Interval { lower: total.lower, upper: total.upper.saturating_add(slack) }, dropped_entries }
+    }
+
+    /// Removes the entries which are pruned according to the options and returns them.
+    fn prune(entries : &mut HashMap<Mapping, Interval>, options : &PruningOptions) -> Vec<(Mapping, Interval)> {
+        let mut dropped : Vec<(Mapping, Interval)> = vec![];
+        if options.drop_below > 0 {
+            entries.retain(|&f, interval| {
+                let keep = interval.upper >= options.drop_below;
+                if !keep { dropped.push((f, *interval)); }
+                keep
+            });
+        }
+
+        if let Some(max_entries) = options.max_entries {
+            if entries.len() > max_entries {
+                // the entries with the largest upper bounds are kept, ties are broken by the mapping
+                let mut sorted : Vec<(Mapping, Interval)> = entries.drain().collect();
+                sorted.sort_unstable_by_key(|&(f, interval)| (std::cmp::Reverse(interval.upper), f));
+                dropped.extend(sorted.split_off(max_entries));
+                entries.extend(sorted);
+            }
+        }
+        dropped
+    }
+}
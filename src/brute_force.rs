@@ -1,71 +1,59 @@
 /// A module containing brute force homomorphism counter
 pub mod brute_force_homomorphism_counter{
 
-    use petgraph::matrix_graph::MatrixGraph;
-    use petgraph::Undirected;
-    use crate::graph_generation::graph_generation_algorithms::{generate_graphs, generate_possible_edges};
-    use crate::integer_functions::integer_functions_methods;
-    use crate::integer_functions::integer_functions_methods::{apply, Mapping, max_mappings};
+    use crate::graph_generation::graph_generation_algorithms::generate_graphs_containing;
+    use crate::integer_functions::integer_functions_methods::{Mapping, MappingSpace};
+    use crate::loop_semantics::loop_semantics_methods::LoopSemantics;
+    use crate::parallelism::parallel_execution;
     use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
-    use crate::tree_decompositions::tree_structure::Vertex;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// The maximal number of mappings which are checked by a single task of simple_brute_force.
+    const BRUTE_FORCE_CHUNK_SIZE : Mapping = 4096;
 
     /// a simple brute force algorithm which iterates over all possible mappings from "from_graph" to "to_graph"
     /// todo: a possible improvement would be to first seperate the graph into its connected components and then execute this algo for each of them
     /// todo: generalize them for more graph types
-    pub fn simple_brute_force(from_graph : &MatrixGraph<(),(), Undirected>, to_graph : &MatrixGraph<(),(), Undirected>) -> u64{
+    pub fn simple_brute_force(from_graph : &Graph, to_graph : &Graph) -> u64{
 
         let h = from_graph.node_count();
         let g = to_graph.node_count();
 
-        // Checks if mapping is a homomorphism
-        let check_mapping = |f : Mapping|{
-
-            let mut ret = true;
-
-            for u in 0..h{
-                for v in 0..h{
-                    if from_graph.has_edge(Vertex::new(u ), Vertex::new(v )){
-
-                        let map_u = integer_functions_methods::apply(g as Mapping, f, u as Mapping);
-                        let map_v = integer_functions_methods::apply(g as Mapping, f, v as Mapping);
-
-                        if !to_graph.has_edge(Vertex::new(map_u as usize), Vertex::new(map_v as usize))
-                        {
-                            ret = false;
-                        }
-                    }
-                }
-            }
+        // the edges of the pattern as pairs of significances
+        let edges : Vec<(usize, usize)> = (0..h)
+            .flat_map(|u| (u..h).map(move |v| (u, v)))
+            .filter(|&(u, v)| from_graph.has_edge(Vertex::new(u), Vertex::new(v)))
+            .collect();
 
-            ret
+        // Checks if the images of a mapping form a homomorphism
+        let check_mapping = |images : &[Mapping]|{
+            edges.iter().all(|&(u, v)| to_graph.has_edge(Vertex::new(images[u] as usize), Vertex::new(images[v] as usize)))
         };
 
-        let max = max_mappings(h as Mapping, g as Mapping);
-        let mut counter = 0;
+        let space = MappingSpace::new(h as Mapping, g as Mapping, BRUTE_FORCE_CHUNK_SIZE);
 
-        // for all mapings from H to G
-        for f in 0..max{
-            if check_mapping(f){counter += 1;}
-        }
-        counter
+        // for all mapings from H to G, whose chunks are checked in parallel with the "parallel" feature
+        parallel_execution::map_range(space.chunk_count() as usize, |i| space.chunk(i as Mapping).count(check_mapping))
+            .into_iter().sum()
     }
 
+    /// simple_brute_force where loops of both graphs are treated according to the given loop semantics.
+    pub fn simple_brute_force_with_loop_semantics(from_graph : &Graph, to_graph : &Graph, loop_semantics : LoopSemantics) -> u64{
+        simple_brute_force(&loop_semantics.apply_to_pattern(from_graph), &loop_semantics.apply_to_target(to_graph))
+    }
 
     /// Implementation of simple_brute_force for all graphs in $H_\tau$
-    pub fn simple_brute_force_for_ntd_set(ntd : &NiceTreeDecomposition, to_graph : &MatrixGraph<(),(), Undirected>) -> Vec<(MatrixGraph<(), (), Undirected>, u64)>{
-        let mut result = vec![];
-
-        let possible_edges = generate_possible_edges(ntd);
-
-        let graphs = generate_graphs(ntd.vertex_count() as u64,
-                                     possible_edges.get(&ntd.root()).unwrap().clone() );
-
-        for graph in graphs{
+    pub fn simple_brute_force_for_ntd_set(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<(Graph, u64)>{
+        simple_brute_force_for_ntd_set_containing(ntd, to_graph, &[])
+    }
 
-            let hom_number = simple_brute_force(&graph, to_graph);
-            result.push(( graph, hom_number));
-        }
+    /// Implementation of simple_brute_force for all graphs in $H_\tau$ containing the given required edges.
+    pub fn simple_brute_force_for_ntd_set_containing(ntd : &NiceTreeDecomposition, to_graph : &Graph, required_edges : &[(usize, usize)]) -> Vec<(Graph, u64)>{
+        let graphs = generate_graphs_containing(ntd.vertex_count() as u64,
+                                                ntd.all_possible_edges().clone(),
+                                                required_edges);
 
-        result
+        let hom_numbers = parallel_execution::map_range(graphs.len(), |i| simple_brute_force(&graphs[i], to_graph));
+        graphs.into_iter().zip(hom_numbers).collect()
     }
 }
@@ -0,0 +1,157 @@
+/// A module containing capacitated homomorphism counting. Each vertex v of the target graph has a
+/// capacity c(v) which limits the number of pattern vertices that may be mapped onto v.
+pub mod capacitated_algorithms {
+    use std::collections::HashMap;
+    use petgraph::visit::NodeIndexable;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{forget_entries, introduce_entries, join_entries, NodeTable};
+    use crate::integer_functions::integer_functions_methods;
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping, max_mappings};
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
+
+    /// Usage of the target vertices: usage[a] is the number of pattern vertices mapped onto a.
+    pub type Usage = Vec<u64>;
+
+    /// The message of the panic if the number of capacitated homomorphisms does not fit into 64 bits.
+    const COUNT_OVERFLOW: &str = "The number of homomorphisms does not fit into 64 bits!";
+
+    /// A brute force algorithm counting all homomorphisms from "from_graph" to "to_graph" which map
+    /// at most capacities[a] vertices onto each target vertex a.
+    /// Panics if there is no capacity for each vertex of "to_graph".
+    pub fn capacitated_brute_force(from_graph : &Graph, to_graph : &Graph, capacities : &[u64]) -> u64{
+        assert_eq!(capacities.len(), to_graph.node_count(), "The capacities have to contain a capacity for each vertex of the target!");
+
+        let h = from_graph.node_count();
+        let g = to_graph.node_count();
+
+        let mut counter = 0;
+
+        // for all mappings from H to G
+        for f in 0..max_mappings(h as Mapping, g as Mapping){
+            let image = |u : usize| integer_functions_methods::apply(g as Mapping, f, u as Mapping) as usize;
+
+            let mut usage = vec![0; g];
+            for u in 0..h { usage[image(u)] += 1; }
+            if usage.iter().zip(capacities).any(|(used, capacity)| used > capacity) { continue; }
+
+            let is_homomorphism = (0..h).all(|u| (0..h).all(|v| {
+                !from_graph.has_edge(Vertex::new(u), Vertex::new(v)) ||
+                    to_graph.has_edge(Vertex::new(image(u)), Vertex::new(image(v)))
+            }));
+
+            if is_homomorphism { counter += 1; }
+        }
+        counter
+    }
+
+    /// The algorithm of diaz et all extended by capacities. The state of the dynamic program is
+    /// augmented by the usage of the target vertices by all pattern vertices of the subtree rooted at p,
+    /// i.e. the table of p consists of a node table I[p,usage,f] for each usage. Introduce nodes increase the
+    /// usage of the new image, forget nodes keep the usage and join nodes add the usages of both children while
+    /// subtracting the usage of the common bag. Entries exceeding a capacity are never created. The node tables
+    /// are extended, reduced and multiplied by the steps of the algorithm of diaz et all.
+    /// Note that the number of states grows with the product of (c(a) + 1) over all target vertices a.
+    /// Panics if there is no capacity for each vertex of "to_graph" or if the number does not fit into 64 bits.
+    pub fn capacitated_diaz_serna_thilikos(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, capacities : &[u64]) -> u64{
+        assert_eq!(capacities.len(), to_graph.node_count(), "The capacities have to contain a capacity for each vertex of the target!");
+
+        let n = to_graph.node_count();
+        let has_edge = |a : usize, b : usize| to_graph.has_edge(to_graph.from_index(a), to_graph.from_index(b));
+        let mut table : HashMap<TreeNode, HashMap<Usage, NodeTable>> = HashMap::new();
+
+        for p in ntd.stingy_ordering(){
+            let mut layers : HashMap<Usage, NodeTable> = HashMap::new();
+
+            match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    let v = *ntd.unique_vertex(p).unwrap();
+
+                    for a in 0..n{
+                        if capacities[a] == 0 { continue; }
+                        if from_graph.has_edge(v, v) && !has_edge(a, a) { continue; }
+
+                        let mut usage = vec![0; n];
+                        usage[a] = 1;
+                        layers.entry(usage).or_default().insert(a as Mapping, 1);
+                    }
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+
+                    let indexer_q = BagIndexer::of_node(ntd, q, n as Mapping);
+
+                    // significances of the neighbours of v in the bag of q, i.e. of the neighbours which are already mapped
+                    let s_q = indexer_q.neighbour_positions(from_graph, v);
+                    let has_loop = from_graph.has_edge(v, v);
+
+                    // each image a with remaining capacity leads to the usage with one more vertex mapped onto a
+                    for (usage, entries_q) in table.remove(&q).unwrap_or_default(){
+                        for a in (0..n).filter(|&a| usage[a] < capacities[a]){
+                            let mut new_usage = usage.clone();
+                            new_usage[a] += 1;
+
+                            let entries = layers.entry(new_usage).or_default();
+                            introduce_entries(&indexer_q, v, &entries_q, |f_q, buffer| {
+                                let condition = (!has_loop || has_edge(a, a)) &&
+                                    s_q.iter().all(|&significance| has_edge(a, indexer_q.image_at(f_q, significance) as usize));
+                                buffer.push((a as Mapping, condition as u64));
+                                Some(())
+                            }, entries).expect(COUNT_OVERFLOW);
+                        }
+                    }
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+
+                    let indexer_q = BagIndexer::of_node(ntd, q, n as Mapping);
+
+                    // the usage already contains the image of the forgotten vertex
+                    for (usage, entries_q) in table.remove(&q).unwrap_or_default(){
+                        let entries = layers.entry(usage).or_default();
+                        forget_entries(&indexer_q, forgotten_vertex, &entries_q, entries).expect(COUNT_OVERFLOW);
+                    }
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    let layers_q1 = table.remove(&children[0]).unwrap_or_default();
+                    let layers_q2 = table.remove(&children[1]).unwrap_or_default();
+
+                    let indexer = BagIndexer::of_node(ntd, p, n as Mapping);
+                    let bag_size = indexer.bag().len() as Mapping;
+
+                    for (usage1, entries_q1) in &layers_q1{
+                        for (usage2, entries_q2) in &layers_q2{
+                            // the usage of both children only differs from the joined usage by their bag usage
+                            let mut entries = NodeTable::new();
+                            join_entries(entries_q1, entries_q2, &mut entries).expect(COUNT_OVERFLOW);
+
+                            for (f, value) in entries{
+                                // the vertices of the bag are counted in the usage of both children
+                                let mut usage : Usage = (0..n).map(|a| usage1[a] + usage2[a]).collect();
+                                for s in 0..bag_size{
+                                    usage[indexer.image_at(f, s) as usize] -= 1;
+                                }
+                                if usage.iter().zip(capacities).any(|(used, capacity)| used > capacity) { continue; }
+
+                                let layer = layers.entry(usage).or_default();
+                                let sum = layer.get(&f).copied().unwrap_or(0).checked_add(value).expect(COUNT_OVERFLOW);
+                                layer.insert(f, sum);
+                            }
+                        }
+                    }
+                }
+                None => {}
+            }
+
+            layers.retain(|_, entries| !entries.is_empty());
+            table.insert(p, layers);
+        }
+
+        // the bag of the root is empty, sum up over all usages
+        table.remove(&ntd.root()).unwrap_or_default().values()
+            .map(|entries| entries.get(&0).copied().unwrap_or(0))
+            .fold(0u64, |sum, value| sum.checked_add(value).expect(COUNT_OVERFLOW))
+    }
+}
@@ -0,0 +1,138 @@
+/// A module containing the chromatic polynomial of a pattern, i.e. hom(H, K_x) as a polynomial in x.
+/// Every homomorphism into K_x maps onto exactly k of the x vertices for some k <= |V(H)|, hence
+/// $hom(H, K_x) = \sum_k a_k \cdot x(x-1)...(x-k+1)$ where a_k is the number of partitions of V(H) into k
+/// independent sets, i.e. the number of surjective homomorphisms onto K_k divided by k!. The numbers of
+/// surjective homomorphisms are obtained from hom(H, K_j) for j <= k by inclusion-exclusion.
+pub mod chromatic_polynomial_methods {
+    use std::fmt;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// A polynomial with integer coefficients in the variable x.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct ChromaticPolynomial {
+        /// coefficients[i] is the coefficient of x^i, the last coefficient is not 0
+        coefficients: Vec<i128>,
+        /// partition_counts[k] is the coefficient of the falling factorial x(x-1)...(x-k+1)
+        partition_counts: Vec<i128>,
+    }
+
+    impl ChromaticPolynomial {
+
+        /// Returns the coefficients in the monomial basis, starting with the constant coefficient.
+        pub fn coefficients(&self) -> &[i128] { &self.coefficients }
+
+        /// Returns the coefficients in the basis of falling factorials, i.e. the k-th entry is the number of
+        /// partitions of the vertices of the pattern into k independent sets.
+        pub fn falling_factorial_coefficients(&self) -> &[i128] { &self.partition_counts }
+
+        /// Returns the degree of the polynomial, None for the zero polynomial.
+        pub fn degree(&self) -> Option<usize> { self.coefficients.len().checked_sub(1) }
+
+        /// Returns the smallest k such that the pattern has a homomorphism into K_k, None if there is none.
+        pub fn chromatic_number(&self) -> Option<usize> { self.partition_counts.iter().position(|&count| count != 0) }
+
+        /// Evaluates the polynomial at x, which is hom(H, K_x) for x >= 0.
+        pub fn evaluate(&self, x : i128) -> i128 {
+            self.coefficients.iter().rev().fold(0, |value, coefficient| value * x + coefficient)
+        }
+    }
+
+    impl fmt::Display for ChromaticPolynomial {
+        /// Writes the polynomial in descending powers, e.g. "x^3 - 3x^2 + 2x".
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            if self.coefficients.is_empty() { return write!(f, "0"); }
+
+            let mut first = true;
+            for (i, &coefficient) in self.coefficients.iter().enumerate().rev(){
+                if coefficient == 0 { continue; }
+
+                match (first, coefficient < 0) {
+                    (true, true) => write!(f, "-")?,
+                    (true, false) => {}
+                    (false, true) => write!(f, " - ")?,
+                    (false, false) => write!(f, " + ")?,
+                }
+                first = false;
+
+                let absolute = coefficient.unsigned_abs();
+                if absolute != 1 || i == 0 { write!(f, "{}", absolute)?; }
+                match i {
+                    0 => {}
+                    1 => write!(f, "x")?,
+                    _ => write!(f, "x^{}", i)?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Returns the complete graph K_n without loops.
+    fn complete_graph(n : usize) -> Graph{
+        let mut graph = Graph::default();
+        for _ in 0..n{
+            graph.add_node(());
+        }
+        for u in 0..n{
+            for v in u + 1..n{
+                graph.add_edge(Vertex::new(u), Vertex::new(v), ());
+            }
+        }
+        graph
+    }
+
+    /// Computes the chromatic polynomial hom(H, K_x) of the pattern "from_graph" with the algorithm of diaz
+    /// et all for the targets K_1,..,K_n, where n is the number of vertices of the pattern. The polynomial
+    /// is 0 if the pattern contains a loop. Panics if hom(H, K_n) <= n^n does not fit into 64 bits, which only
+    /// happens for patterns with at least 16 vertices, e.g. the edgeless pattern on 16 vertices.
+    pub fn chromatic_polynomial(from_graph : &Graph, ntd : &NiceTreeDecomposition) -> ChromaticPolynomial{
+        let n = from_graph.node_count();
+
+        // hom(H, K_k) for all k <= n, where there is no mapping into K_0 unless the pattern is empty
+        let hom_numbers : Vec<i128> = (0..=n)
+            .map(|k| match k {
+                0 => if n == 0 { 1 } else { 0 },
+                _ => diaz_serna_thilikos_algorithm(from_graph, ntd, &complete_graph(k)) as i128,
+            })
+            .collect();
+
+        // the number of surjective homomorphisms onto K_k divided by k!, where
+        // surj(H, K_k) = \sum_j (-1)^(k-j) binom(k,j) hom(H, K_j)
+        let mut factorial : i128 = 1;
+        let partition_counts : Vec<i128> = (0..=n)
+            .map(|k| {
+                if k > 0 { factorial *= k as i128; }
+
+                let mut binomial : i128 = 1;
+                let mut surjections : i128 = 0;
+                for (j, &hom_number) in hom_numbers.iter().enumerate().take(k + 1){
+                    let sign = if (k - j) % 2 == 0 { 1 } else { -1 };
+                    surjections += sign * binomial * hom_number;
+                    binomial = binomial * (k - j) as i128 / (j + 1) as i128;
+                }
+                surjections / factorial
+            })
+            .collect();
+
+        // expand the falling factorials x(x-1)...(x-k+1) into the monomial basis
+        let mut coefficients = vec![0; n + 1];
+        let mut falling_factorial : Vec<i128> = vec![1];
+        for (k, &count) in partition_counts.iter().enumerate(){
+            for (i, &coefficient) in falling_factorial.iter().enumerate(){
+                coefficients[i] += count * coefficient;
+            }
+
+            // multiply by (x - k)
+            let mut next = vec![0; falling_factorial.len() + 1];
+            for (i, &coefficient) in falling_factorial.iter().enumerate(){
+                next[i + 1] += coefficient;
+                next[i] -= k as i128 * coefficient;
+            }
+            falling_factorial = next;
+        }
+
+        while coefficients.last() == Some(&0) { coefficients.pop(); }
+        ChromaticPolynomial { coefficients, partition_counts }
+    }
+}
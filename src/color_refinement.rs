@@ -0,0 +1,124 @@
+/// A module containing the 1-dimensional Weisfeiler-Leman algorithm (color refinement).
+/// The colors of a stable coloring are canonical, i.e. isomorphic graphs receive the same multiset of colors.
+pub mod weisfeiler_leman {
+    use std::collections::{BTreeMap, HashMap};
+    use petgraph::visit::NodeIndexable;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// Colors are represented by unsigned integers.
+    pub type Color = usize;
+
+    /// Computes the stable coloring of the given graph by 1-WL color refinement. The initial color of a
+    /// vertex expresses whether it has a self loop. In each round a vertex is recolored by its own color
+    /// together with the multiset of colors of its neighbours, until the partition does not change anymore.
+    /// Returns a vector which maps each vertex index to its color.
+    pub fn color_refinement(graph : &Graph) -> Vec<Color>{
+        color_refinement_of_graphs(&[graph]).pop().unwrap()
+    }
+
+    /// Runs color refinement on the disjoint union of the given graphs and returns the stable coloring of
+    /// each graph. Since all graphs are refined simultaneously, their colors are comparable.
+    pub fn color_refinement_of_graphs(graphs : &[&Graph]) -> Vec<Vec<Color>>{
+
+        // initial coloring: vertices with loops get color 1, all others get color 0
+        let mut colors : Vec<Vec<Color>> = graphs.iter().map(|graph| {
+            (0..graph.node_count())
+                .map(|v| graph.has_edge(graph.from_index(v), graph.from_index(v)) as Color)
+                .collect()
+        }).collect();
+        let mut number_of_colors = number_of_distinct_colors(&colors);
+
+        loop {
+            // signature of each vertex: own color and the sorted colors of its neighbours
+            let signatures : Vec<Vec<(Color, Vec<Color>)>> = graphs.iter().zip(&colors).map(|(graph, graph_colors)| {
+                (0..graph.node_count()).map(|v| {
+                    let mut neighbour_colors : Vec<Color> = graph.neighbors(graph.from_index(v))
+                        .map(|u| graph_colors[u.index()])
+                        .collect();
+                    neighbour_colors.sort_unstable();
+                    (graph_colors[v], neighbour_colors)
+                }).collect()
+            }).collect();
+
+            // the new colors are the ranks of the signatures in sorted order, which makes them canonical
+            let mut ranks : BTreeMap<&(Color, Vec<Color>), Color> = BTreeMap::new();
+            for signature in signatures.iter().flatten(){
+                ranks.insert(signature, 0);
+            }
+            for (rank, value) in ranks.values_mut().enumerate(){
+                *value = rank;
+            }
+
+            colors = signatures.iter()
+                .map(|graph_signatures| graph_signatures.iter().map(|signature| *ranks.get(signature).unwrap()).collect())
+                .collect();
+
+            // refinement never merges color classes, hence the partition is stable if the number of colors stays
+            let new_number_of_colors = number_of_distinct_colors(&colors);
+            if new_number_of_colors == number_of_colors { break; }
+            number_of_colors = new_number_of_colors;
+        }
+
+        colors
+    }
+
+    /// Returns the number of vertices of each color in the given coloring.
+    pub fn color_histogram(coloring : &[Color]) -> HashMap<Color, usize>{
+        let mut histogram = HashMap::new();
+        for &color in coloring{
+            *histogram.entry(color).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// Checks whether color refinement distinguishes the given graphs. If it returns false, the graphs are
+    /// not isomorphic. The converse does not hold in general.
+    pub fn indistinguishable_by_color_refinement(a : &Graph, b : &Graph) -> bool{
+        if a.node_count() != b.node_count() { return false; }
+
+        let colors = color_refinement_of_graphs(&[a, b]);
+        color_histogram(&colors[0]) == color_histogram(&colors[1])
+    }
+
+    /// Computes for each vertex u of from_graph the set of feasible images in to_graph as a boolean vector.
+    /// Since homomorphisms do not preserve the colors of color refinement, the candidate sets are refined
+    /// in the same iterative manner but with respect to adjacency: Initially a vertex with a loop can only be
+    /// mapped onto vertices with a loop. Then an image a of u is removed as long as some neighbour w of u
+    /// has no feasible image adjacent to a. Every homomorphism maps each vertex onto one of its feasible images.
+    pub fn feasible_images(from_graph : &Graph, to_graph : &Graph) -> Vec<Vec<bool>>{
+        let h = from_graph.node_count();
+        let g = to_graph.node_count();
+
+        let has_edge = |graph : &Graph, u : usize, v : usize| graph.has_edge(graph.from_index(u), graph.from_index(v));
+
+        let mut feasible : Vec<Vec<bool>> = (0..h)
+            .map(|u| (0..g).map(|a| !has_edge(from_graph, u, u) || has_edge(to_graph, a, a)).collect())
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for u in 0..h{
+                for w in from_graph.neighbors(from_graph.from_index(u)).map(|w| w.index()){
+                    for a in 0..g{
+                        if feasible[u][a] && !(0..g).any(|b| feasible[w][b] && has_edge(to_graph, a, b)){
+                            feasible[u][a] = false;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        feasible
+    }
+
+    /// Counts the distinct colors over all colorings.
+    fn number_of_distinct_colors(colors : &[Vec<Color>]) -> usize{
+        let mut all_colors : Vec<Color> = colors.iter().flatten().copied().collect();
+        all_colors.sort_unstable();
+        all_colors.dedup();
+        all_colors.len()
+    }
+}
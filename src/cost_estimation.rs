@@ -0,0 +1,150 @@
+/// A module containing estimates of the memory and time consumption of the counting algorithms. The estimates
+/// only depend on the nice tree decomposition and the number of vertices of the target graph, hence they can be
+/// evaluated before running an experiment.
+pub mod cost_model {
+    use std::collections::HashSet;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::TreeNode;
+
+    /// The estimated number of bytes of a single table entry including the overhead of the hashmap.
+    pub const BYTES_PER_ENTRY: f64 = 32.0;
+
+    /// An upper bound on the resources of a single run of an algorithm. The values are floating point numbers,
+    /// since they grow exponentially and easily exceed the range of integers.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct CostEstimate {
+        /// the maximal number of table entries stored at the same time
+        pub peak_entries: f64,
+        /// the number of computed table entries
+        pub operations: f64,
+    }
+
+    impl CostEstimate {
+        /// Returns the estimated peak memory consumption in bytes.
+        pub fn memory_bytes(&self) -> f64 { self.peak_entries * BYTES_PER_ENTRY }
+    }
+
+    /// A function which estimates the cost of an algorithm given the nice tree decomposition and the number of
+    /// vertices of the target graph.
+    pub type CostEstimator = fn(&NiceTreeDecomposition, usize) -> CostEstimate;
+
+    /// Estimates the cost of the algorithm of diaz et all for a single pattern. The table of node p has at most
+    /// |V(G)|^|bag(p)| entries and the table of p is stored together with the tables of its children.
+    pub fn estimate_diaz_serna_thilikos(ntd : &NiceTreeDecomposition, v_g : usize) -> CostEstimate{
+        estimate_tables(ntd, |p| (v_g as f64).powi(ntd.bag(p).unwrap().len() as i32))
+    }
+
+    /// Estimates the cost of running the algorithm of diaz et all for all graphs of $H_\tau$, i.e. once for each
+    /// of the 2^|E_\tau| patterns.
+    pub fn estimate_diaz_serna_thilikos_for_ntd_set(ntd : &NiceTreeDecomposition, v_g : usize) -> CostEstimate{
+        let estimate = estimate_diaz_serna_thilikos(ntd, v_g);
+        CostEstimate { peak_entries: estimate.peak_entries, operations: estimate.operations * number_of_patterns(ntd) }
+    }
+
+    /// Estimates the cost of the modified dynamic program. The table of node p additionally distinguishes all
+    /// subsets of the possible edges of the subtree rooted at p.
+    pub fn estimate_modified_dp(ntd : &NiceTreeDecomposition, v_g : usize) -> CostEstimate{
+        estimate_tables(ntd, |p| {
+            2_f64.powi(ntd.possible_edges(p).unwrap().len() as i32) * (v_g as f64).powi(ntd.bag(p).unwrap().len() as i32)
+        })
+    }
+
+    /// Estimates the cost of the brute force algorithm for all graphs of $H_\tau$, which checks all
+    /// |V(G)|^|V(H)| mappings for each pattern and stores only the resulting list of patterns.
+    pub fn estimate_brute_force_for_ntd_set(ntd : &NiceTreeDecomposition, v_g : usize) -> CostEstimate{
+        let patterns = number_of_patterns(ntd);
+        CostEstimate { peak_entries: patterns, operations: patterns * (v_g as f64).powi(ntd.vertex_count() as i32) }
+    }
+
+    /// Returns the number of graphs of $H_\tau$.
+    fn number_of_patterns(ntd : &NiceTreeDecomposition) -> f64 { 2_f64.powi(ntd.all_possible_edges().len() as i32) }
+
+    /// Estimates the cost of a dynamic program over the nice tree decomposition given the size of the table of
+    /// each node. Computing the table of a node touches each entry of the table itself and of its children.
+    fn estimate_tables<F>(ntd : &NiceTreeDecomposition, table_size : F) -> CostEstimate
+        where F: Fn(TreeNode) -> f64
+    {
+        let mut peak_entries : f64 = 0.0;
+        let mut operations = 0.0;
+
+        for p in ntd.stingy_ordering(){
+            let children_entries : f64 = ntd.children(p).into_iter().flatten().map(|&q| table_size(q)).sum();
+            let entries = table_size(p) + children_entries;
+
+            peak_entries = peak_entries.max(entries);
+            operations += entries;
+        }
+
+        CostEstimate { peak_entries, operations }
+    }
+
+    /// The tables retained by a dynamic program at the moment the table of a node has been computed, i.e. before
+    /// the tables of its children are dropped.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct LifetimeStep {
+        /// the node whose table has been computed
+        pub node: TreeNode,
+        /// the number of retained tables, i.e. the tables of the node, its children and all computed nodes whose
+        /// parent has not been computed yet
+        pub live_tables: usize,
+        /// the estimated number of entries of the retained tables
+        pub live_entries: f64,
+    }
+
+    /// The sizes of the set of retained tables over the course of a dynamic program, one step per node.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TableLifetimes {
+        pub steps: Vec<LifetimeStep>,
+    }
+
+    impl TableLifetimes {
+        /// Returns the maximal number of tables retained at the same time.
+        pub fn max_live_tables(&self) -> usize { self.steps.iter().map(|step| step.live_tables).max().unwrap_or(0) }
+
+        /// Returns the maximal number of entries retained at the same time.
+        pub fn peak_entries(&self) -> f64 { self.steps.iter().map(|step| step.live_entries).fold(0.0, f64::max) }
+
+        /// Returns the first step with the maximal number of retained entries, i.e. the node at which the memory
+        /// consumption peaks.
+        pub fn peak_step(&self) -> Option<&LifetimeStep> {
+            let peak_entries = self.peak_entries();
+            self.steps.iter().find(|step| step.live_entries == peak_entries)
+        }
+    }
+
+    /// Simulates the algorithm of diaz et all on a target graph with v_g vertices following the stingy ordering
+    /// and returns the retained tables after each node, where the table of node p has at most |V(G)|^|bag(p)|
+    /// entries. Unlike the estimates, the tables of finished subtrees waiting for their join node are counted,
+    /// which explains a high memory consumption of nice tree decompositions with many join nodes and allows to
+    /// compare nice tree decompositions rerooted or built with different join trees.
+    pub fn table_lifetimes(ntd : &NiceTreeDecomposition, v_g : usize) -> TableLifetimes{
+        table_lifetimes_for_ordering(ntd, &ntd.stingy_ordering(), v_g)
+    }
+
+    /// Simulates the algorithm of diaz et all like table_lifetimes, but following the given ordering of the
+    /// nodes. Panics if the ordering does not contain every node exactly once after all of its children.
+    pub fn table_lifetimes_for_ordering(ntd : &NiceTreeDecomposition, ordering : &[TreeNode], v_g : usize) -> TableLifetimes{
+        assert_eq!(ordering.len() as u64, ntd.node_count(), "The ordering has to contain every node exactly once!");
+        let table_size = |p : TreeNode| (v_g as f64).powi(ntd.bag(p).unwrap().len() as i32);
+
+        let mut live = HashSet::new();
+        let mut live_entries = 0.0;
+        let mut steps = vec![];
+
+        for &p in ordering{
+            let children = ntd.children(p).cloned().unwrap_or_default();
+            assert!(children.iter().all(|q| live.contains(q)), "The node {} is ordered before one of its children!", p);
+            assert!(live.insert(p), "The node {} is ordered twice!", p);
+            live_entries += table_size(p);
+
+            steps.push(LifetimeStep { node: p, live_tables: live.len(), live_entries });
+
+            for q in children{
+                live.remove(&q);
+                live_entries -= table_size(q);
+            }
+        }
+
+        TableLifetimes { steps }
+    }
+}
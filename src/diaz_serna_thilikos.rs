@@ -1,51 +1,174 @@
 
 /// A module containing the algorithm of diaz [todo: add reference with all names]
 pub mod diaz_algorithm {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::fmt;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
     use itertools::sorted;
-    use petgraph::matrix_graph::MatrixGraph;
-    use petgraph::Undirected;
     use petgraph::visit::NodeIndexable;
-    use crate::graph_generation::graph_generation_algorithms::{generate_graphs, generate_possible_edges};
+    use crate::color_refinement::weisfeiler_leman::feasible_images;
+    use crate::graph_generation::graph_generation_algorithms::generate_graphs_containing;
     use crate::integer_functions::integer_functions_methods;
-    use crate::integer_functions::integer_functions_methods::Mapping;
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::loop_semantics::loop_semantics_methods::LoopSemantics;
+    use crate::parallelism::parallel_execution;
+    use crate::tables::table_backends::{CompactTable, DenseTable, MappingTable, Semiring, TableBackend};
+    use crate::testing::consistency_testing::audit_node_table;
     use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
-    use crate::tree_decompositions::tree_structure::{TreeNode, Vertex};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
+
+    /// The non-zero entries I[p,f] of a single node p indexed by the mapping f in the default storage layout.
+    pub type NodeTable = HashMap<Mapping, u64>;
 
     /// A struct containing all important information for the dynamic program.
-    pub(crate) struct DPData<'a> {
-        table: HashMap<TreeNode, HashMap<Mapping, u64>>,
+    /// The table maps each tree node p and each mapping f from the sorted bag of p to to_graph
+    /// (in integer representation) to the number of extending homomorphisms I[p,f].
+    ///
+    /// The table is sharded by nodes: each node owns its own NodeTable, which can be taken out, computed
+    /// elsewhere and inserted again. A shard is an empty DPData on the same graphs, such that disjoint subtrees
+    /// can be processed in different threads without locking and merged afterwards. DPData is Send and Sync.
+    /// The storage layout of the node tables is given by T, see TableBackend.
+    ///
+    /// This is a low-level interface for building custom algorithms on top of the table and
+    /// integer function plumbing. Its methods may change together with the algorithm of diaz et all.
+    pub struct DPData<'a, T : MappingTable = NodeTable> {
+        table: HashMap<TreeNode, T>,
         nice_tree_decomposition: &'a NiceTreeDecomposition,
-        from_graph: &'a MatrixGraph<(), (), Undirected>,
-        to_graph: &'a MatrixGraph<(), (), Undirected>,
-        sorted_bags : HashMap<TreeNode, Vec<Vertex>>,
+        from_graph: &'a Graph,
+        to_graph: &'a Graph,
+        sorted_bags : Arc<HashMap<TreeNode, Vec<Vertex>>>, // shared by all shards
+        neighbourhoods : Arc<NeighbourhoodSets>, // shared by all shards
+        pool : Vec<T>, // cleared tables of processed nodes whose capacity is reused
+        scalar_adjacency : bool, // checks the edges of introduced vertices one by one, see DiazOptions
     }
 
+    // the shards of the table are moved between threads
+    const _: () = {
+        const fn assert_send_sync<T : Send + Sync>() {}
+        assert_send_sync::<DPData<'static>>();
+    };
+
     /// Implementation of functions being necessary for writing and reading the table
     /// of the dynamic program.
     impl<'a> DPData<'a> {
         /// A simple constructor for creating an empty table
-        pub fn new<'b>(from_graph: &'b MatrixGraph<(), (), Undirected>,
-                       to_graph: &'b MatrixGraph<(), (), Undirected>,
+        pub fn new<'b>(from_graph: &'b Graph,
+                       to_graph: &'b Graph,
                        nice_tree_decomposition: &'b NiceTreeDecomposition, ) -> DPData<'b> {
-            let sorted_bags = DPData::sort_bags(nice_tree_decomposition);
-            DPData { table: HashMap::new(), nice_tree_decomposition, from_graph, to_graph, sorted_bags }
+            DPData::with_backend(from_graph, to_graph, nice_tree_decomposition)
         }
 
         /// Returns the entry I[p,f] where p is a tree node and f is a mapping.
         pub fn get(&self, p: &TreeNode, f: &Mapping) -> Option<&u64> {
             if let Some(mappings) = self.table.get(p) { mappings.get(f) } else { None }
         }
+    }
+
+    impl<'a, T : MappingTable> DPData<'a, T> {
+        /// A constructor for creating an empty table whose node tables are stored as T.
+        pub fn with_backend<'b>(from_graph: &'b Graph,
+                                to_graph: &'b Graph,
+                                nice_tree_decomposition: &'b NiceTreeDecomposition, ) -> DPData<'b, T> {
+            let sorted_bags = Arc::new(Self::sort_bags(nice_tree_decomposition));
+            let neighbourhoods = Arc::new(NeighbourhoodSets::new(to_graph));
+            DPData { table: HashMap::new(), nice_tree_decomposition, from_graph, to_graph, sorted_bags, neighbourhoods, pool: Vec::new(), scalar_adjacency: false }
+        }
+
+        /// Returns an empty table on the same graphs and nice tree decomposition, e.g. for processing a
+        /// subtree in another thread. The sorted bags and the neighbourhoods are shared instead of being computed again.
+        pub fn shard(&self) -> DPData<'a, T> {
+            DPData { table: HashMap::new(), nice_tree_decomposition: self.nice_tree_decomposition, from_graph: self.from_graph,
+                     to_graph: self.to_graph, sorted_bags: Arc::clone(&self.sorted_bags), neighbourhoods: Arc::clone(&self.neighbourhoods),
+                     pool: Vec::new(), scalar_adjacency: self.scalar_adjacency }
+        }
+
+        /// Moves all node tables of the shard into this table. The shard has to contain other nodes than this
+        /// table, e.g. nodes of a disjoint subtree, otherwise the tables of this table are replaced.
+        pub fn merge(&mut self, shard : DPData<'a, T>) {
+            debug_assert!(shard.table.keys().all(|p| !self.table.contains_key(p)), "The shard contains nodes of this table!");
+            self.table.extend(shard.table);
+            self.pool.extend(shard.pool);
+        }
+
+        /// Replaces the table of node p by the given entries, zero entries are dropped.
+        pub fn insert_table(&mut self, p : TreeNode, entries : T) {
+            let mut non_zero = T::default();
+            for (f, value) in entries.iter(){
+                non_zero.insert(f, value);
+            }
+            if non_zero.is_empty() { self.table.remove(&p); } else { self.table.insert(p, non_zero); }
+        }
+
+        /// Adds the given entries to the table of node p, e.g. the partial sums of several threads
+        /// processing disjoint parts of the table of a child.
+        pub fn add_table(&mut self, p : TreeNode, entries : &T) {
+            for (f, value) in entries.iter(){
+                self.add(p, f, value);
+            }
+        }
+
+        /// Returns the value of the entry I[p,f]. Entries which are not stored are 0.
+        pub fn value(&self, p: &TreeNode, f: &Mapping) -> u64 {
+            self.table.get(p).and_then(|mappings| mappings.get(*f)).unwrap_or(0)
+        }
 
         /// Sets the entry I[p,f] of the dynamic table to the value of v.
+        /// Since missing entries are 0, zero values are not written into the table.
         pub fn set(&mut self, p: TreeNode, f: Mapping, v: u64) {
+            if v == 0 {
+                if let Some(mappings) = self.table.get_mut(&p) { mappings.insert(f, 0); }
+                return;
+            }
+
             if let Some(mappings) = self.table.get_mut(&p) {
                 mappings.insert(f, v);
             } else {
-                self.table.insert(p, HashMap::from([(f, v)]));
+                // reuses the capacity of a recycled table if there is one
+                let mut mappings = self.pool.pop().unwrap_or_default();
+                mappings.insert(f, v);
+                self.table.insert(p, mappings);
             }
         }
 
+        /// Adds v to the entry I[p,f] of the dynamic table.
+        pub fn add(&mut self, p: TreeNode, f: Mapping, v: u64) {
+            let value = self.value(&p, &f);
+            self.set(p, f, value + v);
+        }
+
+        /// Removes all entries of node p and returns them. These are exactly the non-zero entries of p.
+        pub fn take(&mut self, p: TreeNode) -> T {
+            self.table.remove(&p).unwrap_or_default()
+        }
+
+        /// Returns the number of entries stored for node p, which equals the number of its non-zero entries.
+        pub fn table_size(&self, p: TreeNode) -> usize {
+            self.table.get(&p).map_or(0, |mappings| mappings.len())
+        }
+
+        /// Returns a table which is not needed anymore to the pool. Its allocated capacity
+        /// is reused by the next node whose table is created.
+        pub fn recycle(&mut self, mut entries: T) {
+            entries.clear();
+            self.pool.push(entries);
+        }
+
+        /// Stores the computed entries as the table of node p, an empty table is recycled instead.
+        fn store(&mut self, p : TreeNode, entries : T) {
+            if entries.is_empty() { self.recycle(entries); } else { self.table.insert(p, entries); }
+        }
+
+        /// Returns the nice tree decomposition the table is built on.
+        pub fn nice_tree_decomposition(&self) -> &'a NiceTreeDecomposition { self.nice_tree_decomposition }
+
+        /// Returns the graph homomorphisms are counted from.
+        pub fn from_graph(&self) -> &'a Graph { self.from_graph }
+
+        /// Returns the graph homomorphisms are counted to.
+        pub fn to_graph(&self) -> &'a Graph { self.to_graph }
+
         /// Apply function where the dimension is already set to |V(G)|.
         pub fn table_apply(&self, f : Mapping, s : Mapping) -> Mapping{
             integer_functions_methods::apply(self.to_graph.node_count() as Mapping, f, s)
@@ -64,7 +187,7 @@ pub mod diaz_algorithm {
         /// This is basically the max mapping function applied to the bag(p) and |V(G)|.
         /// It returns the number of mappings from bag(p) to |V(G)|
         pub fn max_bag_mappings(&self, node : TreeNode) -> Mapping{
-            integer_functions_methods::max_mappings(self.nice_tree_decomposition.bag(node).unwrap().len() as Mapping,
+            integer_functions_methods::max_mappings(self.nice_tree_decomposition.bit_bag(node).unwrap().len() as Mapping,
                                                     self.to_graph.node_count() as Mapping )
         }
 
@@ -72,10 +195,9 @@ pub mod diaz_algorithm {
         fn sort_bags(nice_tree_decomposition : &NiceTreeDecomposition) -> HashMap<TreeNode, Vec<Vertex>>{
             let mut sorted_bags = HashMap::new();
 
+            // the vertices of a bitmask bag are already iterated in ascending order
             for p in nice_tree_decomposition.stingy_ordering(){
-                let mut vertex_vector = Vec::from_iter(nice_tree_decomposition.bag(p).unwrap().iter());
-                vertex_vector.sort();
-                sorted_bags.insert(p, vertex_vector.iter().map(|e| **e).collect());
+                sorted_bags.insert(p, nice_tree_decomposition.bit_bag(p).unwrap().iter().collect());
             }
 
             sorted_bags
@@ -90,172 +212,851 @@ pub mod diaz_algorithm {
         }
     }
 
+    /// The neighbourhoods of the vertices of a target graph as bitsets, such that the common neighbourhood of
+    /// the images of several vertices is computed by intersecting words instead of testing every vertex.
+    pub(crate) struct NeighbourhoodSets {
+        words: usize,
+        rows: Vec<u64>, // the neighbourhood of a is stored in rows[a * words..(a + 1) * words]
+        loops: Vec<u64>,
+    }
+
+    impl NeighbourhoodSets {
+        pub(crate) fn new(graph : &Graph) -> NeighbourhoodSets {
+            let n = graph.node_count();
+            let words = n.div_ceil(64);
+            let mut rows = vec![0; n * words];
+            let mut loops = vec![0; words];
+
+            for a in 0..n{
+                for b in graph.neighbors(graph.from_index(a)){
+                    rows[a * words + b.index() / 64] |= 1 << (b.index() % 64);
+                    if b.index() == a { loops[a / 64] |= 1 << (a % 64); }
+                }
+            }
+            NeighbourhoodSets { words, rows, loops }
+        }
+
+        /// Returns the set of the images a with feasible[a].
+        pub(crate) fn feasible_set(&self, feasible : &[bool]) -> Vec<u64> {
+            let mut set = vec![0; self.words];
+            for (a, _) in feasible.iter().enumerate().filter(|(_, &is_feasible)| is_feasible){
+                set[a / 64] |= 1 << (a % 64);
+            }
+            set
+        }
+
+        /// Removes all vertices which are not adjacent to a from the set.
+        pub(crate) fn intersect_neighbourhood(&self, set : &mut [u64], a : usize) {
+            for (word, row) in set.iter_mut().zip(&self.rows[a * self.words..(a + 1) * self.words]){
+                *word &= row;
+            }
+        }
+
+        /// Removes all vertices without a loop from the set.
+        pub(crate) fn intersect_loops(&self, set : &mut [u64]) {
+            for (word, row) in set.iter_mut().zip(&self.loops){
+                *word &= row;
+            }
+        }
+
+        /// Writes the feasible images which are adjacent to all given images into candidates. The neighbourhoods
+        /// are intersected as bitsets, or if scalar is set, every edge is checked one by one in the graph.
+        pub(crate) fn common_neighbours<I>(&self, graph : &Graph, feasible_images : &[u64], images : I, scalar : bool, candidates : &mut [u64])
+            where I: Iterator<Item = usize> + Clone
+        {
+            candidates.copy_from_slice(feasible_images);
+            if scalar {
+                for a in NeighbourhoodSets::iter(feasible_images){
+                    if !images.clone().all(|b| graph.has_edge(graph.from_index(a), graph.from_index(b))) {
+                        candidates[a / 64] &= !(1 << (a % 64));
+                    }
+                }
+            }
+            else {
+                for b in images{ self.intersect_neighbourhood(candidates, b); }
+            }
+        }
+
+        /// Iterates over the vertices of the set in ascending order.
+        pub(crate) fn iter(set : &[u64]) -> impl Iterator<Item = usize> + '_ {
+            set.iter().enumerate().flat_map(|(i, &word)| {
+                let mut rest = word;
+                std::iter::from_fn(move || {
+                    if rest == 0 { return None; }
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    Some(i * 64 + bit)
+                })
+            })
+        }
+    }
+
+    /// Options enabling optional optimizations of the algorithm of diaz et all.
+    /// All optional optimizations are disabled by default, while the optimizations which are always used,
+    /// i.e. the bitset adjacency and the early exit at empty tables, can be switched off to measure their effect.
+    #[derive(PartialEq, Eq, Debug, Clone, Default)]
+    pub struct DiazOptions {
+        /// Restricts the images of each vertex to its feasible images computed in advance by
+        /// the refinement of the color refinement module. Infeasible images are set to 0 without
+        /// checking any edge condition.
+        pub image_pruning: bool,
+        /// Stores mappings and counts as u32 instead of u64, which halves the memory of the table.
+        /// Entries whose mapping or count does not fit into 32 bits are promoted to u64 without
+        /// restarting the computation, see CompactTable. Takes precedence over the backend.
+        pub compact_tables: bool,
+        /// The storage layout of the tables.
+        pub backend: TableBackend,
+        /// Compares the table of every node with a brute force count of the homomorphisms of the vertices of its
+        /// subtree extending each mapping and panics at the first wrong entry, such that index shifts in new
+        /// introduce or forget handlers are caught at the node they occur. The streaming of path decompositions is
+        /// not used, since it does not keep the tables of all nodes.
+        /// The brute force count is exponential in the number of vertices, hence only for small instances.
+        pub audit: bool,
+        /// Checks the edges between the image of an introduced vertex and the images of its neighbours one by one
+        /// instead of intersecting the neighbourhoods of the target as bitsets.
+        pub scalar_adjacency: bool,
+        /// Processes all nodes even if a table is empty instead of returning 0 at the first empty table.
+        pub keep_zero_tables: bool,
+    }
+
+    /// Statistics of a single node of the nice tree decomposition collected while running a dynamic program.
+    /// The reports can be used to identify the nodes dominating the running time, e.g. to decide whether
+    /// the nice tree decomposition should be re-rooted or rebalanced.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct NodeReport {
+        /// The node of the nice tree decomposition.
+        pub node: TreeNode,
+        /// The type of the node.
+        pub node_type: NodeType,
+        /// The number of entries stored in the table of the node after it has been processed.
+        pub table_size: usize,
+        /// The time spent on computing the table of the node.
+        pub time: Duration,
+    }
+
+    /// A condition under which a run of the dynamic program is interrupted. It is checked before each node of
+    /// the stingy ordering, hence a single node which takes long is always completed. Without a deadline and
+    /// a flag the run is never interrupted.
+    #[derive(Debug, Clone, Default)]
+    pub struct StopCondition {
+        /// the run is interrupted once this point in time has passed
+        pub deadline: Option<Instant>,
+        /// the run is interrupted once the flag is set, e.g. by another thread or a signal handler
+        pub cancelled: Option<Arc<AtomicBool>>,
+    }
+
+    impl StopCondition {
+        /// Interrupts the run once the given time has elapsed from now on.
+        pub fn timeout(timeout : Duration) -> StopCondition {
+            StopCondition { deadline: Some(Instant::now() + timeout), cancelled: None }
+        }
+
+        /// Interrupts the run once the flag is set.
+        pub fn cancellation(cancelled : Arc<AtomicBool>) -> StopCondition {
+            StopCondition { deadline: None, cancelled: Some(cancelled) }
+        }
+
+        /// Returns true if the run has to be interrupted.
+        pub fn is_met(&self) -> bool {
+            self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                || self.cancelled.as_ref().is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+        }
+    }
+
+    /// The state of an interrupted run of the dynamic program, such that it can be logged how far the
+    /// algorithm got instead of discarding the work done.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct PartialRun {
+        /// the prefix of the stingy ordering whose tables have been computed
+        pub completed: Vec<TreeNode>,
+        /// the reports of the completed nodes
+        pub reports: Vec<NodeReport>,
+        /// the tables of the completed nodes which have not been consumed by their parents yet,
+        /// i.e. of the roots of the completed subtrees
+        pub tables: HashMap<TreeNode, NodeTable>,
+    }
+
+    impl PartialRun {
+        /// Returns the time spent on the completed nodes.
+        pub fn elapsed(&self) -> Duration { self.reports.iter().map(|report| report.time).sum() }
+
+        /// Returns the number of entries stored in the remaining tables.
+        pub fn stored_entries(&self) -> usize { self.tables.values().map(|table| table.len()).sum() }
+
+        /// Returns the exact number of homomorphisms from the subgraph induced by the vertices of each completed
+        /// subtree, i.e. the sum of the remaining table of its root, ordered by the roots.
+        pub fn subtree_counts(&self) -> Vec<(TreeNode, u64)> {
+            let mut counts : Vec<(TreeNode, u64)> = self.tables.iter().map(|(&q, table)| (q, table.values().sum())).collect();
+            counts.sort_unstable();
+            counts
+        }
+
+        /// Returns an upper bound on the number of homomorphisms into a target with v_g vertices, which saturates
+        /// at u64::MAX. Every homomorphism restricts to a homomorphism of the subgraph induced by each completed
+        /// subtree and maps the vertices of no completed subtree anywhere, hence it is bounded by the product of
+        /// the subtree counts and v_g to the power of the number of the remaining vertices.
+        pub fn upper_bound(&self, ntd : &NiceTreeDecomposition, v_g : usize) -> u64 {
+            let mut covered = vec![false; ntd.vertex_count() as usize];
+            let mut stack : Vec<TreeNode> = self.tables.keys().copied().collect();
+            while let Some(p) = stack.pop() {
+                for v in ntd.bag(p).into_iter().flatten(){
+                    covered[v.index()] = true;
+                }
+                stack.extend(ntd.children(p).into_iter().flatten());
+            }
+
+            let remaining = covered.iter().filter(|&&is_covered| !is_covered).count();
+            self.subtree_counts().into_iter()
+                .map(|(_, count)| count)
+                .chain(std::iter::repeat_n(v_g as u64, remaining))
+                .fold(1, u64::saturating_mul)
+        }
+    }
+
+    /// The result of a run of the algorithm of diaz et all under a time budget. If the run is completed, both
+    /// bounds are the number of homomorphisms, otherwise the lower bound is 0 and the upper bound is the one of
+    /// the partial run.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct AnytimeCount {
+        pub lower: u64,
+        /// the upper bound, u64::MAX if it exceeds the range of u64
+        pub upper: u64,
+        /// the state of the interrupted run, None if the run has been completed
+        pub partial: Option<PartialRun>,
+    }
+
+    impl AnytimeCount {
+        /// Returns true if the bounds coincide, i.e. the number of homomorphisms is known.
+        pub fn is_exact(&self) -> bool { self.lower == self.upper }
+    }
+
+    impl fmt::Display for PartialRun {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "interrupted after {} nodes in {:?}, {} tables with {} entries stored",
+                   self.completed.len(), self.elapsed(), self.tables.len(), self.stored_entries())
+        }
+    }
+
+    /// The result of the algorithm of diaz et all. Every homomorphism restricts to a non-zero entry of the table
+    /// of each node, hence if the table of a node contains no non-zero entry, there is no homomorphism at all and
+    /// the computation stops at this node instead of processing the remaining nodes of the stingy ordering.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub enum DiazResult {
+        /// the number of homomorphisms computed at the root
+        Count(u64),
+        /// the first node in the stingy ordering whose table contains no non-zero entry
+        ZeroAtNode(TreeNode),
+        /// the first node in the stingy ordering whose table contains an entry which does not fit into 64 bits
+        Overflow(TreeNode),
+    }
+
+    impl DiazResult {
+        /// Returns the number of homomorphisms. Panics if the number does not fit into 64 bits.
+        pub fn count(&self) -> u64 {
+            match self {
+                DiazResult::Count(count) => *count,
+                DiazResult::ZeroAtNode(_) => 0,
+                DiazResult::Overflow(_) => panic!("{}", COUNT_OVERFLOW),
+            }
+        }
+    }
+
     /// Implementation of the algorithm of diaz et all
-    pub fn diaz_serna_thilikos_algorithm(from_graph : &MatrixGraph<(),(), Undirected>, ntd : &NiceTreeDecomposition, to_graph : &MatrixGraph<(),(), Undirected>) -> u64{
+    pub fn diaz_serna_thilikos_algorithm(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> u64{
+        diaz_serna_thilikos_with_options(from_graph, ntd, to_graph, &DiazOptions::default())
+    }
+
+    /// Implementation of the algorithm of diaz et all with the optimizations enabled in the given options.
+    pub fn diaz_serna_thilikos_with_options(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, options : &DiazOptions) -> u64{
+        diaz_serna_thilikos_with_report(from_graph, ntd, to_graph, options).0
+    }
+
+    /// Implementation of the algorithm of diaz et all which additionally returns a report for each node
+    /// in the stingy ordering, containing the size of its table and the time spent on it.
+    pub fn diaz_serna_thilikos_with_report(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, options : &DiazOptions) -> (u64, Vec<NodeReport>){
+        let (result, report) = diaz_serna_thilikos_with_result(from_graph, ntd, to_graph, options);
+        (result.count(), report)
+    }
+
+    /// Implementation of the algorithm of diaz et all which returns whether the computation stopped early
+    /// at a node with an empty table together with the reports of all processed nodes.
+    pub fn diaz_serna_thilikos_with_result(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, options : &DiazOptions) -> (DiazResult, Vec<NodeReport>){
+
+        let mut report = vec![];
+
+        // feasible images of each vertex of from_graph, all images are feasible without pruning
+        let feasible = if options.image_pruning { feasible_images(from_graph, to_graph) }
+                       else { vec![vec![true; to_graph.node_count()]; from_graph.node_count()] };
+
+        // path decompositions need the tables of the current node and of its child only
+        if ntd.is_path_decomposition() && !options.audit {
+            let result = match options.backend {
+                _ if options.compact_tables => path_diaz_serna_thilikos::<CompactTable>(from_graph, ntd, to_graph, &feasible, options, &mut report),
+                TableBackend::Hash => path_diaz_serna_thilikos::<NodeTable>(from_graph, ntd, to_graph, &feasible, options, &mut report),
+                TableBackend::BTree => path_diaz_serna_thilikos::<BTreeMap<Mapping, u64>>(from_graph, ntd, to_graph, &feasible, options, &mut report),
+                TableBackend::Dense => path_diaz_serna_thilikos::<DenseTable>(from_graph, ntd, to_graph, &feasible, options, &mut report),
+            };
+            return (result, report);
+        }
+
+        run_with_backend(from_graph, ntd, to_graph, &feasible, options, report, &StopCondition::default())
+            .expect("A run without stop condition cannot be interrupted!")
+    }
 
+    /// Implementation of the algorithm of diaz et all which is interrupted as soon as the stop condition is met
+    /// before a node of the stingy ordering. In that case the completed prefix of the stingy ordering, its
+    /// reports and the remaining tables are returned as PartialRun. The tables of an interrupted run are converted
+    /// into NodeTables, such that they have the same layout for every backend.
+    pub fn diaz_serna_thilikos_interruptible(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, options : &DiazOptions, stop : &StopCondition) -> Result<(DiazResult, Vec<NodeReport>), PartialRun>{
+        let feasible = if options.image_pruning { feasible_images(from_graph, to_graph) }
+                       else { vec![vec![true; to_graph.node_count()]; from_graph.node_count()] };
+
+        run_with_backend(from_graph, ntd, to_graph, &feasible, options, vec![], stop)
+    }
+
+    /// Implementation of the algorithm of diaz et all which processes the stingy ordering for at most the given
+    /// time budget, see diaz_serna_thilikos_interruptible. If the budget is exhausted, the exact counts of the
+    /// completed subtrees and an upper bound derived from them are returned instead of the count, such that
+    /// instances can be triaged without waiting for the hard ones.
+    pub fn diaz_serna_thilikos_anytime(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, options : &DiazOptions, budget : Duration) -> AnytimeCount{
+        match diaz_serna_thilikos_interruptible(from_graph, ntd, to_graph, options, &StopCondition::timeout(budget)) {
+            Ok((result, _)) => AnytimeCount { lower: result.count(), upper: result.count(), partial: None },
+            Err(partial) => AnytimeCount { lower: 0, upper: partial.upper_bound(ntd, to_graph.node_count()), partial: Some(partial) },
+        }
+    }
+
+    /// Runs the dynamic program on node tables in the layout of the backend of the options.
+    fn run_with_backend(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, feasible : &[Vec<bool>], options : &DiazOptions, report : Vec<NodeReport>, stop : &StopCondition) -> Result<(DiazResult, Vec<NodeReport>), PartialRun>{
+        match options.backend {
+            _ if options.compact_tables => run_dynamic_program::<CompactTable>(from_graph, ntd, to_graph, feasible, options, report, stop),
+            TableBackend::Hash => run_dynamic_program::<NodeTable>(from_graph, ntd, to_graph, feasible, options, report, stop),
+            TableBackend::BTree => run_dynamic_program::<BTreeMap<Mapping, u64>>(from_graph, ntd, to_graph, feasible, options, report, stop),
+            TableBackend::Dense => run_dynamic_program::<DenseTable>(from_graph, ntd, to_graph, feasible, options, report, stop),
+        }
+    }
+
+    /// Runs the dynamic program on node tables of type T by following the stingy ordering until the
+    /// stop condition is met. With audit every table is verified by brute force.
+    fn run_dynamic_program<T : MappingTable>(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, feasible : &[Vec<bool>], options : &DiazOptions, mut report : Vec<NodeReport>, stop : &StopCondition) -> Result<(DiazResult, Vec<NodeReport>), PartialRun>{
         let stingy_ordering = ntd.stingy_ordering();
-        let mut dp_data = DPData::new(from_graph, to_graph, ntd);
+        let mut dp_data : DPData<T> = DPData::with_backend(from_graph, to_graph, ntd);
+        dp_data.scalar_adjacency = options.scalar_adjacency;
 
         // traversing the tree of the nice tree decomposition by following the stingy ordering.
-        for p in stingy_ordering{
+        for (i, &p) in stingy_ordering.iter().enumerate(){
 
-            // matching node types
-            match ntd.node_type(p) {
-                None => {}
-                Some(NodeType::Leaf) => {
-                    // get the unique vertex of p´s bag
-                    if let Some(&unique_vertex) = ntd.unique_vertex(p){
-                        // Checks if unique vertex has a self loop
-                        if from_graph.has_edge(unique_vertex,unique_vertex){
-                            // iterate over all possible images of unique_vertex
-                            for image in 0..to_graph.node_count(){
-                                // checks if image of unique_vertex also has self loop
-                                if to_graph.has_edge(to_graph.from_index(image),
-                                                     to_graph.from_index(image) ){ dp_data.set(p, image as Mapping, 1); }
-                                else { dp_data.set(p, image as Mapping, 0); }
-                            }
-                        }
-                        else {
-                            // set all mappings to 1
-                            for image in 0..to_graph.node_count(){ dp_data.set(p, image as Mapping, 1); }
-                        }
-                    }
+            if stop.is_met() {
+                let tables = dp_data.table.iter().map(|(&q, entries)| (q, entries.iter().collect())).collect();
+                return Err(PartialRun { completed: stingy_ordering[..i].to_vec(), reports: report, tables });
+            }
+
+            let start = Instant::now();
+
+            if process_node(&mut dp_data, p, feasible).is_none() { return Ok((DiazResult::Overflow(p), report)); }
+
+            if options.audit {
+                let entries = dp_data.table.get(&p).into_iter().flat_map(|entries| entries.iter());
+                if let Err(mismatch) = audit_node_table(from_graph, ntd, to_graph, p, feasible, entries) {
+                    panic!("Audit of the {:?} node {} failed: {}", ntd.node_type(p).unwrap(), p, mismatch);
                 }
-                Some(NodeType::Introduce) => {
-                    // get the unique child of p
-                    let q = *ntd.unique_child(p).unwrap();
-                    // get the introduced vertex
-                    let v = *ntd.unique_vertex(p).unwrap();
+            }
 
+            if let Some(node_type) = ntd.node_type(p){
+                report.push(NodeReport { node: p, node_type: node_type.clone(), table_size: dp_data.table_size(p), time: start.elapsed() });
+            }
 
-                    let neighbours_of_v: HashSet<Vertex> = HashSet::from_iter(from_graph.neighbors(v));
-                    let s_q : Vec<&Vertex> = neighbours_of_v.intersection(ntd.bag(p).unwrap()).collect();
+            if dp_data.table_size(p) == 0 && !options.keep_zero_tables { return Ok((DiazResult::ZeroAtNode(p), report)); }
+        }
 
+        Ok((DiazResult::Count(dp_data.value(&ntd.root(), &0)), report))
+    }
 
-                    // sorted bag of q
-                    let sorted_q_bag = dp_data.sorted_bag(q).unwrap();
+    /// Runs the algorithm of diaz et all where each vertex v of from_graph is only mapped to the images a with
+    /// feasible[v][a] and returns the table of the root, i.e. the number of homomorphisms extending each mapping
+    /// of the root bag. If the root bag is empty, the table consists of the single entry of the empty mapping 0.
+    pub fn diaz_serna_thilikos_root_table(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, feasible : &[Vec<bool>]) -> NodeTable{
+        let mut dp_data = DPData::new(from_graph, to_graph, ntd);
 
-                    // That is the case when no index will be found
-                    // the mapping will be but to the end of the new mapping
-                    let mut new_index = sorted_q_bag.len();
+        for p in ntd.stingy_ordering(){
+            process_node(&mut dp_data, p, feasible).expect(COUNT_OVERFLOW);
+        }
 
-                    // Find the position of the introduce vertex in the new mapping
-                    if let Some(index) = sorted_q_bag.iter().position(|&vertex| v.index() < vertex.index() ){ new_index = index; }
+        dp_data.take(ntd.root())
+    }
 
+    /// Runs the algorithm of diaz et all like diaz_serna_thilikos_root_table, but starting from the given tables of
+    /// the roots of completed subtrees, e.g. the tables of an interrupted run, whose nodes are not processed again.
+    /// Returns the non-zero entries of the tables of all given and processed nodes instead of consuming the tables
+    /// of the children, such that follow-up queries can descend from the root. Without given tables every node
+    /// is processed and the tables of all nodes are returned.
+    pub fn diaz_serna_thilikos_all_tables(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, feasible : &[Vec<bool>], frontier : HashMap<TreeNode, NodeTable>) -> HashMap<TreeNode, NodeTable>{
+        let mut dp_data = DPData::new(from_graph, to_graph, ntd);
+        for (&q, entries) in &frontier{
+            dp_data.insert_table(q, entries.clone());
+        }
 
-                    let sorted_p_bag = dp_data.sorted_bag(p).unwrap();
+        // the nodes of the completed subtrees, i.e. with a given table at the node or at one of its ancestors
+        let completed = subtree_nodes(ntd, frontier.keys().copied());
 
-                    // maps vertex to its significance in the bag of p
-                    let mut significance_hash = HashMap::new();
-                    /*
-                    for i in 0..sorted_p_bag.len() {
-                        significance_hash.insert(sorted_p_bag[i], i);
-                    }
+        let mut tables = HashMap::new();
+        for p in ntd.stingy_ordering().into_iter().filter(|p| !completed.contains(p)){
+            process_node(&mut dp_data, p, feasible).expect(COUNT_OVERFLOW);
 
-                     */
+            // the table of p is consumed by its parent, hence it is copied
+            tables.insert(p, dp_data.table.get(&p).cloned().unwrap_or_default());
+        }
 
-                    for (i, item) in sorted_p_bag.iter().enumerate() {
-                        significance_hash.insert(*item, i);
-                    }
+        tables.extend(frontier);
+        tables
+    }
 
-                        // iterate over all new mappings by inserting (introduced_vertex,a)
-                    for f_q in 0..dp_data.max_bag_mappings(q){
-                        for a in 0..to_graph.node_count(){
+    /// Implementation of the algorithm of diaz et all which processes the two subtrees of join nodes in parallel,
+    /// each on its own shard of the table, as long as more than one of the given threads is available.
+    /// Without the "parallel" feature the subtrees are processed one after the other.
+    /// The result equals the result of the sequential algorithm.
+    pub fn diaz_serna_thilikos_parallel(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, threads : usize) -> u64{
+        let feasible = vec![vec![true; to_graph.node_count()]; from_graph.node_count()];
+        let stingy_ordering = ntd.stingy_ordering();
 
-                            // extend mapping by a at the new index
-                            let f_prime = dp_data.table_extend(f_q, new_index as Mapping, a as Mapping);
+        let mut dp_data = DPData::new(from_graph, to_graph, ntd);
+        process_subtree(&mut dp_data, ntd.root(), &stingy_ordering, &feasible, threads.max(1));
+        dp_data.value(&ntd.root(), &0)
+    }
 
-                            let condition = {
-                                let mut value = true;
+    /// Processes all nodes of the subtree rooted at p. If more than one thread is available, the path from p
+    /// down to the topmost join node is processed after both subtrees of the join node, which are processed
+    /// by parallel_execution::join on a shard and on the given table with the threads divided among them.
+    fn process_subtree(dp_data : &mut DPData, p : TreeNode, stingy_ordering : &[TreeNode], feasible : &[Vec<bool>], threads : usize){
+        let ntd = dp_data.nice_tree_decomposition();
 
-                                for u in &s_q{
-                                    let image_of_unique_vertex = to_graph.from_index(a);
+        // the path from p down to the topmost join node of the subtree, or down to a leaf
+        let mut path = vec![p];
+        while let Some(&q) = ntd.unique_child(*path.last().unwrap()) {
+            path.push(q);
+        }
+        let join = *path.last().unwrap();
 
-                                    // get the significance of vertex u in mapping f_prime
-                                    let significance = *significance_hash.get(u).unwrap();
+        if threads > 1 && ntd.node_type(join) == Some(&NodeType::Join) {
+            let children = ntd.children(join).unwrap();
+            let (q1, q2) = (children[0], children[1]);
 
-                                    let image_of_u = to_graph.from_index(dp_data.table_apply(f_prime, significance as Mapping) as usize);
+            let mut shard = dp_data.shard();
+            parallel_execution::join(
+                || process_subtree(&mut shard, q1, stingy_ordering, feasible, threads / 2),
+                || process_subtree(dp_data, q2, stingy_ordering, feasible, threads - threads / 2),
+            );
+            dp_data.merge(shard);
 
-                                    if !to_graph.has_edge(image_of_unique_vertex, image_of_u){
-                                        value = false;
-                                        break;
-                                    }
-                                }
+            for &q in path.iter().rev(){
+                process_node(dp_data, q, feasible).expect(COUNT_OVERFLOW);
+            }
+        } else {
+            // the stingy ordering restricted to the subtree of p
+            let subtree = subtree_nodes(ntd, [p]);
+            for &q in stingy_ordering.iter().filter(|q| subtree.contains(q)){
+                process_node(dp_data, q, feasible).expect(COUNT_OVERFLOW);
+            }
+        }
+    }
 
-                                value
-                            };
+    /// Returns the nodes of the subtrees of the given nodes, collected once such that filtering the stingy
+    /// ordering by them takes linear time.
+    fn subtree_nodes<I>(ntd : &NiceTreeDecomposition, roots : I) -> HashSet<TreeNode>
+        where I: IntoIterator<Item = TreeNode>
+    {
+        let mut stack : Vec<TreeNode> = roots.into_iter().collect();
+        let mut nodes : HashSet<TreeNode> = stack.iter().copied().collect();
+        while let Some(q) = stack.pop() {
+            for &child in ntd.children(q).into_iter().flatten(){
+                if nodes.insert(child) { stack.push(child); }
+            }
+        }
+        nodes
+    }
 
-                            dp_data.set(p, f_prime,*dp_data.get(&q, &f_q).unwrap() * (condition as u64 ));
-                        }
-                    }
+    /// Computes the table of node p of the dynamic program from the tables of its children, which are removed
+    /// from the table. Images which are not feasible are never used. Returns None if an entry does not fit into 64 bits.
+    pub(crate) fn process_node<T : MappingTable>(dp_data : &mut DPData<T>, p : TreeNode, feasible : &[Vec<bool>]) -> Option<()>{
+        let ntd = dp_data.nice_tree_decomposition();
+        let from_graph = dp_data.from_graph();
+        let to_graph = dp_data.to_graph();
+
+        // matching node types
+        match ntd.node_type(p) {
+            None => {}
+            Some(NodeType::Leaf) => {
+                // get the unique vertex of p´s bag
+                if let Some(&unique_vertex) = ntd.unique_vertex(p){
+                    let mut entries = dp_data.pool.pop().unwrap_or_default();
+                    leaf_entries(from_graph, to_graph, unique_vertex, &feasible[unique_vertex.index()], &mut entries);
+                    dp_data.store(p, entries);
+                }
+            }
+            Some(NodeType::Introduce) => {
+                // get the unique child of p
+                let q = *ntd.unique_child(p).unwrap();
+                // get the introduced vertex
+                let v = *ntd.unique_vertex(p).unwrap();
+
+                // the mappings of q are extended by the image of v
+                let indexer_q = BagIndexer::of_node(ntd, q, to_graph.node_count() as Mapping);
 
-                    dp_data.remove(q);
+                // the images of v are the feasible common neighbours of the images of the neighbours of v
+                let neighbourhoods = Arc::clone(&dp_data.neighbourhoods);
+                let mut images = AdjacentImages::new(&neighbourhoods, from_graph, to_graph, &indexer_q, v, &feasible[v.index()], dp_data.scalar_adjacency);
 
+                // only the non-zero entries of q can be extended to non-zero entries of p
+                let entries_q = dp_data.take(q);
+                let mut entries = dp_data.pool.pop().unwrap_or_default();
+                introduce_entries(&indexer_q, v, &entries_q, |f_q, buffer| images.fill(f_q, buffer), &mut entries)?;
+
+                dp_data.store(p, entries);
+                dp_data.recycle(entries_q);
+            }
+            Some(NodeType::Forget) => {
+                // get the unique child of p
+                let q = *ntd.unique_child(p).unwrap();
+                // get the introduced vertex
+                let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+
+                // the mappings of q are reduced by the image of the forgotten vertex
+                let indexer_q = BagIndexer::of_node(ntd, q, to_graph.node_count() as Mapping);
+
+                // Summing up all extending homomorphisms by adding each non-zero entry of q
+                // to the mapping without the image of the forgotten vertex
+                let entries_q = dp_data.take(q);
+                let mut entries = dp_data.pool.pop().unwrap_or_default();
+                forget_entries(&indexer_q, forgotten_vertex, &entries_q, &mut entries)?;
+
+                dp_data.store(p, entries);
+                dp_data.recycle(entries_q);
+            }
+            Some(NodeType::Join) => {
+                if let Some(children) = ntd.children(p){
+                    // Takes the entries of q1 and q2
+                    let entries_q1 = dp_data.take(children[0]);
+                    let entries_q2 = dp_data.take(children[1]);
+
+                    // Only mappings which are non-zero in both children are non-zero in p
+                    let mut entries = dp_data.pool.pop().unwrap_or_default();
+                    join_entries(&entries_q1, &entries_q2, &mut entries)?;
+
+                    dp_data.store(p, entries);
+                    dp_data.recycle(entries_q1);
+                    dp_data.recycle(entries_q2);
                 }
-                Some(NodeType::Forget) => {
-                    // get the unique child of p
-                    let q = *ntd.unique_child(p).unwrap();
-                    // get the introduced vertex
-                    let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+            }
+        }
+        Some(())
+    }
 
-                    // transforms the bag into a sorted vertex used for integer functions
-                    let sorted_bag_q = dp_data.sorted_bag(q).unwrap();
+    /// The message of the panic if an entry of the dynamic program does not fit into 64 bits.
+    const COUNT_OVERFLOW: &str = "The number of homomorphisms does not fit into 64 bits!";
 
-                    // find significance of forgotten vertex in the mappings of F_q
-                    let significance_forgotten_vertex = sorted_bag_q.iter().position(|x| *x == forgotten_vertex).unwrap();
+    /// The weights of a dynamic program in the partition function sense: an entry I[p,f] is the sum over all
+    /// mappings extending f of the products of the weights of the vertices and of the edges of the pattern.
+    /// The algorithm of diaz et all is the special case of the weights 0 and 1 given by the adjacency of a
+    /// target graph, other weights are e.g. edge multiplicities or the adjacency queries of an implicit target.
+    /// A weight of None denotes a weight which does not fit into 64 bits.
+    ///
+    /// A vertex is introduced below each child of a join node whose bag contains it, hence introduce nodes only
+    /// discard the images of weight zero. The weight of a vertex and of its edges to the vertices of the bag are
+    /// multiplied when it is forgotten, the remaining ones at the root, such that every weight is counted once.
+    pub trait Weights {
+        /// Returns the number of vertices of the target, i.e. the images are 0,..,n-1.
+        fn node_count(&self) -> usize;
 
-                    // Iterate over all mappings
-                    for f_prime in 0..dp_data.max_bag_mappings(p){
+        /// Returns the weight of mapping the vertex v of the pattern onto a, e.g. of the loops at v.
+        fn vertex_weight(&self, v : Vertex, a : Mapping) -> Option<u64>;
 
-                        // summing up all extending homomorphisms
-                        let mut sum = 0;
+        /// Returns the weight of mapping the edge uv of the pattern, where u and v are different, onto ab.
+        fn edge_weight(&self, u : Vertex, v : Vertex, a : Mapping, b : Mapping) -> Option<u64>;
+    }
 
-                        // iterate over all images of the forgotten node
-                        for a in 0..to_graph.node_count(){
-                            let f_old = dp_data.table_extend(f_prime, significance_forgotten_vertex as Mapping, a as Mapping);
-                            sum += dp_data.get(&q, &f_old).unwrap();
-                        }
+    /// Stores the entry one for each feasible image a of the vertex v of a leaf, where a loop at v has to be mapped
+    /// onto a loop.
+    pub(crate) fn leaf_entries<V : Semiring, T : MappingTable<V>>(from_graph : &Graph, to_graph : &Graph, v : Vertex, feasible : &[bool], entries : &mut T){
+        let has_loop = from_graph.has_edge(v, v);
+        for (a, &is_feasible) in feasible.iter().enumerate(){
+            if is_feasible && (!has_loop || to_graph.has_edge(to_graph.from_index(a), to_graph.from_index(a))) { entries.insert(a as Mapping, V::one()); }
+        }
+    }
 
-                        dp_data.set(p, f_prime, sum);
-                    }
+    /// The images of a vertex v introduced into the bag of its child q, i.e. the feasible common neighbours of
+    /// the images of its neighbours which are already mapped, where a loop at v has to be mapped onto a loop.
+    /// The common neighbourhoods are computed by intersecting the neighbourhoods of the target as bitsets.
+    pub(crate) struct AdjacentImages<'a> {
+        neighbourhoods: &'a NeighbourhoodSets,
+        to_graph: &'a Graph,
+        indexer_q: &'a BagIndexer,
+        neighbours: Vec<Mapping>, // significances of the neighbours of v in the bag of q
+        feasible_images: Vec<u64>,
+        candidates: Vec<u64>,
+        scalar_adjacency: bool,
+    }
+
+    impl<'a> AdjacentImages<'a> {
+        pub(crate) fn new(neighbourhoods : &'a NeighbourhoodSets, from_graph : &Graph, to_graph : &'a Graph, indexer_q : &'a BagIndexer,
+                          v : Vertex, feasible : &[bool], scalar_adjacency : bool) -> AdjacentImages<'a> {
+            let mut feasible_images = neighbourhoods.feasible_set(feasible);
+            if from_graph.has_edge(v, v) { neighbourhoods.intersect_loops(&mut feasible_images); }
+            let candidates = feasible_images.clone();
+            AdjacentImages { neighbourhoods, to_graph, indexer_q, neighbours: indexer_q.neighbour_positions(from_graph, v), feasible_images, candidates, scalar_adjacency }
+        }
+
+        /// Fills the buffer with the images of v extending the mapping f_q of the bag of q, each with weight one.
+        pub(crate) fn fill<V : Semiring>(&mut self, f_q : Mapping, buffer : &mut Vec<(Mapping, V)>) -> Option<()> {
+            let indexer_q = self.indexer_q;
+            let images = self.neighbours.iter().map(|&significance| indexer_q.image_at(f_q, significance) as usize);
+            self.neighbourhoods.common_neighbours(self.to_graph, &self.feasible_images, images, self.scalar_adjacency, &mut self.candidates);
+            buffer.extend(NeighbourhoodSets::iter(&self.candidates).map(|a| (a as Mapping, V::one())));
+            Some(())
+        }
+    }
+
+    /// Extends each entry of the child table by mapping the introduced vertex v onto the images a of f_q, which
+    /// images(f_q, buffer) pushes into the buffer together with the weight the entry of f_q is multiplied with.
+    /// Zero weights are skipped. Returns None if images does or if an entry cannot be represented, e.g. does not
+    /// fit into 64 bits.
+    pub(crate) fn introduce_entries<V, T, I>(indexer_q : &BagIndexer, v : Vertex, entries_q : &T, mut images : I, entries : &mut T) -> Option<()>
+        where V : Semiring, T : MappingTable<V>, I : FnMut(Mapping, &mut Vec<(Mapping, V)>) -> Option<()>
+    {
+        let mut buffer = vec![];
+        for (f_q, value) in entries_q.iter(){
+            buffer.clear();
+            images(f_q, &mut buffer)?;
+            for &(a, factor) in &buffer{
+                if !factor.is_zero() { entries.insert(indexer_q.extend(f_q, v, a), value.mul(factor)?); }
+            }
+        }
+        Some(())
+    }
+
+    /// Adds the value to the entry of f. Returns None if the sum cannot be represented.
+    fn add_entry<V : Semiring, T : MappingTable<V>>(entries : &mut T, f : Mapping, value : V) -> Option<()>{
+        let sum = match entries.get(f) {
+            Some(entry) => entry.add(value)?,
+            None => value,
+        };
+        entries.insert(f, sum);
+        Some(())
+    }
+
+    /// Adds each entry of the child table to the mapping reduced by the image of the forgotten vertex v.
+    /// Returns None if a sum cannot be represented, e.g. does not fit into 64 bits.
+    pub(crate) fn forget_entries<V : Semiring, T : MappingTable<V>>(indexer_q : &BagIndexer, v : Vertex, entries_q : &T, entries : &mut T) -> Option<()>{
+        for (f_q, value) in entries_q.iter(){
+            add_entry(entries, indexer_q.reduce(f_q, v), value)?;
+        }
+        Some(())
+    }
+
+    /// Adds the products of the entries of both child tables which are non-zero in both by iterating over the
+    /// smaller table and looking up the larger one. Returns None if an entry cannot be represented, e.g. does not
+    /// fit into 64 bits.
+    pub(crate) fn join_entries<V : Semiring, T : MappingTable<V>>(entries_q1 : &T, entries_q2 : &T, entries : &mut T) -> Option<()>{
+        let (smaller, larger) = if entries_q1.len() <= entries_q2.len() { (entries_q1, entries_q2) } else { (entries_q2, entries_q1) };
+
+        for (f, value_smaller) in smaller.iter(){
+            if let Some(value_larger) = larger.get(f){
+                add_entry(entries, f, value_smaller.mul(value_larger)?)?;
+            }
+        }
+        Some(())
+    }
 
-                    dp_data.remove(q);
+    /// Returns the weight of the image of the vertex v under the mapping f of the bag times the weights of the
+    /// images of the edges from v to the remaining vertices of the bag. Returns None if it does not fit into 64 bits.
+    fn forgotten_weight<W : Weights>(from_graph : &Graph, weights : &W, indexer : &BagIndexer, f : Mapping, v : Vertex, remaining : &[Vertex]) -> Option<u64>{
+        let a = indexer.image(f, v);
+        remaining.iter()
+            .filter(|&&u| from_graph.has_edge(v, u))
+            .try_fold(weights.vertex_weight(v, a)?, |product, &u| {
+                if product == 0 { return Some(0); }
+                product.checked_mul(weights.edge_weight(v, u, a, indexer.image(f, u))?)
+            })
+    }
+
+    /// Computes the table of node p of the dynamic program with the given weights from the tables of its
+    /// children, which are removed from the table. The target of the table is not accessed, only the weights.
+    /// Returns None if an entry does not fit into 64 bits.
+    pub fn process_weighted_node<T, W>(dp_data : &mut DPData<T>, p : TreeNode, weights : &W) -> Option<()>
+        where T : MappingTable, W : Weights
+    {
+        let ntd = dp_data.nice_tree_decomposition();
+        let n = weights.node_count() as Mapping;
+        let mut entries = dp_data.pool.pop().unwrap_or_default();
+
+        match ntd.node_type(p) {
+            None => {}
+            Some(NodeType::Leaf) => {
+                let v = *ntd.unique_vertex(p).unwrap();
+                for a in 0..n{
+                    if weights.vertex_weight(v, a)? != 0 { entries.insert(a, 1); }
                 }
-                Some(NodeType::Join) => {
-                    if let Some(children) = ntd.children(p){
-                        let q1 = children.get(0).unwrap();
-                        let q2 = children.get(1).unwrap();
-
-                        // Updates every new mapping
-                        for f in 0..dp_data.max_bag_mappings(p){
-                            dp_data.set(p,
-                                      f as Mapping,
-                                        dp_data.get(q1, &(f as Mapping)).unwrap() *
-                                            dp_data.get(q2, &(f as Mapping)).unwrap());
-                        }
+            }
+            Some(NodeType::Introduce) => {
+                let q = *ntd.unique_child(p).unwrap();
+                let v = *ntd.unique_vertex(p).unwrap();
+                let indexer_q = BagIndexer::of_node(ntd, q, n);
 
-                        // Deletes entries og q1 and q2
-                        dp_data.remove(*q1);
-                        dp_data.remove(*q2);
+                // the neighbours of v which are already mapped
+                let neighbours : Vec<(Vertex, Mapping)> = indexer_q.bag().iter()
+                    .filter(|&u| dp_data.from_graph().has_edge(v, u))
+                    .map(|u| (u, indexer_q.position(u).unwrap()))
+                    .collect();
+
+                let entries_q = dp_data.take(q);
+                introduce_entries(&indexer_q, v, &entries_q, |f_q, buffer| {
+                    for a in 0..n{
+                        if weights.vertex_weight(v, a)? == 0 { continue; }
+                        let mut is_feasible = true;
+                        for &(u, significance) in &neighbours{
+                            if weights.edge_weight(v, u, a, indexer_q.image_at(f_q, significance))? == 0 {
+                                is_feasible = false;
+                                break;
+                            }
+                        }
+                        if is_feasible { buffer.push((a, 1)); }
                     }
+                    Some(())
+                }, &mut entries)?;
+                dp_data.recycle(entries_q);
+            }
+            Some(NodeType::Forget) => {
+                let q = *ntd.unique_child(p).unwrap();
+                let v = *ntd.unique_vertex(p).unwrap();
+
+                let indexer_q = BagIndexer::of_node(ntd, q, n);
+                let remaining : Vec<Vertex> = indexer_q.bag().iter().filter(|&u| u != v).collect();
+                let entries_q = dp_data.take(q);
+                for (f_q, value) in entries_q.iter(){
+                    let weighted_value = value.checked_mul(forgotten_weight(dp_data.from_graph(), weights, &indexer_q, f_q, v, &remaining)?)?;
+                    let f = indexer_q.reduce(f_q, v);
+                    entries.insert(f, entries.get(f).unwrap_or(0).checked_add(weighted_value)?);
                 }
+                dp_data.recycle(entries_q);
             }
+            Some(NodeType::Join) => {
+                let children = ntd.children(p).unwrap();
+                let entries_q1 = dp_data.take(children[0]);
+                let entries_q2 = dp_data.take(children[1]);
 
+                join_entries(&entries_q1, &entries_q2, &mut entries)?;
+                dp_data.recycle(entries_q1);
+                dp_data.recycle(entries_q2);
+            }
         }
 
-        *dp_data.get(&ntd.root(), &0).unwrap()
+        dp_data.store(p, entries);
+        Some(())
+    }
+
+    /// Runs the dynamic program with the given weights on node tables of type T along the stingy ordering and
+    /// returns the sum of the entries of the root weighted by the vertices of its bag, i.e. the sum over all
+    /// mappings from "from_graph" into the vertices of the products of their weights. Only the edges of "from_graph"
+    /// are weighted, the table is built on an empty target since the weights are its only representation.
+    /// Returns None if an entry does not fit into 64 bits.
+    pub fn weighted_diaz_serna_thilikos<T, W>(from_graph : &Graph, ntd : &NiceTreeDecomposition, weights : &W) -> Option<u64>
+        where T : MappingTable, W : Weights
+    {
+        let target = Graph::default();
+        let mut dp_data : DPData<T> = DPData::with_backend(from_graph, &target, ntd);
+
+        for p in ntd.stingy_ordering(){
+            process_weighted_node(&mut dp_data, p, weights)?;
+
+            // every non-zero entry of the root extends a non-zero entry of each node
+            if dp_data.table_size(p) == 0 { return Some(0); }
+        }
+
+        // the vertices of the bag of the root are not forgotten, hence they are forgotten one after another
+        let root = ntd.root();
+        let indexer = BagIndexer::of_node(ntd, root, weights.node_count() as Mapping);
+        let root_bag : Vec<Vertex> = indexer.bag().iter().collect();
+        let mut sum : u64 = 0;
+        for (f, value) in dp_data.take(root).iter(){
+            let product = root_bag.iter().enumerate().try_fold(value, |product, (i, &v)| {
+                product.checked_mul(forgotten_weight(from_graph, weights, &indexer, f, v, &root_bag[i + 1..])?)
+            })?;
+            sum = sum.checked_add(product)?;
+        }
+        Some(sum)
+    }
+
+    /// The algorithm of diaz et all for nice tree decompositions without join nodes. The nodes form a path from
+    /// the leaf to the root, which is the stingy ordering, hence the table of each node is computed from the table
+    /// of its child only and both are streamed through two buffers instead of storing a table per node.
+    /// The reports of all processed nodes are appended to the given report.
+    fn path_diaz_serna_thilikos<T : MappingTable>(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, feasible : &[Vec<bool>], options : &DiazOptions, report : &mut Vec<NodeReport>) -> DiazResult{
+        debug_assert!(ntd.is_path_decomposition(), "The nice tree decomposition contains a join node!");
+
+        let n = to_graph.node_count() as Mapping;
+        let neighbourhoods = NeighbourhoodSets::new(to_graph);
+
+        // the table of the child of the current node and the table of the current node
+        let mut entries_q = T::default();
+        let mut entries = T::default();
+
+        for p in ntd.stingy_ordering(){
+            let start = Instant::now();
+            entries.clear();
+
+            let result = match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    leaf_entries(from_graph, to_graph, v, &feasible[v.index()], &mut entries);
+                    Some(())
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+                    let mut images = AdjacentImages::new(&neighbourhoods, from_graph, to_graph, &indexer_q, v, &feasible[v.index()], options.scalar_adjacency);
+                    introduce_entries(&indexer_q, v, &entries_q, |f_q, buffer| images.fill(f_q, buffer), &mut entries)
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+                    forget_entries(&BagIndexer::of_node(ntd, q, n), forgotten_vertex, &entries_q, &mut entries)
+                }
+                Some(NodeType::Join) => unreachable!("A path decomposition has no join nodes!"),
+                None => Some(()),
+            };
+            if result.is_none() { return DiazResult::Overflow(p); }
+
+            if let Some(node_type) = ntd.node_type(p){
+                report.push(NodeReport { node: p, node_type: node_type.clone(), table_size: entries.len(), time: start.elapsed() });
+            }
+
+            if entries.is_empty() && !options.keep_zero_tables { return DiazResult::ZeroAtNode(p); }
+            std::mem::swap(&mut entries_q, &mut entries);
+        }
+
+        DiazResult::Count(entries_q.get(0).unwrap_or(0))
+    }
+
+    /// The algorithm of diaz et all where loops of both graphs are treated according to the given loop semantics.
+    pub fn diaz_serna_thilikos_with_loop_semantics(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, loop_semantics : LoopSemantics) -> u64{
+        diaz_serna_thilikos_algorithm(&loop_semantics.apply_to_pattern(from_graph), ntd, &loop_semantics.apply_to_target(to_graph))
     }
 
     /// Implementation of diaz et all for all graphs in $H_\tau$
     /// Here the graph generation is already contained in the function.
     /// This method is mainly used for testing.
-    pub fn diaz_serna_thilikos_for_ntd_set(ntd : &NiceTreeDecomposition, to_graph : &MatrixGraph<(),(), Undirected>) -> Vec<(MatrixGraph<(), (), Undirected>, u64)>{
-        let mut result = vec![];
+    pub fn diaz_serna_thilikos_for_ntd_set(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<(Graph, u64)>{
+        diaz_serna_thilikos_for_ntd_set_containing(ntd, to_graph, &[])
+    }
 
-        let possible_edges = generate_possible_edges(ntd);
+    /// Implementation of diaz et all for all graphs in $H_\tau$ containing the given required edges.
+    /// This corresponds to counting all supergraphs of the required edges within the edge universe of the ntd.
+    pub fn diaz_serna_thilikos_for_ntd_set_containing(ntd : &NiceTreeDecomposition, to_graph : &Graph, required_edges : &[(usize, usize)]) -> Vec<(Graph, u64)>{
+        let mut result = vec![];
 
-        let graphs = generate_graphs(ntd.vertex_count() as u64,
-                                     possible_edges.get(&ntd.root()).unwrap().clone() );
+        let graphs = generate_graphs_containing(ntd.vertex_count() as u64,
+                                                ntd.all_possible_edges().clone(),
+                                                required_edges);
         for graph in graphs{
             let hom_number = diaz_serna_thilikos_algorithm(&graph, ntd, to_graph);
             result.push(( graph, hom_number));
@@ -0,0 +1,183 @@
+/// A module containing conversions between vertex elimination orderings and tree decompositions.
+/// Eliminating a vertex connects all of its not yet eliminated neighbours, the graph together with
+/// these fill-in edges is a chordalization of the graph and the ordering is a perfect elimination
+/// ordering of it. Every vertex together with its later eliminated neighbours forms a bag.
+pub mod elimination_ordering_methods {
+    use std::collections::{HashMap, HashSet};
+    use petgraph::visit::NodeIndexable;
+    use crate::tree_decompositions::nice_tree_decomposition::Bag;
+    use crate::tree_decompositions::tree_decomposition::TreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, TreeStructure, Vertex};
+
+    /// Returns the neighbourhoods of all vertices without loops.
+    fn neighbourhoods(graph : &Graph) -> Vec<HashSet<usize>>{
+        let n = graph.node_count();
+        (0..n).map(|u| (0..n).filter(|&v| u != v && graph.has_edge(graph.from_index(u), graph.from_index(v))).collect())
+            .collect()
+    }
+
+    /// Returns the position of each vertex in the ordering, panics if the ordering is not a permutation of the vertices.
+    fn positions(ordering : &[usize], n : usize) -> Vec<usize>{
+        let mut position = vec![usize::MAX; n];
+        for (i, &v) in ordering.iter().enumerate(){
+            assert!(v < n && position[v] == usize::MAX, "The ordering is not a permutation of the vertices!");
+            position[v] = i;
+        }
+        assert_eq!(ordering.len(), n, "The ordering is not a permutation of the vertices!");
+        position
+    }
+
+    /// Eliminates the vertices in the given order and returns for each vertex its neighbours
+    /// which are eliminated later, including the neighbours gained by fill-in edges.
+    fn higher_neighbourhoods(graph : &Graph, ordering : &[usize]) -> Vec<HashSet<usize>>{
+        let position = positions(ordering, graph.node_count());
+        let mut neighbours = neighbourhoods(graph);
+        let mut higher = vec![HashSet::new(); graph.node_count()];
+
+        for &v in ordering{
+            higher[v] = neighbours[v].iter().copied().filter(|&u| position[u] > position[v]).collect();
+
+            // the remaining neighbours of v form a clique
+            for &u in &higher[v]{
+                for &w in &higher[v]{
+                    if u != w { neighbours[u].insert(w); }
+                }
+            }
+        }
+
+        higher
+    }
+
+    /// Returns the width of the tree decomposition induced by the elimination ordering,
+    /// i.e. the maximal number of later eliminated neighbours of a vertex.
+    pub fn ordering_width(graph : &Graph, ordering : &[usize]) -> u32{
+        higher_neighbourhoods(graph, ordering).iter().map(|higher| higher.len() as u32).max().unwrap_or(0)
+    }
+
+    /// Returns the chordalization of the graph given by the elimination ordering, i.e. the graph together
+    /// with all fill-in edges. Loops of the graph are kept.
+    pub fn chordalization(graph : &Graph, ordering : &[usize]) -> Graph{
+        let mut chordal_graph = graph.clone();
+        for (v, higher) in higher_neighbourhoods(graph, ordering).iter().enumerate(){
+            for &u in higher{
+                if !chordal_graph.has_edge(Vertex::new(u), Vertex::new(v)) { chordal_graph.add_edge(Vertex::new(u), Vertex::new(v), ()); }
+            }
+        }
+        chordal_graph
+    }
+
+    /// Turns the elimination ordering into a tree decomposition. The node i has the bag containing the i-th
+    /// vertex of the ordering together with its later eliminated neighbours and its parent is the node of the
+    /// first eliminated of these neighbours. Nodes without such neighbours are attached to the node of the last
+    /// vertex, such that the decomposition is connected for disconnected graphs as well.
+    pub fn tree_decomposition_from_ordering(graph : &Graph, ordering : &[usize]) -> TreeDecomposition{
+        let n = graph.node_count();
+        assert!(n > 0, "The graph has to contain at least one vertex!");
+
+        let position = positions(ordering, n);
+        let higher = higher_neighbourhoods(graph, ordering);
+
+        let mut tree_structure = TreeStructure::new(n as TreeNode);
+        let mut bags : HashMap<TreeNode, Bag> = HashMap::new();
+
+        for (i, &v) in ordering.iter().enumerate(){
+            let mut bag : Bag = higher[v].iter().map(|&u| Vertex::new(u)).collect();
+            bag.insert(Vertex::new(v));
+            bags.insert(i as TreeNode, bag);
+
+            if i + 1 < n {
+                let parent = higher[v].iter().map(|&u| position[u]).min().unwrap_or(n - 1);
+                tree_structure.add_child(parent as TreeNode, i as TreeNode);
+            }
+        }
+
+        TreeDecomposition::new(tree_structure, bags, n as u32)
+    }
+
+    /// Returns the elimination ordering of the greedy min degree heuristic, which always eliminates a vertex
+    /// of minimal degree in the graph with the fill-in edges of the already eliminated vertices.
+    /// Ties are broken by the smallest vertex.
+    pub fn min_degree_ordering(graph : &Graph) -> Vec<usize>{
+        let n = graph.node_count();
+        let mut neighbours = neighbourhoods(graph);
+        let mut eliminated = vec![false; n];
+        let mut ordering = Vec::with_capacity(n);
+
+        for _ in 0..n{
+            let v = (0..n).filter(|&v| !eliminated[v]).min_by_key(|&v| (neighbours[v].len(), v)).unwrap();
+            eliminated[v] = true;
+            ordering.push(v);
+
+            // connect the remaining neighbours of v and remove v from the graph
+            let remaining : Vec<usize> = neighbours[v].iter().copied().collect();
+            for &u in &remaining{
+                neighbours[u].remove(&v);
+                for &w in &remaining{
+                    if u != w { neighbours[u].insert(w); }
+                }
+            }
+        }
+
+        ordering
+    }
+
+    /// Returns an elimination ordering whose induced tree decomposition is not wider than the given one.
+    /// The vertices are ordered by decreasing depth of the topmost node containing them, such that all
+    /// later eliminated neighbours of a vertex are contained in the bag of this node.
+    pub fn ordering_from_tree_decomposition(td : &TreeDecomposition) -> Vec<usize>{
+        let depth = |mut p : TreeNode| {
+            let mut depth = 0;
+            while let Some(&parent) = td.parent(p) { p = parent; depth += 1; }
+            depth
+        };
+
+        // the depth of the topmost node containing each vertex
+        let mut topmost : Vec<Option<usize>> = vec![None; td.vertex_count() as usize];
+        for p in 0..td.node_count(){
+            let depth_of_p = depth(p);
+            for v in td.bag(p).unwrap(){
+                let entry = &mut topmost[v.index()];
+                *entry = Some(entry.map_or(depth_of_p, |d| d.min(depth_of_p)));
+            }
+        }
+
+        let mut ordering : Vec<usize> = (0..td.vertex_count() as usize).collect();
+        ordering.sort_by_key(|&v| (std::cmp::Reverse(topmost[v].unwrap_or(usize::MAX)), v));
+        ordering
+    }
+
+    /// Computes a perfect elimination ordering of a chordal graph by reversing a maximum cardinality search.
+    /// Returns None if the graph is not chordal, e.g. if it is not a chordalization.
+    pub fn perfect_elimination_ordering(graph : &Graph) -> Option<Vec<usize>>{
+        let n = graph.node_count();
+        let neighbours = neighbourhoods(graph);
+
+        // maximum cardinality search: always visit the vertex with the most visited neighbours
+        let mut weight = vec![0; n];
+        let mut visited = vec![false; n];
+        let mut search_order = Vec::with_capacity(n);
+        for _ in 0..n{
+            let v = (0..n).filter(|&v| !visited[v]).max_by_key(|&v| (weight[v], std::cmp::Reverse(v))).unwrap();
+            visited[v] = true;
+            search_order.push(v);
+            for &u in &neighbours[v]{
+                weight[u] += 1;
+            }
+        }
+
+        let ordering : Vec<usize> = search_order.into_iter().rev().collect();
+        if is_perfect_elimination_ordering(graph, &ordering) { Some(ordering) } else { None }
+    }
+
+    /// Checks whether the ordering is a perfect elimination ordering, i.e. whether eliminating the vertices
+    /// in this order does not add any fill-in edges.
+    pub fn is_perfect_elimination_ordering(graph : &Graph, ordering : &[usize]) -> bool{
+        let position = positions(ordering, graph.node_count());
+        let neighbours = neighbourhoods(graph);
+
+        ordering.iter().all(|&v| {
+            let higher : Vec<usize> = neighbours[v].iter().copied().filter(|&u| position[u] > position[v]).collect();
+            higher.iter().all(|&u| higher.iter().all(|&w| u == w || neighbours[u].contains(&w)))
+        })
+    }
+}
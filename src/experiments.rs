@@ -1,26 +1,82 @@
 
 pub mod single_running_time_measurement {
-    use std::fs;
-    use std::fs::{OpenOptions, ReadDir};
+    use std::{env, fs};
+    use std::collections::HashMap;
+    use std::fs::{File, OpenOptions, ReadDir};
+    use std::io;
+    use std::io::ErrorKind;
+    use std::io::Write;
     use std::ops::Add;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::slice;
+    #[cfg(feature = "parallel")]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "parallel")]
+    use std::sync::mpsc;
+    use std::thread;
     use std::time::{Duration, Instant};
+    use chrono::Local;
     use csv;
     use itertools::Itertools;
-    use petgraph::matrix_graph::MatrixGraph;
-    use petgraph::Undirected;
+    use petgraph::visit::IntoNodeIdentifiers;
     use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force_for_ntd_set;
     use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_for_ntd_set;
+    use crate::cost_estimation::cost_model::{CostEstimate, CostEstimator};
+    use crate::graph_generation::graph_generation_algorithms::number_of_components;
     use crate::modified_dp::algorithm::modified_dp;
+    use crate::modified_dp::algorithm::HomNumberList;
+    use crate::hom_counts::hom_count_results::HomCounts;
+    use crate::inspect::instance_statistics::NtdStats;
     use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::{escape_json, read_lines};
     use crate::file_handler::tree_decomposition_handler::import_ntd;
-    use crate::graph_generation::graph_generation_algorithms::generate_possible_edges;
     use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
 
     const RESULT_PATH: &str = "./target/experiment_results/";
     const NTD_PATH: &str = "data/Experiments/ntds/";
     const GRAPH_PATH: &str = "data/Experiments/graphs/";
 
+    /// The directories containing the inputs of the experiments and the directory the results are written to.
+    #[derive(Debug, Clone)]
+    pub struct ExperimentPaths {
+        pub ntd_directory: PathBuf,
+        pub graph_directory: PathBuf,
+        pub result_directory: PathBuf,
+    }
+
+    impl Default for ExperimentPaths {
+        fn default() -> ExperimentPaths {
+            ExperimentPaths {
+                ntd_directory: PathBuf::from(NTD_PATH),
+                graph_directory: PathBuf::from(GRAPH_PATH),
+                result_directory: PathBuf::from(RESULT_PATH),
+            }
+        }
+    }
+
+    impl ExperimentPaths {
+
+        /// Returns the default input directories together with the given result directory.
+        pub fn with_result_directory<P>(result_directory : P) -> ExperimentPaths
+            where P: AsRef<Path>
+        {
+            ExperimentPaths { result_directory: result_directory.as_ref().to_path_buf(), ..ExperimentPaths::default() }
+        }
+
+        /// Creates the result directory and all of its parents if they do not exist yet.
+        pub fn create_result_directory(&self) -> io::Result<()> {
+            fs::create_dir_all(&self.result_directory)
+                .map_err(|error| path_error(&self.result_directory, "could not create result directory", error))
+        }
+    }
+
+    /// Adds the path and a description of the failed operation to the given error.
+    fn path_error(path : &Path, description : &str, error : io::Error) -> io::Error {
+        io::Error::new(error.kind(), format!("{} {}: {}", description, path.display(), error))
+    }
+
     /// lists necessary information of the tree decomposition and write them into a csv file
     pub fn list_ntd_data() {
 
@@ -46,113 +102,785 @@ pub mod single_running_time_measurement {
 
             let ntd = import_ntd(ntd_path.as_ref().unwrap().path()).unwrap();
 
-            let width = ntd.width();
-            let v_t = ntd.node_count();
-            let e_tau = generate_possible_edges(&ntd).get(&ntd.root()).unwrap().len();
-            let v_tau = ntd.vertex_count();
+            let stats = NtdStats::compute(&ntd);
 
             wtr.write_record(&["DATA",
                 &ntd_name.to_str().unwrap(),
-                &width.to_string(),
-                &v_t.to_string(),
-                &e_tau.to_string(),
-                &v_tau.to_string()]);
+                &stats.width.to_string(),
+                &stats.nodes.to_string(),
+                &stats.possible_edges.to_string(),
+                &stats.vertices.to_string()]);
         }
     }
 
-    /// This methods executes the experiment given by matrix_path with the algorithm alg and the name alg_name
-    pub fn measure_running_time(matrix_file : &Path, alg : fn(&NiceTreeDecomposition, &MatrixGraph<(), (), Undirected>) -> Vec<(MatrixGraph<(), (), Undirected>, u64)>, alg_name : &String){
+    /// The measurements of a single algorithm on a single pair of nice tree decomposition and target graph.
+    pub struct ExperimentRecord {
+        /// the name of the measured algorithm
+        pub algorithm: String,
+        /// the name of the experiment matrix
+        pub experiment: String,
+        pub ntd_name: String,
+        pub width: u32,
+        /// number of nodes of the nice tree decomposition
+        pub v_t: u64,
+        /// number of possible edges of the nice tree decomposition
+        pub e_tau: usize,
+        /// number of vertices of the nice tree decomposition
+        pub v_tau: u32,
+        pub graph_name: String,
+        pub v_g: usize,
+        pub e_g: usize,
+        /// the running time of each run
+        pub durations: Vec<Duration>,
+        /// the homomorphism numbers computed by the last run
+        pub counts: HomCounts,
+    }
 
-        let test_name = matrix_file.file_stem().unwrap().to_str().unwrap();
+    impl ExperimentRecord {
 
-        // Setting output path
-        let filepath = format!("{}{}_{}_results.csv", RESULT_PATH, alg_name, test_name);
-        let filepath = Path::new(&filepath);
+        /// Returns the average running time over all runs, or 0 if there is no run.
+        pub fn average(&self) -> Duration {
+            if self.durations.is_empty() { return Duration::ZERO; }
+            let sum: Duration = self.durations.iter().sum();
+            sum.div_f32(self.durations.len() as f32)
+        }
 
-        // Reading experiment matrix
-        let mut reader = csv::Reader::from_path(matrix_file).unwrap();
-        let headers = reader.headers().unwrap().clone();
+        /// Returns the fastest running time over all runs.
+        pub fn minimum(&self) -> Duration { self.durations.iter().min().copied().unwrap_or_default() }
+
+        /// Returns the slowest running time over all runs.
+        pub fn maximum(&self) -> Duration { self.durations.iter().max().copied().unwrap_or_default() }
+    }
+
+    /// Features of a target graph used in the experiments.
+    #[derive(Debug, PartialEq)]
+    pub struct GraphData {
+        pub v_g: usize,
+        pub e_g: usize,
+        /// the number of edges divided by the number of vertex pairs
+        pub density: f64,
+        pub max_degree: usize,
+        pub components: usize,
+    }
+
+    /// Computes the features of the given graph.
+    pub fn graph_data(graph : &Graph) -> GraphData {
+        let v_g = graph.node_count();
+        let e_g = graph.edge_count();
+        let pairs = v_g * v_g.saturating_sub(1) / 2;
+
+        GraphData {
+            v_g,
+            e_g,
+            density: if pairs == 0 { 0.0 } else { e_g as f64 / pairs as f64 },
+            max_degree: graph.node_identifiers().map(|v| graph.neighbors(v).count()).max().unwrap_or(0),
+            components: number_of_components(graph),
+        }
+    }
+
+    /// lists necessary information of the graphs of the experiments and write them into the file graph_data.csv
+    /// of the result directory
+    pub fn list_graph_data(paths : &ExperimentPaths) -> io::Result<()> {
+
+        paths.create_result_directory()?;
+        let filepath = paths.result_directory.join("graph_data.csv");
+
+        let graph_paths = fs::read_dir(&paths.graph_directory)
+            .map_err(|error| path_error(&paths.graph_directory, "could not read graph directory", error))?;
+
+        for graph_path in graph_paths {
+            let graph_path = graph_path?.path();
+            let graph_name = graph_path.file_name().unwrap().to_string_lossy().to_string();
+
+            println!("file: {:?}", graph_name);
+
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&filepath)
+                .map_err(|error| path_error(&filepath, "could not open", error))?;
+
+            let mut wtr = csv::Writer::from_writer(file);
+
+            let graph = import_metis(&graph_path)
+                .ok_or_else(|| invalid_data(format!("could not import graph {}", graph_path.display())))?;
+            let data = graph_data(&graph);
+
+            wtr.write_record(["DATA",
+                &graph_name,
+                &data.v_g.to_string(),
+                &data.e_g.to_string(),
+                &data.density.to_string(),
+                &data.max_degree.to_string(),
+                &data.components.to_string()])?;
+        }
+
+        Ok(())
+    }
+
+    /// Information about the machine and the build which produced the measurements.
+    pub struct EnvironmentInfo {
+        pub hostname: String,
+        pub cpu_model: String,
+        pub cores: usize,
+        pub rustc_version: String,
+        pub crate_version: String,
+        pub commit: String,
+        pub timestamp: String,
+    }
+
+    impl EnvironmentInfo {
+
+        /// Collects the information about the current machine. Values which can not be determined are "unknown".
+        pub fn collect() -> EnvironmentInfo {
+            let hostname = fs::read_to_string("/etc/hostname").ok()
+                .map(|hostname| hostname.trim().to_string())
+                .or_else(|| env::var("HOSTNAME").ok())
+                .or_else(|| command_output("hostname", &[]))
+                .unwrap_or_else(|| UNKNOWN.to_string());
+
+            let cpu_model = fs::read_to_string("/proc/cpuinfo").ok()
+                .and_then(|cpuinfo| cpuinfo.lines()
+                    .find(|line| line.starts_with("model name"))
+                    .and_then(|line| line.split(':').nth(1))
+                    .map(|model| model.trim().to_string()))
+                .unwrap_or_else(|| UNKNOWN.to_string());
+
+            EnvironmentInfo {
+                hostname,
+                cpu_model,
+                cores: thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1),
+                rustc_version: command_output("rustc", &["--version"]).unwrap_or_else(|| UNKNOWN.to_string()),
+                crate_version: env!("CARGO_PKG_VERSION").to_string(),
+                commit: command_output("git", &["rev-parse", "HEAD"]).unwrap_or_else(|| UNKNOWN.to_string()),
+                timestamp: Local::now().to_rfc3339(),
+            }
+        }
+
+        /// Writes the information as a json object into the given file.
+        pub fn write_json<P>(&self, path : P) -> io::Result<()>
+            where P: AsRef<Path>
+        {
+            let mut file = File::create(path)?;
+            writeln!(file, "{{")?;
+            writeln!(file, "  \"hostname\": \"{}\",", escape_json(&self.hostname))?;
+            writeln!(file, "  \"cpu_model\": \"{}\",", escape_json(&self.cpu_model))?;
+            writeln!(file, "  \"cores\": {},", self.cores)?;
+            writeln!(file, "  \"rustc_version\": \"{}\",", escape_json(&self.rustc_version))?;
+            writeln!(file, "  \"crate_version\": \"{}\",", escape_json(&self.crate_version))?;
+            writeln!(file, "  \"commit\": \"{}\",", escape_json(&self.commit))?;
+            writeln!(file, "  \"timestamp\": \"{}\"", escape_json(&self.timestamp))?;
+            writeln!(file, "}}")
+        }
+
+        /// Writes the information into the sidecar file of the given results file, which is the results
+        /// file with the extension ".env.json" instead of its own extension.
+        pub fn write_sidecar<P>(&self, results_path : P) -> io::Result<()>
+            where P: AsRef<Path>
+        {
+            self.write_json(results_path.as_ref().with_extension("env.json"))
+        }
+    }
+
+    const UNKNOWN: &str = "unknown";
+
+    /// Runs the given command and returns its trimmed standard output, if it succeeds.
+    fn command_output(program : &str, args : &[&str]) -> Option<String>{
+        let output = Command::new(program).args(args).output().ok()?;
+        if !output.status.success() { return None; }
+        String::from_utf8(output.stdout).ok().map(|output| output.trim().to_string())
+    }
+
+    /// An algorithm which counts the homomorphisms from all graphs of $H_\tau$ into a target graph.
+    pub type NtdSetAlgorithm = fn(&NiceTreeDecomposition, &Graph) -> HomNumberList;
+
+    /// The number of runs of each measurement.
+    const NUMBER_OF_RUNS: usize = 5;
+
+    /// This methods executes the experiment given by matrix_path with the algorithm alg and the name alg_name
+    /// and returns one record for each measured pair of nice tree decomposition and graph. The inputs are read
+    /// from the directories given by paths.
+    pub fn measure_running_time(matrix_file : &Path, alg : NtdSetAlgorithm, alg_name : &String, paths : &ExperimentPaths) -> io::Result<Vec<ExperimentRecord>>{
+
+        let test_name = experiment_name(matrix_file);
 
         println!("###### Running time experiment for {} ####", alg_name);
 
+        experiment_cells(matrix_file)?.iter()
+            .map(|(ntd_name, graph_name)| measure_cell(&test_name, ntd_name, graph_name, alg, alg_name, paths))
+            .collect()
+    }
+
+    /// Executes the experiment given by matrix_file like measure_running_time, but measures the cells of the
+    /// experiment matrix in the given number of worker threads. Each finished record is passed to on_record
+    /// by the calling thread, hence writes of on_record are serialized. The returned records are in the
+    /// order of the experiment matrix.
+    /// note: measurements running in parallel compete for memory bandwidth and caches
+    /// If a cell fails, the remaining cells are still measured and the first error is returned.
+    /// Without the "parallel" feature the cells are measured one after the other in the calling thread.
+    pub fn measure_running_time_parallel<F>(matrix_file : &Path, alg : NtdSetAlgorithm, alg_name : &String, paths : &ExperimentPaths, threads : usize, mut on_record : F) -> io::Result<Vec<ExperimentRecord>>
+        where F: FnMut(&ExperimentRecord)
+    {
+        let test_name = experiment_name(matrix_file);
+        let test_name = test_name.as_str();
+        let cells = experiment_cells(matrix_file)?;
+
+        println!("###### Running time experiment for {} with {} threads ####", alg_name, threads);
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            let _ = threads;
+            let mut records = vec![];
+            let mut first_error = None;
+            for (ntd_name, graph_name) in &cells{
+                match measure_cell(test_name, ntd_name, graph_name, alg, alg_name, paths) {
+                    Ok(record) => { on_record(&record); records.push(record); }
+                    Err(error) => { first_error.get_or_insert(error); }
+                }
+            }
+            first_error.map_or(Ok(records), Err)
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            // the index of the next cell which is not yet measured
+            let next_cell = AtomicUsize::new(0);
+            let (sender, receiver) = mpsc::channel();
+
+            let mut results : Vec<(usize, io::Result<ExperimentRecord>)> = thread::scope(|scope| {
+                for _ in 0..threads.max(1) {
+                    let sender = sender.clone();
+                    let cells = &cells;
+                    let next_cell = &next_cell;
+
+                    scope.spawn(move || {
+                        loop {
+                            let i = next_cell.fetch_add(1, Ordering::SeqCst);
+                            if i >= cells.len() { break; }
+
+                            let (ntd_name, graph_name) = &cells[i];
+                            sender.send((i, measure_cell(test_name, ntd_name, graph_name, alg, alg_name, paths))).unwrap();
+                        }
+                    });
+                }
+                drop(sender);
+
+                receiver.iter()
+                    .inspect(|(_, result)| if let Ok(record) = result { on_record(record) })
+                    .collect()
+            });
+
+            results.sort_by_key(|(i, _)| *i);
+            results.into_iter().map(|(_, result)| result).collect()
+        }
+    }
+
+    /// Returns the name of the experiment, which is the file name of the experiment matrix without extension.
+    fn experiment_name(matrix_file : &Path) -> String {
+        matrix_file.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    /// Creates an error for malformed input files.
+    fn invalid_data(message : String) -> io::Error { io::Error::new(ErrorKind::InvalidData, message) }
+
+    /// Returns all pairs of ntd name and graph name of the experiment matrix which should be measured.
+    fn experiment_cells(matrix_file : &Path) -> io::Result<Vec<(String, String)>>{
+        let matrix_error = |error : csv::Error| path_error(matrix_file, "could not read experiment matrix", error.into());
+
+        // Reading experiment matrix
+        let mut reader = csv::Reader::from_path(matrix_file).map_err(matrix_error)?;
+        let headers = reader.headers().map_err(matrix_error)?.clone();
+
+        let mut cells = vec![];
 
         // iterates over all ntd
         for record in reader.records() {
-            let record = record.unwrap();
+            let record = record.map_err(matrix_error)?;
             let ntd_name = &record[0];
 
-            let single_ntd_path = format!("{}{}", NTD_PATH, ntd_name);
-            let single_ntd_path = Path::new(&single_ntd_path);
-
             // iterate over all graphs
             for (u, v) in record.iter().enumerate() {
-                // u = 0 is just the ntd_name or the graph should not been measured
-                if u == 0 || v.parse::<u32>().unwrap() == 0 { continue; }
+                // u = 0 is just the ntd_name
+                if u == 0 { continue; }
 
-                let graph_name = &headers[u];
-                let single_graph_path = format!("{}{}", GRAPH_PATH, graph_name);
-                let single_graph_path = Path::new(&single_graph_path);
+                let measure = v.trim().parse::<u32>()
+                    .map_err(|_| invalid_data(format!("{}: entry {:?} of ntd {} is not a number", matrix_file.display(), v, ntd_name)))?;
 
-                let ntd = import_ntd(single_ntd_path).unwrap();
-                let graph = import_metis(single_graph_path).unwrap();
+                // the graph should not been measured
+                if measure == 0 { continue; }
 
-                // Open the writer for the csv output
-                let mut file = OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .append(true)
-                    .open(filepath)
-                    .unwrap();
+                let graph_name = headers.get(u)
+                    .ok_or_else(|| invalid_data(format!("{}: row of ntd {} has more entries than the header", matrix_file.display(), ntd_name)))?;
+                cells.push((ntd_name.to_string(), graph_name.to_string()));
+            }
+        }
 
-                let mut wtr = csv::Writer::from_writer(file);
+        Ok(cells)
+    }
 
-                let width = ntd.width();
-                let v_t = ntd.node_count();
-                let e_tau = generate_possible_edges(&ntd).get(&ntd.root()).unwrap().len();
-                let v_tau = ntd.vertex_count();
+    /// Measures the algorithm alg on a single cell of the experiment matrix.
+    fn measure_cell(test_name : &str, ntd_name : &str, graph_name : &str, alg : NtdSetAlgorithm, alg_name : &str, paths : &ExperimentPaths) -> io::Result<ExperimentRecord>{
+        let (ntd, graph) = import_cell(ntd_name, graph_name, paths)?;
 
-                let v_g = graph.node_count();
-                let e_g = graph.edge_count();
+        //Equivalence class algorithm
+        let mut measurements = vec![];
+        let mut counts = vec![];
+        println!("Running experiment for ntd {:?} and graph {:?}", ntd_name, graph_name);
 
-                //Equivalence class algorithm
-                let mut measurements = vec![];
-                println!("Running experiment for ntd {:?} and graph {:?}", ntd_name, graph_name);
+        for i in 0..NUMBER_OF_RUNS {
+            println!("running test number {}", i + 1);
+            let start = Instant::now();
 
-                for i in 0..5 {
-                    println!("running test number {}", i + 1);
-                    let start = Instant::now();
+            counts = alg(&ntd, &graph);
 
-                    alg(&ntd, &graph);
+            let duration = start.elapsed();
+            println!("time needed: {:?}", duration);
+            measurements.push(duration);
+        }
 
-                    let duration = start.elapsed();
-                    println!("time needed: {:?}", duration);
-                    measurements.push(duration);
-                }
+        let stats = NtdStats::compute(&ntd);
+        let experiment_record = ExperimentRecord {
+            algorithm: alg_name.to_string(),
+            experiment: test_name.to_string(),
+            ntd_name: ntd_name.to_string(),
+            width: stats.width,
+            v_t: stats.nodes,
+            e_tau: stats.possible_edges,
+            v_tau: stats.vertices,
+            graph_name: graph_name.to_string(),
+            v_g: graph.node_count(),
+            e_g: graph.edge_count(),
+            durations: measurements,
+            counts: HomCounts::from(counts),
+        };
+        println!("average running time is {:?}", experiment_record.average());
+
+        Ok(experiment_record)
+    }
 
-                let sum: Duration = measurements.iter().sum();
-                let avg_measurements = sum.div_f32(measurements.len() as f32);
-                println!("average running time is {:?}", avg_measurements);
-
-                wtr.write_record(&[
-                    &alg_name,
-                    &ntd_name.to_string(),
-                    &width.to_string(),
-                    &v_t.to_string(),
-                    &e_tau.to_string(),
-                    &v_tau.to_string(),
-                    &graph_name.to_string(),
-                    &v_g.to_string(),
-                    &e_g.to_string(),
-                    &measurements[0].as_micros().to_string(),
-                    &measurements[1].as_micros().to_string(),
-                    &measurements[2].as_micros().to_string(),
-                    &measurements[3].as_micros().to_string(),
-                    &measurements[4].as_micros().to_string(),
-                    &avg_measurements.as_micros().to_string(),
-                ]
-                );
+    /// Imports the nice tree decomposition and the graph of a single cell of the experiment matrix.
+    fn import_cell(ntd_name : &str, graph_name : &str, paths : &ExperimentPaths) -> io::Result<(NiceTreeDecomposition, Graph)>{
+        let single_ntd_path = paths.ntd_directory.join(ntd_name);
+        let single_graph_path = paths.graph_directory.join(graph_name);
+
+        let ntd = import_ntd(&single_ntd_path)
+            .ok_or_else(|| invalid_data(format!("could not import nice tree decomposition {}", single_ntd_path.display())))?;
+        let graph = import_metis(&single_graph_path)
+            .ok_or_else(|| invalid_data(format!("could not import graph {}", single_graph_path.display())))?;
+
+        Ok((ntd, graph))
+    }
+
+    /// The result of screening a single cell of an experiment matrix.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Feasibility {
+        /// both estimates are far below the limits
+        Feasible,
+        /// one of the estimates is within an order of magnitude of its limit
+        Risky,
+        /// one of the estimates exceeds its limit
+        Infeasible,
+    }
+
+    /// The resources available for a single run.
+    pub struct ScreeningLimits {
+        pub memory_bytes: f64,
+        pub operations: f64,
+    }
+
+    impl Default for ScreeningLimits {
+        /// 8 GiB of memory and 10^11 computed table entries, which takes hours on a single core.
+        fn default() -> ScreeningLimits { ScreeningLimits { memory_bytes: 8.0 * 1024.0 * 1024.0 * 1024.0, operations: 1e11 } }
+    }
+
+    impl ScreeningLimits {
+        /// Classifies the given estimate. Estimates above a tenth of a limit are risky.
+        pub fn classify(&self, estimate : &CostEstimate) -> Feasibility {
+            let memory_ratio = estimate.memory_bytes() / self.memory_bytes;
+            let operations_ratio = estimate.operations / self.operations;
+            let ratio = memory_ratio.max(operations_ratio);
+
+            if ratio > 1.0 { Feasibility::Infeasible } else if ratio > 0.1 { Feasibility::Risky } else { Feasibility::Feasible }
+        }
+    }
+
+    /// The screening result of a single cell of an experiment matrix.
+    pub struct ScreeningRecord {
+        pub ntd_name: String,
+        pub graph_name: String,
+        pub estimate: CostEstimate,
+        pub feasibility: Feasibility,
+    }
+
+    /// Evaluates the estimator on every cell of the experiment matrix given by matrix_file without running
+    /// the algorithm. Only the header of each graph file is read to determine its number of vertices.
+    pub fn screen_experiment(matrix_file : &Path, estimator : CostEstimator, limits : &ScreeningLimits, paths : &ExperimentPaths) -> io::Result<Vec<ScreeningRecord>>{
+        experiment_cells(matrix_file)?.into_iter()
+            .map(|(ntd_name, graph_name)| {
+                let ntd_path = paths.ntd_directory.join(&ntd_name);
+                let graph_path = paths.graph_directory.join(&graph_name);
+
+                let ntd = import_ntd(&ntd_path)
+                    .ok_or_else(|| invalid_data(format!("could not import nice tree decomposition {}", ntd_path.display())))?;
+                let v_g = metis_vertex_count(&graph_path)
+                    .ok_or_else(|| invalid_data(format!("could not read the number of vertices of graph {}", graph_path.display())))?;
+
+                let estimate = estimator(&ntd, v_g);
+                Ok(ScreeningRecord { ntd_name, graph_name, estimate, feasibility: limits.classify(&estimate) })
+            })
+            .collect()
+    }
+
+    /// Writes the screening report into a csv file with the columns ntd, graph, memory_bytes, operations and feasibility.
+    pub fn write_screening_csv<P>(records : &[ScreeningRecord], path : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let mut wtr = csv::Writer::from_path(path)?;
+        wtr.write_record(["ntd", "graph", "memory_bytes", "operations", "feasibility"])?;
+
+        for record in records{
+            wtr.write_record([
+                record.ntd_name.clone(),
+                record.graph_name.clone(),
+                format!("{:e}", record.estimate.memory_bytes()),
+                format!("{:e}", record.estimate.operations),
+                format!("{:?}", record.feasibility),
+            ])?;
+        }
+
+        wtr.flush()
+    }
+
+    /// Reads the number of vertices from the first non-comment line of a .graph file.
+    fn metis_vertex_count<P>(filename : P) -> Option<usize>
+        where P: AsRef<Path>
+    {
+        read_lines(filename).ok()?
+            .map_while(Result::ok)
+            .find(|line| !line.starts_with('%'))?
+            .split_whitespace().next()?
+            .parse().ok()
+    }
+
+    /// The version of the layout of the result csv files written by write_records_csv.
+    /// Version 1 files have no header and the columns algorithm, ntd, width, v_t, e_tau, v_tau, graph, v_g, e_g,
+    /// one column for the running time of each run and the average running time.
+    pub const RESULT_SCHEMA_VERSION: u32 = 2;
+
+    /// The header of the result csv files of the current schema version.
+    pub const RESULT_HEADER: [&str; 13] = ["schema_version", "algorithm", "experiment", "ntd", "width", "v_t", "e_tau",
+        "v_tau", "graph", "v_g", "e_g", "durations", "average"];
+
+    /// Appends the given records to a csv file. The header is written if the file is new or empty. Each row
+    /// contains the schema version, the instance features, the space separated running times of all runs and
+    /// the average running time, where all running times are given in microseconds.
+    pub fn write_records_csv<P>(records : &[ExperimentRecord], path : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let is_empty = file.metadata()?.len() == 0;
+
+        let mut wtr = csv::Writer::from_writer(file);
+
+        if is_empty {
+            wtr.write_record(RESULT_HEADER)?;
+        }
+
+        for record in records{
+            wtr.write_record([
+                RESULT_SCHEMA_VERSION.to_string(),
+                record.algorithm.clone(),
+                record.experiment.clone(),
+                record.ntd_name.clone(),
+                record.width.to_string(),
+                record.v_t.to_string(),
+                record.e_tau.to_string(),
+                record.v_tau.to_string(),
+                record.graph_name.clone(),
+                record.v_g.to_string(),
+                record.e_g.to_string(),
+                record.durations.iter().map(|duration| duration.as_micros().to_string()).join(" "),
+                record.average().as_micros().to_string(),
+            ])?;
+        }
+
+        wtr.flush()
+    }
+
+    /// Reads the records of a result csv file written by write_records_csv. Files of schema version 1
+    /// without header are supported as well, their records have an empty experiment name. The homomorphism
+    /// numbers are not part of the result files, hence the counts of all records are empty.
+    pub fn read_records_csv<P>(path : P) -> io::Result<Vec<ExperimentRecord>>
+        where P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)
+            .map_err(|error| path_error(path, "could not read", error.into()))?;
+
+        let mut records = vec![];
+
+        for (line, row) in reader.records().enumerate() {
+            let row = row.map_err(|error| path_error(path, "could not read", error.into()))?;
+            let malformed = || invalid_data(format!("{}: malformed record in line {}", path.display(), line + 1));
+
+            // skip the header
+            if row.get(0) == Some(RESULT_HEADER[0]) { continue; }
+
+            let record = match row.get(0).and_then(|version| version.parse::<u32>().ok()) {
+                Some(2) => parse_record(&row, 1, true),
+                Some(_) => None,
+                None => parse_record(&row, 0, false),
+            };
+            records.push(record.ok_or_else(malformed)?);
+        }
+
+        Ok(records)
+    }
+
+    /// Parses a row of a result csv file starting at the given column. Rows of schema version 2 contain the
+    /// experiment name and all durations in a single column, rows of schema version 1 one column per run.
+    fn parse_record(row : &csv::StringRecord, start : usize, version_2 : bool) -> Option<ExperimentRecord>{
+        let mut columns = row.iter().skip(start);
+        let mut next = || columns.next().map(|column| column.to_string());
+
+        let algorithm = next()?;
+        let experiment = if version_2 { next()? } else { String::new() };
+        let ntd_name = next()?;
+        let width = next()?.parse().ok()?;
+        let v_t = next()?.parse().ok()?;
+        let e_tau = next()?.parse().ok()?;
+        let v_tau = next()?.parse().ok()?;
+        let graph_name = next()?;
+        let v_g = next()?.parse().ok()?;
+        let e_g = next()?.parse().ok()?;
+
+        let durations : Vec<String> = if version_2 {
+            next()?.split_whitespace().map(|duration| duration.to_string()).collect()
+        } else {
+            // all remaining columns except for the average
+            let remaining : Vec<String> = row.iter().skip(start + 9).map(|column| column.to_string()).collect();
+            remaining[..remaining.len().checked_sub(1)?].to_vec()
+        };
+        let durations = durations.iter()
+            .map(|duration| duration.parse::<u64>().ok().map(Duration::from_micros))
+            .collect::<Option<Vec<Duration>>>()?;
+
+        Some(ExperimentRecord { algorithm, experiment, ntd_name, width, v_t, e_tau, v_tau, graph_name, v_g, e_g, durations, counts: HomCounts::new(vec![]) })
+    }
+
+    /// Writes the given records into a json file as an array of objects. All running times are given
+    /// in microseconds.
+    pub fn write_records_json<P>(records : &[ExperimentRecord], path : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let mut file = File::create(path)?;
+
+        let objects = records.iter()
+            .map(|record| {
+                let durations = record.durations.iter().map(|duration| duration.as_micros().to_string()).join(",");
+                format!("  {{\"algorithm\": \"{}\", \"experiment\": \"{}\", \"ntd\": \"{}\", \"width\": {}, \"v_t\": {}, \"e_tau\": {}, \"v_tau\": {}, \
+                         \"graph\": \"{}\", \"v_g\": {}, \"e_g\": {}, \"durations\": [{}], \"average\": {}, \"minimum\": {}, \"maximum\": {}}}",
+                        escape_json(&record.algorithm), escape_json(&record.experiment), escape_json(&record.ntd_name), record.width, record.v_t, record.e_tau, record.v_tau,
+                        escape_json(&record.graph_name), record.v_g, record.e_g, durations,
+                        record.average().as_micros(), record.minimum().as_micros(), record.maximum().as_micros())
+            })
+            .join(",\n");
+
+        writeln!(file, "[")?;
+        writeln!(file, "{}", objects)?;
+        writeln!(file, "]")
+    }
+
+    /// Writes the homomorphism numbers of each record into its own csv file in the given directory.
+    pub fn write_records_counts<P>(records : &[ExperimentRecord], directory : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        for record in records{
+            let file_name = format!("{}_{}_{}_{}_counts.csv", record.algorithm, record.experiment,
+                                    Path::new(&record.ntd_name).file_stem().unwrap().to_str().unwrap(),
+                                    Path::new(&record.graph_name).file_stem().unwrap().to_str().unwrap());
+            let path = directory.as_ref().join(file_name);
+            record.counts.write_csv(&path).map_err(|error| path_error(&path, "could not write", error))?;
+        }
+        Ok(())
+    }
+
+    /// Executes the experiment given by matrix_file and writes the running times as well as the
+    /// homomorphism numbers into the result directory, which is created if it does not exist.
+    pub fn run_running_time_experiment(matrix_file : &Path, alg : NtdSetAlgorithm, alg_name : &String, paths : &ExperimentPaths) -> io::Result<()>{
+        paths.create_result_directory()?;
+        let results_path = paths.result_directory.join(format!("{}_{}_results.csv", alg_name, experiment_name(matrix_file)));
+
+        let records = measure_running_time(matrix_file, alg, alg_name, paths)?;
+
+        write_records_csv(&records, &results_path).map_err(|error| path_error(&results_path, "could not write", error))?;
+        write_records_counts(&records, &paths.result_directory)?;
+        EnvironmentInfo::collect().write_sidecar(&results_path)
+    }
+
+    /// Executes the experiment given by matrix_file in the given number of worker threads. Every record
+    /// is written into the result directory as soon as it is finished.
+    pub fn run_running_time_experiment_parallel(matrix_file : &Path, alg : NtdSetAlgorithm, alg_name : &String, paths : &ExperimentPaths, threads : usize) -> io::Result<()>{
+        paths.create_result_directory()?;
+        let results_path = paths.result_directory.join(format!("{}_{}_results.csv", alg_name, experiment_name(matrix_file)));
+        EnvironmentInfo::collect().write_sidecar(&results_path)?;
+
+        // the first error while writing is kept, later records are still measured
+        let mut write_result = Ok(());
+        measure_running_time_parallel(matrix_file, alg, alg_name, paths, threads, |record| {
+            if write_result.is_ok() {
+                write_result = write_records_csv(slice::from_ref(record), &results_path)
+                    .map_err(|error| path_error(&results_path, "could not write", error))
+                    .and_then(|_| write_records_counts(slice::from_ref(record), &paths.result_directory));
             }
+        })?;
+
+        write_result
+    }
+
+    /// A part of the experiment matrix measured by a single process, e.g. by one of several machines or MPI ranks.
+    /// The cells of the experiment matrix are distributed round robin, i.e. the cell i belongs to the shard
+    /// i mod size, such that neighbouring cells, which tend to have similar running times, are spread over all
+    /// processes.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Shard {
+        pub rank: usize,
+        pub size: usize,
+    }
+
+    /// The pairs of environment variables containing the rank and the number of processes set by common launchers,
+    /// i.e. Open MPI, MPICH and slurm.
+    const SHARD_VARIABLES: [(&str, &str); 3] = [("OMPI_COMM_WORLD_RANK", "OMPI_COMM_WORLD_SIZE"), ("PMI_RANK", "PMI_SIZE"),
+        ("SLURM_PROCID", "SLURM_NTASKS")];
+
+    impl Shard {
+
+        /// Creates the shard, panics if the rank is not smaller than the number of shards.
+        pub fn new(rank : usize, size : usize) -> Shard {
+            assert!(rank < size, "The rank has to be smaller than the number of shards!");
+            Shard { rank, size }
         }
 
+        /// Returns the shard of the current process given by the environment variables of mpirun or srun, or None
+        /// if the process has not been started by one of them.
+        pub fn from_environment() -> Option<Shard> {
+            SHARD_VARIABLES.iter().find_map(|(rank, size)| {
+                let rank = env::var(rank).ok()?.parse().ok()?;
+                let size = env::var(size).ok()?.parse().ok()?;
+                (rank < size).then_some(Shard { rank, size })
+            })
+        }
+
+        /// Returns true if the cell with the given index of the experiment matrix belongs to the shard.
+        pub fn contains(&self, cell : usize) -> bool { cell % self.size == self.rank }
+
+        /// Returns the path of the results file of the shard in the result directory.
+        pub fn results_path(&self, matrix_file : &Path, alg_name : &str, paths : &ExperimentPaths) -> PathBuf {
+            paths.result_directory.join(format!("{}_{}_results_shard_{}_of_{}.csv", alg_name, experiment_name(matrix_file), self.rank, self.size))
+        }
+    }
+
+    /// Executes the cells of the experiment given by matrix_file which belong to the shard like measure_running_time.
+    pub fn measure_running_time_shard(matrix_file : &Path, alg : NtdSetAlgorithm, alg_name : &String, paths : &ExperimentPaths, shard : Shard) -> io::Result<Vec<ExperimentRecord>>{
+        let test_name = experiment_name(matrix_file);
+
+        println!("###### Running time experiment for {} on shard {} of {} ####", alg_name, shard.rank, shard.size);
+
+        experiment_cells(matrix_file)?.iter().enumerate()
+            .filter(|(i, _)| shard.contains(*i))
+            .map(|(_, (ntd_name, graph_name))| measure_cell(&test_name, ntd_name, graph_name, alg, alg_name, paths))
+            .collect()
+    }
+
+    /// Executes the cells of the experiment given by matrix_file which belong to the shard and writes the running
+    /// times into the results file of the shard, which replaces an existing one, the homomorphism numbers into
+    /// the result directory and the environment into the sidecar of the results file. The result directory may
+    /// be shared by all shards, e.g. on a network file system, or the result files are copied into a single
+    /// directory afterwards. Returns the path of the results file of the shard.
+    pub fn run_running_time_experiment_shard(matrix_file : &Path, alg : NtdSetAlgorithm, alg_name : &String, paths : &ExperimentPaths, shard : Shard) -> io::Result<PathBuf>{
+        paths.create_result_directory()?;
+        let results_path = shard.results_path(matrix_file, alg_name, paths);
+
+        let records = measure_running_time_shard(matrix_file, alg, alg_name, paths, shard)?;
+
+        remove_existing(&results_path)?;
+        write_records_csv(&records, &results_path).map_err(|error| path_error(&results_path, "could not write", error))?;
+        write_records_counts(&records, &paths.result_directory)?;
+        EnvironmentInfo::collect().write_sidecar(&results_path)?;
+
+        Ok(results_path)
+    }
+
+    /// Consolidates the results files of all shards of the experiment given by matrix_file in the result directory
+    /// into the results file written by run_running_time_experiment, which replaces an existing one. Returns the
+    /// merged records in the order of the experiment matrix.
+    /// Fails if the shards have different numbers of shards, a shard is missing, or a cell of the experiment matrix
+    /// is missing, measured twice or unknown.
+    pub fn merge_result_shards(matrix_file : &Path, alg_name : &String, paths : &ExperimentPaths) -> io::Result<Vec<ExperimentRecord>>{
+        let prefix = format!("{}_{}_results_shard_", alg_name, experiment_name(matrix_file));
+        let directory_error = |error| path_error(&paths.result_directory, "could not read result directory", error);
+
+        // the shards found in the result directory
+        let mut shards = vec![];
+        for entry in fs::read_dir(&paths.result_directory).map_err(directory_error)? {
+            let file_name = entry.map_err(directory_error)?.file_name().to_string_lossy().to_string();
+            let Some(shard) = file_name.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(".csv")) else { continue; };
+            let shard = shard.split_once("_of_")
+                .and_then(|(rank, size)| Some(Shard { rank: rank.parse().ok()?, size: size.parse().ok()? }))
+                .ok_or_else(|| invalid_data(format!("{}: malformed name of a shard", file_name)))?;
+            shards.push(shard);
+        }
+
+        let size = shards.first().map(|shard| shard.size)
+            .ok_or_else(|| invalid_data(format!("{}: no shards of {}", paths.result_directory.display(), prefix.trim_end_matches("_shard_"))))?;
+        if shards.iter().any(|shard| shard.size != size) {
+            return Err(invalid_data(format!("{}: the shards of {} have different numbers of shards", paths.result_directory.display(), prefix.trim_end_matches("_shard_"))));
+        }
+        if let Some(rank) = (0..size).find(|&rank| !shards.contains(&Shard { rank, size })) {
+            return Err(invalid_data(format!("{}: shard {} of {} is missing", paths.result_directory.display(), rank, size)));
+        }
+
+        let index : HashMap<(String, String), usize> = experiment_cells(matrix_file)?.into_iter().enumerate()
+            .map(|(i, cell)| (cell, i))
+            .collect();
+        let mut merged : Vec<Option<ExperimentRecord>> = (0..index.len()).map(|_| None).collect();
+
+        for rank in 0..size{
+            let shard_path = Shard { rank, size }.results_path(matrix_file, alg_name, paths);
+            for record in read_records_csv(&shard_path)?{
+                let cell = (record.ntd_name.clone(), record.graph_name.clone());
+                let i = *index.get(&cell)
+                    .ok_or_else(|| invalid_data(format!("{}: cell {:?} is not part of the experiment matrix", shard_path.display(), cell)))?;
+                if merged[i].replace(record).is_some() {
+                    return Err(invalid_data(format!("{}: cell {:?} has been measured twice", shard_path.display(), cell)));
+                }
+            }
+        }
+
+        let missing = merged.iter().filter(|record| record.is_none()).count();
+        if missing > 0 {
+            return Err(invalid_data(format!("{}: {} cells of the experiment matrix are missing", paths.result_directory.display(), missing)));
+        }
+        let records : Vec<ExperimentRecord> = merged.into_iter().flatten().collect();
+
+        let results_path = paths.result_directory.join(format!("{}_{}_results.csv", alg_name, experiment_name(matrix_file)));
+        remove_existing(&results_path)?;
+        write_records_csv(&records, &results_path).map_err(|error| path_error(&results_path, "could not write", error))?;
+
+        Ok(records)
+    }
+
+    /// Removes the file if it exists, such that records written afterwards do not get appended to old ones.
+    fn remove_existing(path : &Path) -> io::Result<()> {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(path_error(path, "could not remove", error)),
+            _ => Ok(()),
+        }
     }
 }
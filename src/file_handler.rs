@@ -2,36 +2,118 @@ use std::fs::File;
 use std::io;
 use std::io::BufRead;
 use std::path::Path;
+use flate2::read::GzDecoder;
+
+/// Opens the file with the given filename as a buffered reader.
+/// Files ending with .gz are decompressed transparently.
+pub(crate) fn open_reader<P>(filename: P) -> io::Result<Box<dyn BufRead>>
+    where P: AsRef<Path>, {
+    let is_compressed = filename.as_ref().extension().is_some_and(|extension| extension == "gz");
+    let file = File::open(filename)?;
+
+    if is_compressed {
+        Ok(Box::new(io::BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(io::BufReader::new(file)))
+    }
+}
 
 /// Reads file with given filename and returns BufReader
 /// taken from https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+pub(crate) fn read_lines<P>(filename: P) -> io::Result<io::Lines<Box<dyn BufRead>>>
     where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    Ok(open_reader(filename)?.lines())
+}
+
+/// Escapes the text such that it can be written between quotes as a json string, i.e. quotes, backslashes
+/// and control characters are escaped as required by RFC 8259.
+pub fn escape_json(text : &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars(){
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 /// A module containing the import and export functions for .ntd and (eventually .nt) files.
 pub mod tree_decomposition_handler {
     use std::collections::HashMap;
+    use std::fmt;
     use std::fs::File;
+    use std::io;
+    use std::io::{BufRead, BufWriter, Write};
     use std::path::Path;
-    use crate::file_handler::read_lines;
+    use crate::file_handler::open_reader;
     use crate::tree_decompositions::nice_tree_decomposition::{Bag, NiceTreeDecomposition, NodeData, NodeType};
     use crate::tree_decompositions::tree_structure::{TreeNode, TreeStructure, Vertex};
 
+    /// The reason why a .ntd-file could not be parsed. Line numbers start with 1.
+    #[derive(Debug)]
+    pub enum NtdParseError {
+        /// the file could not be read
+        Io(io::Error),
+        /// the line does not follow the .ntd format
+        MalformedLine { line: usize, content: String, reason: String },
+        /// the file does not contain a start line "s nodes bag_size vertices"
+        MissingStartLine,
+        /// the node (numbered as in the file) has no "n" line
+        MissingNode(TreeNode),
+    }
+
+    impl fmt::Display for NtdParseError {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                NtdParseError::Io(error) => write!(f, "could not read the nice tree decomposition: {}", error),
+                NtdParseError::MalformedLine { line, content, reason } => write!(f, "line {}: {} in \"{}\"", line, reason, content),
+                NtdParseError::MissingStartLine => write!(f, "the start line \"s nodes bag_size vertices\" is missing"),
+                NtdParseError::MissingNode(p) => write!(f, "node {} is not defined", p),
+            }
+        }
+    }
+
+    impl std::error::Error for NtdParseError {}
+
+    impl From<io::Error> for NtdParseError {
+        fn from(error : io::Error) -> NtdParseError { NtdParseError::Io(error) }
+    }
+
     /// Given a .ntd-file this functions returns a NiceTreeDecomposition if possible.
     pub fn import_ntd<P>(filename : P) -> Option<NiceTreeDecomposition>
         where P: AsRef<Path>
     {
-        // Info given by the import format
-        let mut number_of_nodes = 0;
+        load_ntd(filename).ok()
+    }
 
-        // This argument will not be used in the following function
-        let mut max_bag_size = 0;
+    /// Reads a nice tree decomposition in the .ntd format from the given reader, e.g. a string or stdin.
+    pub fn read_ntd<R>(reader : R) -> Option<NiceTreeDecomposition>
+        where R: BufRead
+    {
+        parse_ntd(reader).ok()
+    }
 
-        // This information is not needed yet
-        let mut number_of_vertices = 0;
+    /// Like import_ntd, but returns the reason why the file could not be read or parsed.
+    pub fn load_ntd<P>(filename : P) -> Result<NiceTreeDecomposition, NtdParseError>
+        where P: AsRef<Path>
+    {
+        parse_ntd(open_reader(filename)?)
+    }
+
+    /// Like read_ntd, but returns the reason why the nice tree decomposition could not be parsed.
+    /// Tokens are separated by arbitrary whitespace, hence tabs, multiple spaces and Windows line endings
+    /// are accepted. Blank lines and comment lines starting with "c" or "#" are ignored.
+    pub fn parse_ntd<R>(reader : R) -> Result<NiceTreeDecomposition, NtdParseError>
+        where R: BufRead
+    {
+        // the number of nodes, the maximal bag size and the number of vertices given by the start line
+        let mut start : Option<(TreeNode, u32, u32)> = None;
 
         // create an dummy tree structure to late override it
         let mut tree_structure : TreeStructure = TreeStructure::new(1);
@@ -39,84 +121,137 @@ pub mod tree_decomposition_handler {
         // creat an empty hashmap saving the node_data
         let mut nodes_data : HashMap<TreeNode, NodeData> = HashMap::new();
 
-        // read lines of file if possible
-        if let Ok(lines) = read_lines(filename){
-
-            // loop over all written lines in the file
-            for line in lines {
-
-                let line_string = line.unwrap();
-                // get all args divided by a space
-                let mut args = line_string.split(' ');
-                // get the first argument, which denotes the function of this line
-                let type_arg = args.next();
-
-                // match the first argument of the line
-                match type_arg {
-                    // s is the start line, containing info about the nice tree decomposition
-                    Some("s") => {
-
-                        // get the arguments contained in the start line
-                        number_of_nodes = args.next().unwrap().parse::<u64>().unwrap();
-                        max_bag_size = args.next().unwrap().parse::<u32>().unwrap();
-                        number_of_vertices = args.next().unwrap().parse::<u32>().unwrap();
-
-                        // Create the tree structure when info has been found
-                        tree_structure = TreeStructure::new(number_of_nodes);
-                    },
-                    // Manages node lines, which represent the node data
-                    Some("n") => {
-
-                        /*
-                        The index of the node will be reduced by one since the internal
-                        representation of node goes from 0 to N-1 while the nodes in the .ntd
-                        files have indices 1..N.
-                         */
-                        let node_index = (args.next().unwrap().parse::<u32>().unwrap() - 1) as TreeNode;
-
-                        // get the type of node
-                        let node_type = args.next();
-
-                        // This closure is used to construct the bag out of the following arguments
-                        let mut constructed_bag = || {
-                            let mut bag = Bag::new();
-
-                            for v in args.by_ref(){
-                                bag.insert(Vertex::new((v.parse::<u64>().unwrap() - 1) as usize) );
+        // loop over all written lines
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let malformed = |reason : &str| NtdParseError::MalformedLine { line: index + 1, content: line.trim().to_string(), reason: reason.to_string() };
+
+            // get all args divided by whitespace
+            let args : Vec<&str> = line.split_whitespace().collect();
+
+            // parses the i-th argument as a number between 1 and max, which is reduced by one since the internal
+            // representation of nodes and vertices goes from 0 to N-1 while the .ntd files use 1..N.
+            let index_arg = |i : usize, name : &str, max : u64| {
+                args.get(i).and_then(|arg| arg.parse::<u64>().ok())
+                    .filter(|&value| value >= 1 && value <= max)
+                    .map(|value| value - 1)
+                    .ok_or_else(|| malformed(&format!("expected a {} between 1 and {}", name, max)))
+            };
+
+            // match the first argument of the line, which denotes the function of this line
+            match args.first().copied() {
+                // blank lines and comments
+                None | Some("c") => {}
+                Some(arg) if arg.starts_with('#') => {}
+                // s is the start line, containing info about the nice tree decomposition
+                Some("s") => {
+                    if start.is_some() { return Err(malformed("duplicate start line")); }
+
+                    // get the arguments contained in the start line
+                    let numbers : Option<Vec<u64>> = args[1..].iter().map(|arg| arg.parse::<u64>().ok()).collect();
+                    let (number_of_nodes, max_bag_size, number_of_vertices) = match numbers.as_deref() {
+                        Some(&[nodes, bag_size, vertices]) if nodes > 0 && bag_size > 0 => {
+                            match (u32::try_from(bag_size), u32::try_from(vertices)) {
+                                (Ok(bag_size), Ok(vertices)) => (nodes, bag_size, vertices),
+                                _ => return Err(malformed("the bag size or the number of vertices is too large")),
                             }
+                        }
+                        _ => return Err(malformed("expected the number of nodes, the maximal bag size and the number of vertices")),
+                    };
+
+                    start = Some((number_of_nodes, max_bag_size, number_of_vertices));
+
+                    // Create the tree structure when info has been found
+                    tree_structure = TreeStructure::new(number_of_nodes);
+                },
+                // Manages node lines, which represent the node data
+                Some("n") => {
+                    let Some((number_of_nodes, _, number_of_vertices)) = start else { return Err(malformed("node line before the start line")); };
+                    let node_index = index_arg(1, "node", number_of_nodes)?;
+
+                    // construct the bag out of the arguments following the node type
+                    let mut bag = Bag::new();
+                    for i in 3..args.len(){
+                        bag.insert(Vertex::new(index_arg(i, "vertex", number_of_vertices as u64)? as usize));
+                    }
 
+                    // construct node data from the information given
+                    let node_type = match args.get(2).copied() {
+                        Some("l") => NodeType::Leaf,
+                        Some("i") => NodeType::Introduce,
+                        Some("f") => NodeType::Forget,
+                        Some("j") => NodeType::Join,
+                        _ => return Err(malformed("expected a node type l, i, f or j")),
+                    };
+
+                    // inserts node data into the nodes_data hashmap.
+                    if nodes_data.insert(node_index, NodeData::new(node_type, bag)).is_some() {
+                        return Err(malformed("duplicate node"));
+                    }
+                },
+                // Manages adjacency lines
+                Some("a") => {
+                    let Some((number_of_nodes, _, _)) = start else { return Err(malformed("adjacency line before the start line")); };
+                    let p = index_arg(1, "node", number_of_nodes)?;
+                    let q = index_arg(2, "node", number_of_nodes)?;
+                    if args.len() != 3 { return Err(malformed("expected exactly two nodes")); }
+
+                    if p == q { return Err(malformed("a node cannot be its own child")); }
+                    if tree_structure.parent(q).is_some() { return Err(malformed("the child already has a parent")); }
+                    tree_structure.add_child(p, q);
+                }
+                Some(_) => return Err(malformed("unknown line type")),
+            }
+        }
 
-                            bag
-                        };
-
-                        // construct node data from the information given
-                        let node_data = match node_type {
-                            Some("l") => NodeData::new(NodeType::Leaf, constructed_bag()),
-                            Some("i") => NodeData::new(NodeType::Introduce, constructed_bag()),
-                            Some("f") => NodeData::new(NodeType::Forget, constructed_bag()),
-                            Some("j") => NodeData::new(NodeType::Join, constructed_bag()),
-                            _ => {panic!("cannot identify this node type");} // This case should never happen
-                        };
+        let (number_of_nodes, max_bag_size, number_of_vertices) = start.ok_or(NtdParseError::MissingStartLine)?;
+        if let Some(p) = (0..number_of_nodes).find(|p| !nodes_data.contains_key(p)) {
+            return Err(NtdParseError::MissingNode(p + 1));
+        }
 
-                        // inserts node data into the nodes_data hashmap.
-                        nodes_data.insert(node_index, node_data);
+        Ok(NiceTreeDecomposition::new(tree_structure, nodes_data, number_of_vertices, max_bag_size - 1))
+    }
 
+    /// Writes the nice tree decomposition into the given .ntd-file, such that import_ntd returns it again.
+    pub fn export_ntd<P>(ntd : &NiceTreeDecomposition, filename : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        write_ntd(ntd, &mut writer)?;
+        writer.flush()
+    }
 
-                    },
-                    // Manages adjacency lines
-                    Some("a") => {
-                        let p = (args.next().unwrap().parse::<TreeNode>().unwrap() - 1) as TreeNode;
-                        let q = (args.next().unwrap().parse::<TreeNode>().unwrap() - 1) as TreeNode;
-                        tree_structure.add_child(p, q);
-                    }
-                    _ => {}
-                }
+    /// Writes the nice tree decomposition in the .ntd format into the given writer. The children of join
+    /// nodes are written in their order, vertices and nodes are written with indices starting at 1.
+    pub fn write_ntd<W>(ntd : &NiceTreeDecomposition, writer : &mut W) -> io::Result<()>
+        where W: Write
+    {
+        writeln!(writer, "s {} {} {}", ntd.node_count(), ntd.width() + 1, ntd.vertex_count())?;
+
+        for p in 0..ntd.node_count(){
+            let node_type = match ntd.node_type(p) {
+                Some(NodeType::Leaf) => "l",
+                Some(NodeType::Introduce) => "i",
+                Some(NodeType::Forget) => "f",
+                Some(NodeType::Join) => "j",
+                None => continue,
+            };
+            let mut bag : Vec<usize> = ntd.bag(p).unwrap().iter().map(|v| v.index() + 1).collect();
+            bag.sort_unstable();
+
+            write!(writer, "n {} {}", p + 1, node_type)?;
+            for v in bag{
+                write!(writer, " {}", v)?;
             }
-            Some(NiceTreeDecomposition::new(tree_structure, nodes_data, number_of_vertices,max_bag_size - 1 ))
+            writeln!(writer)?;
         }
-        else { None }
-
 
+        for p in 0..ntd.node_count(){
+            for q in ntd.children(p).into_iter().flatten(){
+                writeln!(writer, "a {} {}", p + 1, q + 1)?;
+            }
+        }
+        Ok(())
     }
 
 
@@ -124,119 +259,339 @@ pub mod tree_decomposition_handler {
 
 /// A module containing the import and export functions for several graph formats
 pub mod graph_handler {
+    use std::collections::HashMap;
+    use std::io;
+    use std::io::{BufRead, Write};
     use std::path::Path;
-    use petgraph::matrix_graph::NodeIndex;
-    use petgraph::Undirected;
-    use crate::file_handler::read_lines;
-    use crate::tree_decompositions::tree_structure::Vertex;
+    use crate::file_handler::open_reader;
+    use crate::loop_semantics::loop_semantics_methods::{GraphRole, LoopSemantics};
+    use crate::multigraph::multigraph_algorithms::MultiGraph;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
 
     /// Given a .graph file f, import this graph as a Petgraph Matrix_Graph.
     /// Node-Indices will be subtracted by one (1,..,N) -> (0,..,N-1)
     /// More information on Metis could be found under https://www.lrz.de/services/software/mathematik/metis/metis_5_0.pdf
-    pub fn import_metis<P>(filename : P) -> Option<petgraph::matrix_graph::MatrixGraph<(),(), Undirected>>
+    pub fn import_metis<P>(filename : P) -> Option<Graph>
         where P: AsRef<Path>
     {
-        let mut graph = petgraph::matrix_graph::MatrixGraph::new_undirected();
+        // a file which cannot be opened results in an empty graph
+        match open_reader(filename) {
+            Ok(reader) => read_metis(reader),
+            Err(_) => Some(Graph::default()),
+        }
+    }
+
+    /// Parses a vertex index (1,..,N) of a graph with N vertices and returns it as (0,..,N-1).
+    /// Returns None for tokens which are no numbers or out of range.
+    fn parse_vertex(token : &str, number_of_vertices : usize) -> Option<usize> {
+        token.parse::<usize>().ok()
+            .filter(|value| (1..=number_of_vertices).contains(value))
+            .map(|value| value - 1)
+    }
+
+    /// Reads a graph in the .graph format from the given reader, e.g. a string or stdin.
+    /// Returns None if the content is malformed, e.g. contains a token which is no vertex of the graph.
+    pub fn read_metis<R>(reader : R) -> Option<Graph>
+        where R: BufRead
+    {
+        let mut graph = Graph::default();
 
         let mut number_of_vertices : usize = 0;
         let mut number_of_edges : usize = 0;
         let mut current_vertex : usize = 0;
 
-        if let Ok(lines) = read_lines(filename) {
+        // go through each line
+        for line in reader.lines() {
+            let content = line.ok()?;
 
-            // go through each line of the file
-            for line in lines {
-                let content = line.unwrap();
+            // % means comment -> ignore
+            // empty lines are vertices without out-going edges
+            match content.chars().next() {
+                Some('%') => {continue;}
+                None => {
+                    current_vertex += 1;
+                    continue;
+                }
+                Some(_) => {}
+            }
 
-                // % means comment -> ignore
-                // empty lines are vertices without out-going edges
-                match content.chars().next() {
-                    Some('%') => {continue;}
-                    None => {
-                        current_vertex += 1;
-                        continue;
-                    }
-                    Some(_) => {}
+            // separate entries by space, consecutive spaces are ignored
+            let mut args = content.split(' ').filter(|arg| !arg.is_empty());
+
+            if number_of_vertices == 0 {
+                number_of_vertices = args.next()?.parse::<usize>().ok()?;
+                number_of_edges = args.next()?.parse::<usize>().ok()?;
+
+                for _ in 1..(number_of_vertices + 1){
+                    graph.add_node(());
                 }
+                continue;
+            }
 
-                // separate entries by space
-                let mut args = content.split(' ');
+            // more neighbour lists than vertices
+            if current_vertex >= number_of_vertices { return None; }
 
-                if number_of_vertices == 0 {
-                    number_of_vertices = args.next().unwrap().parse::<usize>().unwrap();
-                    number_of_edges = args.next().unwrap().parse::<usize>().unwrap();
+            for ver in args {
+                let value = parse_vertex(ver, number_of_vertices)?;
+                if !graph.has_edge(Vertex::new(current_vertex), Vertex::new(value)) {
+                    graph.add_edge(Vertex::new(current_vertex), Vertex::new(value), ());
+                }
+            }
 
-                    for _ in 1..(number_of_vertices + 1){
-                        graph.add_node(());
-                    }
+            current_vertex += 1;
+        }
+        Some(graph)
+    }
+
+    /// Imports a .graph file like import_metis and treats its loops according to the given loop semantics
+    /// for a graph of the given role, e.g. Reflexive semantics only add loops to targets.
+    pub fn import_metis_with_loop_semantics<P>(filename : P, loop_semantics : LoopSemantics, role : GraphRole) -> Option<Graph>
+        where P: AsRef<Path>
+    {
+        import_metis(filename).map(|graph| loop_semantics.apply(&graph, role))
+    }
+
+    /// Given a .graph file f, import this graph as a multigraph where the multiplicity of an edge (u,v)
+    /// is the number of times v appears in the neighbour list of u. Since every edge is listed at
+    /// both of its endpoints, the multiplicity is the maximum of both counts.
+    /// Node-Indices will be subtracted by one (1,..,N) -> (0,..,N-1)
+    pub fn import_metis_multigraph<P>(filename : P) -> Option<MultiGraph>
+        where P: AsRef<Path>
+    {
+        // a file which cannot be opened results in an empty graph
+        match open_reader(filename) {
+            Ok(reader) => read_metis_multigraph(reader),
+            Err(_) => Some(MultiGraph::default()),
+        }
+    }
+
+    /// Reads a multigraph in the .graph format from the given reader, see import_metis_multigraph.
+    pub fn read_metis_multigraph<R>(reader : R) -> Option<MultiGraph>
+        where R: BufRead
+    {
+        let mut graph = MultiGraph::default();
+
+        let mut number_of_vertices : usize = 0;
+        let mut current_vertex : usize = 0;
+
+        // counts the occurrences of v in the neighbour list of u
+        let mut occurrences : HashMap<(usize, usize), u64> = HashMap::new();
+
+        // go through each line
+        for line in reader.lines() {
+            let content = line.ok()?;
+
+            // % means comment -> ignore
+            // empty lines are vertices without out-going edges
+            match content.chars().next() {
+                Some('%') => {continue;}
+                None => {
+                    current_vertex += 1;
                     continue;
                 }
+                Some(_) => {}
+            }
 
-                for ver in args {
-                    let value = ver.parse::<usize>().unwrap();
-                    if !graph.has_edge(Vertex::new(current_vertex), Vertex::new(value - 1)) {
-                        graph.add_edge(Vertex::new(current_vertex), Vertex::new(value - 1), ());
-                    }
+            // separate entries by space, consecutive spaces are ignored
+            let mut args = content.split(' ').filter(|arg| !arg.is_empty());
+
+            if number_of_vertices == 0 {
+                number_of_vertices = args.next()?.parse::<usize>().ok()?;
+
+                for _ in 0..number_of_vertices{
+                    graph.add_node(());
                 }
+                continue;
+            }
+
+            // more neighbour lists than vertices
+            if current_vertex >= number_of_vertices { return None; }
 
-                current_vertex += 1;
+            for ver in args {
+                let value = parse_vertex(ver, number_of_vertices)?;
+                *occurrences.entry((current_vertex, value)).or_insert(0) += 1;
             }
 
+            current_vertex += 1;
+        }
+
+        for (&(u,v), &count) in &occurrences{
+            let reverse_count = *occurrences.get(&(v,u)).unwrap_or(&0);
+            graph.update_edge(Vertex::new(u), Vertex::new(v), count.max(reverse_count));
         }
+
         Some(graph)
     }
 
     /// Given a .gr file used by DIMACS challenges, import this graph as a Petgraph Matrix_Graph
     /// Node-Indices will be subtracted by one (1,..,N) -> (0,..,N-1)
     /// More Information on the .gr format can be found under https://github.com/PACE-challenge/Treewidth
-    pub fn import_dimacs<P>(filename : P) -> Option<petgraph::matrix_graph::MatrixGraph<(),(), Undirected>>
+    pub fn import_dimacs<P>(filename : P) -> Option<Graph>
         where P: AsRef<Path>{
+        // a file which cannot be opened results in an empty graph
+        match open_reader(filename) {
+            Ok(reader) => read_dimacs(reader),
+            Err(_) => Some(Graph::default()),
+        }
+    }
+
+    /// Reads a graph in the .gr format from the given reader, e.g. a string or stdin.
+    /// Returns None if the content is malformed, e.g. an edge refers to a vertex which does not exist.
+    pub fn read_dimacs<R>(reader : R) -> Option<Graph>
+        where R: BufRead
+    {
 
-        let mut graph = petgraph::matrix_graph::MatrixGraph::new_undirected();
+        let mut graph = Graph::default();
 
         let mut number_of_vertices : usize = 0;
         let mut number_of_edges : usize = 0;
 
-        if let Ok(lines) = read_lines(filename) {
+        // go through each line
+        for line in reader.lines() {
+            let line_string = line.ok()?;
 
-            // go through each line of the file
-            for line in lines {
-                let line_string = line.unwrap();
+            let mut args = line_string.split(' ').filter(|arg| !arg.is_empty());
+            // get the first argument, which denotes the function of this line
+            let type_arg = args.next();
 
-                let mut args = line_string.split(' ');
-                // get the first argument, which denotes the function of this line
-                let type_arg = args.next();
+            // c means comment -> ignore
+            // empty lines are vertices without out-going edges
+            match type_arg {
+                Some("c") => { continue; }
+                Some("p") => {
+                    let problem_descriptor = args.next();
 
-                // c means comment -> ignore
-                // empty lines are vertices without out-going edges
-                match type_arg {
-                    Some("c") => { continue; }
-                    Some("p") => {
-                        let problem_descriptor = args.next();
+                    number_of_vertices = args.next()?.parse::<usize>().ok()?;
+                    number_of_edges = args.next()?.parse::<usize>().ok()?;
 
-                        number_of_vertices = args.next().unwrap().parse::<usize>().unwrap();
-                        number_of_edges = args.next().unwrap().parse::<usize>().unwrap();
+                    for _ in 1..(number_of_vertices + 1){
+                        graph.add_node(());
+                    }
 
-                        for _ in 1..(number_of_vertices + 1){
-                            graph.add_node(());
-                        }
+                }
+                Some(first_arg) => {
+                    let first_vertex = parse_vertex(first_arg, number_of_vertices)?;
+                    let second_vertex = parse_vertex(args.next()?, number_of_vertices)?;
 
+                    if !graph.has_edge(Vertex::new(first_vertex), Vertex::new(second_vertex)) {
+                        graph.add_edge(Vertex::new(first_vertex), Vertex::new(second_vertex), ());
                     }
-                    Some(_) => {
-                        let first_vertex = type_arg.unwrap().parse::<usize>().unwrap() - 1;
-                        let second_vertex = args.next().unwrap().parse::<usize>().unwrap() - 1;
+                }
+                None => { continue; }
+            }
+        }
 
-                        if !graph.has_edge(Vertex::new(first_vertex), Vertex::new(second_vertex)) {
-                            graph.add_edge(Vertex::new(first_vertex), Vertex::new(second_vertex), ());
-                        }
-                    }
-                    None => { continue; }
+        Some(graph)
+    }
+
+    /// Parses a single graph in the graph6 format, which is e.g. produced by geng of nauty.
+    /// The optional header ">>graph6<<" is ignored. Returns None if the line is not a valid graph6 string.
+    /// More information on the format can be found under https://users.cecs.anu.edu.au/~bdm/data/formats.txt
+    pub fn parse_graph6(line : &str) -> Option<Graph>{
+        let line = line.trim_end();
+        let line = line.strip_prefix(">>graph6<<").unwrap_or(line);
+
+        // every byte encodes 6 bits shifted by 63
+        let mut values = vec![];
+        for byte in line.bytes(){
+            if !(63..=126).contains(&byte) { return None; }
+            values.push((byte - 63) as usize);
+        }
+
+        // the number of vertices is encoded in 1, 4 or 8 bytes
+        let (number_of_vertices, rest) = match values.as_slice() {
+            [63, 63, rest @ ..] if rest.len() >= 6 => (rest[..6].iter().fold(0, |n, &x| (n << 6) | x), &rest[6..]),
+            [63, rest @ ..] if rest.len() >= 3 => (rest[..3].iter().fold(0, |n, &x| (n << 6) | x), &rest[3..]),
+            [n, rest @ ..] => (*n, rest),
+            [] => return None,
+        };
+
+        // the upper triangle of the adjacency matrix is stored column by column
+        let number_of_bits = number_of_vertices * number_of_vertices.saturating_sub(1) / 2;
+        if rest.len() != number_of_bits.div_ceil(6) { return None; }
+
+        let mut graph = Graph::default();
+        for _ in 0..number_of_vertices{
+            graph.add_node(());
+        }
+
+        let mut k = 0;
+        for v in 1..number_of_vertices{
+            for u in 0..v{
+                if (rest[k / 6] >> (5 - k % 6)) & 1 == 1 {
+                    graph.add_edge(Vertex::new(u), Vertex::new(v), ());
                 }
+                k += 1;
             }
         }
 
         Some(graph)
     }
 
-}
+    /// Writes the graph in the .graph format into the given writer, such that read_metis returns it again.
+    /// A loop at v is written as v in the neighbour list of v.
+    pub fn write_metis<W>(graph : &Graph, writer : &mut W) -> io::Result<()>
+        where W: Write
+    {
+        let n = graph.node_count();
+        writeln!(writer, "{} {}", n, graph.edge_count())?;
+
+        for u in 0..n{
+            let neighbours : Vec<String> = (0..n)
+                .filter(|&v| graph.has_edge(Vertex::new(u), Vertex::new(v)))
+                .map(|v| (v + 1).to_string())
+                .collect();
+            writeln!(writer, "{}", neighbours.join(" "))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the graph in the .gr format into the given writer, such that read_dimacs returns it again.
+    pub fn write_dimacs<W>(graph : &Graph, writer : &mut W) -> io::Result<()>
+        where W: Write
+    {
+        let n = graph.node_count();
+        writeln!(writer, "p tw {} {}", n, graph.edge_count())?;
 
+        for u in 0..n{
+            for v in u..n{
+                if graph.has_edge(Vertex::new(u), Vertex::new(v)) { writeln!(writer, "{} {}", u + 1, v + 1)?; }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the graph in the graph6 format, such that parse_graph6 returns it again.
+    /// Returns None if the graph contains a loop, since loops cannot be represented in graph6.
+    pub fn to_graph6(graph : &Graph) -> Option<String>{
+        let n = graph.node_count();
+        if (0..n).any(|v| graph.has_edge(Vertex::new(v), Vertex::new(v))) { return None; }
+
+        // the number of vertices is encoded in 1, 4 or 8 bytes
+        let mut values : Vec<usize> = match n {
+            0..=62 => vec![n],
+            63..=258047 => vec![63, n >> 12, (n >> 6) & 63, n & 63],
+            _ => vec![63, 63, (n >> 30) & 63, (n >> 24) & 63, (n >> 18) & 63, (n >> 12) & 63, (n >> 6) & 63, n & 63],
+        };
+
+        // the upper triangle of the adjacency matrix column by column, padded to a multiple of 6 bits
+        let mut bits = vec![];
+        for v in 1..n{
+            for u in 0..v{
+                bits.push(graph.has_edge(Vertex::new(u), Vertex::new(v)) as usize);
+            }
+        }
+        values.extend(bits.chunks(6).map(|chunk| chunk.iter().enumerate().fold(0, |x, (i, &bit)| x | (bit << (5 - i)))));
+
+        Some(values.into_iter().map(|value| (value as u8 + 63) as char).collect())
+    }
+
+    /// Imports a .gr file like import_dimacs and treats its loops according to the given loop semantics
+    /// for a graph of the given role.
+    pub fn import_dimacs_with_loop_semantics<P>(filename : P, loop_semantics : LoopSemantics, role : GraphRole) -> Option<Graph>
+        where P: AsRef<Path>
+    {
+        import_dimacs(filename).map(|graph| loop_semantics.apply(&graph, role))
+    }
+
+}
@@ -0,0 +1,63 @@
+/// A module containing homomorphism fingerprints of target graphs, i.e. the vectors of the numbers of
+/// homomorphisms from a fixed list of patterns. Since isomorphic targets have the same number of homomorphisms
+/// from every pattern, the fingerprints are isomorphism invariant feature vectors, and by a theorem of Lovász
+/// the fingerprint of all patterns determines a graph up to isomorphism. All patterns of a universe are counted
+/// simultaneously by a single run of the modified dynamic program.
+pub mod hom_fingerprints {
+    use std::collections::BTreeMap;
+    use crate::graph_generation::graph_generation_algorithms::{canonical_form, graph_from_edge_subset};
+    use crate::modified_dp::algorithm::{graph_to_edges, is_connected_edge_set, modified_dp_counts, EdgeList};
+    use crate::ntd_generation::ntd_generators::complete_ntd;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The maximal number of vertices of the patterns, such that the n(n+1)/2 possible edges of the complete
+    /// decomposition on n vertices fit into an EdgeList.
+    pub const MAX_PATTERN_VERTICES: usize = 10;
+
+    /// Returns the connected patterns without loops with 1 to k vertices, one of each isomorphism class, ordered
+    /// by their number of vertices and their canonical form. These are the patterns of hom_fingerprint.
+    /// Panics if k exceeds MAX_PATTERN_VERTICES.
+    pub fn fingerprint_patterns(k : usize) -> Vec<Graph> {
+        assert!(k <= MAX_PATTERN_VERTICES, "The patterns are limited to {} vertices!", MAX_PATTERN_VERTICES);
+        (1..=k).flat_map(patterns_with_vertices).collect()
+    }
+
+    /// Returns the vector of the numbers of homomorphisms from the patterns of fingerprint_patterns(k) into the
+    /// target in the same order. The patterns with j vertices are counted by a single run of the modified dynamic
+    /// program on the decomposition of width j-1 which contains all edges, whose tables have |V(G)|^j mappings per
+    /// edge set, hence this is meant for small k, e.g. k <= 4. Panics if k exceeds MAX_PATTERN_VERTICES.
+    pub fn hom_fingerprint(to_graph : &Graph, k : usize) -> Vec<u64> {
+        assert!(k <= MAX_PATTERN_VERTICES, "The patterns are limited to {} vertices!", MAX_PATTERN_VERTICES);
+        (1..=k)
+            .flat_map(|j| {
+                let ntd = complete_ntd(j);
+                let counts = modified_dp_counts(&ntd, to_graph);
+                patterns_with_vertices(j).into_iter()
+                    .map(move |pattern| counts[&graph_to_edges(&ntd, &pattern).unwrap()])
+            })
+            .collect()
+    }
+
+    /// Returns the vector of the numbers of homomorphisms from all graphs of $H_\tau$ into the target, where
+    /// the i-th entry belongs to the graph with the edge set i, see edges_to_graph. In contrast to
+    /// hom_fingerprint, isomorphic patterns and patterns with loops occur multiple times.
+    pub fn ntd_fingerprint(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<u64> {
+        let counts = modified_dp_counts(ntd, to_graph);
+        (0..counts.len() as EdgeList).map(|edges| counts[&edges]).collect()
+    }
+
+    /// Returns the connected patterns without loops with exactly j vertices, one of each isomorphism class,
+    /// ordered by their canonical form.
+    fn patterns_with_vertices(j : usize) -> Vec<Graph> {
+        let universe : Vec<(usize, usize)> = (0..j).flat_map(|u| (u + 1..j).map(move |v| (u, v))).collect();
+
+        let mut patterns = BTreeMap::new();
+        for edges in 0..(1 as EdgeList) << universe.len(){
+            if !is_connected_edge_set(j, &universe, edges) { continue; }
+            let pattern = graph_from_edge_subset(j, &universe, edges);
+            patterns.entry(canonical_form(&pattern)).or_insert(pattern);
+        }
+        patterns.into_values().collect()
+    }
+}
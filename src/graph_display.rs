@@ -0,0 +1,35 @@
+/// A module containing a compact printer for graphs, which writes one line per vertex with its neighbours
+/// instead of the nested structures of the debug output of petgraph.
+pub mod graph_printer {
+    use std::fmt;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// A wrapper writing the graph as adjacency lists, e.g. the path 0 - 1 - 2 with a loop at 2 as
+    /// ```text
+    /// 3 vertices, 3 edges
+    /// 0: 1
+    /// 1: 0 2
+    /// 2: 1 2
+    /// ```
+    /// Loops are listed as neighbours of their vertex and counted as edges.
+    pub struct AdjacencyList<'a>(pub &'a Graph);
+
+    impl fmt::Display for AdjacencyList<'_> {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            let graph = self.0;
+            let n = graph.node_count();
+            let neighbours = |u : usize| (0..n).filter(move |&v| graph.has_edge(Vertex::new(u), Vertex::new(v)));
+
+            let edges : usize = (0..n).map(|u| neighbours(u).filter(|&v| v >= u).count()).sum();
+            write!(f, "{} vertices, {} edges", n, edges)?;
+
+            for u in 0..n{
+                write!(f, "\n{}:", u)?;
+                for v in neighbours(u){
+                    write!(f, " {}", v)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -1,12 +1,12 @@
 /// A module containing all functions necessary for generating graphs.
 pub mod graph_generation_algorithms {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use itertools::Itertools;
-    use petgraph::matrix_graph::{MatrixGraph, NodeIndex};
-    use petgraph::Undirected;
+    use petgraph::matrix_graph::MatrixGraph;
     use petgraph::visit::NodeIndexable;
-    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
-    use crate::tree_decompositions::tree_structure::TreeNode;
+    use crate::color_refinement::weisfeiler_leman::color_refinement;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
 
     /// Returns true if the *undirected* edge is contained in the list.
     pub fn edge_in_list((u,v) : (usize, usize), list : &Vec<(usize, usize)>) -> bool{
@@ -15,86 +15,84 @@ pub mod graph_generation_algorithms {
 
     /// Given a nice tree decomposition, this functions computes a hashmap that maps each node p to the set of
     /// possible edges that could occur in the subtree rooted at p.
+    /// The possible edges are computed once when the nice tree decomposition is constructed, hence this
+    /// function only collects them.
     pub fn generate_possible_edges(ntd : &NiceTreeDecomposition) -> HashMap<TreeNode, Vec<(usize, usize)>>
     {
-        let stingy_ordering = ntd.stingy_ordering();
-        let mut possible_edges: HashMap<TreeNode, Vec<(usize, usize)>> = HashMap::new();
-
-        // follow the stingy ordering
-        for p in stingy_ordering{
-
-            // match the Type of node p
-            match ntd.node_type(p) {
-                Some(NodeType::Leaf) => {
-                    //returns the only vertex in the bag of p
-                    let vertex = ntd.bag(p).unwrap().iter().next().unwrap();
-                    possible_edges.insert(p, vec![(vertex.index(), vertex.index())]);
-                }
-                Some(NodeType::Introduce) => {
-                    let q = ntd.unique_child(p).unwrap();
-                    let v = ntd.unique_vertex(p).unwrap();
-                    let mut edges = possible_edges.get(q).unwrap().clone();
-
-                    let bag = ntd.bag(p).unwrap();
-
-                    for u in bag{
-                        // checks if edge has already been added
-                        if  !edge_in_list((u.index(), v.index()), &edges){
-                            edges.push((u.index(), v.index()));
-                        }
-                    }
-                    possible_edges.insert(p, edges);
+        ntd.stingy_ordering().into_iter()
+            .map(|p| (p, ntd.possible_edges(p).unwrap().clone()))
+            .collect()
+    }
 
-                }
-                Some(NodeType::Forget) => {
-                    let q = ntd.unique_child(p).unwrap();
-                    // just clone the set of possible edges
-                    possible_edges.insert(p, possible_edges.get(q).unwrap().clone());
-                }
-                Some(NodeType::Join) => {
-                    let children = ntd.children(p).unwrap();
+    /// Given a number of vertices, an edge universe and an edge subset in bitmask representation, this function
+    /// returns the graph containing exactly the edges universe[i] for which bit i of the mask is set.
+    pub fn graph_from_edge_subset(number_of_vertices : usize, universe : &[(usize, usize)], mask : u64) -> Graph{
+        let mut graph : Graph = Graph::default();
 
-                    let q1 = children.get(0).unwrap();
-                    let q2 = children.get(1).unwrap();
+        // add vertices
+        for _ in 0..number_of_vertices {
+            graph.add_node(());
+        }
 
-                    let first : &TreeNode;
-                    let second : &TreeNode;
+        // add the edges selected by the mask
+        for (i, (u,v)) in universe.iter().enumerate(){
+            if mask & (1 << i) != 0 {
+                graph.add_edge(Vertex::new(*u),Vertex::new(*v), ());
+            }
+        }
 
-                    if possible_edges.get(q1).unwrap().len() >= possible_edges.get(q2).unwrap().len(){
-                        first = q1;
-                        second = q2;
-                    }
-                    else {
-                        first = q2;
-                        second = q1;
-                    }
+        graph
+    }
 
-                    let mut edges = possible_edges.get(first).unwrap().clone();
-                    // merge the edges
-                    for (u,v) in possible_edges.get(second).unwrap(){
-                        if !edge_in_list((*u, *v), &edges){
-                            edges.push((*u , *v));
-                        }
-                    }
-                    possible_edges.insert(p,edges);
-                }
-                None => ()
+    /// Given a number of vertices, a set of possible edges and a set of required edges this function
+    /// computes all graphs with the same number of vertices whose edge set lies between the required
+    /// and the possible edges. Hence only supersets of the required edges will be enumerated.
+    /// Panics if a required edge is not contained in the possible edges.
+    pub fn generate_graphs_containing(number_of_vertices: u64, possible_edges : Vec<(usize, usize)>, required_edges : &[(usize, usize)]) -> Vec<Graph>{
+
+        // every required edge has to be part of the edge universe
+        let required_edges = required_edges.to_vec();
+        for &(u,v) in &required_edges{
+            if !edge_in_list((u,v), &possible_edges){
+                panic!("Required edge {:?} is not a possible edge!", (u,v));
             }
         }
 
-        possible_edges
+        // only the edges which are not required are free to choose
+        let free_edges : Vec<(usize, usize)> = possible_edges.into_iter()
+            .filter(|&e| !edge_in_list(e, &required_edges))
+            .collect();
+
+        let mut graphs = vec![];
+
+        // iterate over the powerset of free edges
+        for edges in free_edges.iter().powerset(){
+            let mut graph : Graph = Graph::default();
+
+            // add vertices
+            for _ in 0..number_of_vertices {
+                graph.add_node(());
+            }
+
+            // add required and chosen edges
+            for (u,v) in required_edges.iter().chain(edges){
+                graph.add_edge(Vertex::new(*u),Vertex::new(*v), ());
+            }
+            graphs.push(graph);
+        }
+        graphs
     }
 
     /// Given a number of vertices and a set of possible edges this function computes all graphs
     /// with a subset of the possible edges and the same number of vertices.
-    pub fn generate_graphs(number_of_vertices: u64, possible_edges : Vec<(usize, usize)>) -> Vec<petgraph::matrix_graph::MatrixGraph<(),(), Undirected>>{
+    pub fn generate_graphs(number_of_vertices: u64, possible_edges : Vec<(usize, usize)>) -> Vec<Graph>{
 
         // list of graphsas
-        let mut graphs : Vec<petgraph::matrix_graph::MatrixGraph<(),(), Undirected>> = vec![];
+        let mut graphs : Vec<Graph> = vec![];
 
         // iterate over the powerset of possible edges
         for edges in possible_edges.iter().powerset().collect::<Vec<_>>(){
-            let mut graph : MatrixGraph<(), (), Undirected> = petgraph::matrix_graph::MatrixGraph::new_undirected();
+            let mut graph : Graph = Graph::default();
 
             // add vertices
             for i in 0..number_of_vertices {
@@ -103,19 +101,259 @@ pub mod graph_generation_algorithms {
 
             // add edges
             for (u,v) in edges{
-                graph.add_edge(NodeIndex::new(*u),NodeIndex::new(*v), ());
+                graph.add_edge(Vertex::new(*u),Vertex::new(*v), ());
             }
             graphs.push(graph);
         }
         graphs
     }
 
+    /// Given a number of vertices, a set of possible edges and a predicate this function computes all graphs
+    /// with a subset of the possible edges and the same number of vertices, which satisfy the predicate.
+    /// Graphs which do not satisfy the predicate are dropped right after their construction.
+    pub fn generate_graphs_filtered<F>(number_of_vertices: u64, possible_edges : Vec<(usize, usize)>, predicate : F) -> Vec<Graph>
+        where F: Fn(&Graph) -> bool
+    {
+        let mut graphs = vec![];
+
+        // iterate over the powerset of possible edges
+        for edges in possible_edges.iter().powerset(){
+            let mut graph : Graph = Graph::default();
+
+            // add vertices
+            for _ in 0..number_of_vertices {
+                graph.add_node(());
+            }
+
+            // add edges
+            for (u,v) in edges{
+                graph.add_edge(Vertex::new(*u),Vertex::new(*v), ());
+            }
+
+            if predicate(&graph){
+                graphs.push(graph);
+            }
+        }
+        graphs
+    }
+
+    /// Given a number of vertices and a set of possible edges this function computes all connected graphs
+    /// with a subset of the possible edges and the same number of vertices.
+    pub fn generate_connected_graphs(number_of_vertices: u64, possible_edges : Vec<(usize, usize)>) -> Vec<Graph>{
+        generate_graphs_filtered(number_of_vertices, possible_edges, is_connected)
+    }
+
+    /// Checks if the given graph is connected by running a depth first search starting at vertex 0.
+    /// The graph without vertices is considered to be connected.
+    pub fn is_connected(graph : &Graph) -> bool{
+        let n = graph.node_count();
+        if n == 0 { return true; }
+
+        let mut visited = vec![false; n];
+        let mut stack = vec![graph.from_index(0)];
+        visited[0] = true;
+        let mut number_of_visited = 1;
+
+        while let Some(u) = stack.pop(){
+            for w in graph.neighbors(u){
+                if !visited[w.index()]{
+                    visited[w.index()] = true;
+                    number_of_visited += 1;
+                    stack.push(w);
+                }
+            }
+        }
+
+        number_of_visited == n
+    }
+
+    /// Returns the number of connected components of the given graph by depth first search.
+    pub fn number_of_components(graph : &Graph) -> usize{
+        connected_components(graph).len()
+    }
+
+    /// Returns the vertex sets of the connected components of the given graph by depth first search.
+    /// The components are ordered by their smallest vertex and the vertices of each component are sorted.
+    pub fn connected_components(graph : &Graph) -> Vec<Vec<usize>>{
+        let n = graph.node_count();
+        let mut visited = vec![false; n];
+        let mut components = vec![];
+
+        for start in 0..n{
+            if visited[start] { continue; }
+            let mut component = vec![start];
+
+            let mut stack = vec![graph.from_index(start)];
+            visited[start] = true;
+            while let Some(u) = stack.pop(){
+                for w in graph.neighbors(u){
+                    if !visited[w.index()]{
+                        visited[w.index()] = true;
+                        component.push(w.index());
+                        stack.push(w);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Returns the graph on the vertices 0,..,n-1 containing the edge {u,v} if and only if adjacent(u,v) holds
+    /// for u <= v.
+    fn graph_from_adjacency<F>(n : usize, adjacent : F) -> Graph
+        where F: Fn(usize, usize) -> bool
+    {
+        let mut graph : Graph = MatrixGraph::with_capacity(n);
+        for _ in 0..n {
+            graph.add_node(());
+        }
+
+        for u in 0..n{
+            for v in u..n{
+                if adjacent(u, v) { graph.add_edge(Vertex::new(u), Vertex::new(v), ()); }
+            }
+        }
+
+        graph
+    }
+
+    /// Returns the hypercube Q_d, whose vertices are the bit strings of length d, where two bit strings
+    /// are adjacent if they differ in exactly one bit.
+    pub fn hypercube_graph(d : u32) -> Graph{
+        graph_from_adjacency(1 << d, |u, v| (u ^ v).count_ones() == 1)
+    }
+
+    /// Returns the circulant graph on n vertices, where u and v are adjacent if u - v or v - u is one of the
+    /// given jumps modulo n. A jump which is a multiple of n adds a loop to every vertex.
+    pub fn circulant_graph(n : usize, jumps : &[usize]) -> Graph{
+        let jumps : Vec<usize> = jumps.iter().map(|&jump| jump % n.max(1)).collect();
+        graph_from_adjacency(n, |u, v| {
+            let difference = (v - u) % n;
+            jumps.contains(&difference) || jumps.contains(&((n - difference) % n))
+        })
+    }
+
+    /// Returns the kneser graph K(n,k), whose vertices are the k-subsets of {0,..,n-1} in lexicographic order,
+    /// where two subsets are adjacent if they are disjoint. K(5,2) is the petersen graph.
+    /// Panics if n is larger than 64.
+    pub fn kneser_graph(n : usize, k : usize) -> Graph{
+        assert!(n <= 64, "The kneser graph is only supported for n <= 64!");
+
+        let subsets : Vec<u64> = (0..n).combinations(k)
+            .map(|subset| subset.iter().fold(0, |mask, &i| mask | (1 << i)))
+            .collect();
+        graph_from_adjacency(subsets.len(), |u, v| subsets[u] & subsets[v] == 0)
+    }
+
+    /// Returns all trees with 1 to k vertices up to isomorphism, ordered by their number of vertices.
+    /// The trees with n vertices are obtained by attaching a leaf to every vertex of every tree with n-1
+    /// vertices, where isomorphic copies are detected by the canonical form of the trees.
+    pub fn generate_trees(k : usize) -> Vec<Graph>{
+        let mut trees : Vec<Vec<(usize, usize)>> = if k == 0 { vec![] } else { vec![vec![]] };
+        let mut smaller = 0;
+
+        for n in 2..=k{
+            let larger = trees.len();
+            let mut seen = HashSet::new();
+
+            for i in smaller..larger{
+                for v in 0..n - 1{
+                    let mut edges = trees[i].clone();
+                    edges.push((v, n - 1));
+                    if seen.insert(tree_canonical_form(n, &edges)) { trees.push(edges); }
+                }
+            }
+            smaller = larger;
+        }
+
+        trees.iter()
+            .map(|edges| {
+                let n = edges.len() + 1;
+                let edges = edges.clone();
+                graph_from_adjacency(n, |u, v| edges.contains(&(u, v)) || edges.contains(&(v, u)))
+            })
+            .collect()
+    }
+
+    /// Returns the canonical form of the tree on n vertices with the given edges, i.e. the smallest encoding
+    /// of the tree rooted at one of its centers, where each vertex is encoded by the sorted encodings of its
+    /// children in parentheses. Two trees are isomorphic if and only if their canonical forms are equal.
+    fn tree_canonical_form(n : usize, edges : &[(usize, usize)]) -> String{
+        let mut neighbours = vec![vec![]; n];
+        for &(u, v) in edges{
+            neighbours[u].push(v);
+            neighbours[v].push(u);
+        }
+
+        // the centers are the one or two vertices remaining after removing the leaves repeatedly
+        let mut degree : Vec<usize> = neighbours.iter().map(|list| list.len()).collect();
+        let mut leaves : Vec<usize> = (0..n).filter(|&v| degree[v] <= 1).collect();
+        let mut remaining = n;
+        while remaining > 2 {
+            remaining -= leaves.len();
+            let mut next_leaves = vec![];
+            for &leaf in &leaves{
+                for &w in &neighbours[leaf]{
+                    degree[w] -= 1;
+                    if degree[w] == 1 { next_leaves.push(w); }
+                }
+            }
+            leaves = next_leaves;
+        }
+
+        fn encode(v : usize, parent : Option<usize>, neighbours : &[Vec<usize>]) -> String{
+            let mut children : Vec<String> = neighbours[v].iter()
+                .filter(|&&w| Some(w) != parent)
+                .map(|&w| encode(w, Some(v), neighbours))
+                .collect();
+            children.sort();
+            format!("({})", children.concat())
+        }
+
+        leaves.iter().map(|&center| encode(center, None, &neighbours)).min().unwrap_or_default()
+    }
+
+    /// Returns the canonical form of the graph, i.e. a string which is equal for two graphs if and only if they
+    /// are isomorphic. It consists of the number of vertices and the edges of the relabeling with the
+    /// lexicographically largest adjacency matrix, e.g. "3: 0-2 1-2" for a path on three vertices. Only vertices
+    /// of the same color in the stable coloring of color refinement are permuted among each other.
+    /// note: the permutations of each color class are enumerated explicitly, which is only feasible for small patterns
+    pub fn canonical_form(graph : &Graph) -> String{
+        let n = graph.node_count();
+        let has_edge = |u : usize, v : usize| graph.has_edge(graph.from_index(u), graph.from_index(v));
+
+        // the vertices ordered by their colors, which are invariant under isomorphisms
+        let colors = color_refinement(graph);
+        let mut order : Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&v| colors[v]);
+        let classes : Vec<&[usize]> = order.chunk_by(|&u, &v| colors[u] == colors[v]).collect();
+
+        // the relabeling places the vertex labeling[i] at position i
+        let labelings = classes.iter()
+            .map(|class| class.iter().copied().permutations(class.len()))
+            .multi_cartesian_product()
+            .map(|permutations| permutations.concat());
+        let adjacency = |labeling : &[usize]| -> Vec<bool> {
+            (0..n).flat_map(|i| (i..n).map(move |j| (i, j))).map(|(i, j)| has_edge(labeling[i], labeling[j])).collect()
+        };
+        let best = labelings.max_by_key(|labeling| adjacency(labeling)).unwrap_or_default();
+
+        let edges : Vec<String> = (0..n).flat_map(|i| (i..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| has_edge(best[i], best[j]))
+            .map(|(i, j)| format!("{}-{}", i, j))
+            .collect();
+        format!("{}: {}", n, edges.join(" ")).trim_end().to_string()
+    }
 
     /// This function checks if two given graphs are identical. (not isomorphic)
     /// This is just a naive implementation for testing
     /// todo: If not needed later, move it to the test module
-    pub fn equal_graphs(a : &petgraph::matrix_graph::MatrixGraph<(),(), Undirected>,
-                        b : &petgraph::matrix_graph::MatrixGraph<(),(), Undirected>)  -> bool{
+    pub fn equal_graphs(a : &Graph,
+                        b : &Graph)  -> bool{
 
         // Checks if nodes are equal
         if a.node_count() != b.node_count() {return false;}
@@ -0,0 +1,109 @@
+/// A module containing basic operations constructing new graphs from a given graph, i.e. relabeling the
+/// vertices, taking induced subgraphs and identifying or contracting vertices. Loops are kept by all
+/// operations unless stated otherwise.
+pub mod graph_operations {
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// Returns the graph without edges on n vertices.
+    fn empty_graph(n : usize) -> Graph{
+        let mut graph = Graph::default();
+        for _ in 0..n{
+            graph.add_node(());
+        }
+        graph
+    }
+
+    /// Adds the edge if it is not contained yet.
+    fn add_edge(graph : &mut Graph, u : usize, v : usize){
+        if !graph.has_edge(Vertex::new(u), Vertex::new(v)) { graph.add_edge(Vertex::new(u), Vertex::new(v), ()); }
+    }
+
+    /// Returns all edges (u,v) with u <= v.
+    fn edges(graph : &Graph) -> Vec<(usize, usize)>{
+        let n = graph.node_count();
+        (0..n).flat_map(|u| (u..n).map(move |v| (u, v)))
+            .filter(|&(u, v)| graph.has_edge(Vertex::new(u), Vertex::new(v)))
+            .collect()
+    }
+
+    /// Returns the isomorphic graph in which the vertex v is relabeled to permutation[v].
+    /// Panics if the permutation is not a permutation of the vertices.
+    pub fn permute_vertices(graph : &Graph, permutation : &[usize]) -> Graph{
+        let n = graph.node_count();
+        let mut used = vec![false; n];
+        for &v in permutation{
+            assert!(v < n && !used[v], "The permutation is not a permutation of the vertices!");
+            used[v] = true;
+        }
+        assert_eq!(permutation.len(), n, "The permutation is not a permutation of the vertices!");
+
+        let mut permuted = empty_graph(n);
+        for (u, v) in edges(graph){
+            add_edge(&mut permuted, permutation[u], permutation[v]);
+        }
+        permuted
+    }
+
+    /// Returns the subgraph induced by the given vertices, where the i-th given vertex becomes the vertex i.
+    /// Panics if a vertex is given twice or does not exist.
+    pub fn induced_subgraph(graph : &Graph, vertices : &[usize]) -> Graph{
+        let mut position = vec![None; graph.node_count()];
+        for (i, &v) in vertices.iter().enumerate(){
+            assert!(v < graph.node_count() && position[v].is_none(), "The vertices have to be distinct vertices of the graph!");
+            position[v] = Some(i);
+        }
+
+        let mut subgraph = empty_graph(vertices.len());
+        for (u, v) in edges(graph){
+            if let (Some(i), Some(j)) = (position[u], position[v]) { add_edge(&mut subgraph, i, j); }
+        }
+        subgraph
+    }
+
+    /// Returns the graph in which the vertices u and v are identified, i.e. the quotient graph of the
+    /// partition with the single non-trivial class {u, v}. The merged vertex is min(u, v) and the vertices
+    /// greater than max(u, v) are shifted down by one. An edge between u and v becomes a loop.
+    pub fn identify_vertices(graph : &Graph, u : usize, v : usize) -> Graph{
+        let n = graph.node_count();
+        assert!(u < n && v < n && u != v, "The vertices have to be distinct vertices of the graph!");
+
+        let (kept, removed) = (u.min(v), u.max(v));
+        let image = |w : usize| match w {
+            w if w == removed => kept,
+            w if w > removed => w - 1,
+            w => w,
+        };
+
+        let mut quotient = empty_graph(n - 1);
+        for (a, b) in edges(graph){
+            add_edge(&mut quotient, image(a), image(b));
+        }
+        quotient
+    }
+
+    /// Returns the graph in which the edge {u, v} is contracted, i.e. the vertices u and v are identified as
+    /// in identify_vertices without creating a loop from the edge. Loops at u or v are kept.
+    /// Panics if {u, v} is not an edge.
+    pub fn contract_edge(graph : &Graph, u : usize, v : usize) -> Graph{
+        assert!(u != v && graph.has_edge(Vertex::new(u), Vertex::new(v)), "The edge to contract is not an edge between distinct vertices!");
+
+        let mut contracted = identify_vertices(graph, u, v);
+        let had_loop = graph.has_edge(Vertex::new(u), Vertex::new(u)) || graph.has_edge(Vertex::new(v), Vertex::new(v));
+        let merged = Vertex::new(u.min(v));
+        if !had_loop { contracted.remove_edge(merged, merged); }
+        contracted
+    }
+
+    /// Returns the disjoint union of both graphs, where the vertex v of b becomes the vertex |V(a)| + v.
+    pub fn disjoint_union(a : &Graph, b : &Graph) -> Graph{
+        let offset = a.node_count();
+        let mut union = empty_graph(offset + b.node_count());
+        for (u, v) in edges(a){
+            add_edge(&mut union, u, v);
+        }
+        for (u, v) in edges(b){
+            add_edge(&mut union, offset + u, offset + v);
+        }
+        union
+    }
+}
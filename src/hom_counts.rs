@@ -0,0 +1,89 @@
+/// A module containing a container for the results of the algorithms which count homomorphisms from a set
+/// of patterns, e.g. all graphs of $H_\tau$, into a target graph. The results can be exported to csv and
+/// json files, where each pattern is written as its number of vertices and its list of edges.
+pub mod hom_count_results {
+    use std::fs::File;
+    use std::io;
+    use std::io::Write;
+    use std::path::Path;
+    use petgraph::visit::NodeIndexable;
+    use crate::modified_dp::algorithm::HomNumberList;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// A list of patterns together with their number of homomorphisms into a fixed target graph.
+    pub struct HomCounts {
+        entries: HomNumberList,
+    }
+
+    impl HomCounts {
+
+        /// Creates a new container from the given list of patterns and homomorphism numbers.
+        pub fn new(entries : HomNumberList) -> HomCounts { HomCounts { entries } }
+
+        /// Returns the patterns together with their homomorphism numbers.
+        pub fn entries(&self) -> &HomNumberList { &self.entries }
+
+        /// Returns the number of patterns.
+        pub fn len(&self) -> usize { self.entries.len() }
+
+        /// Returns true if the container contains no patterns.
+        pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+        /// Writes the results into a csv file with the columns vertices, edges and count.
+        /// The edges are written as a space separated list of "u-v" pairs.
+        pub fn write_csv<P>(&self, path : P) -> io::Result<()>
+            where P: AsRef<Path>
+        {
+            let mut wtr = csv::Writer::from_path(path)?;
+            wtr.write_record(["vertices", "edges", "count"])?;
+
+            for (graph, count) in &self.entries{
+                let edges = edge_list(graph).iter()
+                    .map(|(u,v)| format!("{}-{}", u, v))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                wtr.write_record([graph.node_count().to_string(), edges, count.to_string()])?;
+            }
+
+            wtr.flush()
+        }
+
+        /// Writes the results into a json file as an array of objects of the form
+        /// {"vertices": n, "edges": [[u,v], ...], "count": c}.
+        pub fn write_json<P>(&self, path : P) -> io::Result<()>
+            where P: AsRef<Path>
+        {
+            let mut file = File::create(path)?;
+
+            let objects = self.entries.iter()
+                .map(|(graph, count)| {
+                    let edges = edge_list(graph).iter()
+                        .map(|(u,v)| format!("[{},{}]", u, v))
+                        .collect::<Vec<String>>()
+                        .join(",");
+                    format!("  {{\"vertices\": {}, \"edges\": [{}], \"count\": {}}}", graph.node_count(), edges, count)
+                })
+                .collect::<Vec<String>>();
+
+            writeln!(file, "[")?;
+            writeln!(file, "{}", objects.join(",\n"))?;
+            writeln!(file, "]")
+        }
+    }
+
+    impl From<HomNumberList> for HomCounts {
+        fn from(entries : HomNumberList) -> HomCounts { HomCounts::new(entries) }
+    }
+
+    /// Returns the edges (u,v) with u <= v of the given graph in lexicographic order.
+    fn edge_list(graph : &Graph) -> Vec<(usize, usize)>{
+        let n = graph.node_count();
+        let mut edges = vec![];
+        for u in 0..n{
+            for v in u..n{
+                if graph.has_edge(graph.from_index(u), graph.from_index(v)) { edges.push((u,v)); }
+            }
+        }
+        edges
+    }
+}
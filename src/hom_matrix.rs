@@ -0,0 +1,104 @@
+/// A module containing the homomorphism matrix of a set of patterns and a set of target graphs, i.e. the matrix
+/// whose entry (i, j) is the number of homomorphisms from the i-th pattern into the j-th target. The rows of
+/// such matrices are the homomorphism vectors used to compare the expressiveness of graph invariants.
+/// Everything which only depends on one side is computed once per graph instead of once per entry: the nice
+/// tree decompositions of the connected components of each pattern and the invariants of the structural checks.
+pub mod homomorphism_matrix {
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::parallelism::parallel_execution;
+    use crate::pipeline::counting_pipeline::{component_graphs, compute_nice_tree_decomposition};
+    use crate::prefilter::feasibility_prefilter::{degree_sequence, loop_count};
+    use crate::structural_checks::structural_shortcuts::odd_girth;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The invariants of a graph needed by the structural checks.
+    struct Invariants {
+        vertices: usize,
+        has_loops: bool,
+        has_edges: bool,
+        odd_girth: Option<usize>,
+    }
+
+    impl Invariants {
+        fn new(graph : &Graph) -> Invariants {
+            Invariants {
+                vertices: graph.node_count(),
+                has_loops: loop_count(graph) > 0,
+                has_edges: degree_sequence(graph).first().is_some_and(|&degree| degree > 0),
+                odd_girth: odd_girth(graph),
+            }
+        }
+    }
+
+    /// A pattern split into its connected components together with their nice tree decompositions.
+    struct PreparedPattern {
+        components: Vec<(Graph, NiceTreeDecomposition)>,
+        invariants: Invariants,
+    }
+
+    impl PreparedPattern {
+        fn new(graph : &Graph) -> PreparedPattern {
+            let components = component_graphs(graph).into_iter()
+                .map(|component| {
+                    let ntd = compute_nice_tree_decomposition(&component);
+                    (component, ntd)
+                })
+                .collect();
+            PreparedPattern { components, invariants: Invariants::new(graph) }
+        }
+    }
+
+    /// Returns true if one of the checks of the prefilter or the structural checks certifies that there is no
+    /// homomorphism from the pattern into the target.
+    fn known_zero(pattern : &Invariants, target : &Invariants) -> bool {
+        let odd_girth_violated = match (pattern.odd_girth, target.odd_girth) {
+            (Some(_), None) => true,
+            (Some(pattern_girth), Some(target_girth)) => pattern_girth < target_girth,
+            (None, _) => false,
+        };
+
+        (pattern.vertices > 0 && target.vertices == 0)
+            || (pattern.has_loops && !target.has_loops)
+            || (pattern.has_edges && !target.has_edges && !target.has_loops)
+            || odd_girth_violated
+    }
+
+    /// Counts the homomorphisms from the prepared pattern into the target as the product over its components.
+    /// Panics if the product does not fit into 64 bits.
+    fn count(pattern : &PreparedPattern, to_graph : &Graph, target : &Invariants) -> u64 {
+        if known_zero(&pattern.invariants, target) { return 0; }
+
+        let mut count : u64 = 1;
+        for (component, ntd) in &pattern.components{
+            if count == 0 { break; }
+            count = count.checked_mul(diaz_serna_thilikos_algorithm(component, ntd, to_graph))
+                .expect("The number of homomorphisms does not fit into 64 bits!");
+        }
+        count
+    }
+
+    /// Returns the homomorphism matrix whose entry [i][j] is the number of homomorphisms from patterns[i] into
+    /// targets[j], computed by the algorithm of diaz et all. Panics if an entry does not fit into 64 bits.
+    pub fn hom_matrix(patterns : &[Graph], targets : &[Graph]) -> Vec<Vec<u64>> {
+        hom_matrix_with_threads(patterns, targets, 1)
+    }
+
+    /// Computes the homomorphism matrix as in hom_matrix, where the patterns are prepared and the entries are
+    /// computed in parallel by at most the given number of threads. Without the "parallel" feature everything
+    /// is computed in the calling thread. Panics if an entry does not fit into 64 bits.
+    pub fn hom_matrix_with_threads(patterns : &[Graph], targets : &[Graph], threads : usize) -> Vec<Vec<u64>> {
+        parallel_execution::with_threads(threads.max(1), || {
+            let prepared_patterns = parallel_execution::map_range(patterns.len(), |i| PreparedPattern::new(&patterns[i]));
+            let target_invariants : Vec<Invariants> = targets.iter().map(Invariants::new).collect();
+
+            let columns = targets.len();
+            let counts = parallel_execution::map_range(patterns.len() * columns, |k| {
+                count(&prepared_patterns[k / columns], &targets[k % columns], &target_invariants[k % columns])
+            });
+
+            if columns == 0 { return vec![vec![]; patterns.len()]; }
+            counts.chunks(columns).map(|row| row.to_vec()).collect()
+        })
+    }
+}
@@ -0,0 +1,59 @@
+/// A module containing the numbers of homomorphisms broken down by the size of their image, i.e. by the number
+/// of distinct target vertices they use. Let h_j be the sum of hom(H, G[T]) over all sets T of j target vertices
+/// and N_k the number of homomorphisms whose image has exactly k vertices. Every homomorphism with image S is
+/// counted in h_j once for each of the binom(n-k, j-k) supersets T of S, hence inverting
+/// $h_j = \sum_k binom(n-k, j-k) N_k$ gives $N_k = \sum_{j <= k} (-1)^(k-j) binom(n-j, k-j) h_j$.
+/// Since the image of a pattern H has at most |V(H)| vertices, only sets T with up to |V(H)| vertices are counted.
+pub mod image_size_counting {
+    use itertools::Itertools;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_root_table;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// Returns the vector c where c[k] is the number of homomorphisms from "from_graph" into "to_graph" whose
+    /// image consists of exactly k vertices, for k = 0,..,min(|V(H)|, |V(G)|). The entries sum up to hom(H, G).
+    /// The algorithm of diaz et all is run once for each set of at most |V(H)| target vertices, where the images
+    /// are restricted to the set, hence this is meant for small patterns or small targets.
+    pub fn hom_counts_by_image_size(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<u64>{
+        let n = to_graph.node_count();
+        let max_image_size = from_graph.node_count().min(n);
+
+        // h[j] = the sum of hom(H, G[T]) over all sets T of j target vertices, only the empty pattern maps into G[{}]
+        let mut h : Vec<i128> = vec![(from_graph.node_count() == 0) as i128];
+        for j in 1..=max_image_size{
+            let sum = (0..n).combinations(j)
+                .map(|subset| {
+                    let mut allowed = vec![false; n];
+                    for a in subset { allowed[a] = true; }
+                    let feasible = vec![allowed; from_graph.node_count()];
+                    *diaz_serna_thilikos_root_table(from_graph, ntd, to_graph, &feasible).get(&0).unwrap_or(&0) as i128
+                })
+                .sum();
+            h.push(sum);
+        }
+
+        (0..=max_image_size)
+            .map(|k| {
+                let count : i128 = (0..=k)
+                    .map(|j| {
+                        let sign = if (k - j) % 2 == 0 { 1 } else { -1 };
+                        sign * binomial(n - j, k - j) * h[j]
+                    })
+                    .sum();
+                count as u64
+            })
+            .collect()
+    }
+
+    /// Returns the number of surjective homomorphisms from "from_graph" onto the vertices of "to_graph", e.g. the
+    /// number of colorings of the pattern with exactly n colors for the complete graph K_n as target.
+    pub fn surjective_hom_count(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> u64{
+        if to_graph.node_count() > from_graph.node_count() { return 0; }
+        hom_counts_by_image_size(from_graph, ntd, to_graph)[to_graph.node_count()]
+    }
+
+    /// Returns binom(n, k).
+    fn binomial(n : usize, k : usize) -> i128{
+        (0..k).fold(1, |value, i| value * (n - i) as i128 / (i + 1) as i128)
+    }
+}
@@ -0,0 +1,202 @@
+/// A module containing the algorithm of diaz et all for target graphs which are given implicitly by their
+/// number of vertices and a closure deciding adjacency, e.g. Kneser or circulant graphs, which are too large
+/// or too regular to be stored as an adjacency matrix. Only the leaf and introduce nodes look at the target,
+/// hence the closure is the only representation of it which is needed. Adapters like powers, complements and
+/// products answer the adjacency queries of compound targets by queries to their factors.
+pub mod implicit_target_counting {
+    use petgraph::matrix_graph::MatrixGraph;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{weighted_diaz_serna_thilikos, NodeTable, Weights};
+    use crate::integer_functions::integer_functions_methods::Mapping;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// A target graph on the vertices 0,..,n-1 which is only accessed by adjacency queries, such that it does
+    /// not have to be stored as an adjacency matrix. The adjacency has to be symmetric, has_edge(a, a)
+    /// decides whether a has a loop.
+    pub trait AdjacencyOracle {
+        fn node_count(&self) -> usize;
+
+        fn has_edge(&self, a : usize, b : usize) -> bool;
+
+        /// Materializes the graph as an adjacency matrix, which needs n² bits.
+        fn to_matrix_graph(&self) -> Graph {
+            let n = self.node_count();
+            let mut graph = Graph::default();
+            let vertices : Vec<_> = (0..n).map(|_| graph.add_node(())).collect();
+            for a in 0..n{
+                for b in a..n{
+                    if self.has_edge(a, b) { graph.add_edge(vertices[a], vertices[b], ()); }
+                }
+            }
+            graph
+        }
+    }
+
+    impl AdjacencyOracle for Graph {
+        fn node_count(&self) -> usize { MatrixGraph::node_count(self) }
+
+        fn has_edge(&self, a : usize, b : usize) -> bool { MatrixGraph::has_edge(self, Vertex::new(a), Vertex::new(b)) }
+    }
+
+    /// A target graph on the vertices 0,..,n-1 whose edges are given by a symmetric closure,
+    /// where adjacency(a, a) decides whether a has a loop.
+    pub struct ImplicitGraph<F>
+        where F: Fn(usize, usize) -> bool
+    {
+        n: usize,
+        adjacency: F,
+    }
+
+    impl<F> ImplicitGraph<F>
+        where F: Fn(usize, usize) -> bool
+    {
+        pub fn new(n : usize, adjacency : F) -> ImplicitGraph<F> { ImplicitGraph { n, adjacency } }
+    }
+
+    impl<F> AdjacencyOracle for ImplicitGraph<F>
+        where F: Fn(usize, usize) -> bool
+    {
+        fn node_count(&self) -> usize { self.n }
+
+        fn has_edge(&self, a : usize, b : usize) -> bool { (self.adjacency)(a, b) }
+    }
+
+    /// The k-th power of a graph, where two different vertices are adjacent if their distance is at most k.
+    /// The loops of the graph are kept. Each query runs a breadth first search of depth k.
+    pub struct Power<'a, O : AdjacencyOracle> {
+        graph: &'a O,
+        k: usize,
+    }
+
+    impl<'a, O : AdjacencyOracle> Power<'a, O> {
+        pub fn new(graph : &'a O, k : usize) -> Power<'a, O> { Power { graph, k } }
+    }
+
+    impl<'a, O : AdjacencyOracle> AdjacencyOracle for Power<'a, O> {
+        fn node_count(&self) -> usize { self.graph.node_count() }
+
+        fn has_edge(&self, a : usize, b : usize) -> bool {
+            if a == b { return self.graph.has_edge(a, a); }
+
+            let mut visited = vec![false; self.node_count()];
+            visited[a] = true;
+            let mut frontier = vec![a];
+
+            for _ in 0..self.k{
+                let mut next = vec![];
+                for &c in &frontier{
+                    for (d, is_visited) in visited.iter_mut().enumerate(){
+                        if *is_visited || !self.graph.has_edge(c, d) { continue; }
+                        if d == b { return true; }
+                        *is_visited = true;
+                        next.push(d);
+                    }
+                }
+                if next.is_empty() { break; }
+                frontier = next;
+            }
+            false
+        }
+    }
+
+    /// The complement of a graph, where two different vertices are adjacent if they are not adjacent in the
+    /// graph. The complement has no loops.
+    pub struct Complement<'a, O : AdjacencyOracle> {
+        graph: &'a O,
+    }
+
+    impl<'a, O : AdjacencyOracle> Complement<'a, O> {
+        pub fn new(graph : &'a O) -> Complement<'a, O> { Complement { graph } }
+    }
+
+    impl<'a, O : AdjacencyOracle> AdjacencyOracle for Complement<'a, O> {
+        fn node_count(&self) -> usize { self.graph.node_count() }
+
+        fn has_edge(&self, a : usize, b : usize) -> bool { a != b && !self.graph.has_edge(a, b) }
+    }
+
+    /// The tensor product G x H, whose vertex (a, b) is represented by a * |V(H)| + b. Two vertices (a, b) and
+    /// (c, d) are adjacent if a is adjacent to c in G and b is adjacent to d in H, hence
+    /// hom(F, G x H) = hom(F, G) * hom(F, H).
+    pub struct TensorProduct<'a, G : AdjacencyOracle, H : AdjacencyOracle> {
+        first: &'a G,
+        second: &'a H,
+    }
+
+    impl<'a, G : AdjacencyOracle, H : AdjacencyOracle> TensorProduct<'a, G, H> {
+        pub fn new(first : &'a G, second : &'a H) -> TensorProduct<'a, G, H> { TensorProduct { first, second } }
+    }
+
+    impl<'a, G : AdjacencyOracle, H : AdjacencyOracle> AdjacencyOracle for TensorProduct<'a, G, H> {
+        fn node_count(&self) -> usize { self.first.node_count() * self.second.node_count() }
+
+        fn has_edge(&self, a : usize, b : usize) -> bool {
+            let n = self.second.node_count();
+            self.first.has_edge(a / n, b / n) && self.second.has_edge(a % n, b % n)
+        }
+    }
+
+    /// The lexicographic product G[H], whose vertex (a, b) is represented by a * |V(H)| + b. Two vertices
+    /// (a, b) and (c, d) are adjacent if a is adjacent to c in G, or if a = c and b is adjacent to d in H,
+    /// i.e. every vertex of G is replaced by a copy of H.
+    pub struct LexicographicProduct<'a, G : AdjacencyOracle, H : AdjacencyOracle> {
+        first: &'a G,
+        second: &'a H,
+    }
+
+    impl<'a, G : AdjacencyOracle, H : AdjacencyOracle> LexicographicProduct<'a, G, H> {
+        pub fn new(first : &'a G, second : &'a H) -> LexicographicProduct<'a, G, H> { LexicographicProduct { first, second } }
+    }
+
+    impl<'a, G : AdjacencyOracle, H : AdjacencyOracle> AdjacencyOracle for LexicographicProduct<'a, G, H> {
+        fn node_count(&self) -> usize { self.first.node_count() * self.second.node_count() }
+
+        fn has_edge(&self, a : usize, b : usize) -> bool {
+            let n = self.second.node_count();
+            self.first.has_edge(a / n, b / n) || (a / n == b / n && self.second.has_edge(a % n, b % n))
+        }
+    }
+
+    /// Counts the homomorphisms from "from_graph" into the target on the vertices 0,..,n-1 whose edges are
+    /// given by the symmetric closure "adjacency". The tables only store non-zero entries, such that their size
+    /// depends on the number of partial homomorphisms instead of the n^(w+1) mappings of a bag. Panics if
+    /// the mappings of the largest bag or the number of homomorphisms cannot be represented by a u64.
+    pub fn diaz_serna_thilikos_implicit<F>(from_graph : &Graph, ntd : &NiceTreeDecomposition, n : usize, adjacency : F) -> u64
+        where F: Fn(usize, usize) -> bool
+    {
+        count_into(from_graph, ntd, &ImplicitGraph::new(n, adjacency))
+    }
+
+    /// Counts the homomorphisms from "from_graph" into the target given by its adjacency queries, e.g. an
+    /// ImplicitGraph or an adapter like Power or TensorProduct, see diaz_serna_thilikos_implicit.
+    pub fn count_into<O>(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &O) -> u64
+        where O: AdjacencyOracle
+    {
+        let n = to_graph.node_count() as Mapping;
+
+        let max_bag_size = ntd.stingy_ordering().iter().map(|&p| ntd.bit_bag(p).unwrap().len()).max().unwrap_or(0);
+        assert!(n.checked_pow(max_bag_size as u32).is_some(), "The mappings of the largest bag do not fit into 64 bits!");
+
+        weighted_diaz_serna_thilikos::<NodeTable, _>(from_graph, ntd, &OracleWeights { from_graph, to_graph })
+            .expect("The number of homomorphisms does not fit into 64 bits!")
+    }
+
+    /// The weights 0 and 1 of the adjacency queries of a target, such that the weighted dynamic program
+    /// counts the homomorphisms into it.
+    struct OracleWeights<'a, O : AdjacencyOracle> {
+        from_graph: &'a Graph,
+        to_graph: &'a O,
+    }
+
+    impl<'a, O : AdjacencyOracle> Weights for OracleWeights<'a, O> {
+        fn node_count(&self) -> usize { self.to_graph.node_count() }
+
+        fn vertex_weight(&self, v : Vertex, a : Mapping) -> Option<u64> {
+            Some((!self.from_graph.has_edge(v, v) || self.to_graph.has_edge(a as usize, a as usize)) as u64)
+        }
+
+        fn edge_weight(&self, _u : Vertex, _v : Vertex, a : Mapping, b : Mapping) -> Option<u64> {
+            Some(self.to_graph.has_edge(a as usize, b as usize) as u64)
+        }
+    }
+}
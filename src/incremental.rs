@@ -0,0 +1,263 @@
+/// A module containing an incremental version of the algorithm of diaz et all. The tables of all
+/// nodes are kept after the initial computation, such that the number of homomorphisms can be updated
+/// after inserting or removing a single edge of the target graph without redoing the whole dynamic program.
+pub mod incremental_counting {
+    use std::collections::{HashMap, HashSet};
+    use petgraph::visit::NodeIndexable;
+    use crate::integer_functions::integer_functions_methods;
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
+
+    /// Counts the homomorphisms from a fixed pattern into a target graph which changes over time.
+    /// The table I[p,f] of every node is stored, where only non-zero entries are kept.
+    pub struct IncrementalCounter<'a> {
+        from_graph: &'a Graph,
+        nice_tree_decomposition: &'a NiceTreeDecomposition,
+        to_graph: Graph,
+        tables: HashMap<TreeNode, HashMap<Mapping, u64>>,
+    }
+
+    impl<'a> IncrementalCounter<'a> {
+
+        /// Runs the dynamic program on a copy of the given target graph and keeps all tables.
+        pub fn new(from_graph : &'a Graph, nice_tree_decomposition : &'a NiceTreeDecomposition, to_graph : &Graph) -> IncrementalCounter<'a>{
+            let mut counter = IncrementalCounter { from_graph, nice_tree_decomposition, to_graph: to_graph.clone(), tables: HashMap::new() };
+            let n = counter.to_graph.node_count() as Mapping;
+
+            for p in nice_tree_decomposition.stingy_ordering(){
+                // candidates for non-zero entries of p, all other entries are 0
+                let candidates : HashSet<Mapping> = match nice_tree_decomposition.node_type(p){
+                    Some(NodeType::Leaf) => (0..n).collect(),
+                    Some(NodeType::Introduce) => {
+                        let q = *nice_tree_decomposition.unique_child(p).unwrap();
+                        let new_index = counter.introduced_significance(p);
+                        counter.table(q).keys()
+                            .flat_map(|&f_q| (0..n).map(move |a| integer_functions_methods::extend(n, f_q, new_index, a)))
+                            .collect()
+                    }
+                    Some(NodeType::Forget) => {
+                        let q = *nice_tree_decomposition.unique_child(p).unwrap();
+                        let significance = counter.forgotten_significance(p);
+                        counter.table(q).keys().map(|&f_q| integer_functions_methods::reduce(n, f_q, significance)).collect()
+                    }
+                    Some(NodeType::Join) => {
+                        let q1 = nice_tree_decomposition.children(p).unwrap()[0];
+                        counter.table(q1).keys().copied().collect()
+                    }
+                    None => HashSet::new(),
+                };
+
+                counter.tables.insert(p, HashMap::new());
+                counter.recompute(p, candidates);
+            }
+
+            counter
+        }
+
+        /// Returns the current number of homomorphisms from the pattern into the target graph.
+        pub fn count(&self) -> u64 {
+            *self.table(self.nice_tree_decomposition.root()).get(&0).unwrap_or(&0)
+        }
+
+        /// Returns the current target graph.
+        pub fn to_graph(&self) -> &Graph { &self.to_graph }
+
+        /// Inserts the edge (a,b) into the target graph and returns the updated number of homomorphisms.
+        pub fn insert_edge(&mut self, a : usize, b : usize) -> u64 {
+            if !self.target_has_edge(a as Mapping, b as Mapping) {
+                self.to_graph.add_edge(Vertex::new(a), Vertex::new(b), ());
+                self.update(a as Mapping, b as Mapping);
+            }
+            self.count()
+        }
+
+        /// Removes the edge (a,b) from the target graph and returns the updated number of homomorphisms.
+        pub fn remove_edge(&mut self, a : usize, b : usize) -> u64 {
+            if self.target_has_edge(a as Mapping, b as Mapping) {
+                self.to_graph.remove_edge(Vertex::new(a), Vertex::new(b));
+                self.update(a as Mapping, b as Mapping);
+            }
+            self.count()
+        }
+
+        /// Returns hom(H, G - e) for every edge e = (a,b) with a <= b of the current target graph G, ordered by the
+        /// edges, where loops are edges (a,a). Each edge is removed and inserted again, such that only the entries
+        /// depending on the edge are recomputed twice instead of running the dynamic program once per edge.
+        /// Afterwards the target graph and the tables are unchanged.
+        pub fn deletion_counts(&mut self) -> Vec<((usize, usize), u64)> {
+            let n = self.to_graph.node_count();
+            let edges : Vec<(usize, usize)> = (0..n)
+                .flat_map(|a| (a..n).map(move |b| (a, b)))
+                .filter(|&(a, b)| self.target_has_edge(a as Mapping, b as Mapping))
+                .collect();
+
+            edges.into_iter()
+                .map(|(a, b)| {
+                    let count = self.remove_edge(a, b);
+                    self.insert_edge(a, b);
+                    ((a, b), count)
+                })
+                .collect()
+        }
+
+        /// Updates all tables after the pair (a,b) of the target graph has changed. Following the stingy
+        /// ordering, only entries whose introduce or leaf condition involves the pair or whose child entries
+        /// changed are recomputed.
+        fn update(&mut self, a : Mapping, b : Mapping){
+            let ntd = self.nice_tree_decomposition;
+            let n = self.to_graph.node_count() as Mapping;
+
+            // the entries which changed at each processed node
+            let mut changed : HashMap<TreeNode, HashSet<Mapping>> = HashMap::new();
+
+            for p in ntd.stingy_ordering(){
+                let candidates : HashSet<Mapping> = match ntd.node_type(p){
+                    Some(NodeType::Leaf) => {
+                        let v = *ntd.unique_vertex(p).unwrap();
+                        // only the entry of a loop can change
+                        if a == b && self.from_graph.has_edge(v, v) { HashSet::from([a]) } else { HashSet::new() }
+                    }
+                    Some(NodeType::Introduce) => {
+                        let q = *ntd.unique_child(p).unwrap();
+                        let new_index = self.introduced_significance(p);
+                        let neighbours = self.neighbour_significances(p);
+
+                        // entries extending changed entries of the child
+                        let mut candidates : HashSet<Mapping> = changed.remove(&q).unwrap_or_default().iter()
+                            .flat_map(|&f_q| (0..n).map(move |x| integer_functions_methods::extend(n, f_q, new_index, x)))
+                            .collect();
+
+                        // entries mapping the introduced vertex and one of its neighbours onto the pair
+                        for &f_q in self.table(q).keys(){
+                            for x in [a, b]{
+                                let f_prime = integer_functions_methods::extend(n, f_q, new_index, x);
+                                let involves_pair = neighbours.iter().any(|&significance| {
+                                    let image_of_u = integer_functions_methods::apply(n, f_prime, significance);
+                                    (x == a && image_of_u == b) || (x == b && image_of_u == a)
+                                });
+                                if involves_pair { candidates.insert(f_prime); }
+                            }
+                        }
+
+                        candidates
+                    }
+                    Some(NodeType::Forget) => {
+                        let q = *ntd.unique_child(p).unwrap();
+                        let significance = self.forgotten_significance(p);
+                        changed.remove(&q).unwrap_or_default().iter()
+                            .map(|&f_q| integer_functions_methods::reduce(n, f_q, significance))
+                            .collect()
+                    }
+                    Some(NodeType::Join) => {
+                        let children = ntd.children(p).unwrap();
+                        let mut candidates = changed.remove(&children[0]).unwrap_or_default();
+                        candidates.extend(changed.remove(&children[1]).unwrap_or_default());
+                        candidates
+                    }
+                    None => HashSet::new(),
+                };
+
+                let changed_p = self.recompute(p, candidates);
+                changed.insert(p, changed_p);
+            }
+        }
+
+        /// Recomputes the given entries of node p from the tables of its children and returns the
+        /// entries whose value has changed.
+        fn recompute(&mut self, p : TreeNode, candidates : HashSet<Mapping>) -> HashSet<Mapping>{
+            let mut changed = HashSet::new();
+
+            for f in candidates{
+                let value = self.entry(p, f);
+                let table = self.tables.get_mut(&p).unwrap();
+                let old_value = table.get(&f).copied().unwrap_or(0);
+
+                if value != old_value {
+                    if value == 0 { table.remove(&f); } else { table.insert(f, value); }
+                    changed.insert(f);
+                }
+            }
+
+            changed
+        }
+
+        /// Computes the entry I[p,f] from the tables of the children of p.
+        fn entry(&self, p : TreeNode, f : Mapping) -> u64{
+            let ntd = self.nice_tree_decomposition;
+            let n = self.to_graph.node_count() as Mapping;
+
+            match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    (!self.from_graph.has_edge(v, v) || self.target_has_edge(f, f)) as u64
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let new_index = self.introduced_significance(p);
+                    let image_of_v = integer_functions_methods::apply(n, f, new_index);
+
+                    let condition = self.neighbour_significances(p).iter().all(|&significance| {
+                        self.target_has_edge(image_of_v, integer_functions_methods::apply(n, f, significance))
+                    });
+
+                    if condition { self.value(q, integer_functions_methods::reduce(n, f, new_index)) } else { 0 }
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let significance = self.forgotten_significance(p);
+                    (0..n).map(|x| self.value(q, integer_functions_methods::extend(n, f, significance, x))).sum()
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    self.value(children[0], f) * self.value(children[1], f)
+                }
+                None => 0,
+            }
+        }
+
+        /// Returns the table of node p.
+        fn table(&self, p : TreeNode) -> &HashMap<Mapping, u64> { self.tables.get(&p).unwrap() }
+
+        /// Returns the entry I[p,f], missing entries are 0.
+        fn value(&self, p : TreeNode, f : Mapping) -> u64 { *self.table(p).get(&f).unwrap_or(&0) }
+
+        /// Checks whether the target graph contains the edge (a,b).
+        fn target_has_edge(&self, a : Mapping, b : Mapping) -> bool {
+            self.to_graph.has_edge(self.to_graph.from_index(a as usize), self.to_graph.from_index(b as usize))
+        }
+
+        /// Returns the significance of the unique vertex of the introduce node p in the bag of p.
+        fn introduced_significance(&self, p : TreeNode) -> Mapping {
+            let q = *self.nice_tree_decomposition.unique_child(p).unwrap();
+            let v = *self.nice_tree_decomposition.unique_vertex(p).unwrap();
+            self.indexer(q).insert_position(v)
+        }
+
+        /// Returns the significance of the unique vertex of the forget node p in the bag of its child.
+        fn forgotten_significance(&self, p : TreeNode) -> Mapping {
+            let q = *self.nice_tree_decomposition.unique_child(p).unwrap();
+            let v = *self.nice_tree_decomposition.unique_vertex(p).unwrap();
+            self.indexer(q).position(v).unwrap()
+        }
+
+        /// Returns the significances of all neighbours of the introduced vertex of p in the bag of p.
+        /// If the introduced vertex has a loop, it is contained as its own neighbour.
+        fn neighbour_significances(&self, p : TreeNode) -> Vec<Mapping> {
+            let v = *self.nice_tree_decomposition.unique_vertex(p).unwrap();
+            self.indexer(p).neighbour_positions(self.from_graph, v)
+        }
+
+        /// Returns the indexer of the mappings of node p into the target graph.
+        fn indexer(&self, p : TreeNode) -> BagIndexer {
+            BagIndexer::of_node(self.nice_tree_decomposition, p, self.to_graph.node_count() as Mapping)
+        }
+    }
+
+    /// Returns the edge deletion profile of the target, i.e. hom(H, G - e) for every edge e = (a,b) with a <= b of
+    /// "to_graph", ordered by the edges, see IncrementalCounter::deletion_counts. The difference to hom(H, G) is the
+    /// number of homomorphisms using the edge e, which measures the importance of e for robustness analyses.
+    pub fn edge_deletion_profile(from_graph : &Graph, nice_tree_decomposition : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<((usize, usize), u64)> {
+        IncrementalCounter::new(from_graph, nice_tree_decomposition, to_graph).deletion_counts()
+    }
+}
@@ -0,0 +1,126 @@
+/// A module containing an algorithm which counts the homomorphisms from all induced subgraphs
+/// of a pattern graph simultaneously. Analogous to the edge subsets of the modified dynamic program,
+/// the state of the dynamic program is augmented by a vertex subset in bitmask representation.
+pub mod induced_subgraph_algorithm {
+    use std::collections::HashMap;
+    use petgraph::visit::NodeIndexable;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{forget_entries, introduce_entries, join_entries, NodeTable};
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
+
+    /// A vertex subset of the pattern graph represented as a bitmask, where bit i stands for vertex i.
+    /// note: the pattern may therefore contain at most 64 vertices
+    pub type VertexSet = u64;
+
+    /// The message of the panic if a number of homomorphisms does not fit into 64 bits.
+    const COUNT_OVERFLOW: &str = "The number of homomorphisms does not fit into 64 bits!";
+
+    /// Computes for every vertex subset S of the pattern the number of homomorphisms from the induced
+    /// subgraph H[S] to to_graph. The result maps the bitmask of S to hom(H[S], G).
+    ///
+    /// The table of p consists of a node table I[p,S,f] for each subset S of the vertices of the subtree rooted at
+    /// p, where f is a mapping of the bag of p. Bag vertices which are not contained in S are mapped onto
+    /// vertex 0 as a canonical placeholder and are ignored by all edge conditions. The node tables are extended,
+    /// reduced and multiplied by the steps of the algorithm of diaz et all.
+    /// Panics if a number does not fit into 64 bits.
+    pub fn induced_subgraph_counts(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> HashMap<VertexSet, u64>{
+
+        let n = to_graph.node_count() as Mapping;
+        let mut table : HashMap<TreeNode, HashMap<VertexSet, NodeTable>> = HashMap::new();
+
+        let has_edge = |a : Mapping, b : Mapping| to_graph.has_edge(to_graph.from_index(a as usize), to_graph.from_index(b as usize));
+
+        for p in ntd.stingy_ordering(){
+            let mut layers : HashMap<VertexSet, NodeTable> = HashMap::new();
+
+            match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    let v = *ntd.unique_vertex(p).unwrap();
+
+                    // v is not contained in S
+                    layers.entry(0).or_default().insert(0, 1);
+
+                    // v is contained in S
+                    let entries = layers.entry(1 << v.index()).or_default();
+                    for a in 0..n{
+                        if !from_graph.has_edge(v, v) || has_edge(a, a) { entries.insert(a, 1); }
+                    }
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+                    let has_loop = from_graph.has_edge(v, v);
+
+                    // neighbours of v in the bag of q with their significance
+                    let neighbours : Vec<(Vertex, Mapping)> = indexer_q.bag().iter()
+                        .filter(|&u| from_graph.has_edge(v, u))
+                        .map(|u| (u, indexer_q.position(u).unwrap()))
+                        .collect();
+
+                    for (s, entries_q) in table.remove(&q).unwrap_or_default(){
+
+                        // v is not contained in S, the placeholder image 0 is used
+                        let entries = layers.entry(s).or_default();
+                        introduce_entries(&indexer_q, v, &entries_q, |_, buffer| {
+                            buffer.push((0, 1));
+                            Some(())
+                        }, entries).expect(COUNT_OVERFLOW);
+
+                        // v is contained in S, only the edges to neighbours in S have to be preserved
+                        let new_s = s | (1 << v.index());
+                        let entries = layers.entry(new_s).or_default();
+                        introduce_entries(&indexer_q, v, &entries_q, |f_q, buffer| {
+                            for a in 0..n{
+                                let condition = (!has_loop || has_edge(a, a)) && neighbours.iter()
+                                    .filter(|(u, _)| new_s & (1 << u.index()) != 0)
+                                    .all(|&(_, significance)| has_edge(a, indexer_q.image_at(f_q, significance)));
+                                buffer.push((a, condition as u64));
+                            }
+                            Some(())
+                        }, entries).expect(COUNT_OVERFLOW);
+                    }
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+
+                    // the entries with the placeholder image of vertices outside of S are already
+                    // stored only once, hence summing up over all reduced mappings is sufficient
+                    for (s, entries_q) in table.remove(&q).unwrap_or_default(){
+                        let entries = layers.entry(s).or_default();
+                        forget_entries(&indexer_q, forgotten_vertex, &entries_q, entries).expect(COUNT_OVERFLOW);
+                    }
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    let layers_q1 = table.remove(&children[0]).unwrap_or_default();
+                    let layers_q2 = table.remove(&children[1]).unwrap_or_default();
+
+                    let bag_set : VertexSet = ntd.bit_bag(p).unwrap().iter().map(|v| 1 << v.index()).sum();
+
+                    // the subsets of both children have to agree on the bag
+                    for (&s1, entries_q1) in &layers_q1{
+                        for (&s2, entries_q2) in layers_q2.iter().filter(|(&s2, _)| s1 & bag_set == s2 & bag_set){
+                            let entries = layers.entry(s1 | s2).or_default();
+                            join_entries(entries_q1, entries_q2, entries).expect(COUNT_OVERFLOW);
+                        }
+                    }
+                }
+                None => {}
+            }
+
+            // empty layers are kept, such that every subset is contained in the result
+            table.insert(p, layers);
+        }
+
+        // the bag of the root is empty, hence only the empty mapping remains
+        table.remove(&ntd.root()).unwrap_or_default().into_iter()
+            .map(|(s, entries)| (s, entries.get(&0).copied().unwrap_or(0)))
+            .collect()
+    }
+}
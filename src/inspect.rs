@@ -0,0 +1,172 @@
+/// A module containing key statistics of instances, i.e. of graphs and of nice tree decompositions, which
+/// are printed by the inspect subcommand of the command line interface.
+pub mod instance_statistics {
+    use std::fmt;
+    use std::path::Path;
+    use crate::cost_estimation::cost_model::{CostEstimate, estimate_modified_dp};
+    use crate::file_handler::tree_decomposition_handler::{load_ntd, NtdParseError};
+    use crate::graph_generation::graph_generation_algorithms::connected_components;
+    use crate::pipeline::counting_pipeline::{import_graph, PipelineError};
+    use crate::prefilter::feasibility_prefilter::{degree_sequence, loop_count};
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// Statistics of a graph, degrees do not count loops.
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct GraphStatistics {
+        pub vertices: usize,
+        /// the number of edges without loops
+        pub edges: usize,
+        pub loops: usize,
+        /// None for the graph without vertices
+        pub min_degree: Option<usize>,
+        pub max_degree: Option<usize>,
+        pub average_degree: f64,
+        pub components: usize,
+    }
+
+    /// Statistics of a nice tree decomposition, which are shared by the inspect subcommand and the experiments.
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct NtdStats {
+        pub nodes: u64,
+        pub width: u32,
+        pub vertices: u32,
+        pub leaves: usize,
+        pub introduce_nodes: usize,
+        pub forget_nodes: usize,
+        pub join_nodes: usize,
+        /// the number of possible edges |E_τ|, i.e. the number of edges of the largest graph in $H_\tau$
+        pub possible_edges: usize,
+        /// the maximal number of edges on a path from the root to a leaf
+        pub depth: usize,
+        /// the estimated cost of the modified dynamic program, only known for a given size of the target graph
+        pub estimated_cost: Option<CostEstimate>,
+    }
+
+    /// A graph or a nice tree decomposition read from a file.
+    pub enum Instance {
+        Graph(Graph),
+        Ntd(NiceTreeDecomposition),
+    }
+
+    /// The reason why an instance could not be read.
+    #[derive(Debug)]
+    pub enum InspectError {
+        Graph(PipelineError),
+        Ntd(NtdParseError),
+    }
+
+    impl fmt::Display for InspectError {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                InspectError::Graph(error) => write!(f, "{}", error),
+                InspectError::Ntd(error) => write!(f, "{}", error),
+            }
+        }
+    }
+
+    impl std::error::Error for InspectError {}
+
+    /// Returns the statistics of the graph.
+    pub fn graph_statistics(graph : &Graph) -> GraphStatistics {
+        let degrees = degree_sequence(graph);
+        let vertices = graph.node_count();
+        let degree_sum : usize = degrees.iter().sum();
+
+        GraphStatistics {
+            vertices,
+            edges: degree_sum / 2,
+            loops: loop_count(graph),
+            min_degree: degrees.last().copied(),
+            max_degree: degrees.first().copied(),
+            average_degree: if vertices == 0 { 0.0 } else { degree_sum as f64 / vertices as f64 },
+            components: connected_components(graph).len(),
+        }
+    }
+
+    impl NtdStats {
+
+        /// Computes the statistics of the nice tree decomposition without an estimated cost.
+        pub fn compute(ntd : &NiceTreeDecomposition) -> NtdStats {
+            let count = |node_type : NodeType| (0..ntd.node_count()).filter(|&p| ntd.node_type(p) == Some(&node_type)).count();
+
+            // the depth of each node is known once the depth of its parent is known
+            let mut depth = 0;
+            let mut stack = vec![(ntd.root(), 0)];
+            while let Some((p, depth_p)) = stack.pop() {
+                depth = depth.max(depth_p);
+                for &q in ntd.children(p).into_iter().flatten(){
+                    stack.push((q, depth_p + 1));
+                }
+            }
+
+            NtdStats {
+                nodes: ntd.node_count(),
+                width: ntd.width(),
+                vertices: ntd.vertex_count(),
+                leaves: count(NodeType::Leaf),
+                introduce_nodes: count(NodeType::Introduce),
+                forget_nodes: count(NodeType::Forget),
+                join_nodes: count(NodeType::Join),
+                possible_edges: ntd.all_possible_edges().len(),
+                depth,
+                estimated_cost: None,
+            }
+        }
+
+        /// Computes the statistics of the nice tree decomposition together with the estimated cost of the
+        /// modified dynamic program for a target graph with v_g vertices.
+        pub fn compute_for_target(ntd : &NiceTreeDecomposition, v_g : usize) -> NtdStats {
+            NtdStats { estimated_cost: Some(estimate_modified_dp(ntd, v_g)), ..NtdStats::compute(ntd) }
+        }
+    }
+
+    /// Reads the instance from the file. Files ending with .ntd (optionally followed by .gz) are nice tree
+    /// decompositions, all other files are graphs whose format is detected as in the pipeline.
+    pub fn read_instance<P>(path : P) -> Result<Instance, InspectError>
+        where P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        let name = path.file_name().map(|name| name.to_string_lossy().trim_end_matches(".gz").to_string()).unwrap_or_default();
+
+        if name.ends_with(".ntd") {
+            load_ntd(path).map(Instance::Ntd).map_err(InspectError::Ntd)
+        } else {
+            import_graph(path).map(Instance::Graph).map_err(InspectError::Graph)
+        }
+    }
+
+    impl fmt::Display for GraphStatistics {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            let degree = |degree : Option<usize>| degree.map_or("-".to_string(), |degree| degree.to_string());
+
+            writeln!(f, "type: graph")?;
+            writeln!(f, "vertices: {}", self.vertices)?;
+            writeln!(f, "edges: {}", self.edges)?;
+            writeln!(f, "loops: {}", self.loops)?;
+            writeln!(f, "min degree: {}", degree(self.min_degree))?;
+            writeln!(f, "max degree: {}", degree(self.max_degree))?;
+            writeln!(f, "average degree: {:.2}", self.average_degree)?;
+            write!(f, "components: {}", self.components)
+        }
+    }
+
+    impl fmt::Display for NtdStats {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            writeln!(f, "type: nice tree decomposition")?;
+            writeln!(f, "nodes: {}", self.nodes)?;
+            writeln!(f, "width: {}", self.width)?;
+            writeln!(f, "vertices: {}", self.vertices)?;
+            writeln!(f, "leaf nodes: {}", self.leaves)?;
+            writeln!(f, "introduce nodes: {}", self.introduce_nodes)?;
+            writeln!(f, "forget nodes: {}", self.forget_nodes)?;
+            writeln!(f, "join nodes: {}", self.join_nodes)?;
+            writeln!(f, "possible edges: {}", self.possible_edges)?;
+            write!(f, "depth: {}", self.depth)?;
+            if let Some(estimate) = &self.estimated_cost {
+                write!(f, "\nestimated operations: {:.3e}\nestimated memory: {:.3e} bytes", estimate.operations, estimate.memory_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
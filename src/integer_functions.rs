@@ -3,6 +3,8 @@
 /// Emil Ruhwald Nielsen, Otto Stadel Clausen and Elisabeth Terp Reeve.
 pub mod integer_functions_methods {
     use std::collections::HashMap;
+    use crate::tree_decompositions::nice_tree_decomposition::{BitBag, NiceTreeDecomposition};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
 
     /// Defining the type Mapping to distinguish the operation from normal u64 variables.
     pub type Mapping = u64;
@@ -62,4 +64,179 @@ pub mod integer_functions_methods {
 
         mapping
     }
+
+    /// Translates between the vertices of a bag and the digits of the integer functions representing the
+    /// mappings from the sorted bag into a graph with n vertices, where the image of the i-th smallest vertex
+    /// is the digit with significance i. The dynamic programs derive all significances of introduce and forget
+    /// nodes from it: an introduce node extends the mappings of its child by the introduced vertex and a forget
+    /// node reduces the mappings of its child by the forgotten vertex, both with the indexer of the child.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct BagIndexer {
+        bag: BitBag,
+        n: Mapping,
+    }
+
+    impl BagIndexer {
+        pub fn new(bag : BitBag, n : Mapping) -> BagIndexer { BagIndexer { bag, n } }
+
+        /// Returns the indexer of the mappings of node p into a graph with n vertices.
+        pub fn of_node(ntd : &NiceTreeDecomposition, p : TreeNode, n : Mapping) -> BagIndexer {
+            BagIndexer::new(ntd.bit_bag(p).unwrap().clone(), n)
+        }
+
+        pub fn bag(&self) -> &BitBag { &self.bag }
+
+        /// Returns the significance of v, or None if v is not contained in the bag.
+        pub fn position(&self, v : Vertex) -> Option<Mapping> { self.bag.significance(v).map(|s| s as Mapping) }
+
+        /// Returns the significance of the vertex v, which is not contained in the bag, after inserting it.
+        pub fn insert_position(&self, v : Vertex) -> Mapping {
+            debug_assert!(!self.bag.contains(v), "Vertex {} is already contained in the bag!", v.index());
+            self.bag.rank(v) as Mapping
+        }
+
+        /// Returns the significances of the vertices of the bag which are adjacent to v in the graph. If v has a
+        /// loop and is contained in the bag, its own significance is contained.
+        pub fn neighbour_positions(&self, graph : &Graph, v : Vertex) -> Vec<Mapping> {
+            self.bag.iter()
+                .filter(|&u| graph.has_edge(v, u))
+                .map(|u| self.bag.rank(u) as Mapping)
+                .collect()
+        }
+
+        /// Returns the image of the vertex with significance s under f.
+        #[inline]
+        pub fn image_at(&self, f : Mapping, s : Mapping) -> Mapping { apply(self.n, f, s) }
+
+        /// Returns the image of the vertex v of the bag under f.
+        #[inline]
+        pub fn image(&self, f : Mapping, v : Vertex) -> Mapping {
+            apply(self.n, f, self.position(v).expect("The vertex is not contained in the bag!"))
+        }
+
+        /// Extends the mapping f of the bag by mapping the vertex v, which is not contained in the bag, onto a.
+        /// The result is a mapping of the bag with v.
+        #[inline]
+        pub fn extend(&self, f : Mapping, v : Vertex, a : Mapping) -> Mapping { extend(self.n, f, self.insert_position(v), a) }
+
+        /// Removes the image of the vertex v of the bag from the mapping f. The result is a mapping of the bag without v.
+        #[inline]
+        pub fn reduce(&self, f : Mapping, v : Vertex) -> Mapping {
+            reduce(self.n, f, self.position(v).expect("The vertex is not contained in the bag!"))
+        }
+
+        /// Returns the partition of the mappings of the bag into chunks of at most chunk_size mappings.
+        pub fn chunks(&self, chunk_size : Mapping) -> MappingSpace {
+            MappingSpace::new(self.bag.len() as Mapping, self.n, chunk_size)
+        }
+    }
+
+    /// The mappings 0,..,n^d - 1 from d vertices into n vertices, partitioned into chunks of consecutive mappings
+    /// which share the images of the most significant vertices, i.e. their prefix. Each chunk consists of all n^k
+    /// mappings of the k least significant vertices for a fixed prefix, where k is the largest number with
+    /// n^k <= chunk_size. Chunks can be processed independently, e.g. by different threads or machines, and
+    /// iterating over a chunk updates the images incrementally instead of decoding every mapping from scratch.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub struct MappingSpace {
+        d: Mapping,
+        n: Mapping,
+        free: Mapping,
+    }
+
+    impl MappingSpace {
+        /// Returns the partition of the mappings from d vertices into n vertices into chunks of at most chunk_size
+        /// mappings, where a chunk contains at least one mapping even if chunk_size is 0.
+        pub fn new(d : Mapping, n : Mapping, chunk_size : Mapping) -> MappingSpace {
+            let mut free = 0;
+            while free < d && n.checked_pow(free as u32 + 1).is_some_and(|size| size <= chunk_size.max(1)) {
+                free += 1;
+            }
+            // every chunk of a space with at most one vertex in the target contains all mappings
+            if n <= 1 { free = d; }
+            MappingSpace { d, n, free }
+        }
+
+        /// Returns the number of mappings, i.e. max_mappings(d, n).
+        pub fn len(&self) -> Mapping { max_mappings(self.d, self.n) }
+
+        pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+        /// Returns the number of mappings of each chunk.
+        pub fn chunk_size(&self) -> Mapping { max_mappings(self.free, self.n) }
+
+        /// Returns the number of chunks.
+        pub fn chunk_count(&self) -> Mapping {
+            if self.is_empty() { 0 } else { max_mappings(self.d - self.free, self.n) }
+        }
+
+        /// Returns the i-th chunk, i.e. the chunk of the mappings chunk_size() * i,..,chunk_size() * (i+1) - 1.
+        /// Panics if i is not smaller than chunk_count().
+        pub fn chunk(&self, i : Mapping) -> MappingChunk {
+            assert!(i < self.chunk_count(), "The mapping space has only {} chunks!", self.chunk_count());
+            let mut images = vec![0; self.d as usize];
+            let mut rest = i;
+            for image in images.iter_mut().skip(self.free as usize){
+                *image = rest % self.n;
+                rest /= self.n;
+            }
+
+            let start = i * self.chunk_size();
+            MappingChunk { start, end: start + self.chunk_size(), free: self.free as usize, n: self.n, images }
+        }
+
+        /// Returns an iterator over all chunks in increasing order.
+        pub fn chunks(&self) -> impl Iterator<Item = MappingChunk> + '_ {
+            (0..self.chunk_count()).map(|i| self.chunk(i))
+        }
+    }
+
+    /// A chunk of consecutive mappings start,..,end - 1 of a MappingSpace together with the decoded images of its
+    /// first mapping.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct MappingChunk {
+        start: Mapping,
+        end: Mapping,
+        free: usize,
+        n: Mapping,
+        images: Vec<Mapping>,
+    }
+
+    impl MappingChunk {
+        /// Returns the first mapping of the chunk.
+        pub fn start(&self) -> Mapping { self.start }
+
+        /// Returns the first mapping after the chunk.
+        pub fn end(&self) -> Mapping { self.end }
+
+        pub fn len(&self) -> Mapping { self.end - self.start }
+
+        pub fn is_empty(&self) -> bool { self.start == self.end }
+
+        /// Returns the images of the vertices whose images are equal for all mappings of the chunk, where the i-th
+        /// entry is the image of the vertex with significance free + i.
+        pub fn prefix(&self) -> &[Mapping] { &self.images[self.free..] }
+
+        /// Calls the visitor with every mapping f of the chunk in increasing order together with its images, where
+        /// the i-th entry is the image of the vertex with significance i, i.e. apply(n, f, i).
+        pub fn for_each<F>(&self, mut visitor : F) where F: FnMut(Mapping, &[Mapping]) {
+            let mut images = self.images.clone();
+            for f in self.start..self.end{
+                visitor(f, &images);
+
+                // increments the free digits like an odometer
+                for image in images[..self.free].iter_mut(){
+                    *image += 1;
+                    if *image < self.n { break; }
+                    *image = 0;
+                }
+            }
+        }
+
+        /// Returns the number of mappings of the chunk whose images satisfy the predicate.
+        pub fn count<F>(&self, predicate : F) -> u64 where F: Fn(&[Mapping]) -> bool {
+            let mut count = 0;
+            self.for_each(|_, images| if predicate(images) { count += 1; });
+            count
+        }
+    }
 }
@@ -0,0 +1,120 @@
+/// A module containing graph kernels defined by homomorphism counts. Each graph G is mapped onto its
+/// homomorphism vector (hom(F, G))_F over a fixed family of patterns F and the kernel of two graphs is the inner
+/// product of their vectors. The Gram matrices can be passed to kernel methods like support vector machines.
+pub mod hom_kernels {
+    use crate::graph_generation::graph_generation_algorithms::generate_trees;
+    use crate::hom_matrix::homomorphism_matrix::hom_matrix;
+    use crate::modified_dp::algorithm::modified_dp_counts;
+    use crate::parallelism::parallel_execution;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The family of patterns whose homomorphism counts are the features of a graph.
+    #[derive(Clone)]
+    pub enum PatternFamily {
+        /// all graphs in $H_\tau$ of the nice tree decomposition, ordered by the bitmask of their possible edges,
+        /// which are counted by a single run of the modified dynamic program per graph
+        NtdUniverse(Box<NiceTreeDecomposition>),
+        /// all trees with 1 to k vertices up to isomorphism
+        TreesUpTo(usize),
+        /// the given patterns
+        Patterns(Vec<Graph>),
+    }
+
+    /// How the homomorphism counts are turned into features.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum FeatureScaling {
+        /// the counts hom(F, G) themselves
+        #[default]
+        Counts,
+        /// the homomorphism densities hom(F, G) / |V(G)|^|V(F)|, which lie between 0 and 1
+        Densities,
+        /// ln(1 + hom(F, G)), which keeps the counts of large patterns from dominating the kernel
+        Logarithmic,
+    }
+
+    /// A kernel given by a family of patterns and the scaling of their counts.
+    #[derive(Clone)]
+    pub struct HomKernel {
+        pub family: PatternFamily,
+        pub scaling: FeatureScaling,
+    }
+
+    impl HomKernel {
+        pub fn new(family : PatternFamily, scaling : FeatureScaling) -> HomKernel { HomKernel { family, scaling } }
+
+        /// Returns the feature vector of each graph, all vectors have the same length and order of patterns.
+        pub fn feature_vectors(&self, graphs : &[Graph]) -> Vec<Vec<f64>> {
+            // the counts of each graph together with the number of vertices of each pattern
+            let (counts, pattern_sizes) : (Vec<Vec<u64>>, Vec<usize>) = match &self.family {
+                PatternFamily::NtdUniverse(ntd) => {
+                    let masks = 1u64 << ntd.all_possible_edges().len();
+                    let counts = parallel_execution::map_range(graphs.len(), |j| {
+                        let counts = modified_dp_counts(ntd, &graphs[j]);
+                        (0..masks).map(|mask| *counts.get(&mask).unwrap_or(&0)).collect()
+                    });
+                    (counts, vec![ntd.vertex_count() as usize; masks as usize])
+                }
+                PatternFamily::TreesUpTo(k) => counts_of_patterns(&generate_trees(*k), graphs),
+                PatternFamily::Patterns(patterns) => counts_of_patterns(patterns, graphs),
+            };
+
+            counts.iter().zip(graphs)
+                .map(|(counts, graph)| {
+                    counts.iter().zip(&pattern_sizes)
+                        .map(|(&count, &pattern_size)| self.scale(count, pattern_size, graph.node_count()))
+                        .collect()
+                })
+                .collect()
+        }
+
+        /// Returns the Gram matrix whose entry [i][j] is the kernel of graphs[i] and graphs[j].
+        pub fn gram_matrix(&self, graphs : &[Graph]) -> Vec<Vec<f64>> {
+            let features = self.feature_vectors(graphs);
+            inner_products(&features, &features)
+        }
+
+        /// Returns the matrix whose entry [i][j] is the kernel of rows[i] and columns[j], e.g. of test graphs
+        /// and training graphs.
+        pub fn cross_gram_matrix(&self, rows : &[Graph], columns : &[Graph]) -> Vec<Vec<f64>> {
+            inner_products(&self.feature_vectors(rows), &self.feature_vectors(columns))
+        }
+
+        fn scale(&self, count : u64, pattern_size : usize, graph_size : usize) -> f64 {
+            match self.scaling {
+                FeatureScaling::Counts => count as f64,
+                FeatureScaling::Densities if count == 0 => 0.0,
+                FeatureScaling::Densities => count as f64 / (graph_size as f64).powi(pattern_size as i32),
+                FeatureScaling::Logarithmic => (count as f64).ln_1p(),
+            }
+        }
+    }
+
+    /// Returns the counts of all patterns for each graph together with the number of vertices of each pattern.
+    fn counts_of_patterns(patterns : &[Graph], graphs : &[Graph]) -> (Vec<Vec<u64>>, Vec<usize>) {
+        let matrix = hom_matrix(patterns, graphs);
+        let counts = (0..graphs.len()).map(|j| matrix.iter().map(|row| row[j]).collect()).collect();
+        (counts, patterns.iter().map(|pattern| pattern.node_count()).collect())
+    }
+
+    /// Returns the matrix of the inner products of the vectors a[i] and b[j].
+    fn inner_products(a : &[Vec<f64>], b : &[Vec<f64>]) -> Vec<Vec<f64>> {
+        a.iter()
+            .map(|x| b.iter().map(|y| x.iter().zip(y).map(|(x, y)| x * y).sum()).collect())
+            .collect()
+    }
+
+    /// Normalizes the Gram matrix to K[i][j] / sqrt(K[i][i] * K[j][j]), i.e. to the cosine similarities of the
+    /// feature vectors, such that every graph has kernel 1 with itself. Rows of graphs with a zero feature
+    /// vector are left at 0.
+    pub fn normalize_gram_matrix(gram_matrix : &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let norms : Vec<f64> = (0..gram_matrix.len()).map(|i| gram_matrix[i][i].sqrt()).collect();
+        gram_matrix.iter().enumerate()
+            .map(|(i, row)| {
+                row.iter().enumerate()
+                    .map(|(j, &value)| if norms[i] == 0.0 || norms[j] == 0.0 { 0.0 } else { value / (norms[i] * norms[j]) })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,113 @@
+/// A module containing the export of the lattice of edge subsets of a nice tree decomposition together with the
+/// numbers of homomorphisms computed by the modified dynamic program. The nodes of the lattice are the graphs of
+/// $H_\tau$, i.e. the subsets of the possible edges, and there is an arc from every subset to each subset with
+/// one additional edge, such that the growth (or decay) of the counts along added pattern edges can be visualized,
+/// e.g. with `dot -Tsvg`. The lattice has 2^|E_τ| nodes and |E_τ| 2^(|E_τ|-1) arcs, hence it is meant for small
+/// decompositions.
+pub mod edge_subset_lattice {
+    use std::collections::HashMap;
+    use std::fmt::Write as _;
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+    use std::str::FromStr;
+    use crate::modified_dp::algorithm::{modified_dp_counts, EdgeList};
+    use crate::report::hom_report::describe_edges;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The supported formats of the lattice.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum LatticeFormat {
+        /// a digraph of graphviz
+        Dot,
+        /// a json object with the possible edges, the nodes and the arcs
+        Json,
+    }
+
+    impl FromStr for LatticeFormat {
+        type Err = String;
+
+        fn from_str(name : &str) -> Result<LatticeFormat, String> {
+            match name {
+                "dot" | "gv" => Ok(LatticeFormat::Dot),
+                "json" => Ok(LatticeFormat::Json),
+                _ => Err(format!("unknown lattice format {}, expected dot or json", name)),
+            }
+        }
+    }
+
+    /// Returns the arcs of the lattice as triples of the subset, the subset with the added edge and the index of
+    /// the added edge, where only subsets contained in "counts" are considered.
+    pub fn lattice_arcs(number_of_edges : usize, counts : &HashMap<EdgeList, u64>) -> Vec<(EdgeList, EdgeList, usize)> {
+        let mut subsets : Vec<EdgeList> = counts.keys().copied().collect();
+        subsets.sort_unstable();
+
+        subsets.iter()
+            .flat_map(|&subset| (0..number_of_edges)
+                .filter(move |&i| subset & (1 << i) == 0)
+                .map(move |i| (subset, subset | 1 << i, i)))
+            .filter(|(_, superset, _)| counts.contains_key(superset))
+            .collect()
+    }
+
+    /// Returns the lattice as graphviz digraph, where every node is labelled by its edges and its count and
+    /// every arc by its added edge. The nodes are ranked by their number of edges.
+    pub fn lattice_to_dot(ntd : &NiceTreeDecomposition, counts : &HashMap<EdgeList, u64>) -> String {
+        let possible_edges = ntd.all_possible_edges();
+        let mut subsets : Vec<EdgeList> = counts.keys().copied().collect();
+        subsets.sort_unstable();
+
+        let mut out = String::from("digraph lattice {\n    rankdir=BT;\n    node [shape=box];\n");
+        for &subset in &subsets{
+            let _ = writeln!(out, "    {} [label=\"{}\\n{}\"];", subset, describe_edges(possible_edges, subset), counts[&subset]);
+        }
+        for rank in 0..=possible_edges.len() as u32{
+            let members : Vec<String> = subsets.iter().filter(|subset| subset.count_ones() == rank).map(|subset| subset.to_string()).collect();
+            if !members.is_empty() {
+                let _ = writeln!(out, "    {{ rank=same; {}; }}", members.join("; "));
+            }
+        }
+        for (subset, superset, i) in lattice_arcs(possible_edges.len(), counts){
+            let (u, v) = possible_edges[i];
+            let _ = writeln!(out, "    {} -> {} [label=\"+{}-{}\"];", subset, superset, u.min(v), u.max(v));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Returns the lattice as a json object with the possible edges as pairs of vertices, the nodes with the
+    /// bitmask of their edge set as id, the indices of their edges and their count, and the arcs with the index
+    /// of the added edge.
+    pub fn lattice_to_json(ntd : &NiceTreeDecomposition, counts : &HashMap<EdgeList, u64>) -> String {
+        let possible_edges = ntd.all_possible_edges();
+        let mut subsets : Vec<EdgeList> = counts.keys().copied().collect();
+        subsets.sort_unstable();
+
+        let edges : Vec<String> = possible_edges.iter().map(|(u, v)| format!("[{}, {}]", u, v)).collect();
+        let nodes : Vec<String> = subsets.iter()
+            .map(|&subset| {
+                let members : Vec<String> = (0..possible_edges.len()).filter(|i| subset & (1 << i) != 0).map(|i| i.to_string()).collect();
+                format!("{{\"id\": {}, \"edges\": [{}], \"count\": {}}}", subset, members.join(", "), counts[&subset])
+            })
+            .collect();
+        let arcs : Vec<String> = lattice_arcs(possible_edges.len(), counts).into_iter()
+            .map(|(subset, superset, i)| format!("{{\"from\": {}, \"to\": {}, \"added\": {}}}", subset, superset, i))
+            .collect();
+
+        format!("{{\"possible_edges\": [{}], \"nodes\": [{}], \"arcs\": [{}]}}\n", edges.join(", "), nodes.join(", "), arcs.join(", "))
+    }
+
+    /// Counts the homomorphisms from all graphs of the nice tree decomposition into the target with the modified
+    /// dynamic program and writes the lattice in the given format into the file.
+    pub fn export_lattice<P>(ntd : &NiceTreeDecomposition, to_graph : &Graph, format : LatticeFormat, path : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let counts = modified_dp_counts(ntd, to_graph);
+        let content = match format {
+            LatticeFormat::Dot => lattice_to_dot(ntd, &counts),
+            LatticeFormat::Json => lattice_to_json(ntd, &counts),
+        };
+        fs::write(path, content)
+    }
+}
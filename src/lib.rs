@@ -8,4 +8,52 @@ pub mod diaz_serna_thilikos;
 pub mod modified_dp;
 pub mod integer_functions;
 pub mod graph_generation;
-pub mod experiments;
\ No newline at end of file
+pub mod experiments;
+pub mod multigraph;
+pub mod loop_semantics;
+pub mod capacitated;
+pub mod induced_subgraphs;
+pub mod quantum_graphs;
+pub mod color_refinement;
+pub mod symmetry;
+pub mod incremental;
+pub mod prepared_pattern;
+pub mod hom_counts;
+pub mod cost_estimation;
+pub mod testing;
+pub mod relational_structures;
+pub mod elimination_orderings;
+pub mod pipeline;
+pub mod structural_checks;
+pub mod prefilter;
+pub mod ntd_cache;
+pub mod chromatic;
+pub mod graph_ops;
+pub mod tables;
+pub mod parity;
+pub mod rooted;
+pub mod hom_matrix;
+pub mod parallelism;
+pub mod graph_display;
+pub mod inspect;
+pub mod regression;
+pub mod implicit_target;
+pub mod kernels;
+pub mod pattern_export;
+pub mod image_size;
+pub mod pattern_reduction;
+pub mod target_partition;
+pub mod sanity;
+pub mod ntd_generation;
+pub mod walks;
+pub mod fingerprint;
+pub mod report;
+pub mod lattice;
+pub mod quotient;
+pub mod bounds;
+pub mod log_space;
+pub mod ablation;
+pub mod trees;
+pub mod warm_start;
+#[cfg(feature = "sqlite")]
+pub mod result_database;
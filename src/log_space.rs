@@ -0,0 +1,138 @@
+/// A module containing a floating point variant of the algorithm of diaz et all for instances whose numbers of
+/// homomorphisms exceed any practical integer width. Every table entry stores the natural logarithm of I[p,f]
+/// as f64, such that the shared steps of the dynamic program run on logarithms: products add them and sums
+/// combine them by log-sum-exp, i.e. $\ln \sum_i e^{x_i} = m + \ln \sum_i e^{x_i - m}$ for the maximum m, which
+/// neither overflows nor underflows. Zero entries are not stored, hence there is no logarithm of 0.
+///
+/// Every entry additionally carries an estimate of its absolute error, which grows by the rounding errors of the
+/// operations: a sum of logarithms adds the errors of its summands and log-sum-exp keeps the largest error of
+/// its arguments, since it is 1-Lipschitz in the maximum norm. Both add a rounding error relative to the
+/// magnitude of the result and to the number of summed terms.
+pub mod log_counting {
+    use std::collections::HashMap;
+    use std::f64::consts::LN_10;
+    use std::fmt;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{forget_entries, introduce_entries, join_entries, leaf_entries, AdjacentImages, NeighbourhoodSets};
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::tables::table_backends::Semiring;
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode};
+
+    /// The message of the panic if a step of the dynamic program fails, which floating point numbers rule out.
+    const INFALLIBLE: &str = "The logarithms are summed and multiplied without failing!";
+
+    /// The natural logarithm of a positive number together with an estimate of its absolute error.
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    struct LogValue {
+        ln: f64,
+        error: f64,
+    }
+
+    impl LogValue {
+        /// Returns the logarithm of the product.
+        fn mul(&self, other : &LogValue) -> LogValue {
+            let ln = self.ln + other.ln;
+            LogValue { ln, error: self.error + other.error + f64::EPSILON * ln.abs() }
+        }
+
+        /// Returns the logarithm of the sum of all values, which have to be non-empty.
+        fn sum(values : &[LogValue]) -> LogValue {
+            let max = values.iter().map(|value| value.ln).fold(f64::NEG_INFINITY, f64::max);
+            let sum : f64 = values.iter().map(|value| (value.ln - max).exp()).sum();
+            let ln = max + sum.ln();
+            let error = values.iter().map(|value| value.error).fold(0.0, f64::max)
+                + f64::EPSILON * (ln.abs() + values.len() as f64 + 1.0);
+            LogValue { ln, error }
+        }
+    }
+
+    /// The logarithms of positive numbers, where the sum is computed by log-sum-exp. Zero entries are not stored,
+    /// hence no logarithm is zero.
+    impl Semiring for LogValue {
+        fn one() -> LogValue { LogValue { ln: 0.0, error: 0.0 } }
+
+        fn is_zero(&self) -> bool { false }
+
+        fn add(self, other : LogValue) -> Option<LogValue> { Some(LogValue::sum(&[self, other])) }
+
+        fn mul(self, other : LogValue) -> Option<LogValue> { Some(LogValue::mul(&self, &other)) }
+    }
+
+    /// A number of homomorphisms given by its decimal logarithm and an estimate of the absolute error of the
+    /// logarithm. The number 0 has the logarithm -inf and the error 0.
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub struct LogCount {
+        pub log10: f64,
+        pub error: f64,
+    }
+
+    impl LogCount {
+        /// Returns true if there is no homomorphism.
+        pub fn is_zero(&self) -> bool { self.log10 == f64::NEG_INFINITY }
+
+        /// Returns the number of homomorphisms as f64, which is infinite beyond the range of f64.
+        pub fn value(&self) -> f64 { 10f64.powf(self.log10) }
+
+        /// Returns true if the number lies within the estimated error of the logarithm.
+        pub fn contains(&self, count : u64) -> bool {
+            if count == 0 { return self.is_zero(); }
+            ((count as f64).log10() - self.log10).abs() <= self.error
+        }
+    }
+
+    impl fmt::Display for LogCount {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            if self.is_zero() { return write!(f, "0"); }
+            write!(f, "10^{:.6} ± {:.1e} (log10)", self.log10, self.error)
+        }
+    }
+
+    /// Computes the decimal logarithm of the number of homomorphisms from "from_graph" to "to_graph" by the
+    /// algorithm of diaz et all on tables of logarithms.
+    pub fn log_hom_count(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> LogCount {
+        let n = to_graph.node_count() as Mapping;
+        let neighbourhoods = NeighbourhoodSets::new(to_graph);
+        // every image is feasible, the candidates are only pruned by the adjacency of the target
+        let feasible = vec![true; n as usize];
+
+        let mut table : HashMap<TreeNode, HashMap<Mapping, LogValue>> = HashMap::new();
+
+        for p in ntd.stingy_ordering(){
+            let mut entries : HashMap<Mapping, LogValue> = HashMap::new();
+
+            match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    leaf_entries(from_graph, to_graph, *ntd.unique_vertex(p).unwrap(), &feasible, &mut entries);
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+
+                    let mut images = AdjacentImages::new(&neighbourhoods, from_graph, to_graph, &indexer_q, v, &feasible, false);
+                    introduce_entries(&indexer_q, v, &table.remove(&q).unwrap(), |f_q, buffer| images.fill(f_q, buffer), &mut entries).expect(INFALLIBLE);
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    forget_entries(&BagIndexer::of_node(ntd, q, n), v, &table.remove(&q).unwrap(), &mut entries).expect(INFALLIBLE);
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    let entries_q1 = table.remove(&children[0]).unwrap();
+                    let entries_q2 = table.remove(&children[1]).unwrap();
+                    join_entries(&entries_q1, &entries_q2, &mut entries).expect(INFALLIBLE);
+                }
+                None => {}
+            }
+
+            table.insert(p, entries);
+        }
+
+        let root_entries : Vec<LogValue> = table.remove(&ntd.root()).unwrap().into_values().collect();
+        if root_entries.is_empty() { return LogCount { log10: f64::NEG_INFINITY, error: 0.0 }; }
+
+        let total = LogValue::sum(&root_entries);
+        LogCount { log10: total.ln / LN_10, error: total.error / LN_10 }
+    }
+}
@@ -0,0 +1,75 @@
+/// A module defining how self loops of pattern and target graphs are interpreted.
+pub mod loop_semantics_methods {
+    use petgraph::visit::NodeIndexable;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// Describes how self loops are treated when counting homomorphisms.
+    /// - Respect: loops are ordinary edges, a vertex with a loop has to be mapped onto a vertex with a loop.
+    /// - Ignore: all loops of pattern and target are removed before counting.
+    /// - Reflexive: every target vertex gets a loop, hence loops of the pattern are always satisfied.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+    pub enum LoopSemantics {
+        #[default]
+        Respect,
+        Ignore,
+        Reflexive,
+    }
+
+    /// Whether a graph is counted from, i.e. a pattern, or counted into, i.e. a target. The loop semantics
+    /// treat the loops of both differently.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub enum GraphRole {
+        Pattern,
+        Target,
+    }
+
+    impl LoopSemantics {
+
+        /// Returns a copy of the given graph with loops treated according to the semantics for its role,
+        /// see apply_to_pattern and apply_to_target.
+        pub fn apply(&self, graph : &Graph, role : GraphRole) -> Graph{
+            match role {
+                GraphRole::Pattern => self.apply_to_pattern(graph),
+                GraphRole::Target => self.apply_to_target(graph),
+            }
+        }
+
+        /// Returns a copy of the given pattern graph with loops treated according to the semantics.
+        /// Under Ignore and Reflexive semantics the loops of the pattern do not matter and are removed.
+        pub fn apply_to_pattern(&self, graph : &Graph) -> Graph{
+            match self {
+                LoopSemantics::Respect => graph.clone(),
+                LoopSemantics::Ignore | LoopSemantics::Reflexive => without_loops(graph),
+            }
+        }
+
+        /// Returns a copy of the given target graph with loops treated according to the semantics.
+        pub fn apply_to_target(&self, graph : &Graph) -> Graph{
+            match self {
+                LoopSemantics::Respect => graph.clone(),
+                LoopSemantics::Ignore => without_loops(graph),
+                LoopSemantics::Reflexive => reflexive_closure(graph),
+            }
+        }
+    }
+
+    /// Returns a copy of the given graph without any self loops.
+    pub fn without_loops(graph : &Graph) -> Graph{
+        let mut result = graph.clone();
+        for v in 0..graph.node_count(){
+            let v = graph.from_index(v);
+            if result.has_edge(v, v) { result.remove_edge(v, v); }
+        }
+        result
+    }
+
+    /// Returns a copy of the given graph where every vertex has a self loop.
+    pub fn reflexive_closure(graph : &Graph) -> Graph{
+        let mut result = graph.clone();
+        for v in 0..graph.node_count(){
+            let v = graph.from_index(v);
+            if !result.has_edge(v, v) { result.add_edge(v, v, ()); }
+        }
+        result
+    }
+}
@@ -1,51 +1,377 @@
 extern crate core;
 
-use std::path::Path;
+use std::io;
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use Counting_Homomorphisms::ablation::optimization_ablation::{run_ablation, speedups, write_ablation_csv};
 use Counting_Homomorphisms::brute_force::brute_force_homomorphism_counter::simple_brute_force_for_ntd_set;
 use Counting_Homomorphisms::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_for_ntd_set;
-use Counting_Homomorphisms::experiments::single_running_time_measurement::{measure_running_time};
+use Counting_Homomorphisms::experiments::single_running_time_measurement::{ExperimentPaths, NtdSetAlgorithm, run_running_time_experiment, run_running_time_experiment_shard, Shard};
+use Counting_Homomorphisms::file_handler::escape_json;
+use Counting_Homomorphisms::file_handler::graph_handler::{parse_graph6, read_dimacs, read_metis};
+use Counting_Homomorphisms::graph_display::graph_printer::AdjacencyList;
+use Counting_Homomorphisms::inspect::instance_statistics::{graph_statistics, Instance, NtdStats, read_instance};
 use Counting_Homomorphisms::modified_dp::algorithm::modified_dp;
+use Counting_Homomorphisms::ntd_cache::decomposition_cache::default_directory;
+use Counting_Homomorphisms::ntd_generation::ntd_generators::{export_family, NtdFamily};
+use Counting_Homomorphisms::pipeline::counting_pipeline::{compute_nice_tree_decomposition, count_graphs, count_patterns_dir, detect_line_format, GraphFormat, import_graph, PipelineOptions};
+use Counting_Homomorphisms::file_handler::tree_decomposition_handler::load_ntd;
+use Counting_Homomorphisms::report::hom_report::{Report, ReportFormat, write_report};
+
+const USAGE: &str = "usage: Counting_Homomorphisms --target <file> [--pattern <file>|-] [--pattern-dir <dir>] [--format metis|dimacs|graph6]
+                              [--algorithm brute_force|diaz_serna_thilikos|modified_dp|auto] [--ntd <file>] [--ntd-cache <dir>] [--cached]
+       Counting_Homomorphisms inspect <file> [--verbose]
+       Counting_Homomorphisms report --target <file> --pattern <file>|--ntd-set <file> [--algorithm <algorithm>] [--ntd <file>] [--html] [--out <file>]
+       Counting_Homomorphisms ablation --target <file> --pattern <file> [--ntd <file>] [--repetitions <n>] [--out <file>]
+       Counting_Homomorphisms generate-ntds e_tau_paths <vertices>|fixed_e_tau <possible edges>|fixed_width <width> <max vertices>|paths <max vertices>|complete <max vertices> [--out <dir>]
+Counts the homomorphisms from the pattern into the target and writes the result as json to stdout.
+If no pattern is given, the pattern is read from stdin. In the graph6 format, every line of stdin is a
+pattern and one json object is written per line. With --pattern-dir every file of the directory is a pattern
+and one json object is written per file. Computed nice tree decompositions are stored in and loaded from the
+directory given by --ntd-cache, or by --cached in the user cache directory. Without any arguments the running time experiments are run.
+If started by mpirun or srun, each process measures only its shard of every experiment matrix.
+The inspect subcommand prints statistics of a graph or of a nice tree decomposition (files ending with .ntd),
+with --verbose additionally its adjacency lists or its tree.
+The report subcommand counts the homomorphisms from the pattern, or from all graphs of the nice tree decomposition
+given by --ntd-set, into the target and writes a self-contained Markdown or, with --html, HTML report with the
+statistics, the counts, the timing and drawings of the graphs to stdout or to the file given by --out.
+The ablation subcommand runs the algorithm of diaz et all with every combination of its optimizations, prints
+the fastest running time of each combination and the speedup attributed to each optimization, and writes
+the measurements as csv into the file given by --out.
+The generate-ntds subcommand writes a family of nice tree decompositions for the experiments into the given
+directory, by default data/Experiments/ntds, and prints the paths of the written files.";
+
+/// The arguments of the command line interface.
+struct Arguments {
+    target: PathBuf,
+    pattern: Option<PathBuf>,
+    pattern_dir: Option<PathBuf>,
+    format: Option<GraphFormat>,
+    options: PipelineOptions,
+}
+
+/// Parses the command line arguments.
+fn parse_arguments(args : &[String]) -> Result<Arguments, String>{
+    let mut target = None;
+    let mut pattern = None;
+    let mut pattern_dir = None;
+    let mut format = None;
+    let mut options = PipelineOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--target" => target = Some(PathBuf::from(value()?)),
+            "--pattern" => {
+                let value = value()?;
+                pattern = if value == "-" { None } else { Some(PathBuf::from(value)) };
+            }
+            "--pattern-dir" => pattern_dir = Some(PathBuf::from(value()?)),
+            "--format" => format = Some(value()?.parse()?),
+            "--algorithm" => options.algorithm = value()?.parse()?,
+            "--ntd" => options.ntd_path = Some(PathBuf::from(value()?)),
+            "--ntd-cache" => options.ntd_cache = Some(PathBuf::from(value()?)),
+            "--cached" => options.ntd_cache = Some(default_directory()),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    let target = target.ok_or("missing argument --target")?;
+    Ok(Arguments { target, pattern, pattern_dir, format, options })
+}
+
+/// Prints the statistics of the graph or nice tree decomposition given by the arguments of the inspect subcommand.
+fn run_inspect(args : &[String]) -> Result<(), String>{
+    let mut path = None;
+    let mut verbose = false;
+    for arg in args {
+        match arg.as_str() {
+            "--verbose" | "-v" => verbose = true,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    let path = path.ok_or("missing file to inspect")?;
+
+    match read_instance(path).map_err(|error| error.to_string())? {
+        Instance::Graph(graph) => {
+            println!("{}", graph_statistics(&graph));
+            if verbose { println!("\n{}", AdjacencyList(&graph)); }
+        }
+        Instance::Ntd(ntd) => {
+            println!("{}", NtdStats::compute(&ntd));
+            if verbose { print!("\n{}", ntd); }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the report of the run given by the arguments of the report subcommand.
+fn run_report(args : &[String]) -> Result<(), String>{
+    let mut target = None;
+    let mut pattern = None;
+    let mut ntd_set = None;
+    let mut format = ReportFormat::Markdown;
+    let mut out = None;
+    let mut options = PipelineOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--target" => target = Some(PathBuf::from(value()?)),
+            "--pattern" => pattern = Some(PathBuf::from(value()?)),
+            "--ntd-set" => ntd_set = Some(PathBuf::from(value()?)),
+            "--algorithm" => options.algorithm = value()?.parse()?,
+            "--ntd" => options.ntd_path = Some(PathBuf::from(value()?)),
+            "--html" => format = ReportFormat::Html,
+            "--out" => out = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    let target = target.ok_or("missing argument --target")?;
+    let name = |path : &Path| path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string());
+    let to_graph = import_graph(&target).map_err(|error| error.to_string())?;
+
+    let report = match (pattern, ntd_set) {
+        (Some(pattern), None) => {
+            let from_graph = import_graph(&pattern).map_err(|error| error.to_string())?;
+            Report::for_pair(&name(&pattern), &from_graph, &name(&target), &to_graph, &options).map_err(|error| error.to_string())?
+        }
+        (None, Some(ntd_set)) => {
+            let ntd = load_ntd(&ntd_set).map_err(|error| format!("could not read {}: {}", ntd_set.display(), error))?;
+            Report::for_ntd_set(&ntd, &name(&target), &to_graph)
+        }
+        _ => return Err("expected either --pattern or --ntd-set".to_string()),
+    };
+
+    match out {
+        Some(path) => write_report(&report, format, &path).map_err(|error| error.to_string()),
+        None => io::stdout().lock().write_all(report.render(format).as_bytes()).map_err(|error| error.to_string()),
+    }
+}
+
+/// Measures every combination of optimizations of the algorithm of diaz et all on the instance given by the
+/// arguments of the ablation subcommand.
+fn run_ablation_experiment(args : &[String]) -> Result<(), String>{
+    let mut target = None;
+    let mut pattern = None;
+    let mut ntd_path = None;
+    let mut repetitions = 5;
+    let mut out = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--target" => target = Some(PathBuf::from(value()?)),
+            "--pattern" => pattern = Some(PathBuf::from(value()?)),
+            "--ntd" => ntd_path = Some(PathBuf::from(value()?)),
+            "--repetitions" => {
+                let value = value()?;
+                repetitions = value.parse().map_err(|_| format!("invalid number of repetitions {}", value))?;
+            }
+            "--out" => out = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    let to_graph = import_graph(&target.ok_or("missing argument --target")?).map_err(|error| error.to_string())?;
+    let from_graph = import_graph(&pattern.ok_or("missing argument --pattern")?).map_err(|error| error.to_string())?;
+    let ntd = match ntd_path {
+        Some(path) => load_ntd(&path).map_err(|error| format!("could not read {}: {}", path.display(), error))?,
+        None => compute_nice_tree_decomposition(&from_graph),
+    };
+
+    let records = run_ablation(&from_graph, &ntd, &to_graph, repetitions);
+    for record in &records{
+        println!("{:<60} {:>12?} {}", record.toggles.name(), record.minimum(), record.count);
+    }
+    for (name, speedup) in speedups(&records){
+        println!("speedup of {}: {:.3}", name, speedup);
+    }
+
+    match out {
+        Some(path) => write_ablation_csv(&records, &path).map_err(|error| error.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Writes the family of nice tree decompositions given by the arguments of the generate-ntds subcommand.
+fn run_generate_ntds(args : &[String]) -> Result<(), String>{
+    let mut directory = ExperimentPaths::default().ntd_directory;
+    let mut parameters = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => directory = PathBuf::from(args.next().ok_or("missing value for --out")?),
+            _ => parameters.push(arg.as_str()),
+        }
+    }
+
+    let number = |i : usize| -> Result<usize, String> {
+        let value = parameters.get(i).ok_or("missing parameter of the family")?;
+        value.parse().map_err(|_| format!("invalid parameter {}", value))
+    };
+    let (family, number_of_parameters) = match parameters.first().copied() {
+        Some("e_tau_paths") => (NtdFamily::ETauPaths { vertices: number(1)? }, 2),
+        Some("fixed_e_tau") => (NtdFamily::FixedETau { possible_edges: number(1)? }, 2),
+        Some("fixed_width") => (NtdFamily::FixedWidth { width: number(1)?, max_vertices: number(2)? }, 3),
+        Some("paths") => (NtdFamily::Paths { max_vertices: number(1)? }, 2),
+        Some("complete") => (NtdFamily::Complete { max_vertices: number(1)? }, 2),
+        Some(name) => return Err(format!("unknown family {}, expected e_tau_paths, fixed_e_tau, fixed_width, paths or complete", name)),
+        None => return Err("missing family".to_string()),
+    };
+    if let Some(arg) = parameters.get(number_of_parameters) {
+        return Err(format!("unknown argument {}", arg));
+    }
+
+    for path in export_family(&family, &directory).map_err(|error| error.to_string())? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Counts the homomorphisms of the patterns given by the arguments or stdin and writes the results to stdout.
+fn run_cli(arguments : &Arguments) -> Result<(), String>{
+    if let Some(directory) = &arguments.pattern_dir {
+        let reports = count_patterns_dir(directory, &arguments.target, &arguments.options).map_err(|error| error.to_string())?;
+        let mut stdout = io::stdout().lock();
+        for report in reports {
+            writeln!(stdout, "{}", report.to_json()).map_err(|error| error.to_string())?;
+        }
+        return Ok(());
+    }
+
+    let to_graph = import_graph(&arguments.target).map_err(|error| error.to_string())?;
+    let mut stdout = io::stdout().lock();
+
+    if let Some(pattern) = &arguments.pattern {
+        let from_graph = import_graph(pattern).map_err(|error| error.to_string())?;
+        let result = count_graphs(&from_graph, &to_graph, &arguments.options).map_err(|error| error.to_string())?;
+        return writeln!(stdout, "{}", result.to_json()).map_err(|error| error.to_string());
+    }
+
+    let mut stdin = io::stdin().lock();
+    let mut lines = (&mut stdin).lines();
+
+    // the format is given or detected from the first line which is not a comment
+    let mut first_lines = vec![];
+    let mut format = arguments.format;
+    while format.is_none() {
+        let Some(line) = lines.next() else { break; };
+        let line = line.map_err(|error| error.to_string())?;
+        format = detect_line_format(&line);
+        first_lines.push(line);
+    }
+
+    if format == Some(GraphFormat::Graph6) {
+        // every line is a pattern, the results are written as soon as they are computed
+        for line in first_lines.into_iter().map(Ok).chain(lines) {
+            let line = line.map_err(|error| error.to_string())?;
+            if line.trim().is_empty() { continue; }
+
+            let from_graph = parse_graph6(&line).ok_or_else(|| format!("invalid graph6 string {}", line))?;
+            let result = count_graphs(&from_graph, &to_graph, &arguments.options).map_err(|error| error.to_string())?;
+            writeln!(stdout, "{{\"pattern\": \"{}\", \"result\": {}}}", escape_json(line.trim_end()), result.to_json())
+                .and_then(|_| stdout.flush())
+                .map_err(|error| error.to_string())?;
+        }
+        return Ok(());
+    }
+
+    let mut content = first_lines.join("\n");
+    content.push('\n');
+    stdin.read_to_string(&mut content).map_err(|error| error.to_string())?;
+
+    let from_graph = match format {
+        Some(GraphFormat::Dimacs) => read_dimacs(content.as_bytes()),
+        _ => read_metis(content.as_bytes()),
+    }.ok_or("could not parse the pattern from stdin")?;
+
+    let result = count_graphs(&from_graph, &to_graph, &arguments.options).map_err(|error| error.to_string())?;
+    writeln!(stdout, "{}", result.to_json()).map_err(|error| error.to_string())
+}
+
+/// Runs the experiment in this process, or only the shard of this process if it has been started by mpirun or srun.
+fn run_experiment(matrix_file : &Path, alg : NtdSetAlgorithm, alg_name : &String, paths : &ExperimentPaths, shard : Option<Shard>) -> io::Result<()>{
+    match shard {
+        Some(shard) => run_running_time_experiment_shard(matrix_file, alg, alg_name, paths, shard).map(|_| ()),
+        None => run_running_time_experiment(matrix_file, alg, alg_name, paths),
+    }
+}
 
 fn main(){
 
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+            println!("{}", USAGE);
+            return;
+        }
+        let result = if args[0] == "inspect" { run_inspect(&args[1..]) }
+                     else if args[0] == "generate-ntds" { run_generate_ntds(&args[1..]) }
+                     else if args[0] == "report" { run_report(&args[1..]) }
+                     else if args[0] == "ablation" { run_ablation_experiment(&args[1..]) }
+                     else { parse_arguments(&args).and_then(|arguments| run_cli(&arguments)) };
+        if let Err(message) = result {
+            eprintln!("error: {}\n{}", message, USAGE);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let paths = ExperimentPaths::default();
+    let shard = Shard::from_environment();
+
     // measure single running times
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/brute_force_growth_with_e_tau.csv"),
-                         simple_brute_force_for_ntd_set,
-                         &"brute_force".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/brute_force_growth_with_e_tau.csv"),
+                                simple_brute_force_for_ntd_set,
+                                &"brute_force".to_string(),
+                                &paths, shard).unwrap();
 
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/brute_force_growth_with_graph.csv"),
-                         simple_brute_force_for_ntd_set,
-                         &"brute_force".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/brute_force_growth_with_graph.csv"),
+                                simple_brute_force_for_ntd_set,
+                                &"brute_force".to_string(),
+                                &paths, shard).unwrap();
 
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/diaz_serna_thilikos_growth_with_e_tau.csv"),
-                         diaz_serna_thilikos_for_ntd_set,
-                         &"diaz_serna_thilikos".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/diaz_serna_thilikos_growth_with_e_tau.csv"),
+                                diaz_serna_thilikos_for_ntd_set,
+                                &"diaz_serna_thilikos".to_string(),
+                                &paths, shard).unwrap();
 
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/diaz_serna_thilikos_growth_with_graph.csv"),
-                         diaz_serna_thilikos_for_ntd_set,
-                         &"diaz_serna_thilikos".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/diaz_serna_thilikos_growth_with_graph.csv"),
+                                diaz_serna_thilikos_for_ntd_set,
+                                &"diaz_serna_thilikos".to_string(),
+                                &paths, shard).unwrap();
 
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/modified_dp_growth_with_e_tau.csv"),
-                         modified_dp,
-                         &"modified_dp".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/modified_dp_growth_with_e_tau.csv"),
+                                modified_dp,
+                                &"modified_dp".to_string(),
+                                &paths, shard).unwrap();
 
 
 
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/modified_dp_growth_with_graph.csv"),
-                         modified_dp,
-                         &"modified_dp".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/modified_dp_growth_with_graph.csv"),
+                                modified_dp,
+                                &"modified_dp".to_string(),
+                                &paths, shard).unwrap();
 
 
     // new measurements
 
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/mixed_combinations.csv"),
-                         diaz_serna_thilikos_for_ntd_set,
-                         &"diaz_serna_thilikos".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/mixed_combinations.csv"),
+                                diaz_serna_thilikos_for_ntd_set,
+                                &"diaz_serna_thilikos".to_string(),
+                                &paths, shard).unwrap();
 
 
-    measure_running_time(Path::new("data/Experiments/experiment_matrices/running_time/mixed_combinations.csv"),
-                         modified_dp,
-                         &"modified_dp".to_string());
+    run_experiment(Path::new("data/Experiments/experiment_matrices/running_time/mixed_combinations.csv"),
+                                modified_dp,
+                                &"modified_dp".to_string(),
+                                &paths, shard).unwrap();
 
 
     // Comparison
@@ -3,21 +3,27 @@
 pub mod algorithm {
     use std::arch::x86_64::_mm256_div_ps;
     use std::collections::HashMap;
+    use std::time::Instant;
     use itertools::Itertools;
-    use petgraph::matrix_graph::{MatrixGraph, NodeIndex};
-    use petgraph::Undirected;
     use petgraph::visit::NodeIndexable;
-    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
-    use crate::graph_generation::graph_generation_algorithms::generate_possible_edges;
+    use crate::diaz_serna_thilikos::diaz_algorithm::NodeReport;
+    use crate::graph_generation::graph_generation_algorithms::graph_from_edge_subset;
     use crate::integer_functions::integer_functions_methods;
-    use crate::integer_functions::integer_functions_methods::Mapping;
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::loop_semantics::loop_semantics_methods::LoopSemantics;
     use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
-    use crate::tree_decompositions::tree_structure::{TreeNode, Vertex};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
 
     /// A pseudonym for u64 since EdgeList will represented as u64
     /// note: maximum number of possible Edges is therefore 64
     pub type EdgeList = u64;
 
+    /// A list of graphs together with their number of homomorphisms.
+    pub type HomNumberList = Vec<(Graph, u64)>;
+
+    /// The maximal number of cached conditions of an introduce node, the cache is cleared when it is full.
+    const CONDITION_CACHE_CAPACITY: usize = 1 << 16;
+
     // 1. Implement table
     // 2. Implement algorithm
 
@@ -25,12 +31,12 @@ pub mod algorithm {
     pub struct DPData<'a>{
         table : HashMap<TreeNode, HashMap<(EdgeList, Mapping), u64>>, // table[p,e,phi], p = tree node, e = subset of edges represented by an integer, phi = mapping
         nice_tree_decomposition: &'a NiceTreeDecomposition,
-        to_graph: &'a MatrixGraph<(), (), Undirected>,
+        to_graph: &'a Graph,
         sorted_bags : HashMap<TreeNode, Vec<Vertex>>,
-        possible_edges : HashMap<TreeNode, Vec<usize>>, // list of possible indices of edges until the given tree node
         index_to_edge : HashMap<usize, (usize,usize)>, // maps the edge_index to the actual edge
         edge_to_index : HashMap<(usize,usize), usize>, // maps the edge to its index
         all_possible_edges : Vec<(usize,usize)>,
+        pool : Vec<HashMap<(EdgeList, Mapping), u64>>, // cleared tables of processed nodes whose capacity is reused
     }
 
     /// Implementation of functions being necessary for writing and reading the table
@@ -38,13 +44,12 @@ pub mod algorithm {
     impl<'a> DPData<'a> {
         /// A simple constructor for creating an empty table
         pub fn new<'b>(nice_tree_decomposition: &'b NiceTreeDecomposition,
-                        to_graph: &'b MatrixGraph<(), (), Undirected>,
+                        to_graph: &'b Graph,
                         ) -> DPData<'b> {
 
             let sorted_bags = DPData::sort_bags(nice_tree_decomposition);
 
-            let generated_possible_edges = generate_possible_edges(nice_tree_decomposition);
-            let all_possible_edges = generated_possible_edges.get(&nice_tree_decomposition.root()).unwrap();
+            let all_possible_edges = nice_tree_decomposition.all_possible_edges();
 
             // Hashmaps for faster accessing later on
             let mut index_to_edge = HashMap::new();
@@ -58,21 +63,14 @@ pub mod algorithm {
                 edge_to_index.insert((*v,*u), i);
             }
 
-            let mut possible_edges = HashMap::new();
-
-            for (u,v) in generated_possible_edges.iter(){
-                let edges : Vec<usize> = v.iter().map(|x| { *edge_to_index.get(x).unwrap() }).collect();
-                possible_edges.insert(*u, edges);
-            }
-
             DPData { table: HashMap::new(),
                 nice_tree_decomposition,
                 to_graph,
                 sorted_bags,
-                possible_edges,
                 index_to_edge,
                 edge_to_index,
-                all_possible_edges : all_possible_edges.clone() }
+                all_possible_edges : all_possible_edges.clone(),
+                pool : Vec::new() }
         }
 
         /// Returns the entry I[p,e,f] where p is a tree node, e a subset of possible edges and f is a mapping.
@@ -81,15 +79,52 @@ pub mod algorithm {
             if let Some(mappings) = self.table.get(p) { mappings.get(&(*e,*f)) } else { None }
         }
 
+        /// Returns the value of the entry I[p,e,f]. Entries which are not stored are 0.
+        pub fn value(&self, p: &TreeNode, e : &EdgeList, f: &Mapping) -> u64 {
+            *self.get(p, e, f).unwrap_or(&0)
+        }
+
         /// Sets the entry I[p,e,f] of the dynamic table to the value of v.
+        /// Since missing entries are 0, zero values are not written into the table.
         pub fn set(&mut self, p: TreeNode, e : EdgeList, f: Mapping, v: u64) {
+            if v == 0 {
+                if let Some(mappings) = self.table.get_mut(&p) { mappings.remove(&(e, f)); }
+                return;
+            }
+
             if let Some(mappings) = self.table.get_mut(&p) {
                 mappings.insert((e, f), v);
             } else {
-                self.table.insert(p, HashMap::from([((e, f), v)] ) );
+                // reuses the capacity of a recycled table if there is one
+                let mut mappings = self.pool.pop().unwrap_or_default();
+                mappings.insert((e, f), v);
+                self.table.insert(p, mappings);
             }
         }
 
+        /// Adds v to the entry I[p,e,f] of the dynamic table.
+        pub fn add(&mut self, p: TreeNode, e : EdgeList, f: Mapping, v: u64) {
+            let value = self.value(&p, &e, &f);
+            self.set(p, e, f, value + v);
+        }
+
+        /// Removes all entries of node p and returns them. These are exactly the non-zero entries of p.
+        pub fn take(&mut self, p: TreeNode) -> HashMap<(EdgeList, Mapping), u64> {
+            self.table.remove(&p).unwrap_or_default()
+        }
+
+        /// Returns the number of entries stored for node p, which equals the number of its non-zero entries.
+        pub fn table_size(&self, p: TreeNode) -> usize {
+            self.table.get(&p).map_or(0, |mappings| mappings.len())
+        }
+
+        /// Returns a table which is not needed anymore to the pool. Its allocated capacity
+        /// is reused by the next node whose table is created.
+        pub fn recycle(&mut self, mut entries: HashMap<(EdgeList, Mapping), u64>) {
+            entries.clear();
+            self.pool.push(entries);
+        }
+
         /// Apply function where the dimension is already set to |V(G)|.
         pub fn table_apply(&self, f : Mapping, s : Mapping) -> Mapping{
             integer_functions_methods::apply(self.to_graph.node_count() as Mapping, f, s)
@@ -108,7 +143,7 @@ pub mod algorithm {
         /// This is basically the max mapping function applied to the bag(p) and |V(G)|.
         /// It returns the number of mappings from bag(p) to |V(G)|
         pub fn max_bag_mappings(&self, node : TreeNode) -> Mapping{
-            integer_functions_methods::max_mappings(self.nice_tree_decomposition.bag(node).unwrap().len() as Mapping,
+            integer_functions_methods::max_mappings(self.nice_tree_decomposition.bit_bag(node).unwrap().len() as Mapping,
                                                     self.to_graph.node_count() as Mapping )
         }
 
@@ -116,10 +151,9 @@ pub mod algorithm {
         fn sort_bags(nice_tree_decomposition : &NiceTreeDecomposition) -> HashMap<TreeNode, Vec<Vertex>>{
             let mut sorted_bags = HashMap::new();
 
+            // the vertices of a bitmask bag are already iterated in ascending order
             for p in nice_tree_decomposition.stingy_ordering(){
-                let mut vertex_vector = Vec::from_iter(nice_tree_decomposition.bag(p).unwrap().iter());
-                vertex_vector.sort();
-                sorted_bags.insert(p, vertex_vector.iter().map(|e| **e).collect());
+                sorted_bags.insert(p, nice_tree_decomposition.bit_bag(p).unwrap().iter().collect());
             }
 
             sorted_bags
@@ -137,8 +171,8 @@ pub mod algorithm {
         /// Returns the vector of all possible edges.
         pub fn all_possible_edges(&self) -> &Vec<(usize, usize)> { &self.all_possible_edges }
 
-        /// Returns a vector of the indices of all possible edges until node p
-        pub fn possible_edges(&self, p : TreeNode) -> Option<&Vec<usize>> { self.possible_edges.get(&p) }
+        /// Returns the integer representation of all possible edges until node p, see NiceTreeDecomposition::possible_edge_mask.
+        pub fn possible_edge_mask(&self, p : TreeNode) -> Option<EdgeList> { self.nice_tree_decomposition.possible_edge_mask(p) }
 
         /// A function removing all entries for a given Node.
         pub fn remove(&mut self, p : TreeNode){
@@ -161,49 +195,234 @@ pub mod algorithm {
         pub fn intersection(&self, edge_set_1 : EdgeList, edge_set_2 : EdgeList) -> EdgeList { edge_set_1 & edge_set_2 }
 
         // Given an edge set in integer representation, this functions returns a graph with the given edges.
-        pub fn edges_to_graph(&self, edges : EdgeList) -> MatrixGraph<(), (), Undirected>{
+        pub fn edges_to_graph(&self, edges : EdgeList) -> Graph{
+            graph_from_edge_subset(self.nice_tree_decomposition.vertex_count() as usize, &self.all_possible_edges, edges)
+        }
+    }
 
-            let mut graph : MatrixGraph<(), (), Undirected> = petgraph::matrix_graph::MatrixGraph::new_undirected();
-            let number_of_vertices = self.nice_tree_decomposition.vertex_count();
+    /// Given a nice tree decomposition and an edge set in integer representation regarding the order of
+    /// possible edges of the nice tree decomposition, this function returns the graph with the given edges.
+    /// This can be used to interpret the keys returned by modified_dp_counts.
+    pub fn edges_to_graph(ntd : &NiceTreeDecomposition, edges : EdgeList) -> Graph{
+        graph_from_edge_subset(ntd.vertex_count() as usize, ntd.all_possible_edges(), edges)
+    }
 
-            for _ in 0..number_of_vertices{
-                graph.add_node(());
+    /// Inverse of edges_to_graph: Given a nice tree decomposition and a graph, this function returns the
+    /// integer representation of the edge set of the graph regarding the order of possible edges.
+    /// Returns None if the graph contains an edge which is not a possible edge of the nice tree decomposition.
+    pub fn graph_to_edges(ntd : &NiceTreeDecomposition, graph : &Graph) -> Option<EdgeList>{
+        let all_possible_edges = ntd.all_possible_edges();
+
+        let mut edges : EdgeList = 0;
+        let mut number_of_edges = 0;
+        for (i, (u,v)) in all_possible_edges.iter().enumerate(){
+            if *u.max(v) < graph.node_count() && graph.has_edge(Vertex::new(*u), Vertex::new(*v)){
+                edges |= 1 << i;
+                number_of_edges += 1;
             }
+        }
 
-            let mut edge_list = vec![];
-            // extract possible edges by looping over all possibles indices
-            for i in 0..self.all_possible_edges.len() as u32
-            {
-                let filter = 2_u64.pow(i);
-                if self.intersection(filter, edges) == filter{
-                    edge_list.push(self.index_to_edge(&(i as usize)).unwrap());
-                }
+        if number_of_edges == graph.edge_count() { Some(edges) } else { None }
+    }
+
+    /// implementation of the equivalence class algorithm
+    pub fn modified_dp(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<(Graph, u64)> {
+
+        modified_dp_with_report(ntd, to_graph).0
+    }
+
+    /// Implementation of the equivalence class algorithm which additionally returns a report for each node
+    /// in the stingy ordering, containing the size of its table and the time spent on it.
+    pub fn modified_dp_with_report(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> (HomNumberList, Vec<NodeReport>) {
+
+        let mut report = vec![];
+        let dpdata = compute_table(ntd, to_graph, None, &mut report);
+
+        // final return of all hom numbers
+        let mut graph_hom_number_list = vec![];
+
+        for (graph_number, hom_number) in root_table(dpdata).counts(){
+            graph_hom_number_list.push((edges_to_graph(ntd, graph_number), hom_number) );
+        }
+        (graph_hom_number_list, report)
+    }
+
+    /// Implementation of the equivalence class algorithm returning the number of homomorphisms for each graph
+    /// in $H_\tau$ keyed by the integer representation of its edge set. Graphs can be constructed out of
+    /// the keys with edges_to_graph.
+    pub fn modified_dp_counts(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> HashMap<EdgeList, u64> {
+        modified_dp_root_table(ntd, to_graph).counts()
+    }
+
+    /// Implementation of the equivalence class algorithm restricted to the connected spanning graphs of $H_\tau$,
+    /// i.e. the graphs on all vertices of the nice tree decomposition whose edges connect all vertices.
+    /// The counts are keyed by the integer representation of the edge sets as in modified_dp_counts.
+    pub fn modified_dp_connected_counts(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> HashMap<EdgeList, u64> {
+        let number_of_vertices = ntd.vertex_count() as usize;
+        modified_dp_counts(ntd, to_graph).into_iter()
+            .filter(|&(edges, _)| is_connected_edge_set(number_of_vertices, ntd.all_possible_edges(), edges))
+            .collect()
+    }
+
+    /// Implementation of the equivalence class algorithm returning only the connected spanning graphs of $H_\tau$
+    /// together with their number of homomorphisms, see modified_dp_connected_counts.
+    pub fn modified_dp_connected(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> HomNumberList {
+        modified_dp_connected_counts(ntd, to_graph).into_iter()
+            .map(|(edges, hom_number)| (edges_to_graph(ntd, edges), hom_number))
+            .collect()
+    }
+
+    /// Checks whether the edges of the given subset of the edge universe connect all vertices 0,..,n-1
+    /// by joining the endpoints of each edge in a union-find structure.
+    pub fn is_connected_edge_set(number_of_vertices : usize, universe : &[(usize, usize)], edges : EdgeList) -> bool {
+        let mut parent : Vec<usize> = (0..number_of_vertices).collect();
+
+        fn find(parent : &mut [usize], mut v : usize) -> usize {
+            while parent[v] != v {
+                // path halving
+                parent[v] = parent[parent[v]];
+                v = parent[v];
             }
+            v
+        }
 
-            for (u,v) in edge_list{
-                graph.add_edge(NodeIndex::new(*u),NodeIndex::new(*v), ());
+        let mut components = number_of_vertices;
+        for (i, &(u, v)) in universe.iter().enumerate(){
+            if edges & (1 << i) == 0 { continue; }
+
+            let (root_u, root_v) = (find(&mut parent, u), find(&mut parent, v));
+            if root_u != root_v {
+                parent[root_u] = root_v;
+                components -= 1;
             }
+        }
+
+        components <= 1
+    }
 
-            graph
+    /// Implementation of the equivalence class algorithm where loops are treated according to the given loop semantics.
+    /// Under Ignore semantics the graphs in $H_\tau$ with loops get the same number as the graph without its loops.
+    /// Under Reflexive semantics every target vertex has a loop and therefore loops never restrict homomorphisms.
+    pub fn modified_dp_with_loop_semantics(ntd : &NiceTreeDecomposition, to_graph : &Graph, loop_semantics : LoopSemantics) -> Vec<(Graph, u64)> {
 
+        let to_graph = loop_semantics.apply_to_target(to_graph);
+        let counts = modified_dp_counts(ntd, &to_graph);
+
+        // integer representation of all loops contained in the possible edges
+        let mut loops : EdgeList = 0;
+        for (i, (u,v)) in ntd.all_possible_edges().iter().enumerate(){
+            if u == v { loops |= 1 << i; }
+        }
+
+        let mut graph_hom_number_list = vec![];
+        for edges in counts.keys(){
+            let hom_number = match loop_semantics {
+                LoopSemantics::Ignore => *counts.get(&(edges & !loops)).unwrap(),
+                LoopSemantics::Respect | LoopSemantics::Reflexive => *counts.get(edges).unwrap(),
+            };
+            graph_hom_number_list.push((edges_to_graph(ntd, *edges), hom_number));
         }
+        graph_hom_number_list
     }
 
-    /// implementation of the equivalence class algorithm
-    pub fn modified_dp(ntd : &NiceTreeDecomposition, to_graph : &MatrixGraph<(),(), Undirected>) -> Vec<(MatrixGraph<(), (), Undirected>, u64)> {
+    /// The table of the root node, i.e. the row I[root,e,.] of each edge set e in $H_\tau$, which maps the
+    /// mappings from the root bag to to_graph to the number of their extensions to homomorphisms.
+    /// The number of homomorphisms of the graph with edge set e is the sum of its row. Nice tree
+    /// decompositions constructed by this crate have an empty root bag, such that each row consists of the
+    /// single empty mapping represented by 0, but imported decompositions may end with a non-empty bag.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct RootTable {
+        root_bag : Vec<Vertex>,
+        number_of_edges : usize,
+        rows : HashMap<EdgeList, HashMap<Mapping, u64>>,
+    }
+
+    impl RootTable {
+
+        /// Returns the sorted bag of the root, whose mappings index the rows.
+        pub fn root_bag(&self) -> &[Vertex] { &self.root_bag }
+
+        /// Returns the non-zero entries of the row of the given edge set, None if all entries are zero.
+        pub fn row(&self, edges : EdgeList) -> Option<&HashMap<Mapping, u64>> { self.rows.get(&edges) }
+
+        /// Returns the entry I[root,e,f] of the edge set e and the mapping f of the root bag.
+        pub fn value(&self, edges : EdgeList, f : Mapping) -> u64 {
+            self.row(edges).and_then(|row| row.get(&f)).copied().unwrap_or(0)
+        }
+
+        /// Returns the number of homomorphisms of the graph with the given edge set, i.e. the sum of its row.
+        pub fn hom_number(&self, edges : EdgeList) -> u64 {
+            self.row(edges).map_or(0, |row| row.values().sum())
+        }
+
+        /// Returns the number of homomorphisms of each graph in $H_\tau$ keyed by its edge set. Since zero
+        /// entries are not stored, all subsets of the possible edges are filled in.
+        ///
+        /// Panics if there are 64 possible edges, whose subsets cannot be enumerated by an EdgeList.
+        pub fn counts(&self) -> HashMap<EdgeList, u64> {
+            assert!(self.number_of_edges < EdgeList::BITS as usize, "The subsets of {} possible edges cannot be enumerated!", self.number_of_edges);
+            (0..(1 as EdgeList) << self.number_of_edges)
+                .map(|edges| (edges, self.hom_number(edges)))
+                .collect()
+        }
+    }
+
+    /// Implementation of the equivalence class algorithm returning the complete table of the root node.
+    pub fn modified_dp_root_table(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> RootTable {
+        root_table(compute_table(ntd, to_graph, None, &mut vec![]))
+    }
+
+    /// Implementation of the equivalence class algorithm for the single pattern "from_graph", whose edges have to be
+    /// possible edges of the nice tree decomposition. The edge sets are restricted to the edges of the pattern, such
+    /// that each node has a single edge set and the tables have the size of those of the algorithm of diaz et all.
+    /// Returns None if the pattern contains an edge which is not a possible edge.
+    pub fn modified_dp_for_pattern(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Option<u64> {
+        let edges = graph_to_edges(ntd, from_graph)?;
+        Some(root_table(compute_table(ntd, to_graph, Some(edges), &mut vec![])).hom_number(edges))
+    }
+
+    /// Moves the entries of the root node out of the data of the dynamic program into its rows.
+    fn root_table(mut dpdata : DPData) -> RootTable {
+        let root = dpdata.nice_tree_decomposition.root();
+        let root_bag = dpdata.sorted_bag(root).unwrap().clone();
+        let number_of_edges = dpdata.all_possible_edges().len();
+
+        let mut rows : HashMap<EdgeList, HashMap<Mapping, u64>> = HashMap::new();
+        for ((edges, f), value) in dpdata.take(root){
+            rows.entry(edges).or_default().insert(f, value);
+        }
+
+        RootTable { root_bag, number_of_edges, rows }
+    }
+
+    /// Runs the dynamic program along the stingy ordering and returns the data containing the table of the root.
+    /// If a pattern is given, only the subsets of its edges are computed which contain all of its edges possible
+    /// until the node, otherwise all subsets of the possible edges. The reports of all processed nodes are appended
+    /// to the given report.
+    fn compute_table<'a>(ntd : &'a NiceTreeDecomposition, to_graph : &'a Graph, pattern : Option<EdgeList>, report : &mut Vec<NodeReport>) -> DPData<'a> {
 
         let stingy_ordering = ntd.stingy_ordering();
         let mut dpdata = DPData::new(ntd,to_graph);
 
         for p in stingy_ordering{
 
+            let start = Instant::now();
+
             match ntd.node_type(p){
                 Some(NodeType::Leaf) =>  {
-                    let unique_vertex = (*ntd.unique_vertex(p).unwrap()).index();
+                    // the edge set which only contains the edge (unique_vertex, unique_vertex) is the only possible edge of a leaf
+                    let edge_set = dpdata.possible_edge_mask(p).unwrap();
+
+                    // a single pattern contains either the graph with or the graph without a self loop
+                    let (without_loop, with_loop) = match pattern {
+                        Some(pattern_edges) => (pattern_edges & edge_set == 0, pattern_edges & edge_set != 0),
+                        None => (true, true),
+                    };
 
                     // Set entries for the graph with one vertex without a self loop
                     // Iterate over all possible images of unique_vertex in to_graph
                     for image in 0..to_graph.node_count(){
+                        if !without_loop { break; }
 
                         // sets the entry I[p,0,image] = 1 which is the number of extending
                         // homomorphisms of the mapping (v,a) from the graph with only one vertex without a self loop
@@ -212,15 +431,10 @@ pub mod algorithm {
 
                     }
 
-                    // find the vertex of the edge (unique_vertex, unique_vertex)
-                    let unique_vertex_loop_index = *dpdata.edge_to_index( &( unique_vertex, unique_vertex) ).unwrap();
-
-                    // Construct the edge set which only contains the edge (unique_vertex, unique_vertex)
-                    let edge_set = 2_u32.pow(unique_vertex_loop_index as u32) as u64;
-
                     // Set entries for the graph with one vertex with a self loop
                     // Iterate over all possible images of unique_vertex in to_graph
                     for image in 0..to_graph.node_count(){
+                        if !with_loop { break; }
 
                         // Check if the image vertex has a self loop
                         if to_graph.has_edge(to_graph.from_index(image), to_graph.from_index(image)){
@@ -233,104 +447,79 @@ pub mod algorithm {
                 }
                 Some(NodeType::Introduce) => {
 
-
                     // get the unique child of p
                     let q = *ntd.unique_child(p).unwrap();
                     // get the introduced vertex
                     let v = *ntd.unique_vertex(p).unwrap();
 
-                    // get the indices of all possible edges in the subtree rooted at p
-                    let possible_edges_until_p = dpdata.possible_edges(p).unwrap();
+                    // the mappings of q are extended by the image of v to mappings of p
+                    let n = to_graph.node_count() as Mapping;
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+                    let indexer_p = BagIndexer::of_node(ntd, p, n);
 
-                    // sorted bag of q
-                    let sorted_q_bag = dpdata.sorted_bag(q).unwrap();
+                    // The possible edges of p which are not possible edges of q are exactly the edges between
+                    // v and the bag of p, since v does not occur in the subtree rooted at q.
+                    let new_edges_mask = dpdata.possible_edge_mask(p).unwrap() & !dpdata.possible_edge_mask(q).unwrap();
+                    let new_edges : Vec<usize> = (0..EdgeList::BITS as usize).filter(|&e| new_edges_mask & (1 << e) != 0).collect();
 
-                    // sorted bag of p
-                    let sorted_p_bag = dpdata.sorted_bag(p).unwrap();
+                    // only the non-zero entries of q can be extended to non-zero entries of p
+                    let entries_q = dpdata.take(q);
 
-                    // That is the case when no index will be found
-                    // the mapping will be but to the end of the new mapping
-                    let mut new_index = sorted_q_bag.len();
+                    // The condition whether (v,a) can be added to f_q only depends on the images of the neighbours
+                    // of v in the subset of new edges, hence it is cached per node keyed by the bitmask of their
+                    // significances in the bag of p, their images and a. Mappings which agree on these neighbours
+                    // share it. The cache is cleared when it is full, such that its memory is bounded.
+                    let mut conditions : HashMap<(u64, Mapping, Mapping), bool> = HashMap::new();
 
-                    // Find the position of the introduce vertex in the new mapping
-                    if let Some(index) = sorted_q_bag.iter().position(|&vertex| v.index() < vertex.index() ){ new_index = index; }
+                    // loop over all subsets of the new edges, a single pattern contains exactly its new edges
+                    let subsets : Vec<Vec<&usize>> = match pattern {
+                        Some(pattern_edges) => vec![new_edges.iter().filter(|&&e| pattern_edges & (1 << e) != 0).collect()],
+                        None => new_edges.iter().powerset().collect(),
+                    };
+                    for edges in subsets{
 
-                    // maps vertex to its significance in the bag of p
-                    let mut significance_hash = HashMap::new();
-                    for (i, item) in sorted_p_bag.iter().enumerate(){
-                        significance_hash.insert(*item, i);
-                    }
-
-                    // get the integer representation of all possible edges until q
-                    let possible_edges_of_q_integer = dpdata.possible_edges(q).unwrap();
-                    let possible_edges_of_q_integer = dpdata.edges_to_integer_representation(possible_edges_of_q_integer);
-
-                    // loop over all subsets of possible_edges_until_p
-
-
-                    for edges in possible_edges_until_p.clone().iter().powerset().collect::<Vec<_>>(){
-
-                        let mut s_q = vec![];
+                        // the significances of the neighbors of v in edges as bitmask
+                        let mut s_q : u64 = 0;
 
                         let v_index = v.index();
-                        // generate the set s_q, which corresponds to the neighbors of v in edges
                         for edge_index in &edges {
                             let (x,u) = dpdata.index_to_edge(*edge_index).unwrap();
-
-                            if *x == v_index {
-                                if !s_q.contains(u) {
-                                    s_q.push(*u);
-                                }
-                            }
-
-                            if *u == v_index{
-                                if !s_q.contains(x){
-                                    s_q.push(*x);
-                                }
-                            }
+                            let neighbor = if *x == v_index { *u } else { *x };
+                            s_q |= 1 << indexer_p.position(Vertex::new(neighbor)).unwrap();
                         }
 
                         let edges_without_ref = edges.iter().map(|x| { **x } ).collect();
 
-                        let edges_integer = dpdata.edges_to_integer_representation(&edges_without_ref);
+                        let new_edges_integer = dpdata.edges_to_integer_representation(&edges_without_ref);
 
                         // iterate over all new mappings by inserting (introduced_vertex,a)
-                        for f_q in 0..dpdata.max_bag_mappings(q){
+                        for (&(old_edges_integer, f_q), &value_q) in &entries_q{
                             for a in 0..to_graph.node_count(){
-                                // extend mapping by a at the new index
-                                let f_prime = dpdata.table_extend(f_q, new_index as Mapping, a as Mapping);
-
-                                let condition = {
-                                    let mut value = true;
+                                // extend mapping by (v,a)
+                                let f_prime = indexer_q.extend(f_q, v, a as Mapping);
 
-                                    for u in &s_q{
-                                        let image_of_unique_vertex = to_graph.from_index(a);
+                                // the images of the neighbours of v in edges as mapping of their significances
+                                let neighbour_images = (0..indexer_p.bag().len()).filter(|significance| s_q & (1 << significance) != 0)
+                                    .fold(0, |images, significance| images * n + indexer_p.image_at(f_prime, significance as Mapping));
 
-                                        // get the significance of vertex u in mapping f_prime
-                                        let significance = *significance_hash.get(&Vertex::new(*u) ).unwrap();
+                                if conditions.len() >= CONDITION_CACHE_CAPACITY { conditions.clear(); }
+                                let condition = *conditions.entry((s_q, neighbour_images, a as Mapping)).or_insert_with(|| {
+                                    let image_of_unique_vertex = to_graph.from_index(a);
 
-                                        let image_of_u = to_graph.from_index(dpdata.table_apply(f_prime, significance as Mapping) as usize);
-
-                                        if !to_graph.has_edge(image_of_unique_vertex, image_of_u){
-                                            value = false;
-                                            break;
-                                        }
-                                    }
-
-                                    value
-                                };
-
-                                let old_edges_list = dpdata.intersection(edges_integer, possible_edges_of_q_integer);
-                                dpdata.set(p, edges_integer ,f_prime,
-                                           *dpdata.get(&q, &old_edges_list,&f_q).unwrap() * (condition as u64 ));
+                                    (0..indexer_p.bag().len()).filter(|significance| s_q & (1 << significance) != 0).all(|significance| {
+                                        let image_of_u = to_graph.from_index(indexer_p.image_at(f_prime, significance as Mapping) as usize);
+                                        to_graph.has_edge(image_of_unique_vertex, image_of_u)
+                                    })
+                                });
 
+                                if condition {
+                                    dpdata.set(p, old_edges_integer | new_edges_integer, f_prime, value_q);
+                                }
                             }
                         }
-
-
                     }
-                    dpdata.remove(q);
 
+                    dpdata.recycle(entries_q);
                 }
                 Some(NodeType::Forget) => {
 
@@ -339,41 +528,18 @@ pub mod algorithm {
                     // get the introduced vertex
                     let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
 
-                    // transforms the bag into a sorted vertex used for integer functions
-                    let sorted_bag_q = dpdata.sorted_bag(q).unwrap();
-
-                    // find significance of forgotten vertex in the mappings of F_q
-                    let significance_forgotten_vertex = sorted_bag_q.iter().position(|x| *x == forgotten_vertex).unwrap();
-
-                    // get the indices of all possible edges in the subtree rooted at p
-                    let possible_edges_until_p = dpdata.possible_edges(p).unwrap();
-
-                    // iterate over all possible edge lists
-                    for edges in possible_edges_until_p.clone().iter().powerset().collect::<Vec<_>>() {
-
-                        let edges_without_ref = edges.iter().map(|x| { **x } ).collect();
-
-                        // integer representation of edge list
-                        let edges_integer = dpdata.edges_to_integer_representation(&edges_without_ref);
-
-                        // loop over all possible mappings from bag(p) to to_graph
-                        for f_prime in 0..dpdata.max_bag_mappings(p) {
-
-                            let mut sum = 0;
-
-                            // sum up over all possible images of the forgotten vertex
-                            for a in 0..to_graph.node_count(){
-                                let f_old = dpdata.table_extend(f_prime, significance_forgotten_vertex as Mapping, a as Mapping);
-                                sum += dpdata.get(&q, &edges_integer,&f_old).unwrap();
-                            }
-
-                            dpdata.set(p, edges_integer, f_prime, sum);
-
-                        }
+                    // the mappings of q are reduced by the image of the forgotten vertex
+                    let indexer_q = BagIndexer::of_node(ntd, q, to_graph.node_count() as Mapping);
 
+                    // sum up over all possible images of the forgotten vertex by adding each non-zero
+                    // entry of q to the mapping without the image of the forgotten vertex
+                    let entries_q = dpdata.take(q);
+                    for (&(edges_integer, f_old), &value) in &entries_q{
+                        let f_prime = indexer_q.reduce(f_old, forgotten_vertex);
+                        dpdata.add(p, edges_integer, f_prime, value);
                     }
 
-                    dpdata.remove(q)
+                    dpdata.recycle(entries_q);
 
                 }
                 Some(NodeType::Join) => {
@@ -383,58 +549,49 @@ pub mod algorithm {
                         let q2 = children.get(1).unwrap();
 
                         // get the integer representation of all possible edges until q
-                        let possible_edges_of_q1_integer = dpdata.possible_edges(*q1).unwrap();
-                        let possible_edges_of_q1_integer = dpdata.edges_to_integer_representation(possible_edges_of_q1_integer);
-
-                        let possible_edges_of_q2_integer = dpdata.possible_edges(*q2).unwrap();
-                        let possible_edges_of_q2_integer = dpdata.edges_to_integer_representation(possible_edges_of_q2_integer);
-
-                        // get the indices of all possible edges in the subtree rooted at p
-                        let possible_edges_until_p = dpdata.possible_edges(p).unwrap();
-
-                        // iterate over all possible edge lists
-                        for edges in possible_edges_until_p.clone().iter().powerset().collect::<Vec<_>>() {
-
-                            let edges_without_ref = edges.iter().map(|x| { **x } ).collect();
-
-                            // integer representation of edge list
-                            let edges_integer = dpdata.edges_to_integer_representation(&edges_without_ref);
-
-                            // Updates every new mapping
-                            for f in 0..dpdata.max_bag_mappings(p){
+                        let possible_edges_of_q1_integer = dpdata.possible_edge_mask(*q1).unwrap();
+                        let possible_edges_of_q2_integer = dpdata.possible_edge_mask(*q2).unwrap();
+
+                        let entries_q1 = dpdata.take(*q1);
+                        let entries_q2 = dpdata.take(*q2);
+
+                        // group the smaller table and stream the larger one, such that the memory of the groups is bounded by the smaller table
+                        let ((smaller, possible_edges_of_smaller), (larger, possible_edges_of_larger)) = if entries_q1.len() <= entries_q2.len() {
+                            ((entries_q1, possible_edges_of_q1_integer), (entries_q2, possible_edges_of_q2_integer))
+                        } else {
+                            ((entries_q2, possible_edges_of_q2_integer), (entries_q1, possible_edges_of_q1_integer))
+                        };
+
+                        // group the entries of the smaller table by their mapping and their edges which are also possible edges of the larger one
+                        let mut grouped_entries : HashMap<(EdgeList, Mapping), Vec<(EdgeList, u64)>> = HashMap::new();
+                        for (&(edges_smaller, f), &value_smaller) in &smaller{
+                            grouped_entries.entry((edges_smaller & possible_edges_of_larger, f)).or_default().push((edges_smaller, value_smaller));
+                        }
 
-                                let intersection1 = dpdata.intersection(edges_integer, possible_edges_of_q1_integer);
-                                let intersection2 = dpdata.intersection(edges_integer, possible_edges_of_q2_integer);
+                        // Two edge sets of the children can be combined if they agree on the common possible edges.
+                        // Every edge set of p is the union of exactly one such pair.
+                        for (&(edges_larger, f), &value_larger) in &larger{
+                            let common_edges = edges_larger & possible_edges_of_smaller;
 
-                                dpdata.set(p, edges_integer, f,
-                                dpdata.get(q1, &intersection1, &(f as Mapping)).unwrap() *
-                                    dpdata.get(q2, &intersection2, &(f as Mapping)).unwrap() );
+                            for &(edges_smaller, value_smaller) in grouped_entries.get(&(common_edges, f)).into_iter().flatten(){
+                                dpdata.set(p, edges_smaller | edges_larger, f, value_smaller * value_larger);
                             }
-
                         }
 
-                        // Deletes entries og q1 and q2
-                        dpdata.remove(*q1);
-                        dpdata.remove(*q2);
+                        dpdata.recycle(smaller);
+                        dpdata.recycle(larger);
                     }
 
                 }
                 None => {}
             }
 
-        }
-
-        // final return of all hom numbers
-        let mut graph_hom_number_list = vec![];
-
-        let final_list = dpdata.table.get(&ntd.root()).unwrap();
-        for ((graph_number, i),hom_number) in final_list{
-
-            if *i == 0 {
-                graph_hom_number_list.push((dpdata.edges_to_graph(*graph_number), *hom_number) );
+            if let Some(node_type) = ntd.node_type(p){
+                report.push(NodeReport { node: p, node_type: node_type.clone(), table_size: dpdata.table_size(p), time: start.elapsed() });
             }
         }
-        graph_hom_number_list
+
+        dpdata
     }
 
 }
\ No newline at end of file
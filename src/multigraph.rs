@@ -0,0 +1,130 @@
+/// A module containing homomorphism counting for multigraphs. The multiplicity of an edge is
+/// stored as the edge weight of a MatrixGraph. The number of homomorphisms from H to G is defined
+/// in the partition-function sense as
+/// $hom(H,G) = \sum_{\phi} \prod_{uv \in E(H)} m_G(\phi(u),\phi(v))^{m_H(u,v)}$
+/// where $m$ denotes the multiplicity of an edge and is 0 for non-edges.
+/// For simple graphs (all multiplicities equal 1) this equals the usual number of homomorphisms.
+pub mod multigraph_algorithms {
+    use petgraph::matrix_graph::MatrixGraph;
+    use petgraph::Undirected;
+    use petgraph::visit::NodeIndexable;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{weighted_diaz_serna_thilikos, NodeTable, Weights};
+    use crate::integer_functions::integer_functions_methods;
+    use crate::integer_functions::integer_functions_methods::{Mapping, max_mappings};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex, VertexIndex};
+
+    /// An undirected graph with edge multiplicities as edge weights.
+    pub type MultiGraph = MatrixGraph<(), u64, Undirected, Option<u64>, VertexIndex>;
+
+    /// Returns the multiplicity of the edge (u,v), which is 0 if the edge does not exist.
+    pub fn multiplicity(graph : &MultiGraph, u : Vertex, v : Vertex) -> u64{
+        if graph.has_edge(u, v) { *graph.edge_weight(u, v) } else { 0 }
+    }
+
+    /// Converts a simple graph into a multigraph where every edge has multiplicity 1.
+    pub fn to_multigraph(graph : &Graph) -> MultiGraph{
+        let mut multigraph = MultiGraph::default();
+
+        for _ in 0..graph.node_count(){
+            multigraph.add_node(());
+        }
+
+        for u in 0..graph.node_count(){
+            for v in u..graph.node_count(){
+                if graph.has_edge(graph.from_index(u), graph.from_index(v)){
+                    multigraph.add_edge(Vertex::new(u), Vertex::new(v), 1);
+                }
+            }
+        }
+
+        multigraph
+    }
+
+    /// Returns the simple graph with the same edges as the multigraph.
+    pub fn underlying_graph(multigraph : &MultiGraph) -> Graph {
+        let n = multigraph.node_count();
+        let mut graph = Graph::default();
+        for _ in 0..n{
+            graph.add_node(());
+        }
+        for a in 0..n{
+            for b in a..n{
+                if multigraph.has_edge(Vertex::new(a), Vertex::new(b)) { graph.add_edge(Vertex::new(a), Vertex::new(b), ()); }
+            }
+        }
+        graph
+    }
+
+    /// A brute force algorithm which iterates over all mappings from "from_graph" to "to_graph"
+    /// and sums up the products of the multiplicities of the image edges.
+    /// Returns None if the number does not fit into 64 bits.
+    pub fn brute_force_multigraph(from_graph : &MultiGraph, to_graph : &MultiGraph) -> Option<u64>{
+        let h = from_graph.node_count();
+        let g = to_graph.node_count() as Mapping;
+
+        // collect all edges of from_graph once with their multiplicities
+        let mut edges = vec![];
+        for u in 0..h{
+            for v in u..h{
+                let m = multiplicity(from_graph, Vertex::new(u), Vertex::new(v));
+                if m > 0 { edges.push((u, v, m)); }
+            }
+        }
+
+        let mut sum : u64 = 0;
+
+        // for all mappings from H to G
+        for f in 0..max_mappings(h as Mapping, g){
+            let mut product : u64 = 1;
+            for &(u, v, m) in &edges{
+                let map_u = integer_functions_methods::apply(g, f, u as Mapping) as usize;
+                let map_v = integer_functions_methods::apply(g, f, v as Mapping) as usize;
+                product = product.checked_mul(power(multiplicity(to_graph, Vertex::new(map_u), Vertex::new(map_v)), m)?)?;
+                if product == 0 { break; }
+            }
+            sum = sum.checked_add(product)?;
+        }
+
+        Some(sum)
+    }
+
+    /// Returns the multiplicity m of an edge of the target to the power of the multiplicity k of an edge
+    /// of the pattern, or None if it does not fit into 64 bits.
+    fn power(m : u64, k : u64) -> Option<u64> {
+        match m {
+            0 | 1 => Some(if k == 0 { 1 } else { m }),
+            _ => m.checked_pow(u32::try_from(k).ok()?),
+        }
+    }
+
+    /// The weights of the multigraph homomorphisms, i.e. the multiplicities of the image edges to the power
+    /// of the multiplicities of the pattern edges.
+    struct MultiplicityWeights<'a> {
+        from_graph: &'a MultiGraph,
+        to_graph: &'a MultiGraph,
+    }
+
+    impl<'a> Weights for MultiplicityWeights<'a> {
+        fn node_count(&self) -> usize { self.to_graph.node_count() }
+
+        fn vertex_weight(&self, v : Vertex, a : Mapping) -> Option<u64> {
+            let a = Vertex::new(a as usize);
+            power(multiplicity(self.to_graph, a, a), multiplicity(self.from_graph, v, v))
+        }
+
+        fn edge_weight(&self, u : Vertex, v : Vertex, a : Mapping, b : Mapping) -> Option<u64> {
+            power(multiplicity(self.to_graph, Vertex::new(a as usize), Vertex::new(b as usize)), multiplicity(self.from_graph, u, v))
+        }
+    }
+
+    /// Implementation of the algorithm of diaz et all for multigraphs. Instead of checking
+    /// whether an edge is mapped onto an edge, introduce and leaf nodes multiply the
+    /// multiplicities of the image edges, see weighted_diaz_serna_thilikos.
+    /// Returns None if the number does not fit into 64 bits.
+    pub fn diaz_serna_thilikos_multigraph(from_graph : &MultiGraph, ntd : &NiceTreeDecomposition, to_graph : &MultiGraph) -> Option<u64>{
+        let weights = MultiplicityWeights { from_graph, to_graph };
+        weighted_diaz_serna_thilikos::<NodeTable, _>(&underlying_graph(from_graph), ntd, &weights)
+    }
+
+}
@@ -0,0 +1,166 @@
+/// A module containing an on-disk cache of nice tree decompositions, such that the decomposition of a pattern
+/// which is counted repeatedly, e.g. against several targets or in several runs, has to be computed only once.
+/// The decompositions are stored as .ntd files named after a hash of the pattern, by default in the
+/// user cache directory.
+pub mod decomposition_cache {
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use petgraph::visit::NodeIndexable;
+    use crate::file_handler::tree_decomposition_handler::{export_ntd, import_ntd};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_decomposition::TreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The name of the cache directory within the user cache directory.
+    const CACHE_NAME : &str = "counting_homomorphisms/ntd";
+
+    /// Returns a hash of the pattern given by its number of vertices and its edges (u,v) with u <= v,
+    /// i.e. the hash does not depend on the order in which the edges have been added.
+    /// The FNV-1a hash is used since it is stable across runs and compiler versions, unlike the hasher of the
+    /// standard library. Isomorphic but differently labelled patterns have different hashes.
+    pub fn pattern_hash(graph : &Graph) -> u64{
+        const OFFSET_BASIS : u64 = 0xcbf29ce484222325;
+        const PRIME : u64 = 0x100000001b3;
+
+        let n = graph.node_count();
+        let mut hash = OFFSET_BASIS;
+        let mut add = |value : usize| {
+            for byte in (value as u64).to_le_bytes(){
+                hash = (hash ^ byte as u64).wrapping_mul(PRIME);
+            }
+        };
+
+        add(n);
+        for u in 0..n{
+            for v in u..n{
+                if graph.has_edge(graph.from_index(u), graph.from_index(v)) { add(u); add(v); }
+            }
+        }
+        hash
+    }
+
+    /// Returns the default directory of the cache, i.e. $XDG_CACHE_HOME/counting_homomorphisms/ntd or
+    /// ~/.cache/counting_homomorphisms/ntd. The temporary directory is used if neither variable is set.
+    pub fn default_directory() -> PathBuf{
+        let non_empty = |name : &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+        non_empty("XDG_CACHE_HOME")
+            .or_else(|| non_empty("HOME").map(|home| home.join(".cache")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join(CACHE_NAME)
+    }
+
+    /// A directory containing the cached nice tree decompositions.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct NtdCache {
+        directory: PathBuf,
+    }
+
+    /// A decomposition stored in the cache.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct CacheEntry {
+        /// the hash of the pattern, see pattern_hash
+        pub hash: u64,
+        pub path: PathBuf,
+        /// the size of the .ntd file in bytes
+        pub size: u64,
+    }
+
+    impl Default for NtdCache {
+        /// The cache in the default directory.
+        fn default() -> NtdCache { NtdCache::new(default_directory()) }
+    }
+
+    impl NtdCache {
+
+        /// Creates a cache in the given directory, the directory is created when the first decomposition is stored.
+        pub fn new<P>(directory : P) -> NtdCache
+            where P: AsRef<Path>
+        {
+            NtdCache { directory: directory.as_ref().to_path_buf() }
+        }
+
+        /// Returns the directory of the cache.
+        pub fn directory(&self) -> &Path { &self.directory }
+
+        /// Returns the path of the cached decomposition of the pattern.
+        pub fn path(&self, graph : &Graph) -> PathBuf{
+            self.directory.join(format!("{:016x}.ntd", pattern_hash(graph)))
+        }
+
+        /// Returns the cached decomposition of the pattern. Decompositions which are not valid for the pattern,
+        /// e.g. due to a hash collision, are ignored.
+        pub fn get(&self, graph : &Graph) -> Option<NiceTreeDecomposition>{
+            let path = self.path(graph);
+            if !path.is_file() { return None; }
+
+            let ntd = import_ntd(path)?;
+            if ntd.vertex_count() as usize != graph.node_count() { return None; }
+            TreeDecomposition::from(&ntd).validate(graph).ok()?;
+            Some(ntd)
+        }
+
+        /// Stores the decomposition of the pattern in the cache.
+        pub fn insert(&self, graph : &Graph, ntd : &NiceTreeDecomposition) -> io::Result<()>{
+            fs::create_dir_all(&self.directory)?;
+            export_ntd(ntd, self.path(graph))
+        }
+
+        /// Returns true if a decomposition of the pattern is stored, without checking whether it is valid.
+        pub fn contains(&self, graph : &Graph) -> bool { self.path(graph).is_file() }
+
+        /// Returns all stored decompositions ordered by their hash, other files in the directory are ignored.
+        /// A missing directory is an empty cache.
+        pub fn entries(&self) -> io::Result<Vec<CacheEntry>>{
+            let read_dir = match fs::read_dir(&self.directory) {
+                Ok(read_dir) => read_dir,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+                Err(error) => return Err(error),
+            };
+
+            let mut entries = vec![];
+            for entry in read_dir{
+                let path = entry?.path();
+                if path.extension().is_none_or(|extension| extension != "ntd") { continue; }
+                let Some(hash) = path.file_stem().and_then(|stem| u64::from_str_radix(&stem.to_string_lossy(), 16).ok()) else { continue; };
+                let size = fs::metadata(&path)?.len();
+                entries.push(CacheEntry { hash, path, size });
+            }
+            entries.sort_by_key(|entry| entry.hash);
+            Ok(entries)
+        }
+
+        /// Returns the total size of all stored decompositions in bytes.
+        pub fn size(&self) -> io::Result<u64>{ Ok(self.entries()?.iter().map(|entry| entry.size).sum()) }
+
+        /// Removes the stored decomposition of the pattern, returns false if there was none.
+        pub fn remove(&self, graph : &Graph) -> io::Result<bool>{
+            match fs::remove_file(self.path(graph)) {
+                Ok(()) => Ok(true),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+                Err(error) => Err(error),
+            }
+        }
+
+        /// Removes all stored decompositions and returns their number. Other files in the directory are kept.
+        pub fn clear(&self) -> io::Result<usize>{
+            let entries = self.entries()?;
+            for entry in &entries{
+                fs::remove_file(&entry.path)?;
+            }
+            Ok(entries.len())
+        }
+
+        /// Returns the cached decomposition of the pattern or computes and stores it. A decomposition which
+        /// cannot be stored is still returned, since the cache only saves time.
+        pub fn get_or_compute<F>(&self, graph : &Graph, compute : F) -> NiceTreeDecomposition
+            where F: FnOnce(&Graph) -> NiceTreeDecomposition
+        {
+            if let Some(ntd) = self.get(graph) { return ntd; }
+
+            let ntd = compute(graph);
+            let _ = self.insert(graph, &ntd);
+            ntd
+        }
+    }
+}
@@ -0,0 +1,147 @@
+/// A module containing generators of families of nice tree decompositions with controlled parameters for the
+/// running time experiments, replacing the python scripts which generated the .ntd files of data/Experiments/ntds.
+/// All generated decompositions are paths of introduce and forget nodes above a single leaf, such that only the
+/// number of vertices, the width and the number of possible edges |E_τ| vary between the members of a family.
+pub mod ntd_generators {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::fs::File;
+    use std::io;
+    use std::io::{BufWriter, Write};
+    use std::path::{Path, PathBuf};
+    use crate::file_handler::tree_decomposition_handler::write_ntd;
+    use crate::tree_decompositions::nice_tree_decomposition::{Bag, NiceTreeDecomposition, NodeData, NodeType};
+    use crate::tree_decompositions::tree_structure::{TreeNode, TreeStructure, Vertex};
+
+    /// A family of nice tree decompositions.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum NtdFamily {
+        /// e_tau_path(n, i) for i = 0,..,n-1, i.e. a fixed number of vertices and |E_τ| = n,..,2n-1
+        ETauPaths { vertices: usize },
+        /// e_tau_path(n, m - n) for all n with n <= m <= 2n-1, i.e. a fixed |E_τ| = m and 2n nodes
+        FixedETau { possible_edges: usize },
+        /// window_ntd(n, w) for n = w+1,..,max_vertices, i.e. a fixed width and increasing |E_τ|
+        FixedWidth { width: usize, max_vertices: usize },
+        /// window_ntd(n, 1) for n = 2,..,max_vertices, i.e. the decompositions of the paths
+        Paths { max_vertices: usize },
+        /// complete_ntd(n) for n = 1,..,max_vertices, in which every pair of vertices is a possible edge
+        Complete { max_vertices: usize },
+    }
+
+    /// A generated nice tree decomposition together with its file name, which contains its parameters.
+    pub struct GeneratedNtd {
+        pub name: String,
+        pub ntd: NiceTreeDecomposition,
+    }
+
+    impl NtdFamily {
+        /// Returns all members of the family.
+        pub fn generate(&self) -> Vec<GeneratedNtd> {
+            let e_tau_path_member = |n : usize, i : usize| GeneratedNtd { name: format!("e_tau_modifying_path_{}_{}.ntd", n, i), ntd: e_tau_path(n, i) };
+
+            match *self {
+                NtdFamily::ETauPaths { vertices } => (0..vertices).map(|i| e_tau_path_member(vertices, i)).collect(),
+                NtdFamily::FixedETau { possible_edges } => ((possible_edges + 2) / 2..=possible_edges)
+                    .map(|n| e_tau_path_member(n, possible_edges - n))
+                    .collect(),
+                NtdFamily::FixedWidth { width, max_vertices } => (width + 1..=max_vertices)
+                    .map(|n| GeneratedNtd { name: format!("ntd_window_{}_{}.ntd", n, width), ntd: window_ntd(n, width) })
+                    .collect(),
+                NtdFamily::Paths { max_vertices } => (2..=max_vertices)
+                    .map(|n| GeneratedNtd { name: format!("ntd_path_{}.ntd", n), ntd: window_ntd(n, 1) })
+                    .collect(),
+                NtdFamily::Complete { max_vertices } => (1..=max_vertices)
+                    .map(|n| GeneratedNtd { name: format!("ntd_complete_{}.ntd", n), ntd: complete_ntd(n) })
+                    .collect(),
+            }
+        }
+    }
+
+    /// Returns the path decomposition of width 1 on n vertices whose first i+1 vertices form a path, while all
+    /// further vertices are isolated, hence |E_τ| = n + i. Panics if i >= n.
+    pub fn e_tau_path(n : usize, i : usize) -> NiceTreeDecomposition {
+        assert!(i < n, "At most n-1 of the possible edges of a path on n vertices are no loops!");
+
+        let mut nodes = vec![(NodeType::Leaf, vec![0])];
+        for v in 1..n{
+            if v <= i {
+                nodes.push((NodeType::Introduce, vec![v - 1, v]));
+                nodes.push((NodeType::Forget, vec![v]));
+            } else {
+                nodes.push((NodeType::Forget, vec![]));
+                nodes.push((NodeType::Introduce, vec![v]));
+            }
+        }
+        nodes.push((NodeType::Forget, vec![]));
+        path_ntd(nodes, n)
+    }
+
+    /// Returns the path decomposition of width w on n vertices whose bags are the windows of w+1 consecutive
+    /// vertices, i.e. the possible edges are the loops and the edges of the w-th power of the path. Panics if
+    /// n <= w.
+    pub fn window_ntd(n : usize, w : usize) -> NiceTreeDecomposition {
+        assert!(n > w, "A window of width {} needs at least {} vertices!", w, w + 1);
+
+        let mut nodes = vec![(NodeType::Leaf, vec![0])];
+        for v in 1..n{
+            // the oldest vertex leaves the window before v enters it
+            if v > w { nodes.push((NodeType::Forget, (v - w..v).collect())); }
+            nodes.push((NodeType::Introduce, (v.saturating_sub(w)..=v).collect()));
+        }
+        for first in n - 1 - w..n{
+            nodes.push((NodeType::Forget, (first + 1..n).collect()));
+        }
+        path_ntd(nodes, n)
+    }
+
+    /// Returns the path decomposition of width n-1 on n vertices which introduces all vertices before forgetting
+    /// them, such that |E_τ| = n(n+1)/2. Panics if n is 0.
+    pub fn complete_ntd(n : usize) -> NiceTreeDecomposition {
+        assert!(n > 0, "A nice tree decomposition needs at least one vertex!");
+
+        let mut nodes = vec![(NodeType::Leaf, vec![0])];
+        for v in 1..n{
+            nodes.push((NodeType::Introduce, (0..=v).collect()));
+        }
+        for v in (0..n).rev(){
+            nodes.push((NodeType::Forget, (0..v).collect()));
+        }
+        path_ntd(nodes, n)
+    }
+
+    /// Writes all members of the family into the directory, which is created if it does not exist, and returns
+    /// the paths of the written files. Every file starts with a comment containing its parameters.
+    pub fn export_family<P>(family : &NtdFamily, directory : P) -> io::Result<Vec<PathBuf>>
+        where P: AsRef<Path>
+    {
+        fs::create_dir_all(&directory)?;
+
+        let mut paths = vec![];
+        for member in family.generate(){
+            let path = directory.as_ref().join(&member.name);
+            let mut writer = BufWriter::new(File::create(&path)?);
+            writeln!(writer, "# auto generated nice tree decomposition with {} vertices, width {} and {} possible edges.",
+                     member.ntd.vertex_count(), member.ntd.width(), member.ntd.all_possible_edges().len())?;
+            write_ntd(&member.ntd, &mut writer)?;
+            writer.flush()?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Returns the nice tree decomposition whose i-th node has the i-th node type and bag and the (i-1)-th node as
+    /// child, i.e. the last node is the root.
+    fn path_ntd(nodes : Vec<(NodeType, Vec<usize>)>, number_of_vertices : usize) -> NiceTreeDecomposition {
+        let width = nodes.iter().map(|(_, bag)| bag.len()).max().unwrap() as u32 - 1;
+
+        let mut tree_structure = TreeStructure::new(nodes.len() as TreeNode);
+        for p in 1..nodes.len() as TreeNode{
+            tree_structure.add_child(p, p - 1);
+        }
+        let nodes_data : HashMap<TreeNode, NodeData> = nodes.into_iter().enumerate()
+            .map(|(p, (node_type, bag))| (p as TreeNode, NodeData::new(node_type, bag.into_iter().map(Vertex::new).collect::<Bag>())))
+            .collect();
+
+        NiceTreeDecomposition::new(tree_structure, nodes_data, number_of_vertices as u32, width)
+    }
+}
@@ -0,0 +1,60 @@
+/// A module containing the primitives used by all parallel code paths of the crate. With the "parallel" feature
+/// they are backed by rayon, without it they run sequentially in the calling thread, such that the crate compiles
+/// on platforms without threads, e.g. wasm. Both modes compute identical results, since the results are
+/// combined in the same order and only counts are summed up.
+pub mod parallel_execution {
+    #[cfg(feature = "parallel")]
+    use rayon::prelude::*;
+
+    /// True if the crate has been compiled with the "parallel" feature.
+    pub const ENABLED : bool = cfg!(feature = "parallel");
+
+    /// Runs both closures, potentially in parallel, and returns both results.
+    pub fn join<A, B, RA, RB>(a : A, b : B) -> (RA, RB)
+        where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send
+    {
+        #[cfg(feature = "parallel")]
+        { rayon::join(a, b) }
+        #[cfg(not(feature = "parallel"))]
+        { (a(), b()) }
+    }
+
+    /// Returns the vector [f(0), .., f(n-1)], where the values are potentially computed in parallel.
+    pub fn map_range<R, F>(n : usize, f : F) -> Vec<R>
+        where F: Fn(usize) -> R + Send + Sync, R: Send
+    {
+        #[cfg(feature = "parallel")]
+        { (0..n).into_par_iter().map(f).collect() }
+        #[cfg(not(feature = "parallel"))]
+        { (0..n).map(f).collect() }
+    }
+
+    /// Returns the number of values 0,..,n-1 satisfying the predicate, which are potentially checked in parallel.
+    pub fn count_range<F>(n : u64, predicate : F) -> u64
+        where F: Fn(u64) -> bool + Send + Sync
+    {
+        #[cfg(feature = "parallel")]
+        { (0..n).into_par_iter().filter(|&i| predicate(i)).count() as u64 }
+        #[cfg(not(feature = "parallel"))]
+        { (0..n).filter(|&i| predicate(i)).count() as u64 }
+    }
+
+    /// Runs the closure such that the primitives of this module use at most the given number of threads.
+    /// A value of 0 uses the default number of threads, i.e. one per core.
+    pub fn with_threads<R, F>(threads : usize, f : F) -> R
+        where F: FnOnce() -> R + Send, R: Send
+    {
+        #[cfg(feature = "parallel")]
+        {
+            match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+                Ok(pool) => pool.install(f),
+                Err(_) => f(),
+            }
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let _ = threads;
+            f()
+        }
+    }
+}
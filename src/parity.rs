@@ -0,0 +1,116 @@
+/// A module containing the algorithm of diaz et all over GF(2), i.e. counting homomorphisms modulo 2.
+/// Every entry of the table is a single bit, hence the table of a node is a bitset indexed by the mappings
+/// of its bag in integer representation. Forget nodes add the entries of the child modulo 2, i.e. they flip
+/// bits, and join nodes multiply the tables of both children, which is the bitwise AND of the bitsets.
+pub mod parity_algorithm {
+    use std::collections::HashMap;
+    use petgraph::visit::NodeIndexable;
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping, max_mappings};
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode};
+
+    /// A bitset over the mappings 0,..,len-1 of a bag.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct BitTable {
+        words: Vec<u64>,
+    }
+
+    impl BitTable {
+
+        /// Creates the bitset over the given number of mappings with all bits unset.
+        pub fn new(len : Mapping) -> BitTable { BitTable { words: vec![0; len.div_ceil(64) as usize] } }
+
+        /// Returns the bit of the mapping f.
+        pub fn get(&self, f : Mapping) -> bool { self.words[(f / 64) as usize] & (1 << (f % 64)) != 0 }
+
+        /// Sets the bit of the mapping f.
+        pub fn set(&mut self, f : Mapping) { self.words[(f / 64) as usize] |= 1 << (f % 64); }
+
+        /// Flips the bit of the mapping f, i.e. adds 1 modulo 2.
+        pub fn flip(&mut self, f : Mapping) { self.words[(f / 64) as usize] ^= 1 << (f % 64); }
+
+        /// Returns the number of set bits.
+        pub fn count_ones(&self) -> u64 { self.words.iter().map(|word| word.count_ones() as u64).sum() }
+
+        /// Returns the bitwise AND of both bitsets, which have to be defined over the same mappings.
+        pub fn and(&self, other : &BitTable) -> BitTable {
+            BitTable { words: self.words.iter().zip(&other.words).map(|(a, b)| a & b).collect() }
+        }
+
+        /// Returns an iterator over all mappings whose bit is set in ascending order.
+        pub fn ones(&self) -> impl Iterator<Item = Mapping> + '_ {
+            self.words.iter().enumerate().flat_map(|(i, &word)| {
+                let mut rest = word;
+                std::iter::from_fn(move || {
+                    if rest == 0 { return None; }
+                    let bit = rest.trailing_zeros() as Mapping;
+                    rest &= rest - 1;
+                    Some(64 * i as Mapping + bit)
+                })
+            })
+        }
+    }
+
+    /// Computes the parity of the number of homomorphisms from "from_graph" to "to_graph", i.e. returns true
+    /// if the number is odd. The table of a node needs n^|bag| bits, where n is the number of vertices of
+    /// "to_graph", instead of 64 bits per non-zero entry.
+    pub fn diaz_serna_thilikos_parity(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> bool{
+        let n = to_graph.node_count() as Mapping;
+        let has_edge = |a : Mapping, b : Mapping| to_graph.has_edge(to_graph.from_index(a as usize), to_graph.from_index(b as usize));
+        let bag_mappings = |p : TreeNode| max_mappings(ntd.bit_bag(p).unwrap().len() as Mapping, n);
+
+        let mut table : HashMap<TreeNode, BitTable> = HashMap::new();
+
+        for p in ntd.stingy_ordering(){
+            let mut bits = BitTable::new(bag_mappings(p));
+
+            match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    for a in 0..n{
+                        if !from_graph.has_edge(v, v) || has_edge(a, a) { bits.set(a); }
+                    }
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    let indexer_p = BagIndexer::of_node(ntd, p, n);
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+
+                    // significances of the neighbours of v in the bag of p
+                    let neighbours = indexer_p.neighbour_positions(from_graph, v);
+
+                    // only the set bits of q can be extended to set bits of p
+                    for f_q in table.remove(&q).unwrap().ones(){
+                        for a in 0..n{
+                            let f_prime = indexer_q.extend(f_q, v, a);
+                            if neighbours.iter().all(|&significance| has_edge(a, indexer_p.image_at(f_prime, significance))) {
+                                bits.set(f_prime);
+                            }
+                        }
+                    }
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+
+                    for f_q in table.remove(&q).unwrap().ones(){
+                        bits.flip(indexer_q.reduce(f_q, forgotten_vertex));
+                    }
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    let bits_q1 = table.remove(&children[0]).unwrap();
+                    let bits_q2 = table.remove(&children[1]).unwrap();
+                    bits = bits_q1.and(&bits_q2);
+                }
+                None => {}
+            }
+
+            table.insert(p, bits);
+        }
+
+        table.get(&ntd.root()).unwrap().get(0)
+    }
+}
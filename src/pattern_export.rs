@@ -0,0 +1,93 @@
+/// A module containing the export of pattern families, e.g. all graphs of $H_\tau$, to a directory with one
+/// file per pattern and a manifest.csv listing the id, the file, the canonical form, the number of vertices
+/// and the number of edges of each pattern, such that the same family can be reused by other tools.
+pub mod pattern_family_export {
+    use std::collections::HashSet;
+    use std::fs;
+    use std::fs::File;
+    use std::io;
+    use std::io::{BufWriter, Write};
+    use std::path::{Path, PathBuf};
+    use crate::file_handler::graph_handler::{to_graph6, write_dimacs, write_metis};
+    use crate::graph_generation::graph_generation_algorithms::{canonical_form, graph_from_edge_subset};
+    use crate::pipeline::counting_pipeline::GraphFormat;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// A row of the manifest.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ExportedPattern {
+        /// the index of the pattern in the exported list, or the bitmask of its edge set for $H_\tau$
+        pub id: u64,
+        /// the name of the file within the directory
+        pub file: PathBuf,
+        pub canonical_form: String,
+        pub vertices: usize,
+        pub edges: usize,
+    }
+
+    /// Writes the graphs into the directory, which is created if necessary, where the id of each graph is its
+    /// index. With "deduplicate" only the first graph of each isomorphism class is written.
+    /// Returns the rows of the written manifest.
+    pub fn export_graphs<P>(graphs : &[Graph], directory : P, format : GraphFormat, deduplicate : bool) -> io::Result<Vec<ExportedPattern>>
+        where P: AsRef<Path>
+    {
+        let patterns = graphs.iter().enumerate().map(|(i, graph)| (i as u64, graph.clone()));
+        export_patterns(patterns, directory.as_ref(), format, deduplicate)
+    }
+
+    /// Writes all graphs of $H_\tau$ of the nice tree decomposition into the directory like export_graphs, where
+    /// the id of each graph is the bitmask of its edge set over all_possible_edges, i.e. the key of its
+    /// number of homomorphisms in the result of modified_dp_counts.
+    pub fn export_ntd_universe<P>(ntd : &NiceTreeDecomposition, directory : P, format : GraphFormat, deduplicate : bool) -> io::Result<Vec<ExportedPattern>>
+        where P: AsRef<Path>
+    {
+        let universe = ntd.all_possible_edges();
+        let patterns = (0..1u64 << universe.len())
+            .map(|mask| (mask, graph_from_edge_subset(ntd.vertex_count() as usize, universe, mask)));
+        export_patterns(patterns, directory.as_ref(), format, deduplicate)
+    }
+
+    fn export_patterns<I>(patterns : I, directory : &Path, format : GraphFormat, deduplicate : bool) -> io::Result<Vec<ExportedPattern>>
+        where I: Iterator<Item = (u64, Graph)>
+    {
+        fs::create_dir_all(directory)?;
+        let extension = match format {
+            GraphFormat::Metis => "graph",
+            GraphFormat::Dimacs => "gr",
+            GraphFormat::Graph6 => "g6",
+        };
+
+        let mut seen = HashSet::new();
+        let mut rows = vec![];
+
+        for (id, graph) in patterns{
+            let canonical_form = canonical_form(&graph);
+            if deduplicate && !seen.insert(canonical_form.clone()) { continue; }
+
+            let file = PathBuf::from(format!("pattern_{}.{}", id, extension));
+            let mut writer = BufWriter::new(File::create(directory.join(&file))?);
+            match format {
+                GraphFormat::Metis => write_metis(&graph, &mut writer)?,
+                GraphFormat::Dimacs => write_dimacs(&graph, &mut writer)?,
+                GraphFormat::Graph6 => {
+                    let line = to_graph6(&graph)
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("pattern {} contains a loop, which graph6 cannot represent", id)))?;
+                    writeln!(writer, "{}", line)?;
+                }
+            }
+            writer.flush()?;
+
+            rows.push(ExportedPattern { id, file, canonical_form, vertices: graph.node_count(), edges: graph.edge_count() });
+        }
+
+        let mut wtr = csv::Writer::from_path(directory.join("manifest.csv"))?;
+        wtr.write_record(["id", "file", "canonical_form", "vertices", "edges"])?;
+        for row in &rows{
+            wtr.write_record([row.id.to_string(), row.file.display().to_string(), row.canonical_form.clone(), row.vertices.to_string(), row.edges.to_string()])?;
+        }
+        wtr.flush()?;
+
+        Ok(rows)
+    }
+}
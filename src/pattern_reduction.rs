@@ -0,0 +1,245 @@
+/// A module containing simplifications of a pattern H before counting its homomorphisms into a target G,
+/// each together with the correction of the count:
+/// - an isolated vertex contributes the factor |V(G)|, or the number of loops of G if it has a loop,
+/// - a pendant vertex contributes the factor d if G is d-regular, hence pendant trees are removed completely,
+/// - maximal paths through vertices of degree 2 of the same length L are suppressed, i.e. replaced by single
+///   edges, and counted as multigraph homomorphisms into the graph of walks of length L in G. For example
+///   hom(C_L, G) is the number of closed walks of length L, which is counted on a single vertex with a loop.
+pub mod pattern_reductions {
+    use std::collections::HashSet;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::graph_ops::graph_operations::induced_subgraph;
+    use crate::multigraph::multigraph_algorithms::{diaz_serna_thilikos_multigraph, underlying_graph, MultiGraph};
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// A vertex removed from the pattern, which determines its contribution to the count.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RemovedVertex {
+        /// a vertex without neighbours, which can be mapped onto every vertex of the target
+        Isolated,
+        /// a vertex whose only neighbour is itself, which can be mapped onto every loop of the target
+        IsolatedLoop,
+        /// a vertex without loop with a single neighbour, which can be mapped onto every neighbour of its image
+        Pendant,
+    }
+
+    /// A pattern from which vertices have been removed.
+    #[derive(Clone)]
+    pub struct VertexReduction {
+        /// the remaining pattern, where the remaining vertices keep their order
+        pub pattern: Graph,
+        /// the removed vertices in the order of their removal
+        pub removed: Vec<RemovedVertex>,
+    }
+
+    impl VertexReduction {
+        /// Returns the factor hom(H, G) / hom(reduced pattern, G) for the target G, or None if a pendant
+        /// vertex has been removed but the target is not regular.
+        pub fn correction_factor(&self, to_graph : &Graph) -> Option<u64> {
+            let loops = (0..to_graph.node_count()).filter(|&a| to_graph.has_edge(Vertex::new(a), Vertex::new(a))).count() as u64;
+            let degree = if self.removed.contains(&RemovedVertex::Pendant) { regular_degree(to_graph) } else { None };
+
+            self.removed.iter().try_fold(1u64, |factor, removed| {
+                let contribution = match removed {
+                    RemovedVertex::Isolated => to_graph.node_count() as u64,
+                    RemovedVertex::IsolatedLoop => loops,
+                    RemovedVertex::Pendant => degree? as u64,
+                };
+                Some(factor * contribution)
+            })
+        }
+    }
+
+    /// Returns the degree d if every vertex of the graph has exactly d neighbours, where a loop makes a
+    /// vertex its own neighbour, and None otherwise.
+    pub fn regular_degree(graph : &Graph) -> Option<usize> {
+        let n = graph.node_count();
+        let degrees : HashSet<usize> = (0..n)
+            .map(|a| (0..n).filter(|&b| graph.has_edge(Vertex::new(a), Vertex::new(b))).count())
+            .collect();
+        match degrees.len() {
+            0 => Some(0),
+            1 => degrees.into_iter().next(),
+            _ => None,
+        }
+    }
+
+    /// Removes all isolated vertices of the pattern, whose correction factor exists for every target.
+    pub fn remove_isolated_vertices(graph : &Graph) -> VertexReduction {
+        remove_vertices(graph, false)
+    }
+
+    /// Removes pendant vertices as long as there are any and afterwards all isolated vertices, such that every
+    /// tree attached to the pattern by a single vertex and every tree component is removed.
+    pub fn remove_pendant_trees(graph : &Graph) -> VertexReduction {
+        remove_vertices(graph, true)
+    }
+
+    fn remove_vertices(graph : &Graph, pendant : bool) -> VertexReduction {
+        let n = graph.node_count();
+        let has_edge = |u : usize, v : usize| graph.has_edge(Vertex::new(u), Vertex::new(v));
+        let mut remaining = vec![true; n];
+        let mut removed = vec![];
+
+        // the neighbours of v among the remaining vertices other than v itself
+        let neighbours = |remaining : &[bool], v : usize| (0..n).filter(|&u| u != v && remaining[u] && has_edge(u, v)).count();
+
+        let mut changed = pendant;
+        while changed {
+            changed = false;
+            for v in 0..n{
+                if remaining[v] && !has_edge(v, v) && neighbours(&remaining, v) == 1 {
+                    remaining[v] = false;
+                    removed.push(RemovedVertex::Pendant);
+                    changed = true;
+                }
+            }
+        }
+
+        for v in 0..n{
+            if remaining[v] && neighbours(&remaining, v) == 0 {
+                remaining[v] = false;
+                removed.push(if has_edge(v, v) { RemovedVertex::IsolatedLoop } else { RemovedVertex::Isolated });
+            }
+        }
+
+        let vertices : Vec<usize> = (0..n).filter(|&v| remaining[v]).collect();
+        VertexReduction { pattern: induced_subgraph(graph, &vertices), removed }
+    }
+
+    /// A pattern whose maximal paths through vertices of degree 2 all have the same length and are replaced
+    /// by single edges. hom(H, G) equals the number of multigraph homomorphisms from the kernel into
+    /// walk_graph(G, length).
+    #[derive(Clone)]
+    pub struct SeriesReduction {
+        /// the vertices of the pattern whose degree is not 2 or which have a loop, and one vertex of each cycle
+        /// component, where the multiplicity of an edge is the number of suppressed paths between its ends
+        pub kernel: MultiGraph,
+        /// the common length of the suppressed paths
+        pub length: usize,
+    }
+
+    /// Suppresses the vertices of degree 2 of the pattern. Returns None if the maximal paths through vertices of
+    /// degree 2, including edges between two kernel vertices as paths of length 1, do not all have the same length.
+    pub fn suppress_degree_two_vertices(graph : &Graph) -> Option<SeriesReduction> {
+        let n = graph.node_count();
+        let has_edge = |u : usize, v : usize| graph.has_edge(Vertex::new(u), Vertex::new(v));
+        let neighbours = |v : usize| -> Vec<usize> { (0..n).filter(|&u| u != v && has_edge(u, v)).collect() };
+
+        let mut kernel : Vec<bool> = (0..n).map(|v| has_edge(v, v) || neighbours(v).len() != 2).collect();
+
+        // every cycle component gets its smallest vertex as kernel vertex
+        let mut component_reached = vec![false; n];
+        for v in 0..n{
+            if component_reached[v] { continue; }
+            let mut stack = vec![v];
+            let mut has_kernel_vertex = false;
+            component_reached[v] = true;
+            while let Some(u) = stack.pop() {
+                has_kernel_vertex |= kernel[u];
+                for w in neighbours(u){
+                    if !component_reached[w] { component_reached[w] = true; stack.push(w); }
+                }
+            }
+            if !has_kernel_vertex { kernel[v] = true; }
+        }
+
+        let index : Vec<Option<usize>> = kernel.iter()
+            .scan(0, |next, &is_kernel| Some(if is_kernel { *next += 1; Some(*next - 1) } else { None }))
+            .collect();
+
+        // the suppressed paths as pairs of kernel vertices together with their lengths
+        let mut paths = vec![];
+        let mut visited = vec![false; n];
+        for u in (0..n).filter(|&u| kernel[u]){
+            if has_edge(u, u) { paths.push((u, u, 1)); }
+
+            for first in neighbours(u){
+                if kernel[first] {
+                    if u < first { paths.push((u, first, 1)); }
+                    continue;
+                }
+                if visited[first] { continue; }
+
+                let (mut previous, mut current, mut length) = (u, first, 1);
+                while !kernel[current] {
+                    visited[current] = true;
+                    let next = neighbours(current).into_iter().find(|&w| w != previous).unwrap();
+                    (previous, current, length) = (current, next, length + 1);
+                }
+                paths.push((u, current, length));
+            }
+        }
+
+        let length = paths.first().map_or(1, |&(_, _, length)| length);
+        if paths.iter().any(|&(_, _, other)| other != length) { return None; }
+
+        let mut multigraph = MultiGraph::default();
+        for _ in index.iter().flatten(){
+            multigraph.add_node(());
+        }
+        for (u, w, _) in paths{
+            let (u, w) = (Vertex::new(index[u].unwrap()), Vertex::new(index[w].unwrap()));
+            if multigraph.has_edge(u, w) { *multigraph.edge_weight_mut(u, w) += 1; } else { multigraph.add_edge(u, w, 1); }
+        }
+
+        Some(SeriesReduction { kernel: multigraph, length })
+    }
+
+    /// Returns the multigraph on the vertices of the graph in which the multiplicity of (a,b) is the number of
+    /// walks of the given length from a to b, i.e. the entry of the power of the adjacency matrix.
+    pub fn walk_graph(graph : &Graph, length : usize) -> MultiGraph {
+        let n = graph.node_count();
+        let adjacency : Vec<Vec<u64>> = (0..n)
+            .map(|a| (0..n).map(|b| graph.has_edge(Vertex::new(a), Vertex::new(b)) as u64).collect())
+            .collect();
+
+        let mut walks : Vec<Vec<u64>> = (0..n).map(|a| (0..n).map(|b| (a == b) as u64).collect()).collect();
+        for _ in 0..length{
+            walks = (0..n)
+                .map(|a| (0..n).map(|b| (0..n).map(|c| walks[a][c] * adjacency[c][b]).sum()).collect())
+                .collect();
+        }
+
+        let mut multigraph = MultiGraph::default();
+        for _ in 0..n{
+            multigraph.add_node(());
+        }
+        for (a, row) in walks.iter().enumerate(){
+            for (b, &count) in row.iter().enumerate().skip(a){
+                if count > 0 { multigraph.add_edge(Vertex::new(a), Vertex::new(b), count); }
+            }
+        }
+        multigraph
+    }
+
+    /// Counts the homomorphisms from "from_graph" into "to_graph" after removing pendant trees if the target is
+    /// regular, otherwise only isolated vertices, and suppressing the vertices of degree 2 if all suppressed paths
+    /// have the same length. The nice tree decompositions of the reduced patterns are computed by the min degree
+    /// heuristic. Panics if the number of homomorphisms of the suppressed pattern does not fit into 64 bits.
+    pub fn count_with_reductions(from_graph : &Graph, to_graph : &Graph) -> u64 {
+        let reduction = remove_pendant_trees(from_graph);
+        let (pattern, factor) = match reduction.correction_factor(to_graph) {
+            Some(factor) => (reduction.pattern, factor),
+            None => {
+                let reduction = remove_isolated_vertices(from_graph);
+                let factor = reduction.correction_factor(to_graph).unwrap();
+                (reduction.pattern, factor)
+            }
+        };
+
+        if factor == 0 { return 0; }
+        if pattern.node_count() == 0 { return factor; }
+
+        let count = match suppress_degree_two_vertices(&pattern).filter(|series| series.length > 1) {
+            Some(series) => {
+                let ntd = compute_nice_tree_decomposition(&underlying_graph(&series.kernel));
+                diaz_serna_thilikos_multigraph(&series.kernel, &ntd, &walk_graph(to_graph, series.length))
+                    .expect("The number of homomorphisms does not fit into 64 bits!")
+            }
+            None => diaz_serna_thilikos_algorithm(&pattern, &compute_nice_tree_decomposition(&pattern), to_graph),
+        };
+        factor * count
+    }
+}
@@ -0,0 +1,344 @@
+/// A module containing a pipeline from graph files to homomorphism numbers. The formats of the files are
+/// detected automatically, a nice tree decomposition of the pattern is either loaded or computed and the
+/// selected algorithm is run, such that no intermediate types have to be handled.
+pub mod counting_pipeline {
+    use std::fmt;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use std::time::{Duration, Instant};
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::elimination_orderings::elimination_ordering_methods::{min_degree_ordering, tree_decomposition_from_ordering};
+    use crate::file_handler::graph_handler::{import_dimacs, import_metis, parse_graph6};
+    use crate::file_handler::{escape_json, open_reader, read_lines};
+    use crate::file_handler::tree_decomposition_handler::{load_ntd, NtdParseError};
+    use crate::graph_generation::graph_generation_algorithms::connected_components;
+    use crate::graph_ops::graph_operations::induced_subgraph;
+    use crate::modified_dp::algorithm::modified_dp_for_pattern;
+    use crate::ntd_cache::decomposition_cache::NtdCache;
+    use crate::prefilter::feasibility_prefilter::prefilter;
+    use crate::structural_checks::structural_shortcuts::{structural_zero, ZeroReason};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_decomposition::{TreeDecomposition, TreeDecompositionError};
+    use crate::tree_decompositions::tree_structure::Graph;
+    use crate::trees::tree_counting::{is_forest, tree_hom_count};
+    use crate::walks::walk_counts::path_hom_count;
+
+    /// The algorithms which can be selected for counting.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum Algorithm {
+        BruteForce,
+        #[default]
+        DiazSernaThilikos,
+        ModifiedDp,
+        /// the closed form of walk_counts for paths, the dynamic program of tree_counting for other trees,
+        /// both without a nice tree decomposition, diaz_serna_thilikos otherwise
+        Auto,
+    }
+
+    /// The supported graph formats.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum GraphFormat {
+        /// the .graph format, see import_metis
+        Metis,
+        /// the .gr format of the PACE challenge, see import_dimacs
+        Dimacs,
+        /// the graph6 format of nauty with one graph per line, see parse_graph6
+        Graph6,
+    }
+
+    impl Algorithm {
+        /// Returns the name of the algorithm as used in the result files of the experiments.
+        pub fn name(&self) -> &'static str {
+            match self {
+                Algorithm::BruteForce => "brute_force",
+                Algorithm::DiazSernaThilikos => "diaz_serna_thilikos",
+                Algorithm::ModifiedDp => "modified_dp",
+                Algorithm::Auto => "auto",
+            }
+        }
+    }
+
+    impl FromStr for Algorithm {
+        type Err = String;
+
+        fn from_str(name : &str) -> Result<Algorithm, String> {
+            [Algorithm::BruteForce, Algorithm::DiazSernaThilikos, Algorithm::ModifiedDp, Algorithm::Auto].into_iter()
+                .find(|algorithm| algorithm.name() == name)
+                .ok_or_else(|| format!("unknown algorithm {}, expected brute_force, diaz_serna_thilikos, modified_dp or auto", name))
+        }
+    }
+
+    impl FromStr for GraphFormat {
+        type Err = String;
+
+        fn from_str(name : &str) -> Result<GraphFormat, String> {
+            match name {
+                "metis" | "graph" => Ok(GraphFormat::Metis),
+                "dimacs" | "gr" => Ok(GraphFormat::Dimacs),
+                "graph6" | "g6" => Ok(GraphFormat::Graph6),
+                _ => Err(format!("unknown format {}, expected metis, dimacs or graph6", name)),
+            }
+        }
+    }
+
+    /// The options of the pipeline.
+    #[derive(Debug, Clone, Default)]
+    pub struct PipelineOptions {
+        /// the algorithm used for counting
+        pub algorithm: Algorithm,
+        /// a .ntd file of the pattern, if None a nice tree decomposition is computed with the min degree heuristic
+        pub ntd_path: Option<PathBuf>,
+        /// a directory caching the computed nice tree decompositions keyed by the hash of the pattern, see NtdCache
+        pub ntd_cache: Option<PathBuf>,
+    }
+
+    /// The result of the pipeline.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct CountResult {
+        /// the number of homomorphisms from the pattern into the target
+        pub count: u64,
+        pub algorithm: Algorithm,
+        pub pattern_vertices: usize,
+        pub target_vertices: usize,
+        /// the width of the used nice tree decomposition, the maximal width for a pattern counted per component,
+        /// None for the brute force algorithm, the empty pattern and forests counted by the auto dispatcher
+        pub width: Option<u32>,
+        /// the time spent counting, excluding the import of the files
+        pub duration: Duration,
+        /// the reason why the count is 0 if it has been certified by a structural check without running the algorithm
+        pub shortcut: Option<ZeroReason>,
+    }
+
+    impl CountResult {
+        /// Returns the result as a json object in a single line, where the shortcut is given by the name of its reason.
+        pub fn to_json(&self) -> String {
+            let width = self.width.map_or("null".to_string(), |width| width.to_string());
+            let shortcut = self.shortcut.map_or("null".to_string(), |reason| format!("\"{}\"", reason.name()));
+            format!("{{\"count\": {}, \"algorithm\": \"{}\", \"pattern_vertices\": {}, \"target_vertices\": {}, \"width\": {}, \"duration_us\": {}, \"shortcut\": {}}}",
+                    self.count, self.algorithm.name(), self.pattern_vertices, self.target_vertices, width, self.duration.as_micros(), shortcut)
+        }
+    }
+
+    /// The errors which can occur in the pipeline.
+    #[derive(Debug)]
+    pub enum PipelineError {
+        /// a file could not be read
+        Io(PathBuf, io::Error),
+        /// a file could not be parsed
+        InvalidFile(PathBuf),
+        /// the .ntd-file could not be parsed
+        InvalidNtd(PathBuf, NtdParseError),
+        /// the given nice tree decomposition is not a nice tree decomposition of the pattern
+        InvalidDecomposition(TreeDecompositionError),
+        /// the pattern contains an edge which is not a possible edge of the nice tree decomposition,
+        /// which is required by the modified dynamic program
+        NotAPossibleEdgeSet,
+        /// the number of homomorphisms exceeds the range of u64
+        CountOverflow,
+    }
+
+    impl fmt::Display for PipelineError {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PipelineError::Io(path, error) => write!(f, "could not read {}: {}", path.display(), error),
+                PipelineError::InvalidFile(path) => write!(f, "could not parse {}", path.display()),
+                PipelineError::InvalidNtd(path, error) => write!(f, "could not parse {}: {}", path.display(), error),
+                PipelineError::InvalidDecomposition(error) => write!(f, "invalid nice tree decomposition: {}", error),
+                PipelineError::NotAPossibleEdgeSet => write!(f, "the pattern contains an edge which is not a possible edge of the nice tree decomposition"),
+                PipelineError::CountOverflow => write!(f, "the number of homomorphisms exceeds the range of u64"),
+            }
+        }
+    }
+
+    impl std::error::Error for PipelineError {}
+
+    /// Detects the format of a graph file. Files ending with .gr, .graph or .g6 (optionally followed by .gz)
+    /// are DIMACS, METIS or graph6 files. Otherwise the format is detected from the first line which is not a comment.
+    pub fn detect_format<P>(path : P) -> io::Result<GraphFormat>
+        where P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        let name = path.file_name().map(|name| name.to_string_lossy().trim_end_matches(".gz").to_string()).unwrap_or_default();
+
+        if name.ends_with(".gr") { return Ok(GraphFormat::Dimacs); }
+        if name.ends_with(".graph") { return Ok(GraphFormat::Metis); }
+        if name.ends_with(".g6") { return Ok(GraphFormat::Graph6); }
+
+        for line in read_lines(path)? {
+            if let Some(format) = detect_line_format(&line?) { return Ok(format); }
+        }
+        Ok(GraphFormat::Metis)
+    }
+
+    /// Detects the format from a single line, returns None for comments and empty lines. Only DIMACS files
+    /// contain a problem line starting with "p" and graph6 strings consist of the characters 63 to 126 only,
+    /// while METIS lines consist of numbers.
+    pub fn detect_line_format(line : &str) -> Option<GraphFormat>{
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('%') || line.starts_with("c ") || line == "c" { return None; }
+        if line.starts_with("p ") { return Some(GraphFormat::Dimacs); }
+        if line.starts_with(">>graph6<<") || line.bytes().all(|byte| (63..=126).contains(&byte)) { return Some(GraphFormat::Graph6); }
+        Some(GraphFormat::Metis)
+    }
+
+    /// Imports a graph file in the detected format.
+    pub fn import_graph<P>(path : P) -> Result<Graph, PipelineError>
+        where P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        let io_error = |error| PipelineError::Io(path.to_path_buf(), error);
+
+        // the importers return an empty graph for missing files, hence the file is opened first
+        open_reader(path).map_err(io_error)?;
+
+        let graph = match detect_format(path).map_err(io_error)? {
+            GraphFormat::Metis => import_metis(path),
+            GraphFormat::Dimacs => import_dimacs(path),
+            // the first graph of the file
+            GraphFormat::Graph6 => read_lines(path).map_err(io_error)?
+                .next().and_then(|line| line.ok()).and_then(|line| parse_graph6(&line)),
+        };
+        graph.ok_or_else(|| PipelineError::InvalidFile(path.to_path_buf()))
+    }
+
+    /// Returns a nice tree decomposition of the graph based on the elimination ordering of the min degree heuristic.
+    pub fn compute_nice_tree_decomposition(graph : &Graph) -> NiceTreeDecomposition{
+        tree_decomposition_from_ordering(graph, &min_degree_ordering(graph)).to_nice_tree_decomposition()
+    }
+
+    /// Counts the homomorphisms from the pattern graph into the target graph given by their files.
+    pub fn count_from_files<P, Q>(pattern_path : P, target_path : Q, options : &PipelineOptions) -> Result<CountResult, PipelineError>
+        where P: AsRef<Path>, Q: AsRef<Path>
+    {
+        let from_graph = import_graph(pattern_path)?;
+        let to_graph = import_graph(target_path)?;
+        count_graphs(&from_graph, &to_graph, options)
+    }
+
+    /// Returns the connected components of the graph as graphs on their own, ordered by their smallest vertex.
+    /// The vertices of each component keep their relative order.
+    pub fn component_graphs(graph : &Graph) -> Vec<Graph>{
+        connected_components(graph).iter().map(|component| induced_subgraph(graph, component)).collect()
+    }
+
+    /// Counts the homomorphisms from the pattern graph into the target graph, e.g. for graphs read from stdin.
+    ///
+    /// If the nice tree decomposition is computed, a disconnected pattern is counted per connected component,
+    /// each with its own decomposition, and the counts are multiplied, since hom(H_1 + H_2, G) = hom(H_1, G) * hom(H_2, G).
+    /// A given decomposition is used for the whole pattern.
+    pub fn count_graphs(from_graph : &Graph, to_graph : &Graph, options : &PipelineOptions) -> Result<CountResult, PipelineError>{
+
+        let parts : Vec<(Graph, Option<NiceTreeDecomposition>)> = match (&options.ntd_path, options.algorithm) {
+            (_, Algorithm::BruteForce) => vec![(from_graph.clone(), None)],
+            (Some(ntd_path), _) => {
+                let ntd = load_ntd(ntd_path).map_err(|error| match error {
+                    NtdParseError::Io(error) => PipelineError::Io(ntd_path.clone(), error),
+                    error => PipelineError::InvalidNtd(ntd_path.clone(), error),
+                })?;
+                TreeDecomposition::from(&ntd).validate(from_graph).map_err(PipelineError::InvalidDecomposition)?;
+                vec![(from_graph.clone(), Some(ntd))]
+            }
+            (None, _) => component_graphs(from_graph).into_iter()
+                .map(|component| {
+                    // trees are counted without a nice tree decomposition
+                    if options.algorithm == Algorithm::Auto && is_forest(&component) { return (component, None); }
+                    let ntd = match &options.ntd_cache {
+                        Some(directory) => NtdCache::new(directory).get_or_compute(&component, compute_nice_tree_decomposition),
+                        None => compute_nice_tree_decomposition(&component),
+                    };
+                    (component, Some(ntd))
+                })
+                .collect(),
+        };
+
+        let start = Instant::now();
+
+        // cheap structural checks certify that there is no homomorphism without running the algorithm
+        let shortcut = prefilter(from_graph, to_graph, false).map(ZeroReason::Infeasible)
+            .or_else(|| structural_zero(from_graph, to_graph));
+
+        let mut count : u64 = if shortcut.is_some() { 0 } else { 1 };
+        for (pattern, ntd) in &parts{
+            if count == 0 { break; }
+            let component_count = match (options.algorithm, ntd) {
+                (Algorithm::DiazSernaThilikos, Some(ntd)) => diaz_serna_thilikos_algorithm(pattern, ntd, to_graph),
+                (Algorithm::ModifiedDp, Some(ntd)) => {
+                    modified_dp_for_pattern(pattern, ntd, to_graph).ok_or(PipelineError::NotAPossibleEdgeSet)?
+                }
+                (Algorithm::Auto, Some(ntd)) => match path_hom_count(pattern, to_graph) {
+                    Some(hom_number) => u64::try_from(hom_number).map_err(|_| PipelineError::CountOverflow)?,
+                    None => diaz_serna_thilikos_algorithm(pattern, ntd, to_graph),
+                },
+                (Algorithm::Auto, None) => {
+                    let hom_number = path_hom_count(pattern, to_graph).or_else(|| tree_hom_count(pattern, to_graph))
+                        .expect("Only trees are counted without a nice tree decomposition!");
+                    u64::try_from(hom_number).map_err(|_| PipelineError::CountOverflow)?
+                }
+                _ => simple_brute_force(pattern, to_graph),
+            };
+            // the counts of the components fit into u64 on their own, their product does not have to
+            count = count.checked_mul(component_count).ok_or(PipelineError::CountOverflow)?;
+        }
+
+        Ok(CountResult {
+            count,
+            algorithm: options.algorithm,
+            pattern_vertices: from_graph.node_count(),
+            target_vertices: to_graph.node_count(),
+            width: parts.iter().filter_map(|(_, ntd)| ntd.as_ref().map(|ntd| ntd.width())).max(),
+            duration: start.elapsed(),
+            shortcut,
+        })
+    }
+
+    /// The result of a single pattern file of a directory.
+    #[derive(Debug)]
+    pub struct PatternReport {
+        pub path: PathBuf,
+        pub result: Result<CountResult, PipelineError>,
+    }
+
+    impl PatternReport {
+        /// Returns the report as a json object in a single line, containing either the result or the error.
+        pub fn to_json(&self) -> String {
+            let path = escape_json(&self.path.display().to_string());
+            match &self.result {
+                Ok(result) => format!("{{\"pattern\": \"{}\", \"result\": {}}}", path, result.to_json()),
+                Err(error) => format!("{{\"pattern\": \"{}\", \"error\": \"{}\"}}", path, escape_json(&error.to_string())),
+            }
+        }
+    }
+
+    /// Counts the homomorphisms from every pattern file in the directory into the target, e.g. for a
+    /// dictionary of motifs. The files are processed in the order of their names, subdirectories and .ntd
+    /// files are skipped. The option "ntd_path" is ignored, the decompositions are computed or loaded from
+    /// the cache given by "ntd_cache". A pattern which cannot be read or counted is reported by its error,
+    /// while an unreadable directory or target fails the whole run.
+    pub fn count_patterns_dir<P, Q>(directory : P, target_path : Q, options : &PipelineOptions) -> Result<Vec<PatternReport>, PipelineError>
+        where P: AsRef<Path>, Q: AsRef<Path>
+    {
+        let directory = directory.as_ref();
+        let io_error = |error| PipelineError::Io(directory.to_path_buf(), error);
+
+        let mut paths = vec![];
+        for entry in fs::read_dir(directory).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            let is_ntd = path.to_string_lossy().trim_end_matches(".gz").ends_with(".ntd");
+            if path.is_file() && !is_ntd { paths.push(path); }
+        }
+        paths.sort();
+
+        let to_graph = import_graph(target_path)?;
+        let options = PipelineOptions { ntd_path: None, ..options.clone() };
+
+        Ok(paths.into_iter()
+            .map(|path| {
+                let result = import_graph(&path).and_then(|from_graph| count_graphs(&from_graph, &to_graph, &options));
+                PatternReport { path, result }
+            })
+            .collect())
+    }
+}
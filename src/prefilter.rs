@@ -0,0 +1,86 @@
+/// A module containing quick necessary conditions for the existence of a homomorphism based on the number of
+/// vertices, edges and loops and on the degree sequences of the graphs. The checks run in time quadratic in the
+/// number of vertices and avoid exponential runs of the counting algorithms for pairs without homomorphisms.
+/// Injective homomorphisms, e.g. capacitated homomorphisms with capacity one everywhere, are subject to
+/// additional conditions since they map distinct neighbours onto distinct neighbours.
+pub mod feasibility_prefilter {
+    use petgraph::visit::NodeIndexable;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The reason why there is no (injective) homomorphism from the pattern into the target.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub enum Infeasibility {
+        /// the pattern contains a vertex while the target is empty
+        EmptyTarget,
+        /// the pattern contains a loop while the target does not
+        LoopIntoLoopless,
+        /// the pattern contains an edge while the target contains neither edges nor loops
+        EdgeIntoEdgeless,
+        /// injective only: the pattern contains more vertices than the target
+        TooManyVertices { pattern: usize, target: usize },
+        /// injective only: the pattern contains more edges (or more loops) than the target
+        TooManyEdges { pattern: usize, target: usize },
+        /// injective only: the i-th largest degree of the pattern exceeds the i-th largest degree of the target
+        DegreeSequence { position: usize, pattern: usize, target: usize },
+    }
+
+    impl Infeasibility {
+        /// Returns the name of the violated condition as used in the json output of the pipeline.
+        pub fn name(&self) -> &'static str {
+            match self {
+                Infeasibility::EmptyTarget => "empty_target",
+                Infeasibility::LoopIntoLoopless => "loop_into_loopless",
+                Infeasibility::EdgeIntoEdgeless => "edge_into_edgeless",
+                Infeasibility::TooManyVertices { .. } => "too_many_vertices",
+                Infeasibility::TooManyEdges { .. } => "too_many_edges",
+                Infeasibility::DegreeSequence { .. } => "degree_sequence",
+            }
+        }
+    }
+
+    /// Returns the degrees of all vertices in descending order, loops are not counted.
+    pub fn degree_sequence(graph : &Graph) -> Vec<usize>{
+        let n = graph.node_count();
+        let mut degrees : Vec<usize> = (0..n)
+            .map(|u| (0..n).filter(|&v| u != v && graph.has_edge(graph.from_index(u), graph.from_index(v))).count())
+            .collect();
+        degrees.sort_unstable_by(|a, b| b.cmp(a));
+        degrees
+    }
+
+    /// Returns the number of loops of the graph.
+    pub fn loop_count(graph : &Graph) -> usize{
+        (0..graph.node_count()).filter(|&v| graph.has_edge(graph.from_index(v), graph.from_index(v))).count()
+    }
+
+    /// Returns the reason why there is no homomorphism from "from_graph" to "to_graph", or no injective
+    /// homomorphism if "injective" is set, if one of the checks applies. None does not imply that there is one.
+    ///
+    /// An injective homomorphism maps the neighbours of u onto distinct neighbours of h(u), hence the i vertices
+    /// of largest degree of the pattern need i distinct target vertices of at least the same degree.
+    pub fn prefilter(from_graph : &Graph, to_graph : &Graph, injective : bool) -> Option<Infeasibility>{
+        if from_graph.node_count() > 0 && to_graph.node_count() == 0 { return Some(Infeasibility::EmptyTarget); }
+
+        let pattern_loops = loop_count(from_graph);
+        let target_loops = loop_count(to_graph);
+        if pattern_loops > 0 && target_loops == 0 { return Some(Infeasibility::LoopIntoLoopless); }
+
+        let pattern_degrees = degree_sequence(from_graph);
+        let target_degrees = degree_sequence(to_graph);
+        let pattern_edges = pattern_degrees.iter().sum::<usize>() / 2;
+        let target_edges = target_degrees.iter().sum::<usize>() / 2;
+        if pattern_edges > 0 && target_edges == 0 && target_loops == 0 { return Some(Infeasibility::EdgeIntoEdgeless); }
+
+        if !injective { return None; }
+
+        if from_graph.node_count() > to_graph.node_count() {
+            return Some(Infeasibility::TooManyVertices { pattern: from_graph.node_count(), target: to_graph.node_count() });
+        }
+        if pattern_edges > target_edges { return Some(Infeasibility::TooManyEdges { pattern: pattern_edges, target: target_edges }); }
+        if pattern_loops > target_loops { return Some(Infeasibility::TooManyEdges { pattern: pattern_loops, target: target_loops }); }
+
+        pattern_degrees.iter().zip(&target_degrees).enumerate()
+            .find(|(_, (pattern, target))| pattern > target)
+            .map(|(position, (&pattern, &target))| Infeasibility::DegreeSequence { position, pattern, target })
+    }
+}
@@ -0,0 +1,69 @@
+/// A module containing prepared patterns. All data of the algorithm of diaz et all which only depends
+/// on the pattern and its nice tree decomposition is computed once, such that counting the homomorphisms
+/// into many target graphs does not repeat this preprocessing.
+pub mod pattern_preparation {
+    use crate::diaz_serna_thilikos::diaz_algorithm::{process_node, DPData};
+    use crate::modified_dp::algorithm::{EdgeList, graph_to_edges};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode};
+
+    /// A pattern graph together with its nice tree decomposition and all data derived from them, namely
+    /// the stingy ordering and the possible edges. The tables are computed by the node handlers of the algorithm
+    /// of diaz et all.
+    #[derive(Clone)]
+    pub struct PreparedPattern {
+        from_graph: Graph,
+        nice_tree_decomposition: NiceTreeDecomposition,
+        stingy_ordering: Vec<TreeNode>,
+        possible_edges: Vec<(usize, usize)>,
+        edges: Option<EdgeList>,
+    }
+
+    impl PreparedPattern {
+
+        /// Prepares the given pattern graph for counting with the given nice tree decomposition of it.
+        pub fn new(from_graph : Graph, nice_tree_decomposition : NiceTreeDecomposition) -> PreparedPattern{
+            let stingy_ordering = nice_tree_decomposition.stingy_ordering();
+            let ntd = &nice_tree_decomposition;
+
+            let possible_edges = ntd.all_possible_edges().clone();
+            let edges = graph_to_edges(ntd, &from_graph);
+
+            PreparedPattern { from_graph, nice_tree_decomposition, stingy_ordering, possible_edges, edges }
+        }
+
+        /// Returns the pattern graph.
+        pub fn from_graph(&self) -> &Graph { &self.from_graph }
+
+        /// Returns the nice tree decomposition of the pattern graph.
+        pub fn nice_tree_decomposition(&self) -> &NiceTreeDecomposition { &self.nice_tree_decomposition }
+
+        /// Returns all possible edges of the nice tree decomposition in the order used by the integer
+        /// representation of edge sets.
+        pub fn possible_edges(&self) -> &Vec<(usize, usize)> { &self.possible_edges }
+
+        /// Returns the integer representation of the edges of the pattern regarding the possible edges, which
+        /// is the key of the pattern in the results of the equivalence class algorithm. Returns None if the
+        /// pattern contains an edge which is not a possible edge.
+        pub fn edges(&self) -> Option<EdgeList> { self.edges }
+
+        /// Counts the homomorphisms from the pattern into to_graph with the algorithm of diaz et all along the
+        /// prepared stingy ordering. Only non-zero entries are stored and the count stops at the first empty table.
+        pub fn count(&self, to_graph : &Graph) -> u64{
+            let feasible = vec![vec![true; to_graph.node_count()]; self.from_graph.node_count()];
+            let mut dp_data = DPData::new(&self.from_graph, to_graph, &self.nice_tree_decomposition);
+
+            for &p in &self.stingy_ordering{
+                process_node(&mut dp_data, p, &feasible).expect("The number of homomorphisms does not fit into 64 bits!");
+                if dp_data.table_size(p) == 0 { return 0; }
+            }
+
+            dp_data.value(&self.nice_tree_decomposition.root(), &0)
+        }
+
+        /// Counts the homomorphisms from the pattern into each of the given target graphs.
+        pub fn count_all(&self, to_graphs : &[&Graph]) -> Vec<u64>{
+            to_graphs.iter().map(|to_graph| self.count(to_graph)).collect()
+        }
+    }
+}
@@ -0,0 +1,55 @@
+/// A module containing quantum graphs, i.e. formal linear combinations of pattern graphs.
+pub mod quantum_graph {
+    use std::collections::HashMap;
+    use crate::modified_dp::algorithm::{EdgeList, graph_to_edges, modified_dp_counts};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// A formal linear combination $\sum c_i \cdot H_i$ of graphs $H_i$ in $H_\tau$ of a fixed nice tree
+    /// decomposition. The graphs are stored by the integer representation of their edge sets.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct QuantumGraph<'a> {
+        nice_tree_decomposition: &'a NiceTreeDecomposition,
+        terms: HashMap<EdgeList, i64>,
+    }
+
+    impl<'a> QuantumGraph<'a> {
+
+        /// Creates the empty linear combination over the graphs of the given nice tree decomposition.
+        pub fn new(nice_tree_decomposition : &'a NiceTreeDecomposition) -> QuantumGraph<'a>{
+            QuantumGraph { nice_tree_decomposition, terms: HashMap::new() }
+        }
+
+        /// Adds c * H to the linear combination where H is given by the integer representation of its edges.
+        pub fn add_term(&mut self, edges : EdgeList, coefficient : i64){
+            *self.terms.entry(edges).or_insert(0) += coefficient;
+        }
+
+        /// Adds c * H to the linear combination. Returns false and leaves the combination unchanged if
+        /// the graph is not contained in $H_\tau$.
+        pub fn add_graph(&mut self, graph : &Graph, coefficient : i64) -> bool{
+            if let Some(edges) = graph_to_edges(self.nice_tree_decomposition, graph) {
+                self.add_term(edges, coefficient);
+                true
+            } else { false }
+        }
+
+        /// Returns the coefficient of the graph with the given edges.
+        pub fn coefficient(&self, edges : EdgeList) -> i64 { *self.terms.get(&edges).unwrap_or(&0) }
+
+        /// Returns the terms of the linear combination.
+        pub fn terms(&self) -> &HashMap<EdgeList, i64> { &self.terms }
+
+        /// Evaluates $\sum c_i \cdot hom(H_i, G)$ using already computed counts of modified_dp_counts.
+        pub fn evaluate_counts(&self, counts : &HashMap<EdgeList, u64>) -> i128{
+            self.terms.iter()
+                .map(|(edges, coefficient)| *coefficient as i128 * *counts.get(edges).unwrap() as i128)
+                .sum()
+        }
+
+        /// Evaluates $\sum c_i \cdot hom(H_i, G)$ with a single run of the modified dynamic program.
+        pub fn evaluate(&self, to_graph : &Graph) -> i128{
+            self.evaluate_counts(&modified_dp_counts(self.nice_tree_decomposition, to_graph))
+        }
+    }
+}
@@ -0,0 +1,156 @@
+/// A module containing homomorphism counting into the quotient of the target under a partition of its vertices
+/// into twin classes. Two vertices a and b are twins if they have the same row in the adjacency matrix, i.e. the
+/// same neighbours, where a loop counts as neighbour. Since all vertices of a class have the same neighbours, the
+/// classes A and B of the quotient Q are either completely adjacent or not adjacent at all, and a mapping into G is
+/// a homomorphism iff its composition with the class map is a homomorphism into Q. Hence
+/// $hom(H, G) = \sum_{\psi : V(H) \to V(Q)} [\psi \in Hom(H, Q)] \prod_{v \in V(H)} |\psi(v)|$,
+/// i.e. the homomorphisms into Q weighted by the sizes of the classes. For targets with large twin classes, e.g.
+/// complete multipartite graphs or blow-ups, Q is much smaller than G.
+///
+/// Partitions which are no twin partitions, e.g. the stable coloring of color refinement or the orbits of the
+/// automorphism group, do not yield an exact reformulation, hence they are refined by the twin classes first.
+pub mod target_quotient {
+    use std::collections::HashMap;
+    use std::fmt;
+    use crate::color_refinement::weisfeiler_leman::Color;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{weighted_diaz_serna_thilikos, NodeTable, Weights};
+    use crate::integer_functions::integer_functions_methods::Mapping;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// The quotient of a target graph under a twin partition, where every vertex of the quotient is weighted by
+    /// the size of its class.
+    #[derive(Clone)]
+    pub struct WeightedQuotient {
+        /// the graph on the classes, with an edge between two classes iff their vertices are adjacent
+        pub graph: Graph,
+        /// the number of target vertices of each class
+        pub weights: Vec<u64>,
+        /// the class of each target vertex
+        pub classes: Vec<Color>,
+    }
+
+    /// The reason why a partition does not define a quotient.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum QuotientError {
+        /// the partition does not contain exactly one class for each vertex of the target
+        LengthMismatch { expected: usize, actual: usize },
+        /// the two vertices are in the same class but have different neighbours
+        NotTwins(usize, usize),
+    }
+
+    impl fmt::Display for QuotientError {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                QuotientError::LengthMismatch { expected, actual } => write!(f, "expected a class for each of the {} vertices but got {}", expected, actual),
+                QuotientError::NotTwins(a, b) => write!(f, "the vertices {} and {} are in the same class but have different neighbours", a, b),
+            }
+        }
+    }
+
+    impl std::error::Error for QuotientError {}
+
+    /// Returns the coarsest twin partition of the graph, i.e. the classes of the vertices with the same
+    /// neighbours. The classes are numbered in the order of their smallest vertex.
+    pub fn twin_partition(graph : &Graph) -> Vec<Color> {
+        let n = graph.node_count();
+        let rows : Vec<Vec<bool>> = (0..n).map(|a| (0..n).map(|b| graph.has_edge(Vertex::new(a), Vertex::new(b))).collect()).collect();
+        renumber(&rows)
+    }
+
+    /// Refines the partition by the twin classes, i.e. two vertices are in the same class of the result iff they
+    /// are in the same class of the partition and twins. The classes are numbered in the order of their
+    /// smallest vertex. Panics if the partition does not contain a class for each vertex.
+    pub fn refine_by_twins(graph : &Graph, partition : &[Color]) -> Vec<Color> {
+        assert_eq!(partition.len(), graph.node_count(), "The partition has to contain a class for each vertex!");
+        let twins = twin_partition(graph);
+        let pairs : Vec<(Color, Color)> = partition.iter().copied().zip(twins).collect();
+        renumber(&pairs)
+    }
+
+    /// Returns the quotient of the graph under the twin partition, where the classes of the partition may be
+    /// numbered arbitrarily. Returns an error if two vertices of the same class are not twins.
+    pub fn quotient(graph : &Graph, partition : &[Color]) -> Result<WeightedQuotient, QuotientError> {
+        let n = graph.node_count();
+        if partition.len() != n {
+            return Err(QuotientError::LengthMismatch { expected: n, actual: partition.len() });
+        }
+
+        let classes = renumber(partition);
+        let number_of_classes = classes.iter().max().map_or(0, |&class| class + 1);
+
+        // the first vertex of each class represents the class
+        let mut representatives : Vec<Option<usize>> = vec![None; number_of_classes];
+        let mut weights = vec![0; number_of_classes];
+        for a in 0..n{
+            weights[classes[a]] += 1;
+            match representatives[classes[a]] {
+                None => representatives[classes[a]] = Some(a),
+                Some(r) => if (0..n).any(|b| graph.has_edge(Vertex::new(a), Vertex::new(b)) != graph.has_edge(Vertex::new(r), Vertex::new(b))) {
+                    return Err(QuotientError::NotTwins(r, a));
+                },
+            }
+        }
+
+        let mut quotient_graph = Graph::default();
+        for _ in 0..number_of_classes{
+            quotient_graph.add_node(());
+        }
+        for x in 0..number_of_classes{
+            for y in x..number_of_classes{
+                let (a, b) = (representatives[x].unwrap(), representatives[y].unwrap());
+                if graph.has_edge(Vertex::new(a), Vertex::new(b)) {
+                    quotient_graph.add_edge(Vertex::new(x), Vertex::new(y), ());
+                }
+            }
+        }
+
+        Ok(WeightedQuotient { graph: quotient_graph, weights, classes })
+    }
+
+    /// Counts the homomorphisms from "from_graph" into the target by the algorithm of diaz et all on the quotient of
+    /// the target under its twin partition.
+    pub fn quotient_hom_count(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> u64 {
+        let quotient = quotient(to_graph, &twin_partition(to_graph)).unwrap();
+        weighted_hom_count(from_graph, ntd, &quotient)
+    }
+
+    /// Implementation of the algorithm of diaz et all for vertex weighted targets, which computes
+    /// $\sum_{\psi \in Hom(H, Q)} \prod_{v \in V(H)} w(\psi(v))$ by the weighted dynamic program.
+    /// Panics if the number does not fit into 64 bits.
+    pub fn weighted_hom_count(from_graph : &Graph, ntd : &NiceTreeDecomposition, quotient : &WeightedQuotient) -> u64 {
+        weighted_diaz_serna_thilikos::<NodeTable, _>(from_graph, ntd, &QuotientWeights { from_graph, quotient })
+            .expect("The number of homomorphisms does not fit into 64 bits!")
+    }
+
+    /// The weights of the homomorphisms into a quotient, i.e. the size of the class of each image, where a loop
+    /// has to be mapped onto a loop, and the adjacency of the classes for the edges.
+    struct QuotientWeights<'a> {
+        from_graph: &'a Graph,
+        quotient: &'a WeightedQuotient,
+    }
+
+    impl<'a> Weights for QuotientWeights<'a> {
+        fn node_count(&self) -> usize { self.quotient.graph.node_count() }
+
+        fn vertex_weight(&self, v : Vertex, a : Mapping) -> Option<u64> {
+            let has_loop = self.quotient.graph.has_edge(Vertex::new(a as usize), Vertex::new(a as usize));
+            Some(self.quotient.weights[a as usize] * (!self.from_graph.has_edge(v, v) || has_loop) as u64)
+        }
+
+        fn edge_weight(&self, _u : Vertex, _v : Vertex, a : Mapping, b : Mapping) -> Option<u64> {
+            Some(self.quotient.graph.has_edge(Vertex::new(a as usize), Vertex::new(b as usize)) as u64)
+        }
+    }
+
+    /// Numbers the distinct keys in the order of their first occurrence.
+    fn renumber<K : Eq + std::hash::Hash + Clone>(keys : &[K]) -> Vec<Color> {
+        let mut numbers = HashMap::new();
+        keys.iter()
+            .map(|key| {
+                let next = numbers.len();
+                *numbers.entry(key.clone()).or_insert(next)
+            })
+            .collect()
+    }
+}
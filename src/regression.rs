@@ -0,0 +1,244 @@
+/// A module containing a deterministic regression suite. A manifest lists pairs of pattern and target files
+/// together with their expected number of homomorphisms, every pair is counted by all three algorithms and
+/// each count is compared with the stored one, such that changes of the dynamic programs can be re-verified
+/// with a single call. In update mode the expected numbers are recomputed and written back to the manifest.
+pub mod regression_suite {
+    use std::fmt;
+    use std::fs;
+    use std::io;
+    use std::io::{BufRead, Write};
+    use std::path::{Path, PathBuf};
+    use crate::file_handler::open_reader;
+    use crate::pipeline::counting_pipeline::{count_graphs, import_graph, Algorithm, PipelineError, PipelineOptions};
+
+    /// The algorithms compared by the regression suite, in the order in which they are run.
+    pub const ALGORITHMS : [Algorithm; 3] = [Algorithm::BruteForce, Algorithm::DiazSernaThilikos, Algorithm::ModifiedDp];
+
+    /// A pair of pattern and target files of the manifest. Paths are relative to the directory of the manifest.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ManifestEntry {
+        pub pattern: PathBuf,
+        pub target: PathBuf,
+        /// a .ntd file of the pattern, if None the decompositions are computed as in the pipeline
+        pub ntd: Option<PathBuf>,
+        /// the stored number of homomorphisms, None if it has not been computed yet
+        pub expected: Option<u64>,
+    }
+
+    /// The list of instances of the regression suite. Each line of a manifest file consists of the pattern,
+    /// the target, optionally a .ntd file of the pattern and the expected number of homomorphisms or "?" if it
+    /// is unknown, separated by whitespace. Empty lines and lines starting with "#" are ignored.
+    /// ```text
+    /// # pattern target [ntd] expected
+    /// graphs/path.graph graphs/triangle.graph 24
+    /// graphs/star.graph graphs/triangle.graph ntds/star.ntd ?
+    /// ```
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    pub struct Manifest {
+        pub entries: Vec<ManifestEntry>,
+    }
+
+    /// The errors which can occur while running the regression suite.
+    #[derive(Debug)]
+    pub enum RegressionError {
+        /// the manifest could not be read or written
+        Io(PathBuf, io::Error),
+        /// a line of the manifest could not be parsed, lines are numbered from 1
+        MalformedLine { line: usize, content: String },
+        /// an instance could not be read or counted
+        Pipeline(PipelineError),
+    }
+
+    impl fmt::Display for RegressionError {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RegressionError::Io(path, error) => write!(f, "could not access {}: {}", path.display(), error),
+                RegressionError::MalformedLine { line, content } =>
+                    write!(f, "line {}: \"{}\": expected a pattern, a target, an optional .ntd file and a number or ?", line, content),
+                RegressionError::Pipeline(error) => write!(f, "{}", error),
+            }
+        }
+    }
+
+    impl std::error::Error for RegressionError {}
+
+    impl From<PipelineError> for RegressionError {
+        fn from(error : PipelineError) -> RegressionError { RegressionError::Pipeline(error) }
+    }
+
+    impl Manifest {
+
+        /// Parses a manifest, see the documentation of Manifest for the format.
+        pub fn parse<R>(reader : R) -> Result<Manifest, RegressionError>
+            where R: BufRead
+        {
+            let mut entries = vec![];
+
+            for (i, line) in reader.lines().enumerate(){
+                let line = line.map_err(|error| RegressionError::Io(PathBuf::new(), error))?;
+                let content = line.trim();
+                if content.is_empty() || content.starts_with('#') { continue; }
+
+                let malformed = || RegressionError::MalformedLine { line: i + 1, content: content.to_string() };
+                let args : Vec<&str> = content.split_whitespace().collect();
+                let (ntd, expected) = match args.len() {
+                    3 => (None, args[2]),
+                    4 => (Some(PathBuf::from(args[2])), args[3]),
+                    _ => return Err(malformed()),
+                };
+                let expected = match expected {
+                    "?" => None,
+                    number => Some(number.parse().map_err(|_| malformed())?),
+                };
+
+                entries.push(ManifestEntry { pattern: PathBuf::from(args[0]), target: PathBuf::from(args[1]), ntd, expected });
+            }
+            Ok(Manifest { entries })
+        }
+
+        /// Loads the manifest file.
+        pub fn load<P>(path : P) -> Result<Manifest, RegressionError>
+            where P: AsRef<Path>
+        {
+            let path = path.as_ref();
+            let reader = open_reader(path).map_err(|error| RegressionError::Io(path.to_path_buf(), error))?;
+            Manifest::parse(reader).map_err(|error| match error {
+                RegressionError::Io(_, error) => RegressionError::Io(path.to_path_buf(), error),
+                error => error,
+            })
+        }
+
+        /// Writes the manifest with one line per entry, comments of a loaded manifest are not preserved.
+        pub fn write<W>(&self, writer : &mut W) -> io::Result<()>
+            where W: Write
+        {
+            writeln!(writer, "# pattern target [ntd] expected")?;
+            for entry in &self.entries{
+                write!(writer, "{} {}", entry.pattern.display(), entry.target.display())?;
+                if let Some(ntd) = &entry.ntd { write!(writer, " {}", ntd.display())?; }
+                match entry.expected {
+                    Some(expected) => writeln!(writer, " {}", expected)?,
+                    None => writeln!(writer, " ?")?,
+                }
+            }
+            Ok(())
+        }
+
+        /// Saves the manifest to the file.
+        pub fn save<P>(&self, path : P) -> Result<(), RegressionError>
+            where P: AsRef<Path>
+        {
+            let path = path.as_ref();
+            let mut content = vec![];
+            self.write(&mut content).and_then(|_| fs::write(path, content))
+                .map_err(|error| RegressionError::Io(path.to_path_buf(), error))
+        }
+    }
+
+    /// Whether the stored numbers are compared or replaced.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RegressionMode {
+        /// compares the counts of all algorithms with the stored numbers
+        Check,
+        /// stores the counts in the manifest if all algorithms agree
+        Update,
+    }
+
+    /// A count of an algorithm which differs from the expected number.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Mismatch {
+        /// the index of the entry in the manifest
+        pub entry: usize,
+        pub pattern: PathBuf,
+        pub target: PathBuf,
+        pub algorithm: Algorithm,
+        /// the stored number, in update mode the count of the brute force algorithm
+        pub expected: Option<u64>,
+        pub actual: u64,
+    }
+
+    /// The result of a run of the regression suite.
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    pub struct RegressionReport {
+        /// the number of instances of the manifest
+        pub instances: usize,
+        /// the mismatches in the order of the manifest and the algorithms
+        pub mismatches: Vec<Mismatch>,
+        /// the number of instances whose stored number has been changed in update mode
+        pub updated: usize,
+    }
+
+    impl RegressionReport {
+        /// Returns true if every algorithm computed the expected number on every instance.
+        pub fn is_success(&self) -> bool { self.mismatches.is_empty() }
+    }
+
+    impl fmt::Display for Mismatch {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            let expected = self.expected.map_or("?".to_string(), |expected| expected.to_string());
+            write!(f, "entry {} ({} -> {}): {} counted {} instead of {}",
+                   self.entry, self.pattern.display(), self.target.display(), self.algorithm.name(), self.actual, expected)
+        }
+    }
+
+    impl fmt::Display for RegressionReport {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} instances, {} mismatches, {} updated", self.instances, self.mismatches.len(), self.updated)?;
+            for mismatch in &self.mismatches{
+                write!(f, "\n{}", mismatch)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Counts the homomorphisms of the entry with every algorithm of ALGORITHMS, where the paths are relative
+    /// to the given directory.
+    pub fn count_entry(entry : &ManifestEntry, directory : &Path) -> Result<Vec<u64>, RegressionError> {
+        let from_graph = import_graph(directory.join(&entry.pattern))?;
+        let to_graph = import_graph(directory.join(&entry.target))?;
+
+        ALGORITHMS.iter()
+            .map(|&algorithm| {
+                let options = PipelineOptions { algorithm, ntd_path: entry.ntd.as_ref().map(|ntd| directory.join(ntd)), ntd_cache: None };
+                Ok(count_graphs(&from_graph, &to_graph, &options)?.count)
+            })
+            .collect()
+    }
+
+    /// Runs the regression suite on the manifest. In check mode every count which differs from the stored
+    /// number is reported, an entry without a stored number is reported for every algorithm. In update mode
+    /// the counts of the other algorithms are compared with the brute force algorithm and the manifest file is
+    /// only rewritten if all of them agree, such that a wrong dynamic program cannot overwrite the golden numbers.
+    pub fn run_regression<P>(manifest_path : P, mode : RegressionMode) -> Result<RegressionReport, RegressionError>
+        where P: AsRef<Path>
+    {
+        let manifest_path = manifest_path.as_ref();
+        let directory = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+        let mut manifest = Manifest::load(manifest_path)?;
+        let mut report = RegressionReport { instances: manifest.entries.len(), ..RegressionReport::default() };
+
+        for (i, entry) in manifest.entries.iter_mut().enumerate(){
+            let counts = count_entry(entry, directory)?;
+            let expected = match mode {
+                RegressionMode::Check => entry.expected,
+                RegressionMode::Update => Some(counts[0]),
+            };
+
+            for (&algorithm, &actual) in ALGORITHMS.iter().zip(&counts){
+                if expected != Some(actual) {
+                    report.mismatches.push(Mismatch { entry: i, pattern: entry.pattern.clone(), target: entry.target.clone(), algorithm, expected, actual });
+                }
+            }
+
+            if mode == RegressionMode::Update && entry.expected != expected {
+                entry.expected = expected;
+                report.updated += 1;
+            }
+        }
+
+        if mode == RegressionMode::Update && report.is_success() && report.updated > 0 {
+            manifest.save(manifest_path)?;
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,204 @@
+/// A module containing homomorphism counting for relational structures, i.e. structures with several
+/// relations of arbitrary arity such as directed graphs, hypergraphs or the constraints of a CSP instance.
+/// A homomorphism from A to B is a mapping h from the universe of A to the universe of B such that
+/// $(h(a_1),...,h(a_k)) \in R^B$ for all $(a_1,...,a_k) \in R^A$ and all relations R.
+/// Hence counting homomorphisms from A to B counts the solutions of the #CSP instance with variables A,
+/// constraint scopes given by the tuples of A and allowed assignments given by B.
+///
+/// The algorithm of diaz et all is generalized by using a nice tree decomposition of the gaifman graph
+/// of A, where two elements are adjacent if they occur together in a tuple. Since the elements of a tuple
+/// form a clique in the gaifman graph, every tuple is contained in some bag and can be checked there.
+pub mod relational_structure_algorithms {
+    use std::collections::{HashMap, HashSet};
+    use petgraph::visit::NodeIndexable;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{forget_entries, introduce_entries, join_entries, NodeTable};
+    use crate::integer_functions::integer_functions_methods;
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping, max_mappings};
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
+
+    /// The message of the panic if an entry of the dynamic program does not fit into 64 bits.
+    const COUNT_OVERFLOW: &str = "The number of homomorphisms does not fit into 64 bits!";
+
+    /// The index of a relation within the signature of a structure.
+    pub type RelationIndex = usize;
+
+    /// A finite relational structure on the universe {0,..,n-1}.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct RelationalStructure {
+        universe_size: usize,
+        arities: Vec<usize>,
+        relations: Vec<HashSet<Vec<usize>>>,
+    }
+
+    impl RelationalStructure {
+
+        /// Creates a structure with the given universe size and empty relations of the given arities.
+        pub fn new(universe_size : usize, arities : &[usize]) -> RelationalStructure {
+            assert!(arities.iter().all(|&arity| arity >= 1), "Relations need an arity of at least one!");
+            RelationalStructure { universe_size, arities: arities.to_vec(), relations: vec![HashSet::new(); arities.len()] }
+        }
+
+        /// Converts a graph into a structure with a single symmetric binary relation, where loops are tuples (v,v).
+        pub fn from_graph(graph : &Graph) -> RelationalStructure {
+            let n = graph.node_count();
+            let mut structure = RelationalStructure::new(n, &[2]);
+            for u in 0..n{
+                for v in 0..n{
+                    if graph.has_edge(graph.from_index(u), graph.from_index(v)) { structure.add_tuple(0, vec![u, v]); }
+                }
+            }
+            structure
+        }
+
+        /// Adds the tuple to the given relation. Panics if the tuple does not fit the signature or the universe.
+        pub fn add_tuple(&mut self, relation : RelationIndex, tuple : Vec<usize>) {
+            assert_eq!(tuple.len(), self.arities[relation], "The tuple does not match the arity of relation {}!", relation);
+            assert!(tuple.iter().all(|&a| a < self.universe_size), "The tuple contains an element outside of the universe!");
+            self.relations[relation].insert(tuple);
+        }
+
+        /// Returns the number of elements of the universe.
+        pub fn universe_size(&self) -> usize { self.universe_size }
+
+        /// Returns the arities of all relations.
+        pub fn arities(&self) -> &[usize] { &self.arities }
+
+        /// Returns the tuples of the given relation.
+        pub fn tuples(&self, relation : RelationIndex) -> &HashSet<Vec<usize>> { &self.relations[relation] }
+
+        /// Returns true if the tuple is contained in the given relation.
+        pub fn contains(&self, relation : RelationIndex, tuple : &[usize]) -> bool { self.relations[relation].contains(tuple) }
+
+        /// Returns the gaifman graph of the structure, a nice tree decomposition of this graph can be used for
+        /// counting homomorphisms from this structure. Tuples with a repeated element induce a loop.
+        pub fn gaifman_graph(&self) -> Graph {
+            let mut graph = Graph::default();
+            for _ in 0..self.universe_size{
+                graph.add_node(());
+            }
+            for tuple in self.relations.iter().flatten(){
+                for (i, &a) in tuple.iter().enumerate(){
+                    for &b in &tuple[i + 1..]{
+                        if !graph.has_edge(Vertex::new(a), Vertex::new(b)) { graph.add_edge(Vertex::new(a), Vertex::new(b), ()); }
+                    }
+                }
+            }
+            graph
+        }
+    }
+
+    /// A brute force algorithm which iterates over all mappings from "from_structure" to "to_structure"
+    /// and counts those which map every tuple onto a tuple of the same relation.
+    pub fn brute_force_relational(from_structure : &RelationalStructure, to_structure : &RelationalStructure) -> u64{
+        assert_eq!(from_structure.arities(), to_structure.arities(), "The structures have different signatures!");
+
+        let h = from_structure.universe_size() as Mapping;
+        let g = to_structure.universe_size() as Mapping;
+
+        let mut count = 0;
+        for f in 0..max_mappings(h, g){
+            let is_homomorphism = (0..from_structure.arities().len()).all(|relation| {
+                from_structure.tuples(relation).iter().all(|tuple| {
+                    let image : Vec<usize> = tuple.iter().map(|&a| integer_functions_methods::apply(g, f, a as Mapping) as usize).collect();
+                    to_structure.contains(relation, &image)
+                })
+            });
+            if is_homomorphism { count += 1; }
+        }
+
+        count
+    }
+
+    /// Implementation of the algorithm of diaz et all for relational structures. The nice tree decomposition
+    /// has to be a nice tree decomposition of the gaifman graph of "from_structure". A tuple is checked at all
+    /// introduce nodes of its elements whose bag contains the whole tuple and at the leaf nodes of tuples
+    /// consisting of a single element. Checking a tuple several times does not change the result.
+    /// Only non-zero entries are stored. Panics if the number does not fit into 64 bits.
+    pub fn diaz_serna_thilikos_relational(from_structure : &RelationalStructure, ntd : &NiceTreeDecomposition, to_structure : &RelationalStructure) -> u64{
+        assert_eq!(from_structure.arities(), to_structure.arities(), "The structures have different signatures!");
+
+        let n = to_structure.universe_size() as Mapping;
+        let mut table : HashMap<TreeNode, NodeTable> = HashMap::new();
+
+        // all tuples containing a vertex together with their relation
+        let mut tuples_of_vertex : HashMap<usize, Vec<(RelationIndex, &Vec<usize>)>> = HashMap::new();
+        for relation in 0..from_structure.arities().len(){
+            for tuple in from_structure.tuples(relation){
+                let elements : HashSet<&usize> = tuple.iter().collect();
+                for &a in elements{
+                    tuples_of_vertex.entry(a).or_default().push((relation, tuple));
+                }
+            }
+        }
+
+        // the tuples of v contained in the bag of the indexer, each element replaced by its significance in the mappings of the bag
+        let constraints = |indexer : &BagIndexer, v : Vertex| -> Vec<(RelationIndex, Vec<Mapping>)> {
+            tuples_of_vertex.get(&v.index()).into_iter().flatten()
+                .filter_map(|(relation, tuple)| {
+                    let significances : Option<Vec<Mapping>> = tuple.iter().map(|&a| indexer.position(Vertex::new(a))).collect();
+                    significances.map(|significances| (*relation, significances))
+                })
+                .collect()
+        };
+
+        // checks whether the mapping f of a bag maps all given tuples onto tuples of the target
+        let satisfies = |indexer : &BagIndexer, f : Mapping, constraints : &[(RelationIndex, Vec<Mapping>)]| {
+            constraints.iter().all(|(relation, significances)| {
+                let image : Vec<usize> = significances.iter().map(|&s| indexer.image_at(f, s) as usize).collect();
+                to_structure.contains(*relation, &image)
+            })
+        };
+
+        for p in ntd.stingy_ordering(){
+            let mut entries = NodeTable::new();
+
+            match ntd.node_type(p){
+                Some(NodeType::Leaf) => {
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    let indexer_p = BagIndexer::of_node(ntd, p, n);
+                    let leaf_constraints = constraints(&indexer_p, v);
+
+                    for a in 0..n{
+                        if satisfies(&indexer_p, a, &leaf_constraints) { entries.insert(a, 1); }
+                    }
+                }
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let v = *ntd.unique_vertex(p).unwrap();
+                    let indexer_p = BagIndexer::of_node(ntd, p, n);
+                    let indexer_q = BagIndexer::of_node(ntd, q, n);
+                    let introduce_constraints = constraints(&indexer_p, v);
+
+                    let entries_q = table.remove(&q).unwrap();
+                    introduce_entries(&indexer_q, v, &entries_q, |f_q, buffer| {
+                        for a in 0..n{
+                            if satisfies(&indexer_p, indexer_q.extend(f_q, v, a), &introduce_constraints) { buffer.push((a, 1)); }
+                        }
+                        Some(())
+                    }, &mut entries).expect(COUNT_OVERFLOW);
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+
+                    let entries_q = table.remove(&q).unwrap();
+                    forget_entries(&BagIndexer::of_node(ntd, q, n), forgotten_vertex, &entries_q, &mut entries).expect(COUNT_OVERFLOW);
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    let entries_q1 = table.remove(&children[0]).unwrap();
+                    let entries_q2 = table.remove(&children[1]).unwrap();
+
+                    join_entries(&entries_q1, &entries_q2, &mut entries).expect(COUNT_OVERFLOW);
+                }
+                None => {}
+            }
+
+            table.insert(p, entries);
+        }
+
+        table.get(&ntd.root()).unwrap().get(&0).copied().unwrap_or(0)
+    }
+
+}
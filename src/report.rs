@@ -0,0 +1,282 @@
+/// A module containing self-contained reports of counting runs in Markdown or HTML, i.e. a single file with the
+/// statistics of the instances, the table of counts, the timing and drawings of the graphs, which can be shared
+/// with collaborators who do not run the program themselves. The HTML report embeds the drawings as inline SVG,
+/// the Markdown report contains the adjacency lists of the graphs instead.
+pub mod hom_report {
+    use std::f64::consts::PI;
+    use std::fmt::Write as _;
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+    use std::str::FromStr;
+    use std::time::{Duration, Instant};
+    use crate::graph_display::graph_printer::AdjacencyList;
+    use crate::inspect::instance_statistics::{graph_statistics, NtdStats};
+    use crate::modified_dp::algorithm::{modified_dp_counts, EdgeList};
+    use crate::pipeline::counting_pipeline::{count_graphs, PipelineError, PipelineOptions};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// Graphs with more vertices are not drawn, since the drawing would be unreadable anyway.
+    pub const MAX_DRAWN_VERTICES: usize = 64;
+
+    /// The supported formats of a report.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum ReportFormat {
+        #[default]
+        Markdown,
+        Html,
+    }
+
+    impl FromStr for ReportFormat {
+        type Err = String;
+
+        fn from_str(name : &str) -> Result<ReportFormat, String> {
+            match name {
+                "markdown" | "md" => Ok(ReportFormat::Markdown),
+                "html" => Ok(ReportFormat::Html),
+                _ => Err(format!("unknown report format {}, expected markdown or html", name)),
+            }
+        }
+    }
+
+    /// The content of a report, which is rendered by to_markdown or to_html.
+    #[derive(Clone)]
+    pub struct Report {
+        pub title: String,
+        /// the named graphs of the run, each listed with its statistics and drawn
+        pub graphs: Vec<(String, Graph)>,
+        /// the statistics of the used nice tree decomposition, if a single one has been used
+        pub ntd: Option<NtdStats>,
+        /// further properties of the run as pairs of name and value, e.g. the algorithm
+        pub summary: Vec<(String, String)>,
+        /// the counts as pairs of the description of the pattern and its number of homomorphisms
+        pub counts: Vec<(String, u64)>,
+        /// the time spent counting
+        pub duration: Duration,
+    }
+
+    impl Report {
+        /// Counts the homomorphisms from the pattern into the target with the pipeline and returns the report of
+        /// the run, where the names are the names of the graphs in the report, e.g. their file names.
+        pub fn for_pair(pattern_name : &str, from_graph : &Graph, target_name : &str, to_graph : &Graph, options : &PipelineOptions) -> Result<Report, PipelineError> {
+            let result = count_graphs(from_graph, to_graph, options)?;
+
+            let mut summary = vec![("algorithm".to_string(), result.algorithm.name().to_string())];
+            if let Some(width) = result.width {
+                summary.push(("width".to_string(), width.to_string()));
+            }
+            if let Some(shortcut) = result.shortcut {
+                summary.push(("certified zero".to_string(), format!("{:?}", shortcut)));
+            }
+
+            Ok(Report {
+                title: format!("Homomorphisms from {} into {}", pattern_name, target_name),
+                graphs: vec![(pattern_name.to_string(), from_graph.clone()), (target_name.to_string(), to_graph.clone())],
+                ntd: None,
+                summary,
+                counts: vec![(pattern_name.to_string(), result.count)],
+                duration: result.duration,
+            })
+        }
+
+        /// Counts the homomorphisms from all graphs of $H_\tau$ into the target with the modified dynamic program
+        /// and returns the report of the run. The counts are ordered by the bitmasks of the edge sets, each
+        /// pattern is described by its edges.
+        pub fn for_ntd_set(ntd : &NiceTreeDecomposition, target_name : &str, to_graph : &Graph) -> Report {
+            let start = Instant::now();
+            let counts = modified_dp_counts(ntd, to_graph);
+            let duration = start.elapsed();
+
+            let mut counts : Vec<(EdgeList, u64)> = counts.into_iter().collect();
+            counts.sort_unstable();
+
+            Report {
+                title: format!("Homomorphisms from all graphs of a nice tree decomposition into {}", target_name),
+                graphs: vec![(target_name.to_string(), to_graph.clone())],
+                ntd: Some(NtdStats::compute_for_target(ntd, to_graph.node_count())),
+                summary: vec![("algorithm".to_string(), "modified_dp".to_string()), ("patterns".to_string(), counts.len().to_string())],
+                counts: counts.into_iter().map(|(edges, count)| (describe_edges(ntd.all_possible_edges(), edges), count)).collect(),
+                duration,
+            }
+        }
+
+        /// Renders the report in the given format.
+        pub fn render(&self, format : ReportFormat) -> String {
+            match format {
+                ReportFormat::Markdown => self.to_markdown(),
+                ReportFormat::Html => self.to_html(),
+            }
+        }
+
+        /// Renders the report as Markdown, where the graphs are given by their adjacency lists.
+        pub fn to_markdown(&self) -> String {
+            let mut out = String::new();
+            let table = |out : &mut String, header : (&str, &str), rows : &[(String, String)]| {
+                let _ = writeln!(out, "| {} | {} |\n| --- | --- |", header.0, header.1);
+                for (name, value) in rows{
+                    let _ = writeln!(out, "| {} | {} |", name.replace('|', "\\|"), value);
+                }
+            };
+
+            let _ = writeln!(out, "# {}\n\n## Run\n", self.title);
+            table(&mut out, ("property", "value"), &self.run_rows());
+
+            for (name, graph) in &self.graphs{
+                let _ = writeln!(out, "\n## Graph {}\n", name);
+                table(&mut out, ("statistic", "value"), &graph_rows(graph));
+                if graph.node_count() <= MAX_DRAWN_VERTICES {
+                    let _ = writeln!(out, "\n```text\n{}\n```", AdjacencyList(graph));
+                }
+            }
+
+            if let Some(ntd) = &self.ntd {
+                let _ = writeln!(out, "\n## Nice tree decomposition\n");
+                table(&mut out, ("statistic", "value"), &ntd_rows(ntd));
+            }
+
+            let _ = writeln!(out, "\n## Counts\n");
+            table(&mut out, ("pattern", "homomorphisms"), &self.count_rows());
+            out
+        }
+
+        /// Renders the report as a single HTML page without external resources, where the graphs are drawn as
+        /// inline SVG.
+        pub fn to_html(&self) -> String {
+            let mut out = String::new();
+            let table = |out : &mut String, header : (&str, &str), rows : &[(String, String)]| {
+                let _ = writeln!(out, "<table>\n<tr><th>{}</th><th>{}</th></tr>", header.0, header.1);
+                for (name, value) in rows{
+                    let _ = writeln!(out, "<tr><td>{}</td><td>{}</td></tr>", escape_html(name), escape_html(value));
+                }
+                let _ = writeln!(out, "</table>");
+            };
+
+            let title = escape_html(&self.title);
+            let _ = writeln!(out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>", title);
+            let _ = writeln!(out, "<style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; margin-bottom: 1em; }} \
+                                   td, th {{ border: 1px solid #999; padding: 2px 8px; text-align: left; }}</style>\n</head>\n<body>");
+            let _ = writeln!(out, "<h1>{}</h1>\n<h2>Run</h2>", title);
+            table(&mut out, ("property", "value"), &self.run_rows());
+
+            for (name, graph) in &self.graphs{
+                let _ = writeln!(out, "<h2>Graph {}</h2>", escape_html(name));
+                table(&mut out, ("statistic", "value"), &graph_rows(graph));
+                if graph.node_count() <= MAX_DRAWN_VERTICES {
+                    let _ = writeln!(out, "{}", svg(graph));
+                }
+            }
+
+            if let Some(ntd) = &self.ntd {
+                let _ = writeln!(out, "<h2>Nice tree decomposition</h2>");
+                table(&mut out, ("statistic", "value"), &ntd_rows(ntd));
+            }
+
+            let _ = writeln!(out, "<h2>Counts</h2>");
+            table(&mut out, ("pattern", "homomorphisms"), &self.count_rows());
+            let _ = writeln!(out, "</body>\n</html>");
+            out
+        }
+
+        fn run_rows(&self) -> Vec<(String, String)> {
+            let mut rows = self.summary.clone();
+            rows.push(("duration".to_string(), format!("{:?}", self.duration)));
+            rows
+        }
+
+        fn count_rows(&self) -> Vec<(String, String)> {
+            self.counts.iter().map(|(pattern, count)| (pattern.clone(), count.to_string())).collect()
+        }
+    }
+
+    /// Writes the report in the given format into the file.
+    pub fn write_report<P>(report : &Report, format : ReportFormat, path : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        fs::write(path, report.render(format))
+    }
+
+    /// Returns the statistics of the graph as rows of a table.
+    fn graph_rows(graph : &Graph) -> Vec<(String, String)> {
+        let statistics = graph_statistics(graph);
+        let degree = |degree : Option<usize>| degree.map_or("-".to_string(), |degree| degree.to_string());
+        vec![
+            ("vertices".to_string(), statistics.vertices.to_string()),
+            ("edges".to_string(), statistics.edges.to_string()),
+            ("loops".to_string(), statistics.loops.to_string()),
+            ("min degree".to_string(), degree(statistics.min_degree)),
+            ("max degree".to_string(), degree(statistics.max_degree)),
+            ("average degree".to_string(), format!("{:.2}", statistics.average_degree)),
+            ("components".to_string(), statistics.components.to_string()),
+        ]
+    }
+
+    /// Returns the statistics of the nice tree decomposition as rows of a table.
+    fn ntd_rows(ntd : &NtdStats) -> Vec<(String, String)> {
+        let mut rows = vec![
+            ("nodes".to_string(), ntd.nodes.to_string()),
+            ("width".to_string(), ntd.width.to_string()),
+            ("vertices".to_string(), ntd.vertices.to_string()),
+            ("join nodes".to_string(), ntd.join_nodes.to_string()),
+            ("possible edges".to_string(), ntd.possible_edges.to_string()),
+            ("depth".to_string(), ntd.depth.to_string()),
+        ];
+        if let Some(estimate) = &ntd.estimated_cost {
+            rows.push(("estimated operations".to_string(), format!("{:.3e}", estimate.operations)));
+        }
+        rows
+    }
+
+    /// Describes the edge set given by the bitmask over the possible edges, e.g. "0-1 1-1" or "no edges".
+    pub fn describe_edges(possible_edges : &[(usize, usize)], edges : EdgeList) -> String {
+        let description : Vec<String> = possible_edges.iter().enumerate()
+            .filter(|(i, _)| edges & (1 << i) != 0)
+            .map(|(_, (u, v))| format!("{}-{}", u.min(v), u.max(v)))
+            .collect();
+        if description.is_empty() { "no edges".to_string() } else { description.join(" ") }
+    }
+
+    /// Escapes the characters with a special meaning in HTML.
+    fn escape_html(text : &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    /// Draws the graph as SVG with its vertices on a circle, where a loop is drawn as a small circle outside of
+    /// its vertex.
+    fn svg(graph : &Graph) -> String {
+        const SIZE: f64 = 240.0;
+        const RADIUS: f64 = 90.0;
+
+        let n = graph.node_count();
+        let position = |v : usize| -> (f64, f64) {
+            if n == 1 { return (SIZE / 2.0, SIZE / 2.0); }
+            let angle = 2.0 * PI * v as f64 / n as f64 - PI / 2.0;
+            (SIZE / 2.0 + RADIUS * angle.cos(), SIZE / 2.0 + RADIUS * angle.sin())
+        };
+
+        let mut out = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\">\n", SIZE);
+        for u in 0..n{
+            for v in u..n{
+                if !graph.has_edge(Vertex::new(u), Vertex::new(v)) { continue; }
+                let (x1, y1) = position(u);
+                if u == v {
+                    // the loop lies on the ray from the center through the vertex
+                    let (dx, dy) = (x1 - SIZE / 2.0, y1 - SIZE / 2.0);
+                    let length = (dx * dx + dy * dy).sqrt().max(1.0);
+                    let (cx, cy) = if n == 1 { (x1, y1 - 14.0) } else { (x1 + 14.0 * dx / length, y1 + 14.0 * dy / length) };
+                    let _ = writeln!(out, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"10\" fill=\"none\" stroke=\"black\"/>", cx, cy);
+                } else {
+                    let (x2, y2) = position(v);
+                    let _ = writeln!(out, "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\"/>", x1, y1, x2, y2);
+                }
+            }
+        }
+        for v in 0..n{
+            let (x, y) = position(v);
+            let _ = writeln!(out, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"9\" fill=\"white\" stroke=\"black\"/>", x, y);
+            let _ = writeln!(out, "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>", x, y, v);
+        }
+        out.push_str("</svg>");
+        out
+    }
+}
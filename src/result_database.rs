@@ -0,0 +1,108 @@
+/// A module containing a SQLite backend for the results of the running time experiments. All runs are
+/// stored in a single table keyed by algorithm, instance and timestamp, such that measurements of several
+/// machines and reruns can be merged into one database and queried with SQL.
+/// note: this module is only available with the feature "sqlite"
+pub mod experiment_database {
+    use std::path::Path;
+    use itertools::Itertools;
+    use rusqlite::{Connection, params};
+    use crate::experiments::single_running_time_measurement::ExperimentRecord;
+
+    /// A database of measured runs.
+    pub struct ResultDatabase {
+        connection: Connection,
+    }
+
+    impl ResultDatabase {
+
+        /// Opens the database at the given path and creates the table of runs if it does not exist yet.
+        pub fn open<P>(path : P) -> rusqlite::Result<ResultDatabase>
+            where P: AsRef<Path>
+        {
+            ResultDatabase::from_connection(Connection::open(path)?)
+        }
+
+        /// Opens a database which only lives in memory.
+        pub fn open_in_memory() -> rusqlite::Result<ResultDatabase> {
+            ResultDatabase::from_connection(Connection::open_in_memory()?)
+        }
+
+        fn from_connection(connection : Connection) -> rusqlite::Result<ResultDatabase> {
+            connection.execute_batch(
+                "CREATE TABLE IF NOT EXISTS runs (
+                    algorithm   TEXT NOT NULL,
+                    ntd         TEXT NOT NULL,
+                    graph       TEXT NOT NULL,
+                    timestamp   TEXT NOT NULL,
+                    experiment  TEXT NOT NULL,
+                    width       INTEGER NOT NULL,
+                    v_t         INTEGER NOT NULL,
+                    e_tau       INTEGER NOT NULL,
+                    v_tau       INTEGER NOT NULL,
+                    v_g         INTEGER NOT NULL,
+                    e_g         INTEGER NOT NULL,
+                    durations   TEXT NOT NULL,
+                    average     INTEGER NOT NULL,
+                    minimum     INTEGER NOT NULL,
+                    maximum     INTEGER NOT NULL,
+                    PRIMARY KEY (algorithm, ntd, graph, timestamp)
+                );")?;
+            Ok(ResultDatabase { connection })
+        }
+
+        /// Returns the underlying connection, e.g. for queries.
+        pub fn connection(&self) -> &Connection { &self.connection }
+
+        /// Inserts the record as a run with the given timestamp. If the database already contains a run of the
+        /// same algorithm on the same instance with the same timestamp, this run is replaced.
+        /// All running times are stored in microseconds, the durations of all runs as a comma separated list.
+        pub fn upsert(&self, record : &ExperimentRecord, timestamp : &str) -> rusqlite::Result<()> {
+            upsert_run(&self.connection, record, timestamp)
+        }
+
+        /// Upserts all records with the given timestamp in a single transaction.
+        pub fn upsert_all(&mut self, records : &[ExperimentRecord], timestamp : &str) -> rusqlite::Result<()> {
+            let transaction = self.connection.transaction()?;
+            for record in records{
+                upsert_run(&transaction, record, timestamp)?;
+            }
+            transaction.commit()
+        }
+
+        /// Returns the number of stored runs.
+        pub fn number_of_runs(&self) -> rusqlite::Result<usize> {
+            self.connection.query_row("SELECT COUNT(*) FROM runs", [], |row| row.get::<_, i64>(0)).map(|count| count as usize)
+        }
+    }
+
+    /// Inserts or replaces a single run, see ResultDatabase::upsert.
+    fn upsert_run(connection : &Connection, record : &ExperimentRecord, timestamp : &str) -> rusqlite::Result<()> {
+        let durations = record.durations.iter().map(|duration| duration.as_micros().to_string()).join(",");
+
+        connection.execute(
+            "INSERT INTO runs (algorithm, ntd, graph, timestamp, experiment, width, v_t, e_tau, v_tau, v_g, e_g, durations, average, minimum, maximum)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+             ON CONFLICT (algorithm, ntd, graph, timestamp) DO UPDATE SET
+                experiment = excluded.experiment, width = excluded.width, v_t = excluded.v_t, e_tau = excluded.e_tau,
+                v_tau = excluded.v_tau, v_g = excluded.v_g, e_g = excluded.e_g, durations = excluded.durations,
+                average = excluded.average, minimum = excluded.minimum, maximum = excluded.maximum",
+            params![
+                record.algorithm,
+                record.ntd_name,
+                record.graph_name,
+                timestamp,
+                record.experiment,
+                record.width,
+                record.v_t as i64,
+                record.e_tau as i64,
+                record.v_tau,
+                record.v_g as i64,
+                record.e_g as i64,
+                durations,
+                record.average().as_micros() as i64,
+                record.minimum().as_micros() as i64,
+                record.maximum().as_micros() as i64,
+            ])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+/// A module containing homomorphisms between rooted graphs, i.e. graphs together with a tuple of root vertices.
+/// A homomorphism between rooted graphs with roots (r_1,..,r_k) and (s_1,..,s_k) maps each r_i onto s_i. These
+/// numbers are obtained by pinning the images of the roots, while the homomorphism profile of a rooted pattern,
+/// i.e. the number of homomorphisms for each tuple of root images, is read off the table of a nice tree
+/// decomposition whose root bag consists of the roots.
+pub mod rooted_homomorphisms {
+    use std::collections::HashMap;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_root_table;
+    use crate::graph_ops::graph_operations::{identify_vertices, induced_subgraph};
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_decomposition::TreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// A graph together with a tuple of root vertices, a vertex may occur several times in the tuple.
+    #[derive(Clone)]
+    pub struct RootedGraph {
+        graph: Graph,
+        roots: Vec<usize>,
+    }
+
+    impl RootedGraph {
+
+        /// Creates the rooted graph, panics if a root is not a vertex of the graph.
+        pub fn new(graph : Graph, roots : Vec<usize>) -> RootedGraph {
+            assert!(roots.iter().all(|&r| r < graph.node_count()), "The roots have to be vertices of the graph!");
+            RootedGraph { graph, roots }
+        }
+
+        /// Returns the underlying graph.
+        pub fn graph(&self) -> &Graph { &self.graph }
+
+        /// Returns the tuple of roots.
+        pub fn roots(&self) -> &[usize] { &self.roots }
+    }
+
+    /// Counts the homomorphisms from "from_graph" to "to_graph" which map the i-th root of "from_graph" onto the
+    /// i-th root of "to_graph" by the algorithm of diaz et all, where the only feasible image of each root is
+    /// its pinned image. Panics if the numbers of roots differ.
+    pub fn rooted_hom_count(from_graph : &RootedGraph, ntd : &NiceTreeDecomposition, to_graph : &RootedGraph) -> u64 {
+        assert_eq!(from_graph.roots.len(), to_graph.roots.len(), "The rooted graphs have different numbers of roots!");
+
+        let mut feasible = vec![vec![true; to_graph.graph.node_count()]; from_graph.graph.node_count()];
+        for (&r, &s) in from_graph.roots.iter().zip(&to_graph.roots){
+            // a root occurring several times has to be mapped onto all of its pinned images
+            for (a, is_feasible) in feasible[r].iter_mut().enumerate(){
+                *is_feasible &= a == s;
+            }
+        }
+
+        diaz_serna_thilikos_root_table(from_graph.graph(), ntd, to_graph.graph(), &feasible).values().sum()
+    }
+
+    /// Computes the homomorphism profile of the rooted pattern, i.e. the number of homomorphisms into "to_graph"
+    /// for each tuple of images of the roots, with a single run of the algorithm of diaz et all. The roots are
+    /// added to every bag of the given nice tree decomposition, such that the width grows by at most the number
+    /// of roots. Only tuples with a non-zero number are returned.
+    pub fn rooted_hom_profile(from_graph : &RootedGraph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> HashMap<Vec<usize>, u64> {
+        let roots : Vec<Vertex> = from_graph.roots.iter().map(|&r| Vertex::new(r)).collect();
+        let rooted_ntd = TreeDecomposition::from(ntd).to_nice_tree_decomposition_keeping(&roots);
+
+        let feasible = vec![vec![true; to_graph.node_count()]; from_graph.graph.node_count()];
+        let root_table = diaz_serna_thilikos_root_table(from_graph.graph(), &rooted_ntd, to_graph, &feasible);
+
+        let indexer = BagIndexer::of_node(&rooted_ntd, rooted_ntd.root(), to_graph.node_count() as Mapping);
+
+        root_table.into_iter()
+            .map(|(f, hom_number)| {
+                let images = roots.iter()
+                    .map(|&r| indexer.image(f, r) as usize)
+                    .collect();
+                (images, hom_number)
+            })
+            .collect()
+    }
+
+    /// Returns the vector c with c[a] = the number of homomorphisms from "from_graph" into "to_graph" which map
+    /// the vertex r onto a, read off the root table of a nice tree decomposition whose root bag is {r}, such that
+    /// a single run of the algorithm of diaz et all suffices. The entries sum up to the number of homomorphisms.
+    /// Panics if r is not a vertex of "from_graph".
+    pub fn hom_distribution(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, r : usize) -> Vec<u64> {
+        assert!(r < from_graph.node_count(), "The vertex r has to be a vertex of the graph!");
+
+        let rooted_ntd = TreeDecomposition::from(ntd).to_nice_tree_decomposition_keeping(&[Vertex::new(r)]);
+        let feasible = vec![vec![true; to_graph.node_count()]; from_graph.node_count()];
+        let root_table = diaz_serna_thilikos_root_table(from_graph, &rooted_ntd, to_graph, &feasible);
+
+        // the root bag consists of r only, hence each mapping of the root bag is the image of r
+        (0..to_graph.node_count())
+            .map(|a| *root_table.get(&(a as Mapping)).unwrap_or(&0))
+            .collect()
+    }
+
+    /// Counts the retractions of the graph onto the subgraph induced by the given vertices, i.e. the homomorphisms
+    /// from the graph into the induced subgraph which fix each given vertex. They are the rooted homomorphisms
+    /// whose roots are the given vertices in the graph and their copies in the induced subgraph, where "ntd" is a
+    /// nice tree decomposition of the graph. The induced subgraph is a retract iff the count is not 0.
+    /// Panics if a vertex is given twice or does not exist.
+    pub fn retraction_count(graph : &Graph, ntd : &NiceTreeDecomposition, vertices : &[usize]) -> u64 {
+        let subgraph = induced_subgraph(graph, vertices);
+        let from_graph = RootedGraph::new(graph.clone(), vertices.to_vec());
+        let to_graph = RootedGraph::new(subgraph, (0..vertices.len()).collect());
+        rooted_hom_count(&from_graph, ntd, &to_graph)
+    }
+
+    /// Returns the vertex deletion profile of the target, i.e. the vector c with c[v] = hom(H, G - v) for every
+    /// vertex v of "to_graph", where the vertex v is removed without renumbering the others. A homomorphism into
+    /// G - v is a homomorphism into G which maps no vertex onto v, hence by inclusion-exclusion
+    /// $hom(H, G - v) = \sum_{S \subseteq V(H)} (-1)^{|S|} N_S(v)$, where $N_S(v)$ is the number of homomorphisms
+    /// mapping all vertices of S onto v, i.e. the anchored count of the merged vertex of the pattern in which the
+    /// vertices of S are identified. Hence 2^|V(H)| - 1 runs of the algorithm of diaz et all suffice for all
+    /// vertices of the target instead of |V(G)| runs, which is meant for patterns with few vertices, e.g. motifs.
+    /// hom(H, G) - c[v] is the number of homomorphisms using v, i.e. the participation of v in the motif.
+    /// Panics if the pattern has 64 or more vertices.
+    pub fn vertex_deletion_profile(from_graph : &Graph, to_graph : &Graph) -> Vec<u64> {
+        let h = from_graph.node_count();
+        assert!(h < 64, "The pattern has too many vertices for inclusion-exclusion!");
+
+        // the term of the empty set is hom(H, G) for every vertex, the sums of the other terms are accumulated
+        let mut hom_number : i128 = if h == 0 { 1 } else { 0 };
+        let mut profile : Vec<i128> = vec![0; to_graph.node_count()];
+        for set in 1..1u64 << h{
+            let vertices : Vec<usize> = (0..h).filter(|&u| set & (1 << u) != 0).collect();
+
+            // identifying the largest vertices first keeps the indices of the smaller ones, hence the merged
+            // vertex is the smallest vertex of the set
+            let merged = vertices[1..].iter().rev().fold(from_graph.clone(), |pattern, &u| identify_vertices(&pattern, vertices[0], u));
+            let ntd = compute_nice_tree_decomposition(&merged);
+            let counts = hom_distribution(&merged, &ntd, to_graph, vertices[0]);
+
+            // the number of homomorphisms into G is the sum of the anchored counts of any single vertex
+            if set == 1 { hom_number = counts.iter().sum::<u64>() as i128; }
+
+            let sign = if vertices.len() % 2 == 1 { -1 } else { 1 };
+            for (v, count) in counts.into_iter().enumerate(){
+                profile[v] += sign * count as i128;
+            }
+        }
+
+        profile.into_iter()
+            .map(|value| u64::try_from(hom_number + value).expect("A number of homomorphisms is not negative!"))
+            .collect()
+    }
+}
@@ -0,0 +1,122 @@
+/// A module containing cheap invariants of homomorphism counts, which are checked on small instances derived
+/// from the target graph, such that regressions of an algorithm are noticed without a brute force comparison:
+/// - hom(H, K_1 with loop) = 1 and hom(H, K_1) = 1 if H has no edges, otherwise 0,
+/// - hom(H, K_2 with loops) = 2^|V(H)|,
+/// - hom(H, G ⊍ G) = 2^c hom(H, G), where c is the number of components of H, since every component is mapped
+///   into one of the copies of G,
+/// - hom(H, G) does not change if the vertices of G are relabeled,
+/// - hom(H, G) does not decrease if an edge is added to G.
+///
+/// All derived instances keep the pattern, hence the nice tree decomposition of the pattern is reused.
+pub mod sanity_checks {
+    use std::fmt;
+    use crate::graph_generation::graph_generation_algorithms::number_of_components;
+    use crate::graph_ops::graph_operations::{disjoint_union, permute_vertices};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// An invariant of the number of homomorphisms from a pattern H into a target G.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Invariant {
+        /// hom(H, K_1 with loop) = 1
+        LoopedVertex,
+        /// hom(H, K_1) = 1 if H has no edges, otherwise 0
+        SingleVertex,
+        /// hom(H, K_2 with loops) = 2^|V(H)|
+        CompleteWithLoops,
+        /// hom(H, G ⊍ G) = 2^c hom(H, G)
+        DisjointUnion,
+        /// hom(H, G) = hom(H, G') for G' obtained from G by reversing the order of its vertices
+        Relabeling,
+        /// hom(H, G + e) >= hom(H, G) for the first pair e of vertices which is not an edge of G
+        Monotonicity,
+    }
+
+    impl fmt::Display for Invariant {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            let description = match self {
+                Invariant::LoopedVertex => "hom(H, K_1 with loop) = 1",
+                Invariant::SingleVertex => "hom(H, K_1) = 1 if H has no edges, otherwise 0",
+                Invariant::CompleteWithLoops => "hom(H, K_2 with loops) = 2^|V(H)|",
+                Invariant::DisjointUnion => "hom(H, G ⊍ G) = 2^c hom(H, G)",
+                Invariant::Relabeling => "hom(H, G) is invariant under relabeling G",
+                Invariant::Monotonicity => "hom(H, G + e) >= hom(H, G)",
+            };
+            write!(f, "{}", description)
+        }
+    }
+
+    /// An invariant which does not hold for the counts of an algorithm.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Violation {
+        pub invariant: Invariant,
+        /// the count required by the invariant, which is a lower bound for Monotonicity
+        pub expected: u64,
+        /// the count of the algorithm on the derived instance
+        pub actual: u64,
+    }
+
+    impl fmt::Display for Violation {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            let relation = if self.invariant == Invariant::Monotonicity { "at least " } else { "" };
+            write!(f, "{} violated: expected {}{} homomorphisms but got {}", self.invariant, relation, self.expected, self.actual)
+        }
+    }
+
+    /// Checks all invariants for the given algorithm, pattern and target and returns the violated ones. The
+    /// algorithm is run on the pattern and the target itself and on up to 6 derived targets, of which
+    /// G ⊍ G is the largest one. DisjointUnion is skipped if 2^c hom(H, G) exceeds the range of u64.
+    pub fn check_invariants<F>(algorithm : F, from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<Violation>
+        where F: Fn(&Graph, &NiceTreeDecomposition, &Graph) -> u64
+    {
+        let count = |target : &Graph| algorithm(from_graph, ntd, target);
+        let mut violations = vec![];
+        let mut check = |invariant : Invariant, expected : u64, actual : u64| {
+            let holds = if invariant == Invariant::Monotonicity { actual >= expected } else { actual == expected };
+            if !holds { violations.push(Violation { invariant, expected, actual }); }
+        };
+
+        let hom_number = count(to_graph);
+
+        check(Invariant::LoopedVertex, 1, count(&complete_graph_with_loops(1)));
+        let mut single_vertex = Graph::default();
+        single_vertex.add_node(());
+        check(Invariant::SingleVertex, (from_graph.edge_count() == 0) as u64, count(&single_vertex));
+        if let Some(expected) = 2u64.checked_pow(from_graph.node_count() as u32) {
+            check(Invariant::CompleteWithLoops, expected, count(&complete_graph_with_loops(2)));
+        }
+
+        let components = number_of_components(from_graph) as u32;
+        if let Some(expected) = 2u64.checked_pow(components).and_then(|factor| factor.checked_mul(hom_number)) {
+            check(Invariant::DisjointUnion, expected, count(&disjoint_union(to_graph, to_graph)));
+        }
+
+        let n = to_graph.node_count();
+        let reversed : Vec<usize> = (0..n).rev().collect();
+        check(Invariant::Relabeling, hom_number, count(&permute_vertices(to_graph, &reversed)));
+
+        let non_edge = (0..n).flat_map(|a| (a..n).map(move |b| (a, b)))
+            .find(|&(a, b)| !to_graph.has_edge(Vertex::new(a), Vertex::new(b)));
+        if let Some((a, b)) = non_edge {
+            let mut extended = to_graph.clone();
+            extended.add_edge(Vertex::new(a), Vertex::new(b), ());
+            check(Invariant::Monotonicity, hom_number, count(&extended));
+        }
+
+        violations
+    }
+
+    /// Returns the complete graph on n vertices with a loop at every vertex.
+    fn complete_graph_with_loops(n : usize) -> Graph {
+        let mut graph = Graph::default();
+        for _ in 0..n{
+            graph.add_node(());
+        }
+        for a in 0..n{
+            for b in a..n{
+                graph.add_edge(Vertex::new(a), Vertex::new(b), ());
+            }
+        }
+        graph
+    }
+}
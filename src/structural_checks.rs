@@ -0,0 +1,91 @@
+/// A module containing cheap structural checks which certify that there is no homomorphism from a pattern
+/// into a target graph, such that the dynamic programs do not have to be run.
+/// A homomorphism maps a closed walk of odd length onto a closed walk of the same odd length, which contains
+/// an odd cycle of at most this length. Hence there is no homomorphism if the pattern contains an odd cycle
+/// shorter than every odd cycle of the target, in particular if the pattern is not bipartite but the target is.
+/// Loops are odd cycles of length one.
+pub mod structural_shortcuts {
+    use std::collections::VecDeque;
+    use petgraph::visit::NodeIndexable;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::prefilter::feasibility_prefilter::Infeasibility;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// The reason why there is no homomorphism from the pattern into the target.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub enum ZeroReason {
+        /// the pattern contains an odd cycle (or a loop) while the target is bipartite
+        OddCycleIntoBipartite,
+        /// the shortest odd cycle of the pattern is shorter than the shortest odd cycle of the target
+        OddGirth { pattern: usize, target: usize },
+        /// one of the vertex, edge and degree conditions of the prefilter is violated
+        Infeasible(Infeasibility),
+    }
+
+    impl ZeroReason {
+        /// Returns the name of the reason as used in the json output of the pipeline.
+        pub fn name(&self) -> &'static str {
+            match self {
+                ZeroReason::OddCycleIntoBipartite => "odd_cycle_into_bipartite",
+                ZeroReason::OddGirth { .. } => "odd_girth",
+                ZeroReason::Infeasible(infeasibility) => infeasibility.name(),
+            }
+        }
+    }
+
+    /// Returns the length of a shortest odd cycle of the graph, where a loop is an odd cycle of length 1.
+    /// Returns None if the graph is bipartite. A breadth first search from each vertex finds the shortest odd
+    /// closed walk through it, which is given by an edge between two vertices of the same distance.
+    pub fn odd_girth(graph : &Graph) -> Option<usize>{
+        let n = graph.node_count();
+        let has_edge = |u : usize, v : usize| graph.has_edge(graph.from_index(u), graph.from_index(v));
+
+        if (0..n).any(|v| has_edge(v, v)) { return Some(1); }
+
+        let mut odd_girth : Option<usize> = None;
+        for root in 0..n{
+            let mut distance = vec![usize::MAX; n];
+            distance[root] = 0;
+            let mut queue = VecDeque::from([root]);
+
+            while let Some(u) = queue.pop_front() {
+                // walks longer than the best cycle found so far cannot improve it
+                if odd_girth.is_some_and(|girth| 2 * distance[u] + 1 >= girth) { break; }
+
+                for v in (0..n).filter(|&v| has_edge(u, v)){
+                    if distance[v] == usize::MAX {
+                        distance[v] = distance[u] + 1;
+                        queue.push_back(v);
+                    } else if distance[v] == distance[u] {
+                        let length = 2 * distance[u] + 1;
+                        odd_girth = Some(odd_girth.map_or(length, |girth| girth.min(length)));
+                    }
+                }
+            }
+        }
+
+        odd_girth
+    }
+
+    /// Checks whether the graph is bipartite, i.e. whether it contains neither an odd cycle nor a loop.
+    pub fn is_bipartite(graph : &Graph) -> bool { odd_girth(graph).is_none() }
+
+    /// Returns the reason why there is no homomorphism from "from_graph" to "to_graph" if one of the
+    /// structural checks applies. None does not imply that there is a homomorphism.
+    pub fn structural_zero(from_graph : &Graph, to_graph : &Graph) -> Option<ZeroReason>{
+        let pattern = odd_girth(from_graph)?;
+        match odd_girth(to_graph) {
+            None => Some(ZeroReason::OddCycleIntoBipartite),
+            Some(target) if pattern < target => Some(ZeroReason::OddGirth { pattern, target }),
+            Some(_) => None,
+        }
+    }
+
+    /// The algorithm of diaz et all which returns 0 without running the dynamic program if one of the
+    /// structural checks applies.
+    pub fn diaz_serna_thilikos_with_shortcuts(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> u64{
+        if structural_zero(from_graph, to_graph).is_some() { return 0; }
+        diaz_serna_thilikos_algorithm(from_graph, ntd, to_graph)
+    }
+}
@@ -0,0 +1,136 @@
+/// A module containing a pattern-side symmetry reduction for homomorphism counting.
+/// Every automorphism sigma of the pattern H maps a homomorphism f to the homomorphism f∘sigma,
+/// hence the homomorphisms decompose into orbits under the automorphism group Aut(H). It suffices
+/// to count one representative of each orbit and to multiply it by the size of its orbit.
+pub mod symmetry_reduction {
+    use petgraph::visit::NodeIndexable;
+    use crate::color_refinement::weisfeiler_leman::color_refinement;
+    use crate::graph_generation::graph_generation_algorithms::generate_graphs_containing;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// An automorphism represented as a permutation, where the vertex i is mapped onto automorphism[i].
+    pub type Automorphism = Vec<usize>;
+
+    /// Computes all automorphisms of the given graph by backtracking. Vertices are only mapped onto
+    /// vertices of the same color in the stable coloring of color refinement.
+    /// note: the automorphism group is enumerated explicitly, which is only feasible for small patterns
+    pub fn automorphisms(graph : &Graph) -> Vec<Automorphism>{
+        let n = graph.node_count();
+        let colors = color_refinement(graph);
+
+        let mut result = vec![];
+        let mut permutation = Vec::with_capacity(n);
+        let mut used = vec![false; n];
+
+        extend_automorphism(graph, &colors, &mut permutation, &mut used, &mut result);
+
+        result
+    }
+
+    /// Recursively extends the partial automorphism by all images of the next vertex which preserve
+    /// the adjacency to all vertices mapped so far.
+    fn extend_automorphism(graph : &Graph, colors : &[usize], permutation : &mut Vec<usize>, used : &mut [bool], result : &mut Vec<Automorphism>){
+        let n = graph.node_count();
+        let u = permutation.len();
+
+        if u == n {
+            result.push(permutation.clone());
+            return;
+        }
+
+        let has_edge = |a : usize, b : usize| graph.has_edge(graph.from_index(a), graph.from_index(b));
+
+        for a in 0..n{
+            if used[a] || colors[a] != colors[u] { continue; }
+
+            // the adjacency to all mapped vertices including u itself has to be preserved
+            let consistent = has_edge(u, u) == has_edge(a, a) &&
+                (0..u).all(|w| has_edge(u, w) == has_edge(a, permutation[w]));
+
+            if consistent {
+                used[a] = true;
+                permutation.push(a);
+                extend_automorphism(graph, colors, permutation, used, result);
+                permutation.pop();
+                used[a] = false;
+            }
+        }
+    }
+
+    /// Counts the homomorphisms from "from_graph" to "to_graph" by enumerating only the orbit
+    /// representatives of homomorphisms under the automorphisms of from_graph. A homomorphism f is the
+    /// representative of its orbit if the tuple (f(0),...,f(h-1)) is lexicographically minimal among all
+    /// f∘sigma. Its orbit size is |Aut(H)| divided by the number of automorphisms sigma with f∘sigma = f.
+    pub fn symmetric_brute_force(from_graph : &Graph, to_graph : &Graph) -> u64{
+        let automorphisms = automorphisms(from_graph);
+
+        let mut mapping = Vec::with_capacity(from_graph.node_count());
+        let mut counter = 0;
+
+        extend_representative(from_graph, to_graph, &automorphisms, &mut mapping, &mut counter);
+
+        counter
+    }
+
+    /// Recursively extends the partial mapping by all images of the next vertex preserving the edges to
+    /// all mapped vertices. Partial mappings which can not be extended to an orbit representative are pruned.
+    fn extend_representative(from_graph : &Graph, to_graph : &Graph, automorphisms : &[Automorphism], mapping : &mut Vec<usize>, counter : &mut u64){
+        let h = from_graph.node_count();
+        let u = mapping.len();
+
+        if u == h {
+            let stabilizer = automorphisms.iter()
+                .filter(|sigma| (0..h).all(|i| mapping[sigma[i]] == mapping[i]))
+                .count();
+            *counter += (automorphisms.len() / stabilizer) as u64;
+            return;
+        }
+
+        for a in 0..to_graph.node_count(){
+            let preserves_edges = (0..=u).all(|w| {
+                let image_of_w = if w == u { a } else { mapping[w] };
+                !from_graph.has_edge(from_graph.from_index(u), from_graph.from_index(w)) ||
+                    to_graph.has_edge(to_graph.from_index(a), to_graph.from_index(image_of_w))
+            });
+            if !preserves_edges { continue; }
+
+            mapping.push(a);
+            if !smaller_in_orbit(automorphisms, mapping) {
+                extend_representative(from_graph, to_graph, automorphisms, mapping, counter);
+            }
+            mapping.pop();
+        }
+    }
+
+    /// Checks whether some automorphism sigma certainly yields a lexicographically smaller mapping f∘sigma
+    /// for every extension of the partial mapping f. This is the case if f∘sigma is smaller on the longest
+    /// prefix of positions whose images under sigma are already mapped.
+    fn smaller_in_orbit(automorphisms : &[Automorphism], mapping : &[usize]) -> bool{
+        let k = mapping.len();
+
+        automorphisms.iter().any(|sigma| {
+            for i in 0..k{
+                if sigma[i] >= k { return false; }
+                let permuted = mapping[sigma[i]];
+                if permuted != mapping[i] { return permuted < mapping[i]; }
+            }
+            false
+        })
+    }
+
+    /// Implementation of symmetric_brute_force for all graphs in $H_\tau$. The automorphisms are computed
+    /// for each generated graph separately.
+    pub fn symmetric_brute_force_for_ntd_set(ntd : &NiceTreeDecomposition, to_graph : &Graph) -> Vec<(Graph, u64)>{
+        let graphs = generate_graphs_containing(ntd.vertex_count() as u64,
+                                                ntd.all_possible_edges().clone(),
+                                                &[]);
+
+        graphs.into_iter()
+            .map(|graph| {
+                let hom_number = symmetric_brute_force(&graph, to_graph);
+                (graph, hom_number)
+            })
+            .collect()
+    }
+}
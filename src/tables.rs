@@ -0,0 +1,197 @@
+/// A module containing the storage layouts of the table of a single node of the dynamic programs, i.e. the
+/// map from mappings in integer representation to counts. Only non-zero entries are considered to be stored.
+/// Different instances favor different layouts: hash maps are fast for sparse tables, B-trees iterate in a
+/// deterministic order, dense vectors avoid hashing for tables where most of the mappings are non-zero and compact
+/// tables halve the memory of entries which fit into 32 bits.
+pub mod table_backends {
+    use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
+    use crate::integer_functions::integer_functions_methods::Mapping;
+
+    /// The values of the entries of a table, i.e. a semiring whose operations return None if the result cannot
+    /// be represented. The exact counts are u64 with checked arithmetic, other variants of the dynamic program
+    /// store e.g. intervals or logarithms.
+    pub trait Semiring : Copy + Send + Sync {
+        /// Returns the neutral element of the multiplication.
+        fn one() -> Self;
+
+        /// Returns true if the value is the neutral element of the addition, which is not stored.
+        fn is_zero(&self) -> bool;
+
+        /// Returns the sum of both values.
+        fn add(self, other : Self) -> Option<Self>;
+
+        /// Returns the product of both values.
+        fn mul(self, other : Self) -> Option<Self>;
+    }
+
+    impl Semiring for u64 {
+        fn one() -> u64 { 1 }
+
+        fn is_zero(&self) -> bool { *self == 0 }
+
+        fn add(self, other : u64) -> Option<u64> { self.checked_add(other) }
+
+        fn mul(self, other : u64) -> Option<u64> { self.checked_mul(other) }
+    }
+
+    /// The table of a single node mapping each mapping f to its entry of type V.
+    pub trait MappingTable<V : Semiring = u64> : Default + Send + Sync {
+        /// The iterator over all stored entries.
+        type Iter<'b> : Iterator<Item = (Mapping, V)> where Self : 'b;
+
+        /// Returns the entry of f if it is stored.
+        fn get(&self, f : Mapping) -> Option<V>;
+
+        /// Stores the entry of f, a value of zero removes it.
+        fn insert(&mut self, f : Mapping, value : V);
+
+        /// Returns the number of stored entries.
+        fn len(&self) -> usize;
+
+        /// Returns true if no entry is stored.
+        fn is_empty(&self) -> bool { self.len() == 0 }
+
+        /// Removes all entries while keeping the allocated memory if possible.
+        fn clear(&mut self);
+
+        /// Returns an iterator over all stored entries.
+        fn iter(&self) -> Self::Iter<'_>;
+    }
+
+    impl<V : Semiring> MappingTable<V> for HashMap<Mapping, V> {
+        type Iter<'b> = std::iter::Map<hash_map::Iter<'b, Mapping, V>, fn((&Mapping, &V)) -> (Mapping, V)> where V : 'b;
+
+        fn get(&self, f : Mapping) -> Option<V> { HashMap::get(self, &f).copied() }
+
+        fn insert(&mut self, f : Mapping, value : V) {
+            if value.is_zero() { self.remove(&f); } else { HashMap::insert(self, f, value); }
+        }
+
+        fn len(&self) -> usize { HashMap::len(self) }
+
+        fn clear(&mut self) { HashMap::clear(self) }
+
+        fn iter(&self) -> Self::Iter<'_> { HashMap::iter(self).map(|(&f, &value)| (f, value)) }
+    }
+
+    impl<V : Semiring> MappingTable<V> for BTreeMap<Mapping, V> {
+        type Iter<'b> = std::iter::Map<btree_map::Iter<'b, Mapping, V>, fn((&Mapping, &V)) -> (Mapping, V)> where V : 'b;
+
+        fn get(&self, f : Mapping) -> Option<V> { BTreeMap::get(self, &f).copied() }
+
+        fn insert(&mut self, f : Mapping, value : V) {
+            if value.is_zero() { self.remove(&f); } else { BTreeMap::insert(self, f, value); }
+        }
+
+        fn len(&self) -> usize { BTreeMap::len(self) }
+
+        fn clear(&mut self) { BTreeMap::clear(self) }
+
+        fn iter(&self) -> Self::Iter<'_> { BTreeMap::iter(self).map(|(&f, &value)| (f, value)) }
+    }
+
+    /// A table storing the entries of all mappings up to the largest stored mapping in a vector, where
+    /// zero entries are not stored. The memory is proportional to the largest mapping instead of the
+    /// number of non-zero entries, i.e. at most the number of mappings of the bag.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct DenseTable {
+        entries: Vec<u64>,
+        non_zero: usize,
+    }
+
+    impl MappingTable for DenseTable {
+        type Iter<'b> = std::iter::FilterMap<std::iter::Enumerate<std::slice::Iter<'b, u64>>, fn((usize, &u64)) -> Option<(Mapping, u64)>>;
+
+        fn get(&self, f : Mapping) -> Option<u64> { self.entries.get(f as usize).copied().filter(|&value| value != 0) }
+
+        fn insert(&mut self, f : Mapping, value : u64) {
+            let f = f as usize;
+            if f >= self.entries.len() {
+                if value == 0 { return; }
+                self.entries.resize(f + 1, 0);
+            }
+
+            match (self.entries[f] != 0, value != 0) {
+                (false, true) => self.non_zero += 1,
+                (true, false) => self.non_zero -= 1,
+                _ => {}
+            }
+            self.entries[f] = value;
+        }
+
+        fn len(&self) -> usize { self.non_zero }
+
+        fn clear(&mut self) {
+            self.entries.clear();
+            self.non_zero = 0;
+        }
+
+        fn iter(&self) -> Self::Iter<'_> {
+            self.entries.iter().enumerate().filter_map(|(f, &value)| if value != 0 { Some((f as Mapping, value)) } else { None })
+        }
+    }
+
+    /// A table storing the entries whose mapping and value fit into 32 bits as pairs of u32, which halves the memory
+    /// of hash maps of u64 pairs. An entry exceeding 32 bits is promoted to a second table of u64 pairs, such that
+    /// large instances are computed without restarting and only their large entries take the usual memory. Every
+    /// mapping is stored in at most one of the tables.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct CompactTable {
+        narrow: HashMap<u32, u32>,
+        wide: HashMap<Mapping, u64>,
+    }
+
+    impl CompactTable {
+        /// Returns the number of entries which have been promoted to u64 pairs.
+        pub fn promoted(&self) -> usize { self.wide.len() }
+    }
+
+    impl MappingTable for CompactTable {
+        type Iter<'b> = std::iter::Chain<
+            std::iter::Map<hash_map::Iter<'b, u32, u32>, fn((&u32, &u32)) -> (Mapping, u64)>,
+            std::iter::Map<hash_map::Iter<'b, Mapping, u64>, fn((&Mapping, &u64)) -> (Mapping, u64)>>;
+
+        fn get(&self, f : Mapping) -> Option<u64> {
+            let narrow = u32::try_from(f).ok().and_then(|f| self.narrow.get(&f));
+            narrow.map(|&value| value as u64).or_else(|| self.wide.get(&f).copied())
+        }
+
+        fn insert(&mut self, f : Mapping, value : u64) {
+            match (u32::try_from(f), u32::try_from(value)) {
+                (Ok(f), Ok(value)) if value != 0 => {
+                    self.narrow.insert(f, value);
+                    if !self.wide.is_empty() { self.wide.remove(&(f as Mapping)); }
+                }
+                (narrow_f, _) => {
+                    if let Ok(f) = narrow_f { self.narrow.remove(&f); }
+                    if value == 0 { self.wide.remove(&f); } else { self.wide.insert(f, value); }
+                }
+            }
+        }
+
+        fn len(&self) -> usize { self.narrow.len() + self.wide.len() }
+
+        fn clear(&mut self) {
+            self.narrow.clear();
+            self.wide.clear();
+        }
+
+        fn iter(&self) -> Self::Iter<'_> {
+            let narrow : fn((&u32, &u32)) -> (Mapping, u64) = |(&f, &value)| (f as Mapping, value as u64);
+            let wide : fn((&Mapping, &u64)) -> (Mapping, u64) = |(&f, &value)| (f, value);
+            self.narrow.iter().map(narrow).chain(self.wide.iter().map(wide))
+        }
+    }
+
+    /// The storage layouts which can be selected for the tables of the dynamic program.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+    pub enum TableBackend {
+        /// HashMap, fast for sparse tables, iterates in a nondeterministic order
+        #[default]
+        Hash,
+        /// BTreeMap, iterates in the order of the mappings
+        BTree,
+        /// DenseTable, fast for tables where most of the mappings are non-zero
+        Dense,
+    }
+}
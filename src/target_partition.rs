@@ -0,0 +1,118 @@
+/// A module containing the partition of a target graph G into overlapping chunks, such that the homomorphisms
+/// from a connected pattern H can be counted chunk by chunk, e.g. on different machines. Let c be a center of H
+/// with eccentricity r, i.e. the radius of H. Every homomorphism mapping c onto a maps all vertices of H into the
+/// ball of radius r around a, hence
+/// $hom(H, G) = \sum_a hom_{c -> a}(H, G[B_r(a)])$.
+/// Each chunk owns a set of target vertices and contains the ball of radius r around them, such that the
+/// homomorphisms anchored at its owned vertices are counted in the subgraph induced by the chunk alone and the
+/// sum of the anchored counts of all chunks is hom(H, G).
+pub mod target_partitioning {
+    use std::collections::VecDeque;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_root_table;
+    use crate::graph_ops::graph_operations::induced_subgraph;
+    use crate::parallelism::parallel_execution;
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// A chunk of the target, i.e. its owned vertices together with the vertices within the radius around them.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TargetChunk {
+        /// the owned vertices in increasing order, every target vertex is owned by exactly one chunk
+        pub owned: Vec<usize>,
+        /// all vertices of the chunk in increasing order, including the owned vertices
+        pub vertices: Vec<usize>,
+    }
+
+    impl TargetChunk {
+        /// Returns the subgraph of the target induced by the vertices of the chunk, where the i-th vertex of the
+        /// chunk becomes the vertex i.
+        pub fn graph(&self, to_graph : &Graph) -> Graph {
+            induced_subgraph(to_graph, &self.vertices)
+        }
+
+        /// Returns the positions of the owned vertices within the vertices of the chunk, i.e. the owned vertices of
+        /// the chunk graph.
+        pub fn owned_positions(&self) -> Vec<usize> {
+            self.owned.iter().map(|v| self.vertices.binary_search(v).unwrap()).collect()
+        }
+    }
+
+    /// Returns a center of the pattern together with its eccentricity, i.e. the radius of the pattern, or None if
+    /// the pattern is empty or not connected.
+    pub fn pattern_center(graph : &Graph) -> Option<(usize, usize)> {
+        let mut center = None;
+        for c in 0..graph.node_count(){
+            // a vertex at infinite distance means that the pattern is not connected
+            let eccentricity = distances(graph, &[c], usize::MAX).into_iter().collect::<Option<Vec<usize>>>()?
+                .into_iter().max().unwrap();
+            if center.is_none_or(|(_, radius)| eccentricity < radius) { center = Some((c, eccentricity)); }
+        }
+        center
+    }
+
+    /// Splits the vertices of the target into the given number of ranges of consecutive vertices of almost equal
+    /// size, which are owned by the chunks, and extends each chunk by the vertices within the radius around its
+    /// owned vertices. Chunks without owned vertices are omitted. Panics if the number of chunks is 0.
+    pub fn partition_target(to_graph : &Graph, chunks : usize, radius : usize) -> Vec<TargetChunk> {
+        assert!(chunks > 0, "The target has to be split into at least one chunk!");
+        let n = to_graph.node_count();
+
+        (0..chunks)
+            .map(|i| (i * n / chunks..(i + 1) * n / chunks).collect::<Vec<usize>>())
+            .filter(|owned| !owned.is_empty())
+            .map(|owned| {
+                let vertices = distances(to_graph, &owned, radius).iter().enumerate()
+                    .filter_map(|(v, d)| d.map(|_| v))
+                    .collect();
+                TargetChunk { owned, vertices }
+            })
+            .collect()
+    }
+
+    /// Counts the homomorphisms from "from_graph" into the chunk graph which map the center onto an owned vertex
+    /// of the chunk, where "chunk_graph" is the graph returned by chunk.graph(..). This needs the chunk graph only,
+    /// such that it can be run on a different machine than the partition.
+    pub fn anchored_count(from_graph : &Graph, ntd : &NiceTreeDecomposition, chunk_graph : &Graph, chunk : &TargetChunk, center : usize) -> u64 {
+        let mut feasible = vec![vec![true; chunk_graph.node_count()]; from_graph.node_count()];
+        feasible[center] = vec![false; chunk_graph.node_count()];
+        for a in chunk.owned_positions(){
+            feasible[center][a] = true;
+        }
+
+        diaz_serna_thilikos_root_table(from_graph, ntd, chunk_graph, &feasible).values().sum()
+    }
+
+    /// Counts the homomorphisms from the connected pattern "from_graph" into "to_graph" by partitioning the target
+    /// into the given number of chunks, counting the anchored homomorphisms of each chunk, potentially in parallel,
+    /// and summing them up. Panics if the pattern is empty or not connected.
+    pub fn count_by_chunks(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, chunks : usize) -> u64 {
+        let (center, radius) = pattern_center(from_graph).expect("The pattern has to be connected!");
+        let chunks = partition_target(to_graph, chunks, radius);
+
+        parallel_execution::map_range(chunks.len(), |i| {
+            anchored_count(from_graph, ntd, &chunks[i].graph(to_graph), &chunks[i], center)
+        }).into_iter().sum()
+    }
+
+    /// Returns the distance of each vertex to the given sources if it is at most max_distance, None otherwise.
+    fn distances(graph : &Graph, sources : &[usize], max_distance : usize) -> Vec<Option<usize>> {
+        let mut distance = vec![None; graph.node_count()];
+        let mut queue = VecDeque::new();
+        for &s in sources{
+            distance[s] = Some(0);
+            queue.push_back(s);
+        }
+
+        while let Some(u) = queue.pop_front() {
+            let d = distance[u].unwrap();
+            if d == max_distance { continue; }
+            for w in graph.neighbors(Vertex::new(u)){
+                if distance[w.index()].is_none() {
+                    distance[w.index()] = Some(d + 1);
+                    queue.push_back(w.index());
+                }
+            }
+        }
+        distance
+    }
+}
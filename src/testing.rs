@@ -0,0 +1,298 @@
+/// A module containing randomized consistency tests. Random nice tree decompositions, patterns with one of
+/// them as a decomposition and target graphs are generated, such that any algorithm counting homomorphisms
+/// can be compared against the brute force algorithm with a single call.
+pub mod consistency_testing {
+    use std::collections::HashMap;
+    use std::fmt;
+    use petgraph::visit::NodeIndexable;
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::graph_generation::graph_generation_algorithms::graph_from_edge_subset;
+    use crate::integer_functions::integer_functions_methods::Mapping;
+    use crate::modified_dp::algorithm::modified_dp_for_pattern;
+    use crate::tree_decompositions::nice_tree_decomposition::{Bag, NiceTreeDecomposition, NodeData, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, TreeStructure, Vertex};
+
+    /// A small deterministic pseudo random number generator (xorshift64*), such that failing instances can
+    /// be reproduced from their seed.
+    pub struct TestRng(u64);
+
+    impl TestRng {
+        pub fn new(seed : u64) -> TestRng { TestRng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1) }
+
+        pub fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        /// Returns a number in 0..n, n has to be positive.
+        pub fn below(&mut self, n : usize) -> usize { (self.next_u64() % n as u64) as usize }
+
+        /// Returns true with the given probability.
+        pub fn chance(&mut self, probability : f64) -> bool { (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 <= probability }
+    }
+
+    /// The parameters of the generated instances.
+    #[derive(Debug, Clone)]
+    pub struct ConsistencyConfig {
+        pub seed: u64,
+        pub instances: usize,
+        pub max_pattern_vertices: usize,
+        pub max_bag_size: usize,
+        pub max_joins: usize,
+        pub max_target_vertices: usize,
+    }
+
+    impl Default for ConsistencyConfig {
+        fn default() -> ConsistencyConfig {
+            ConsistencyConfig { seed: 0, instances: 50, max_pattern_vertices: 5, max_bag_size: 3, max_joins: 2, max_target_vertices: 4 }
+        }
+    }
+
+    /// A pattern graph together with a nice tree decomposition of it and a target graph.
+    pub struct Instance {
+        pub from_graph: Graph,
+        pub nice_tree_decomposition: NiceTreeDecomposition,
+        pub to_graph: Graph,
+    }
+
+    impl Instance {
+        /// Describes the instance by the edge lists of both graphs and the possible edges of the nice tree decomposition.
+        pub fn describe(&self) -> String {
+            format!("pattern with {} vertices and edges {:?}, possible edges {:?}, target with {} vertices and edges {:?}",
+                    self.from_graph.node_count(), edge_list(&self.from_graph),
+                    self.nice_tree_decomposition.all_possible_edges(),
+                    self.to_graph.node_count(), edge_list(&self.to_graph))
+        }
+    }
+
+    /// Generates a random nice tree decomposition top down. Every vertex is forgotten exactly once and
+    /// introduced below its forget node only, hence the decomposition is valid by construction.
+    pub fn random_nice_tree_decomposition(rng : &mut TestRng, max_vertices : usize, max_bag_size : usize, max_joins : usize) -> NiceTreeDecomposition{
+        assert!(max_vertices >= 1 && max_bag_size >= 1, "A nice tree decomposition needs at least one vertex and a bag size of at least one!");
+
+        let mut builder = NtdBuilder { rng, nodes: vec![], edges: vec![], next_vertex: 0, max_vertices, max_bag_size, joins_left: max_joins };
+        builder.build(Bag::new());
+
+        let width = builder.nodes.iter().map(|node_data| node_data.bag().len()).max().unwrap() as u32 - 1;
+        let number_of_vertices = builder.next_vertex as u32;
+
+        let mut tree_structure = TreeStructure::new(builder.nodes.len() as TreeNode);
+        for &(p, q) in &builder.edges{
+            tree_structure.add_child(p, q);
+        }
+        let nodes_data : HashMap<TreeNode, NodeData> = builder.nodes.into_iter().enumerate()
+            .map(|(p, node_data)| (p as TreeNode, node_data))
+            .collect();
+
+        NiceTreeDecomposition::new(tree_structure, nodes_data, number_of_vertices, width)
+    }
+
+    /// The state of the generation of a random nice tree decomposition.
+    struct NtdBuilder<'a> {
+        rng: &'a mut TestRng,
+        nodes: Vec<NodeData>,
+        edges: Vec<(TreeNode, TreeNode)>,
+        next_vertex: usize,
+        max_vertices: usize,
+        max_bag_size: usize,
+        joins_left: usize,
+    }
+
+    impl NtdBuilder<'_> {
+
+        /// Creates a node with the given bag together with its subtree and returns the node.
+        fn build(&mut self, bag : Bag) -> TreeNode{
+            let p = self.nodes.len() as TreeNode;
+            self.nodes.push(NodeData::new(NodeType::Leaf, bag.clone()));
+
+            // the node types which are possible for the bag
+            let mut node_types = vec![];
+            if self.next_vertex < self.max_vertices && bag.len() < self.max_bag_size { node_types.push(NodeType::Forget); }
+            if bag.len() >= 2 { node_types.push(NodeType::Introduce); }
+            if bag.len() == 1 { node_types.push(NodeType::Leaf); }
+            if !bag.is_empty() && self.joins_left > 0 { node_types.push(NodeType::Join); }
+
+            let node_type = node_types[self.rng.below(node_types.len())].clone();
+            match node_type {
+                NodeType::Forget => {
+                    // a fresh vertex is forgotten, hence it does not occur outside of the subtree
+                    let mut child_bag = bag.clone();
+                    child_bag.insert(Vertex::new(self.next_vertex));
+                    self.next_vertex += 1;
+                    self.add_child(p, child_bag);
+                }
+                NodeType::Introduce => {
+                    let mut vertices : Vec<Vertex> = bag.iter().copied().collect();
+                    vertices.sort();
+                    let mut child_bag = bag.clone();
+                    child_bag.remove(&vertices[self.rng.below(vertices.len())]);
+                    self.add_child(p, child_bag);
+                }
+                NodeType::Join => {
+                    self.joins_left -= 1;
+                    self.add_child(p, bag.clone());
+                    self.add_child(p, bag.clone());
+                }
+                NodeType::Leaf => {}
+            }
+
+            self.nodes[p as usize] = NodeData::new(node_type, bag);
+            p
+        }
+
+        fn add_child(&mut self, p : TreeNode, child_bag : Bag){
+            let q = self.build(child_bag);
+            self.edges.push((p, q));
+        }
+    }
+
+    /// Returns a random graph on the given number of vertices, where each edge and each loop exists with the
+    /// given probability.
+    pub fn random_graph(rng : &mut TestRng, number_of_vertices : usize, probability : f64) -> Graph{
+        let mut graph = Graph::default();
+        for _ in 0..number_of_vertices{
+            graph.add_node(());
+        }
+        for u in 0..number_of_vertices{
+            for v in u..number_of_vertices{
+                if rng.chance(probability) { graph.add_edge(graph.from_index(u), graph.from_index(v), ()); }
+            }
+        }
+        graph
+    }
+
+    /// Returns a random pattern whose edges are a random subset of the possible edges of the nice tree decomposition.
+    pub fn random_pattern(rng : &mut TestRng, ntd : &NiceTreeDecomposition) -> Graph{
+        graph_from_edge_subset(ntd.vertex_count() as usize, ntd.all_possible_edges(), rng.next_u64())
+    }
+
+    /// Generates a random instance within the bounds of the configuration.
+    pub fn random_instance(rng : &mut TestRng, config : &ConsistencyConfig) -> Instance{
+        let nice_tree_decomposition = random_nice_tree_decomposition(rng, config.max_pattern_vertices, config.max_bag_size, config.max_joins);
+        let from_graph = random_pattern(rng, &nice_tree_decomposition);
+        let number_of_target_vertices = 1 + rng.below(config.max_target_vertices);
+        let probability = if rng.chance(0.5) { 0.3 } else { 0.8 };
+        let to_graph = random_graph(rng, number_of_target_vertices, probability);
+
+        Instance { from_graph, nice_tree_decomposition, to_graph }
+    }
+
+    /// Compares the given algorithm with the brute force algorithm on random instances. Panics with a description
+    /// of the first instance on which the numbers of homomorphisms differ.
+    pub fn assert_consistent_with_brute_force<F>(algorithm : F, config : &ConsistencyConfig)
+        where F: Fn(&Graph, &NiceTreeDecomposition, &Graph) -> u64
+    {
+        let mut rng = TestRng::new(config.seed);
+
+        for i in 0..config.instances{
+            let instance = random_instance(&mut rng, config);
+            let expected = simple_brute_force(&instance.from_graph, &instance.to_graph);
+            let actual = algorithm(&instance.from_graph, &instance.nice_tree_decomposition, &instance.to_graph);
+
+            if actual != expected {
+                panic!("Instance {} of seed {}: expected {} homomorphisms but got {} for {}", i, config.seed, expected, actual, instance.describe());
+            }
+        }
+    }
+
+    /// Checks that the brute force algorithm, the algorithm of diaz et all and the modified dynamic program
+    /// agree on random instances.
+    pub fn assert_reference_algorithms_consistent(config : &ConsistencyConfig){
+        assert_consistent_with_brute_force(diaz_serna_thilikos_algorithm, config);
+        assert_consistent_with_brute_force(modified_dp_count, config);
+    }
+
+    /// An entry I[p,f] of a table of a dynamic program which differs from the brute force count.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TableMismatch {
+        pub node: TreeNode,
+        pub mapping: Mapping,
+        pub expected: u64,
+        pub actual: u64,
+    }
+
+    impl fmt::Display for TableMismatch {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "entry of mapping {} at node {}: expected {} extending homomorphisms but the table contains {}",
+                   self.mapping, self.node, self.expected, self.actual)
+        }
+    }
+
+    /// Returns the non-zero entries I[p,f] of node p by brute force, i.e. for each mapping f of the sorted bag of
+    /// p the number of homomorphisms from the subgraph of "from_graph" induced by the vertices of the subtree of
+    /// p into "to_graph" which extend f and map each vertex v only onto the images a with feasible[v][a].
+    /// All mappings of the vertices of the subtree are enumerated, hence this is only feasible for small instances.
+    pub fn brute_force_node_table(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, p : TreeNode, feasible : &[Vec<bool>]) -> HashMap<Mapping, u64>{
+        let n = to_graph.node_count();
+
+        // the vertices of the subtree of p in ascending order
+        let mut vertices : Vec<usize> = vec![];
+        let mut stack = vec![p];
+        while let Some(q) = stack.pop() {
+            vertices.extend(ntd.bit_bag(q).unwrap().iter().map(|v| v.index()));
+            stack.extend(ntd.children(q).into_iter().flatten());
+        }
+        vertices.sort();
+        vertices.dedup();
+
+        let bag : Vec<usize> = ntd.bit_bag(p).unwrap().iter()
+            .map(|v| vertices.binary_search(&v.index()).unwrap())
+            .collect();
+        let edges : Vec<(usize, usize)> = edge_list(from_graph).into_iter()
+            .filter_map(|(u, v)| Some((vertices.binary_search(&u).ok()?, vertices.binary_search(&v).ok()?)))
+            .collect();
+
+        let mut table = HashMap::new();
+        let mut images = vec![0; vertices.len()];
+        if n == 0 && !vertices.is_empty() { return table; }
+
+        loop {
+            let is_homomorphism = vertices.iter().zip(&images).all(|(&v, &a)| feasible[v][a])
+                && edges.iter().all(|&(i, j)| to_graph.has_edge(to_graph.from_index(images[i]), to_graph.from_index(images[j])));
+            if is_homomorphism {
+                let f = bag.iter().rev().fold(0, |f, &i| f * n as Mapping + images[i] as Mapping);
+                *table.entry(f).or_insert(0) += 1;
+            }
+
+            // the next mapping in lexicographic order
+            let Some(i) = images.iter().position(|&a| a + 1 < n) else { break; };
+            images[i] += 1;
+            images[..i].fill(0);
+        }
+
+        table
+    }
+
+    /// Compares the given entries of node p, e.g. its table computed by a dynamic program, with the table of
+    /// brute_force_node_table and returns the first entry which differs. Missing entries are 0.
+    pub fn audit_node_table<I>(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph, p : TreeNode, feasible : &[Vec<bool>], entries : I) -> Result<(), TableMismatch>
+        where I: IntoIterator<Item = (Mapping, u64)>
+    {
+        let mut expected = brute_force_node_table(from_graph, ntd, to_graph, p, feasible);
+
+        for (mapping, actual) in entries{
+            let expected = expected.remove(&mapping).unwrap_or(0);
+            if actual != expected { return Err(TableMismatch { node: p, mapping, expected, actual }); }
+        }
+        match expected.into_iter().min() {
+            Some((mapping, expected)) => Err(TableMismatch { node: p, mapping, expected, actual: 0 }),
+            None => Ok(()),
+        }
+    }
+
+    /// Counts the homomorphisms of a single pattern with the modified dynamic program.
+    fn modified_dp_count(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> u64{
+        modified_dp_for_pattern(from_graph, ntd, to_graph).expect("The pattern contains an edge which is not a possible edge!")
+    }
+
+    /// Returns the edges (u,v) with u <= v of the given graph.
+    fn edge_list(graph : &Graph) -> Vec<(usize, usize)>{
+        let n = graph.node_count();
+        (0..n).flat_map(|u| (u..n).map(move |v| (u, v)))
+            .filter(|&(u, v)| graph.has_edge(graph.from_index(u), graph.from_index(v)))
+            .collect()
+    }
+}
@@ -5,13 +5,27 @@
 pub mod tree_structure{
     use std::cmp::max;
     use std::collections::{HashMap, HashSet};
-    use petgraph::matrix_graph::NodeIndex;
+    use petgraph::matrix_graph::{MatrixGraph, NodeIndex};
+    use petgraph::Undirected;
 
     /// ## Type alias for better readability
     /// Nodes of the underlying tree simply represented by unsigned integers from 0,..., N-1
     pub type TreeNode = u64;
+
+    /// The integer type of the vertex indices of all graphs. By default u16 keeps bags and adjacency lists small,
+    /// but limits the graphs to 65535 vertices. The feature "vertex-u32" or "vertex-usize" selects a wider type,
+    /// where the widest enabled one is used.
+    #[cfg(not(any(feature = "vertex-u32", feature = "vertex-usize")))]
+    pub type VertexIndex = u16;
+    #[cfg(all(feature = "vertex-u32", not(feature = "vertex-usize")))]
+    pub type VertexIndex = u32;
+    #[cfg(feature = "vertex-usize")]
+    pub type VertexIndex = usize;
+
     /// Vertices contained in bag equal vertices of graphs
-    pub type Vertex = NodeIndex;
+    pub type Vertex = NodeIndex<VertexIndex>;
+    /// The undirected graphs of patterns and targets, a loop is an edge from a vertex to itself
+    pub type Graph = MatrixGraph<(), (), Undirected, Option<()>, VertexIndex>;
 
     /// ## Tree Structure
     /// a simple tree structure to organize the data of tree decompositions
@@ -101,18 +115,436 @@ pub mod tree_structure{
 
 }
 
+/// A public module containing the (not necessarily nice) tree decomposition structure, which allows
+/// arbitrary bags and an arbitrary number of children. It is used as an intermediate representation,
+/// e.g. for tree decompositions computed by external solvers, and can be converted into a nice tree decomposition.
 pub mod tree_decomposition{
+    use std::collections::{HashMap, HashSet};
+    use std::fmt;
+    use petgraph::visit::NodeIndexable;
+    use crate::tree_decompositions::nice_tree_decomposition::{Bag, NiceTreeDecomposition, NodeData, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, TreeStructure, Vertex};
+
+    /// The reasons why a tree decomposition is not a valid tree decomposition of a graph.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub enum TreeDecompositionError {
+        /// the node has no bag
+        MissingBag(TreeNode),
+        /// the tree consists of several components
+        Disconnected,
+        /// the vertex of the graph is not contained in any bag
+        VertexNotCovered(usize),
+        /// the edge of the graph is not contained in any bag
+        EdgeNotCovered(usize, usize),
+        /// the nodes whose bags contain the vertex do not form a connected subtree
+        VertexNotConnected(usize),
+    }
+
+    impl fmt::Display for TreeDecompositionError {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                TreeDecompositionError::MissingBag(p) => write!(f, "node {} has no bag", p),
+                TreeDecompositionError::Disconnected => write!(f, "the tree is not connected"),
+                TreeDecompositionError::VertexNotCovered(v) => write!(f, "vertex {} is not contained in any bag", v),
+                TreeDecompositionError::EdgeNotCovered(u, v) => write!(f, "edge ({},{}) is not contained in any bag", u, v),
+                TreeDecompositionError::VertexNotConnected(v) => write!(f, "the bags containing vertex {} do not form a subtree", v),
+            }
+        }
+    }
+
+    impl std::error::Error for TreeDecompositionError {}
+
+    /// The shape of the join nodes which combine the children of a node with several children during the
+    /// conversion into a nice tree decomposition. Both shapes need one join node less than there are children,
+    /// but they differ in the depth of the nice tree decomposition and in the number of tables the dynamic
+    /// programs keep alive at once.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum JoinTree {
+        /// each join node combines the previous join node with the next child, i.e. a path of k-1 join nodes.
+        /// In the stingy ordering at most two tables of the children are stored at the same time.
+        #[default]
+        Caterpillar,
+        /// the children are combined pairwise level by level, i.e. the join nodes have depth ceil(log2(k)).
+        /// Several tables of the children are stored at the same time, but shorter paths suit parallel evaluation.
+        Balanced,
+    }
+
+    /// The options of the conversion of a tree decomposition into a nice tree decomposition.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct NiceOptions {
+        pub join_tree: JoinTree,
+    }
+
+    /// A tree decomposition consisting of a tree structure and a bag for each node.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct TreeDecomposition{
+        tree_structure : TreeStructure,
+        bags : HashMap<TreeNode, Bag>,
+        number_of_vertices : u32,
+    }
+
+    /// Implementation of methods for tree decompositions
+    impl TreeDecomposition{
+
+        /// A simple constructor for the TreeDecomposition, the decomposition is not validated.
+        pub fn new(tree_structure : TreeStructure, bags : HashMap<TreeNode, Bag>, number_of_vertices : u32) -> TreeDecomposition{
+            TreeDecomposition { tree_structure, bags, number_of_vertices }
+        }
+
+        /// Returns the bag of the given node p.
+        pub fn bag(&self, p : TreeNode) -> Option<&Bag>{ self.bags.get(&p) }
+
+        /// Returns the width of the tree decomposition, i.e. the maximal bag size minus one.
+        pub fn width(&self) -> u32 {
+            self.bags.values().map(|bag| bag.len() as u32).max().unwrap_or(0).saturating_sub(1)
+        }
+
+        /// Returns the number N of vertices of the decomposed graph, which are represented as 0,..,N-1.
+        pub fn vertex_count(&self) -> u32 { self.number_of_vertices }
+
+        /// An Interface function for the node_count() method of the private field tree_structure.
+        pub fn node_count(&self) -> TreeNode { self.tree_structure.node_count() }
+
+        /// An Interface function for the root() method of the private field tree_structure.
+        pub fn root(&self) -> TreeNode { self.tree_structure.root() }
+
+        /// An Interface function for the parent() method of the private field tree_structure.
+        pub fn parent(&self, p : TreeNode) -> Option<&TreeNode> { self.tree_structure.parent(p) }
+
+        /// An Interface function for the children() method of the private field tree_structure.
+        pub fn children(&self, p : TreeNode) -> Option<&Vec<TreeNode>> { self.tree_structure.children(p) }
+
+        /// Checks whether this is a tree decomposition of the given graph, i.e.
+        /// - every node has a bag and the tree is connected,
+        /// - every vertex and every edge (including loops) is contained in some bag,
+        /// - the nodes whose bags contain a vertex form a connected subtree.
+        pub fn validate(&self, graph : &Graph) -> Result<(), TreeDecompositionError>{
+            let nodes = 0..self.node_count();
+
+            if let Some(p) = nodes.clone().find(|p| !self.bags.contains_key(p)) {
+                return Err(TreeDecompositionError::MissingBag(p));
+            }
+
+            // since each node has at most one parent, the tree is connected iff there is exactly one root
+            if nodes.clone().filter(|&p| self.parent(p).is_none()).count() != 1 {
+                return Err(TreeDecompositionError::Disconnected);
+            }
+
+            let contains = |p : TreeNode, v : usize| self.bags.get(&p).unwrap().contains(&Vertex::new(v));
+
+            for v in 0..graph.node_count(){
+                // the subtree of v is connected iff exactly one of its nodes has a parent not containing v
+                let topmost_nodes = nodes.clone()
+                    .filter(|&p| contains(p, v) && !self.parent(p).is_some_and(|&parent| contains(parent, v)))
+                    .count();
+
+                match topmost_nodes {
+                    0 => return Err(TreeDecompositionError::VertexNotCovered(v)),
+                    1 => {}
+                    _ => return Err(TreeDecompositionError::VertexNotConnected(v)),
+                }
+            }
+
+            for u in 0..graph.node_count(){
+                for v in u..graph.node_count(){
+                    if graph.has_edge(graph.from_index(u), graph.from_index(v)) && !nodes.clone().any(|p| contains(p, u) && contains(p, v)) {
+                        return Err(TreeDecompositionError::EdgeNotCovered(u, v));
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Returns the tree decomposition rooted at the given node, i.e. all edges on the path from the
+        /// current root to the new root are reversed. The bags are not changed.
+        pub fn reroot(&self, new_root : TreeNode) -> TreeDecomposition{
+            assert!(new_root < self.node_count(), "Node {} does not exist!", new_root);
+
+            // the nodes on the path from the new root to the current root
+            let mut path = HashSet::from([new_root]);
+            let mut current = new_root;
+            while let Some(&parent) = self.parent(current) {
+                path.insert(parent);
+                current = parent;
+            }
+
+            let mut tree_structure = TreeStructure::new(self.node_count());
+            for q in 0..self.node_count(){
+                if let Some(&p) = self.parent(q) {
+                    if path.contains(&q) { tree_structure.add_child(q, p); } else { tree_structure.add_child(p, q); }
+                }
+            }
+
+            TreeDecomposition::new(tree_structure, self.bags.clone(), self.number_of_vertices)
+        }
+
+        /// Converts the tree decomposition into a nice tree decomposition of the same width. Each node is
+        /// connected to its parent by forgetting the vertices missing in the bag of the parent and then
+        /// introducing the new vertices, several children are combined by join nodes. Finally, all vertices
+        /// of the root are forgotten, such that the root of the nice tree decomposition has an empty bag.
+        /// Subtrees whose bags are all empty are dropped.
+        pub fn to_nice_tree_decomposition(&self) -> NiceTreeDecomposition{
+            self.to_nice_tree_decomposition_with(&NiceOptions::default())
+        }
+
+        /// Converts the tree decomposition into a nice tree decomposition like to_nice_tree_decomposition,
+        /// where the children of a node are combined by join nodes of the given shape.
+        pub fn to_nice_tree_decomposition_with(&self, options : &NiceOptions) -> NiceTreeDecomposition{
+            self.to_nice_tree_decomposition_with_root_bag(&Bag::new(), options)
+        }
+
+        /// Converts the tree decomposition into a nice tree decomposition whose root has exactly the given
+        /// vertices as bag. The vertices are added to every bag, hence the width grows by at most their number.
+        /// This is used to obtain the table of the root for each image of the given vertices.
+        pub fn to_nice_tree_decomposition_keeping(&self, vertices : &[Vertex]) -> NiceTreeDecomposition{
+            let root_bag : Bag = vertices.iter().copied().collect();
+            let bags = self.bags.iter()
+                .map(|(&p, bag)| (p, bag.union(&root_bag).copied().collect()))
+                .collect();
+
+            TreeDecomposition::new(self.tree_structure.clone(), bags, self.number_of_vertices)
+                .to_nice_tree_decomposition_with_root_bag(&root_bag, &NiceOptions::default())
+        }
+
+        /// Converts the tree decomposition into a nice tree decomposition as in to_nice_tree_decomposition,
+        /// where only the vertices of the root which are not contained in the given bag are forgotten.
+        fn to_nice_tree_decomposition_with_root_bag(&self, root_bag : &Bag, options : &NiceOptions) -> NiceTreeDecomposition{
+            let mut builder = NiceBuilder { nodes: vec![], edges: vec![], join_tree: options.join_tree };
+
+            let root = self.nice_subtree(&mut builder, self.root())
+                .expect("Cannot convert a tree decomposition whose bags are all empty!");
+            builder.bridge(root, &self.bags[&self.root()], root_bag);
+
+            let width = builder.nodes.iter().map(|node_data| node_data.bag().len()).max().unwrap() as u32 - 1;
+
+            let mut tree_structure = TreeStructure::new(builder.nodes.len() as TreeNode);
+            for &(p, q) in &builder.edges{
+                tree_structure.add_child(p, q);
+            }
+            let nodes_data : HashMap<TreeNode, NodeData> = builder.nodes.into_iter().enumerate()
+                .map(|(p, node_data)| (p as TreeNode, node_data))
+                .collect();
+
+            NiceTreeDecomposition::new(tree_structure, nodes_data, self.number_of_vertices, width)
+        }
+
+        /// Builds the nice tree decomposition of the subtree rooted at p and returns its root,
+        /// whose bag equals the bag of p. Returns None if all bags of the subtree are empty.
+        fn nice_subtree(&self, builder : &mut NiceBuilder, p : TreeNode) -> Option<TreeNode>{
+            let bag = &self.bags[&p];
+
+            let mut subtrees : Vec<TreeNode> = self.children(p).into_iter().flatten()
+                .filter_map(|&q| self.nice_subtree(builder, q).map(|subtree| builder.bridge(subtree, &self.bags[&q], bag)))
+                .collect();
+
+            if subtrees.is_empty() {
+                let mut vertices = sorted(bag);
+                if vertices.is_empty() { return None; }
+
+                // a leaf node followed by introduce nodes of the remaining vertices
+                let first = vertices.remove(0);
+                let leaf = builder.add_node(NodeType::Leaf, Bag::from([first]), &[]);
+                return Some(builder.bridge(leaf, &Bag::from([first]), bag));
+            }
+
+            match builder.join_tree {
+                JoinTree::Caterpillar => {
+                    let mut current = subtrees.remove(0);
+                    for subtree in subtrees{
+                        current = builder.add_node(NodeType::Join, bag.clone(), &[current, subtree]);
+                    }
+                    Some(current)
+                }
+                JoinTree::Balanced => {
+                    while subtrees.len() > 1 {
+                        subtrees = subtrees.chunks(2)
+                            .map(|pair| if pair.len() == 2 { builder.add_node(NodeType::Join, bag.clone(), pair) } else { pair[0] })
+                            .collect();
+                    }
+                    subtrees.pop()
+                }
+            }
+        }
+    }
+
+    impl From<&NiceTreeDecomposition> for TreeDecomposition {
+        /// Forgets the node types of the nice tree decomposition.
+        fn from(ntd : &NiceTreeDecomposition) -> TreeDecomposition {
+            let mut tree_structure = TreeStructure::new(ntd.node_count());
+            let mut bags = HashMap::new();
+
+            for p in 0..ntd.node_count(){
+                for &q in ntd.children(p).into_iter().flatten(){
+                    tree_structure.add_child(p, q);
+                }
+                bags.insert(p, ntd.bag(p).unwrap().clone());
+            }
+
+            TreeDecomposition::new(tree_structure, bags, ntd.vertex_count())
+        }
+    }
+
+    /// Returns the vertices of the bag in ascending order.
+    fn sorted(bag : &Bag) -> Vec<Vertex>{
+        let mut vertices : Vec<Vertex> = bag.iter().copied().collect();
+        vertices.sort();
+        vertices
+    }
+
+    /// The nodes and edges of a nice tree decomposition under construction.
+    struct NiceBuilder {
+        nodes: Vec<NodeData>,
+        edges: Vec<(TreeNode, TreeNode)>,
+        join_tree: JoinTree,
+    }
+
+    impl NiceBuilder {
+
+        /// Adds a node with the given children and returns it.
+        fn add_node(&mut self, node_type : NodeType, bag : Bag, children : &[TreeNode]) -> TreeNode{
+            let p = self.nodes.len() as TreeNode;
+            self.nodes.push(NodeData::new(node_type, bag));
+            for &q in children{
+                self.edges.push((p, q));
+            }
+            p
+        }
+
+        /// Adds forget nodes for the vertices of "from" missing in "to" and afterwards introduce nodes for the
+        /// vertices of "to" missing in "from" on top of the node q, whose bag is "from". Returns the topmost node.
+        fn bridge(&mut self, q : TreeNode, from : &Bag, to : &Bag) -> TreeNode{
+            let mut current = q;
+            let mut bag = from.clone();
 
+            for v in sorted(&from.difference(to).copied().collect()){
+                bag.remove(&v);
+                current = self.add_node(NodeType::Forget, bag.clone(), &[current]);
+            }
+            for v in sorted(&to.difference(from).copied().collect()){
+                bag.insert(v);
+                current = self.add_node(NodeType::Introduce, bag.clone(), &[current]);
+            }
+
+            current
+        }
+    }
 }
 
 /// A public module containing the nice tree decomposition structure and relating functions.
 pub mod nice_tree_decomposition{
     use std::collections::{HashMap, HashSet};
+    use std::fmt;
+    use crate::tree_decompositions::tree_decomposition::TreeDecomposition;
     use crate::tree_decompositions::tree_structure::{Vertex, TreeStructure, TreeNode};
 
     /// Bag-Type of Bags attached to each Node of the (nice) tree decomposition
     pub(crate) type Bag = HashSet<Vertex>;
 
+    /// A bag in bitmask representation, where bit i of the word i / 64 stands for vertex i. Membership tests,
+    /// differences and ordered iteration work without hashing or sorting. Trailing empty words are never stored,
+    /// such that equal bags have equal representations, and bags of vertices smaller than 64 consist of one word.
+    #[derive(PartialEq, Eq, Debug, Clone, Default, Hash)]
+    pub struct BitBag(Vec<u64>);
+
+    /// Implementation of the set operations on bitmask bags
+    impl BitBag {
+
+        /// Creates an empty bag.
+        pub fn new() -> BitBag { BitBag(Vec::new()) }
+
+        /// Converts the given bag into its bitmask representation.
+        pub fn from_bag(bag : &Bag) -> BitBag{
+            let mut bit_bag = BitBag::new();
+            for &v in bag { bit_bag.insert(v); }
+            bit_bag
+        }
+
+        /// Returns the underlying words of the bitmask, starting with the vertices 0 to 63.
+        pub fn words(&self) -> &[u64] { &self.0 }
+
+        /// Inserts the vertex v into the bag.
+        pub fn insert(&mut self, v : Vertex){
+            let word = v.index() / 64;
+            if word >= self.0.len() { self.0.resize(word + 1, 0); }
+            self.0[word] |= 1 << (v.index() % 64);
+        }
+
+        /// Checks whether the vertex v is contained in the bag.
+        pub fn contains(&self, v : Vertex) -> bool {
+            self.0.get(v.index() / 64).is_some_and(|word| word & (1 << (v.index() % 64)) != 0)
+        }
+
+        /// Returns the number of vertices contained in the bag.
+        pub fn len(&self) -> usize { self.0.iter().map(|word| word.count_ones() as usize).sum() }
+
+        /// Checks whether the bag is empty.
+        pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+        /// Returns the bag of all vertices contained in self but not in other.
+        pub fn difference(&self, other : &BitBag) -> BitBag {
+            let words = self.0.iter().enumerate().map(|(i, word)| word & !other.0.get(i).copied().unwrap_or(0)).collect();
+            BitBag::trimmed(words)
+        }
+
+        /// Returns the bag of all vertices contained in both bags.
+        pub fn intersection(&self, other : &BitBag) -> BitBag {
+            BitBag::trimmed(self.0.iter().zip(&other.0).map(|(word, other_word)| word & other_word).collect())
+        }
+
+        /// Returns the position of the vertex v in the sorted bag, i.e. the number of smaller
+        /// vertices in the bag. This equals the significance of v in the integer representation of
+        /// mappings. If v is not contained in the bag, None is returned.
+        pub fn significance(&self, v : Vertex) -> Option<usize>{
+            if self.contains(v) { Some(self.rank(v)) } else { None }
+        }
+
+        /// Returns the number of vertices of the bag which are smaller than v, whether v is contained or not.
+        /// For a vertex which is not contained this is its significance after inserting it.
+        pub fn rank(&self, v : Vertex) -> usize{
+            let word = v.index() / 64;
+            let lower : usize = self.0.iter().take(word).map(|word| word.count_ones() as usize).sum();
+            let smaller = (1u64 << (v.index() % 64)) - 1;
+            lower + self.0.get(word).map_or(0, |word| (word & smaller).count_ones() as usize)
+        }
+
+        /// Returns an iterator over the vertices of the bag in ascending order.
+        pub fn iter(&self) -> BitBagIter<'_> { BitBagIter { words: &self.0, offset: 0, current: 0 } }
+
+        /// Removes the trailing empty words.
+        fn trimmed(mut words : Vec<u64>) -> BitBag {
+            while words.last() == Some(&0) { words.pop(); }
+            BitBag(words)
+        }
+    }
+
+    /// An iterator over the vertices of a bitmask bag in ascending order.
+    pub struct BitBagIter<'a> {
+        /// the words which have not been loaded yet
+        words: &'a [u64],
+        /// the smallest vertex of the next word, i.e. 64 more than the smallest vertex of the current word
+        offset: usize,
+        /// the remaining vertices of the current word
+        current: u64,
+    }
+
+    impl Iterator for BitBagIter<'_> {
+        type Item = Vertex;
+
+        fn next(&mut self) -> Option<Vertex>{
+            while self.current == 0 {
+                let (&word, rest) = self.words.split_first()?;
+                self.current = word;
+                self.words = rest;
+                self.offset += 64;
+            }
+            let v = self.offset - 64 + self.current.trailing_zeros() as usize;
+            // removes the lowest set bit
+            self.current &= self.current - 1;
+            Some(Vertex::new(v))
+        }
+    }
+
     /// An enum containing types of Nodes in a nice tree decomposition
     #[derive(PartialEq, Eq, Debug, Clone)]
     pub enum NodeType {
@@ -127,6 +559,7 @@ pub mod nice_tree_decomposition{
     pub struct NodeData {
         node_type: NodeType,
         bag: Bag,
+        bit_bag: BitBag,
     }
 
     /// Implementation of methods needed for accessing and creating data of NodeData
@@ -134,7 +567,8 @@ pub mod nice_tree_decomposition{
 
         /// A simple constructor for creating a new NodeData struct.
         pub fn new(node_type: NodeType, bag: Bag) -> NodeData {
-            NodeData { node_type, bag }
+            let bit_bag = BitBag::from_bag(&bag);
+            NodeData { node_type, bag, bit_bag }
         }
 
         /// Returns a reference to the node type of this node.
@@ -143,6 +577,18 @@ pub mod nice_tree_decomposition{
         /// Returns a reference to the bag of this node.
         pub fn bag(&self) -> &Bag { &self.bag }
 
+        /// Returns a reference to the bag of this node in bitmask representation.
+        pub fn bit_bag(&self) -> &BitBag { &self.bit_bag }
+
+    }
+
+    /// Returns a nice tree decomposition of the same graph rooted at the given node. The edges on the path
+    /// from the old root to the new root are reversed, such that introduce and forget nodes along this path
+    /// swap their types, and nodes above the new root forget the remaining vertices. Since the node types
+    /// change, the result is rebuilt via its tree decomposition, hence the nodes are renumbered and the
+    /// stingy ordering is recomputed. A suitable root can reduce the peak memory of the dynamic programs.
+    pub fn reroot(ntd : &NiceTreeDecomposition, new_root : TreeNode) -> NiceTreeDecomposition{
+        TreeDecomposition::from(ntd).reroot(new_root).to_nice_tree_decomposition()
     }
 
     /// A structure organizing all data need for a nice tree decomposition. Containing the following
@@ -154,6 +600,8 @@ pub mod nice_tree_decomposition{
         nodes_data: HashMap<TreeNode, NodeData>,
         stingy_ordering: Vec<TreeNode>,
         unique_vertices: HashMap<TreeNode, Vertex>,
+        possible_edges: HashMap<TreeNode, Vec<(usize, usize)>>,
+        possible_edge_masks: HashMap<TreeNode, u64>, // empty if there are more than 64 possible edges
         number_of_vertices: u32, // Number of all vertices contained in bags
         width : u32 // This follows the definition of tree width: max_bag_size - 1
     }
@@ -166,12 +614,16 @@ pub mod nice_tree_decomposition{
             // Computes stingy ordering of Nice Tree Decomposition in advance
             let stingy_ordering = NiceTreeDecomposition::compute_stingy_ordering(&tree_structure, &nodes_data);
             let unique_vertices = NiceTreeDecomposition::compute_unique_vertices(&tree_structure, &nodes_data, &stingy_ordering);
+            let possible_edges = NiceTreeDecomposition::compute_possible_edges(&tree_structure, &nodes_data, &stingy_ordering, &unique_vertices);
+            let possible_edge_masks = NiceTreeDecomposition::compute_possible_edge_masks(&possible_edges, tree_structure.root());
 
             NiceTreeDecomposition{
                 tree_structure,
                 nodes_data,
                 stingy_ordering,
                 unique_vertices,
+                possible_edges,
+                possible_edge_masks,
                 number_of_vertices,
                 width}
         }
@@ -183,6 +635,11 @@ pub mod nice_tree_decomposition{
             if let Some(node_data) = self.nodes_data.get(&p){ Some( node_data.bag() ) } else { None }
         }
 
+        /// Returns the bag of the given node p in bitmask representation.
+        pub fn bit_bag(&self, p : TreeNode) -> Option<&BitBag>{
+            if let Some(node_data) = self.nodes_data.get(&p){ Some( node_data.bit_bag() ) } else { None }
+        }
+
         /// Returns the node type of the given node p.
         pub fn node_type(&self, p : TreeNode) -> Option<&NodeType>{
             if let Some(node_data) = self.nodes_data.get(&p) {Some( node_data.node_type() ) } else { None }
@@ -240,6 +697,12 @@ pub mod nice_tree_decomposition{
         /// Note that the vertices will represented as 0,.., N-1 (0 inclusive)
         pub fn vertex_count(&self) -> u32{ self.number_of_vertices }
 
+        /// Returns true if the nice tree decomposition has no join nodes, i.e. its tree is a path from its only
+        /// leaf to the root and it is a nice path decomposition.
+        pub fn is_path_decomposition(&self) -> bool {
+            (0..self.node_count()).all(|p| self.node_type(p) != Some(&NodeType::Join))
+        }
+
         /// This private function computes the Hashmap of  unique vertices by following the stingy ordering and compute this entry for each
         /// Introduce, Forget and Leaf nodes. Join nodes do not have unique vertices.
         /// - The unique vertex of a Leaf node is its only contained vertex.
@@ -258,7 +721,7 @@ pub mod nice_tree_decomposition{
                 match node_data.node_type()
                 {
                     NodeType::Leaf => {
-                        let v = *node_data.bag().iter().next().unwrap();
+                        let v = node_data.bit_bag().iter().next().unwrap();
                         unique_vertices.insert(p,v);
                     }
                     NodeType::Introduce => {
@@ -266,12 +729,11 @@ pub mod nice_tree_decomposition{
                         let q = tree_structure.children(p).unwrap().iter().next().unwrap();
 
                         // get bags of both nodes p and q.
-                        let bag_p = node_data.bag();
-                        let bag_q = nodes_data.get(q).unwrap().bag();
+                        let bag_p = node_data.bit_bag();
+                        let bag_q = nodes_data.get(q).unwrap().bit_bag();
 
                         // get the difference of both bags
-                        let difference: HashSet<&Vertex> = bag_p.difference(bag_q).collect();
-                        let v = **difference.iter().next().unwrap();
+                        let v = bag_p.difference(bag_q).iter().next().unwrap();
 
                         unique_vertices.insert(p,v);
 
@@ -281,12 +743,11 @@ pub mod nice_tree_decomposition{
                         let q = tree_structure.children(p).unwrap().iter().next().unwrap();
 
                         // get bags of both nodes p and q.
-                        let bag_p = node_data.bag();
-                        let bag_q = nodes_data.get(q).unwrap().bag();
+                        let bag_p = node_data.bit_bag();
+                        let bag_q = nodes_data.get(q).unwrap().bit_bag();
 
                         // get the difference of both bags
-                        let difference: HashSet<&Vertex> = bag_q.difference(bag_p).collect();
-                        let v = **difference.iter().next().unwrap();
+                        let v = bag_q.difference(bag_p).iter().next().unwrap();
 
                         unique_vertices.insert(p,v);
                     }
@@ -305,6 +766,103 @@ pub mod nice_tree_decomposition{
             self.unique_vertices.get(&p)
         }
 
+        // ## possible edge functions
+
+        /// Returns the possible edges of node p, i.e. all edges that could occur in the subtree rooted at p.
+        /// These are computed once when the nice tree decomposition is constructed.
+        pub fn possible_edges(&self, p : TreeNode) -> Option<&Vec<(usize, usize)>>{ self.possible_edges.get(&p) }
+
+        /// Returns the possible edges of the root, which are all edges a graph in $H_\tau$ can contain.
+        /// Their order defines the integer representation of edge sets.
+        pub fn all_possible_edges(&self) -> &Vec<(usize, usize)>{ self.possible_edges.get(&self.root()).unwrap() }
+
+        /// Returns the possible edges of node p as bitmask, where the i-th bit stands for the i-th edge of
+        /// all_possible_edges, i.e. the integer representation of the edge sets of the modified dynamic program.
+        /// The masks are computed once when the nice tree decomposition is constructed, such that the possible edges
+        /// of a join node are the union and the new edges of an introduce node the difference of masks.
+        /// Returns None if there are more than 64 possible edges.
+        pub fn possible_edge_mask(&self, p : TreeNode) -> Option<u64>{ self.possible_edge_masks.get(&p).copied() }
+
+        /// This private function computes the bitmasks of the possible edges of all nodes with respect to the
+        /// order of the possible edges of the root. No masks are computed for more than 64 possible edges.
+        fn compute_possible_edge_masks(possible_edges : &HashMap<TreeNode, Vec<(usize, usize)>>, root : TreeNode) -> HashMap<TreeNode, u64>{
+            let all_possible_edges = match possible_edges.get(&root) {
+                Some(edges) if edges.len() <= 64 => edges,
+                _ => return HashMap::new(),
+            };
+
+            // maps both directions of an edge onto its index
+            let index : HashMap<(usize, usize), usize> = all_possible_edges.iter().enumerate()
+                .flat_map(|(i, &(u, v))| [((u, v), i), ((v, u), i)])
+                .collect();
+
+            possible_edges.iter()
+                .map(|(&p, edges)| (p, edges.iter().fold(0, |mask, edge| mask | 1 << index[edge])))
+                .collect()
+        }
+
+        /// This private function computes the possible edges of each node by following the stingy ordering.
+        /// - A Leaf node allows the loop of its unique vertex.
+        /// - A Introduce node adds the edges between the introduced vertex and its bag.
+        /// - A Forget node keeps the possible edges of its child.
+        /// - A Join node merges the possible edges of both children.
+        fn compute_possible_edges(tree_structure : &TreeStructure, nodes_data : &HashMap<TreeNode, NodeData>, stingy_ordering : &Vec<TreeNode>, unique_vertices : &HashMap<TreeNode, Vertex>) -> HashMap<TreeNode, Vec<(usize, usize)>>{
+            let mut possible_edges: HashMap<TreeNode, Vec<(usize, usize)>> = HashMap::new();
+
+            // Returns true if the undirected edge is contained in the list.
+            let edge_in_list = |(u,v) : (usize, usize), list : &Vec<(usize, usize)>| list.iter().any(|&i| i == (u , v) || i == (v , u));
+
+            for &p in stingy_ordering{
+                let node_data = nodes_data.get(&p).unwrap();
+
+                match node_data.node_type() {
+                    NodeType::Leaf => {
+                        let vertex = unique_vertices.get(&p).unwrap();
+                        possible_edges.insert(p, vec![(vertex.index(), vertex.index())]);
+                    }
+                    NodeType::Introduce => {
+                        let q = tree_structure.children(p).unwrap()[0];
+                        let v = unique_vertices.get(&p).unwrap();
+                        let mut edges = possible_edges.get(&q).unwrap().clone();
+
+                        // the bag is iterated in ascending order, such that the order of the edges is deterministic
+                        for u in node_data.bit_bag().iter(){
+                            // checks if edge has already been added
+                            if !edge_in_list((u.index(), v.index()), &edges){
+                                edges.push((u.index(), v.index()));
+                            }
+                        }
+                        possible_edges.insert(p, edges);
+                    }
+                    NodeType::Forget => {
+                        let q = tree_structure.children(p).unwrap()[0];
+                        // just clone the set of possible edges
+                        possible_edges.insert(p, possible_edges.get(&q).unwrap().clone());
+                    }
+                    NodeType::Join => {
+                        let children = tree_structure.children(p).unwrap();
+
+                        // the edges of the smaller set are merged into the larger one
+                        let (first, second) = if possible_edges.get(&children[0]).unwrap().len() >= possible_edges.get(&children[1]).unwrap().len() {
+                            (children[0], children[1])
+                        } else {
+                            (children[1], children[0])
+                        };
+
+                        let mut edges = possible_edges.get(&first).unwrap().clone();
+                        for (u,v) in possible_edges.get(&second).unwrap(){
+                            if !edge_in_list((*u, *v), &edges){
+                                edges.push((*u , *v));
+                            }
+                        }
+                        possible_edges.insert(p, edges);
+                    }
+                }
+            }
+
+            possible_edges
+        }
+
         /// ## stingy ordering functions
 
         /// Returns a copy of the stingy ordering. This should have no big overhead since
@@ -377,4 +935,53 @@ pub mod nice_tree_decomposition{
 
     }
 
+    impl fmt::Display for NodeType {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            let name = match self {
+                NodeType::Leaf => "leaf",
+                NodeType::Introduce => "introduce",
+                NodeType::Forget => "forget",
+                NodeType::Join => "join",
+            };
+            write!(f, "{}", name)
+        }
+    }
+
+    impl fmt::Display for BitBag {
+        /// Writes the vertices in ascending order, e.g. "{0, 2}".
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            let vertices : Vec<String> = self.iter().map(|v| v.index().to_string()).collect();
+            write!(f, "{{{}}}", vertices.join(", "))
+        }
+    }
+
+    impl fmt::Display for NiceTreeDecomposition {
+        /// Writes the tree as an indented ASCII tree starting at the root, one node per line with its
+        /// number, its type and its bag, e.g.
+        /// ```text
+        /// 2 forget {}
+        /// └── 1 introduce {0}
+        ///     └── 0 leaf {0}
+        /// ```
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            // nodes to write together with the prefix of their line and of the lines of their children,
+            // an explicit stack avoids deep recursions on long paths
+            let mut stack = vec![(self.root(), String::new(), String::new())];
+
+            while let Some((p, prefix, child_prefix)) = stack.pop() {
+                match (self.node_type(p), self.bit_bag(p)) {
+                    (Some(node_type), Some(bag)) => writeln!(f, "{}{} {} {}", prefix, p, node_type, bag)?,
+                    _ => writeln!(f, "{}{}", prefix, p)?,
+                }
+
+                let children = self.children(p).map_or(&[][..], |children| children.as_slice());
+                for (i, &q) in children.iter().enumerate().rev(){
+                    let (connector, continuation) = if i + 1 == children.len() { ("└── ", "    ") } else { ("├── ", "│   ") };
+                    stack.push((q, format!("{}{}", child_prefix, connector), format!("{}{}", child_prefix, continuation)));
+                }
+            }
+            Ok(())
+        }
+    }
+
 }
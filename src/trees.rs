@@ -0,0 +1,96 @@
+/// A module containing the number of homomorphisms from forests without any decomposition. A forest is rooted
+/// in each of its trees, and in a first phase its vertices are ordered such that every vertex comes after its
+/// parent. In a second phase the vertices are processed in the reverse order, where
+/// $c_v(a) = \prod_{u \text{ child of } v} \sum_{b \in N(a)} c_u(b)$ is the number of homomorphisms from the subtree
+/// of v mapping v onto a, and $hom(T, G) = \sum_a c_r(a)$ for a tree T with root r. Each vertex needs
+/// O(|V(G)| + |E(G)|) operations, e.g. linear time in |V(G)| for targets which are forests themselves, as in
+/// XML or taxonomy matching, instead of building and traversing a nice tree decomposition.
+///
+/// The labeled variant counts the homomorphisms which map every vertex onto a vertex with the same label.
+pub mod tree_counting {
+    use num_bigint::BigUint;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// Returns true if the graph has no cycle, i.e. no loop and no cycle of length at least 3.
+    pub fn is_forest(graph : &Graph) -> bool {
+        let n = graph.node_count();
+        if (0..n).any(|a| graph.has_edge(Vertex::new(a), Vertex::new(a))) { return false; }
+
+        // a graph without loops is a forest iff it has |V| - c edges for c components
+        let (_, roots) = root_forest(graph);
+        graph.edge_count() + roots == n
+    }
+
+    /// Returns hom(from_graph, to_graph) if "from_graph" is a forest, None otherwise.
+    pub fn tree_hom_count(from_graph : &Graph, to_graph : &Graph) -> Option<BigUint> {
+        labeled_tree_hom_count(from_graph, &vec![0; from_graph.node_count()], to_graph, &vec![0; to_graph.node_count()])
+    }
+
+    /// Returns the number of homomorphisms from "from_graph" to "to_graph" which map every vertex v onto a vertex
+    /// a with from_labels[v] == to_labels[a] if "from_graph" is a forest, None otherwise.
+    /// Panics if the labels do not contain a label for each vertex.
+    pub fn labeled_tree_hom_count(from_graph : &Graph, from_labels : &[usize], to_graph : &Graph, to_labels : &[usize]) -> Option<BigUint> {
+        assert_eq!(from_labels.len(), from_graph.node_count(), "The labels have to contain a label for each vertex of the pattern!");
+        assert_eq!(to_labels.len(), to_graph.node_count(), "The labels have to contain a label for each vertex of the target!");
+        if !is_forest(from_graph) { return None; }
+
+        let n = to_graph.node_count();
+        let neighbours : Vec<Vec<usize>> = (0..n).map(|a| to_graph.neighbors(Vertex::new(a)).map(|b| b.index()).collect()).collect();
+
+        // the first phase orders the vertices such that every parent precedes its children
+        let (order, _) = root_forest(from_graph);
+        let mut parent = vec![None; from_graph.node_count()];
+        for &v in &order{
+            for u in from_graph.neighbors(Vertex::new(v)){
+                if parent[v] != Some(u.index()) { parent[u.index()] = Some(v); }
+            }
+        }
+
+        // the second phase multiplies the counts of the children into the counts of their parents
+        let mut counts : Vec<Vec<BigUint>> = (0..from_graph.node_count())
+            .map(|v| (0..n).map(|a| BigUint::from((from_labels[v] == to_labels[a]) as u8)).collect())
+            .collect();
+        let mut hom_number = BigUint::from(1u8);
+        for &u in order.iter().rev(){
+            let counts_u = std::mem::take(&mut counts[u]);
+            match parent[u] {
+                Some(v) => for (a, count) in counts[v].iter_mut().enumerate(){
+                    if *count == BigUint::ZERO { continue; }
+                    *count *= neighbours[a].iter().map(|&b| &counts_u[b]).sum::<BigUint>();
+                },
+                None => hom_number *= counts_u.into_iter().sum::<BigUint>(),
+            }
+        }
+        Some(hom_number)
+    }
+
+    /// Returns the vertices of the graph in breadth first order from the smallest vertex of each component,
+    /// such that every vertex is reached from an earlier neighbour, together with the number of components.
+    fn root_forest(graph : &Graph) -> (Vec<usize>, usize) {
+        let n = graph.node_count();
+        let mut visited = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut roots = 0;
+
+        for root in 0..n{
+            if visited[root] { continue; }
+            visited[root] = true;
+            roots += 1;
+            let start = order.len();
+            order.push(root);
+
+            let mut i = start;
+            while i < order.len() {
+                let v = order[i];
+                for u in graph.neighbors(Vertex::new(v)){
+                    if !visited[u.index()] {
+                        visited[u.index()] = true;
+                        order.push(u.index());
+                    }
+                }
+                i += 1;
+            }
+        }
+        (order, roots)
+    }
+}
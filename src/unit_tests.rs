@@ -31,6 +31,15 @@ fn ntd_test_example() -> NiceTreeDecomposition{
     NiceTreeDecomposition::new(tree_structure, nodes_data, 4, 1)
 }
 
+/// Constructs a graph with n vertices and the given edges.
+#[cfg(test)]
+fn graph_from_edges(n : usize, edges : &[(usize, usize)]) -> crate::tree_decompositions::tree_structure::Graph{
+    let mut graph = crate::tree_decompositions::tree_structure::Graph::default();
+    for _ in 0..n { graph.add_node(()); }
+    for &(u,v) in edges { graph.add_edge(Vertex::new(u), Vertex::new(v), ()); }
+    graph
+}
+
 // naive comparison of two edge lists.
 // O(len(list1) * len(list2))
 fn compare_edge_lists(list1 : &Vec<(usize, usize)>, list2 : &Vec<(usize, usize)>) -> bool
@@ -77,17 +86,50 @@ pub mod tree_structure_tests{
         assert_eq!(tree_structure.root(), 4);
         assert_eq!(tree_structure.children_count(0), 2);
     }
+
+    #[test]
+    pub fn test_vertex_index(){
+        // vertices are stored with the selected index type
+        assert_eq!(std::mem::size_of::<tree_structure::Vertex>(), std::mem::size_of::<tree_structure::VertexIndex>());
+
+        let mut graph = tree_structure::Graph::default();
+        for _ in 0..300 { graph.add_node(()); }
+        graph.add_edge(tree_structure::Vertex::new(299), tree_structure::Vertex::new(3), ());
+        assert!(graph.has_edge(tree_structure::Vertex::new(3), tree_structure::Vertex::new(299)));
+    }
 }
 
 #[cfg(test)]
 pub mod nice_tree_decomposition_tests{
     use std::collections::{HashMap, HashSet};
     use crate::file_handler::tree_decomposition_handler::import_ntd;
-    use crate::tree_decompositions::nice_tree_decomposition::{Bag, NiceTreeDecomposition, NodeData, NodeType};
+    use crate::integer_functions::integer_functions_methods::{apply, BagIndexer, MappingSpace};
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::ntd_generation::ntd_generators::{complete_ntd, window_ntd};
+    use crate::tree_decompositions::nice_tree_decomposition::{Bag, BitBag, NodeType};
     use crate::tree_decompositions::tree_structure::{TreeStructure, Vertex};
-    use crate::unit_tests::ntd_test_example;
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
 
 
+    #[test]
+    fn test_display(){
+        let expected = "\
+9 forget {}
+└── 8 forget {3}
+    └── 7 introduce {1, 3}
+        └── 6 join {1}
+            ├── 2 forget {1}
+            │   └── 1 introduce {0, 1}
+            │       └── 0 leaf {0}
+            └── 5 forget {1}
+                └── 4 introduce {1, 2}
+                    └── 3 leaf {1}
+";
+        assert_eq!(ntd_test_example().to_string(), expected);
+        assert_eq!(NodeType::Introduce.to_string(), "introduce");
+        assert_eq!(BitBag::new().to_string(), "{}");
+    }
+
     #[test]
     fn test_stingy_ordering(){
 
@@ -98,6 +140,154 @@ pub mod nice_tree_decomposition_tests{
         assert_eq!(ntd.stingy_ordering(),vec![0,1,2,3,4,5,6,7,8,9,10,11,12,13]);
     }
 
+    #[test]
+    fn test_possible_edge_masks(){
+        let ntd = ntd_test_example();
+        let all_possible_edges = ntd.all_possible_edges();
+        for p in ntd.stingy_ordering(){
+            let expected = ntd.possible_edges(p).unwrap().iter()
+                .map(|edge| all_possible_edges.iter().position(|e| e == edge).unwrap())
+                .fold(0, |mask, i| mask | 1 << i);
+            assert_eq!(ntd.possible_edge_mask(p), Some(expected));
+        }
+        assert_eq!(ntd.possible_edge_mask(6), Some(ntd.possible_edge_mask(2).unwrap() | ntd.possible_edge_mask(5).unwrap()));
+        assert_eq!(ntd.possible_edge_mask(9), Some((1 << all_possible_edges.len()) - 1));
+        assert_eq!(ntd.possible_edge_mask(10), None);
+
+        // 66 possible edges do not fit into a mask
+        let ntd = complete_ntd(11);
+        assert_eq!(ntd.possible_edge_mask(ntd.root()), None);
+        assert!(complete_ntd(10).possible_edge_mask(0).is_some());
+    }
+
+    #[test]
+    fn test_bit_bag(){
+        let bag = BitBag::from_bag(&Bag::from([Vertex::new(5), Vertex::new(0), Vertex::new(3)]));
+
+        assert_eq!(bag.words(), &[0b101001]);
+        assert_eq!(bag.len(), 3);
+        assert!(bag.contains(Vertex::new(3)));
+        assert!(!bag.contains(Vertex::new(4)));
+        assert!(!bag.contains(Vertex::new(100)));
+
+        // ordered iteration and significance
+        assert_eq!(bag.iter().collect::<Vec<Vertex>>(), vec![Vertex::new(0), Vertex::new(3), Vertex::new(5)]);
+        assert_eq!(bag.significance(Vertex::new(0)), Some(0));
+        assert_eq!(bag.significance(Vertex::new(5)), Some(2));
+        assert_eq!(bag.significance(Vertex::new(4)), None);
+        assert_eq!(bag.rank(Vertex::new(4)), 2);
+        assert_eq!(bag.rank(Vertex::new(100)), 3);
+
+        // set operations
+        let other = BitBag::from_bag(&Bag::from([Vertex::new(3)]));
+        assert_eq!(bag.difference(&other).iter().collect::<Vec<Vertex>>(), vec![Vertex::new(0), Vertex::new(5)]);
+        assert_eq!(bag.intersection(&other), other);
+        assert!(other.difference(&bag).is_empty());
+
+        // bitmask bags of a nice tree decomposition
+        let ntd = ntd_test_example();
+        assert_eq!(ntd.bit_bag(7).unwrap().words(), &[0b1010]);
+        assert!(ntd.bit_bag(9).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bit_bag_beyond_one_word(){
+        let bag = BitBag::from_bag(&Bag::from([Vertex::new(130), Vertex::new(3), Vertex::new(64), Vertex::new(63)]));
+
+        assert_eq!(bag.words(), &[1 << 63 | 1 << 3, 1, 1 << 2]);
+        assert_eq!(bag.len(), 4);
+        assert!(bag.contains(Vertex::new(64)));
+        assert!(!bag.contains(Vertex::new(65)));
+        assert!(!bag.contains(Vertex::new(500)));
+        assert_eq!(bag.iter().map(|v| v.index()).collect::<Vec<usize>>(), vec![3, 63, 64, 130]);
+        assert_eq!(bag.significance(Vertex::new(130)), Some(3));
+        assert_eq!(bag.rank(Vertex::new(100)), 3);
+        assert_eq!(bag.rank(Vertex::new(500)), 4);
+        assert_eq!(bag.to_string(), "{3, 63, 64, 130}");
+
+        // empty trailing words are removed, such that equal bags are equal
+        let low = BitBag::from_bag(&Bag::from([Vertex::new(3), Vertex::new(63)]));
+        assert_eq!(bag.intersection(&low), low);
+        assert_eq!(low.difference(&bag), BitBag::new());
+        assert_eq!(bag.difference(&low).iter().map(|v| v.index()).collect::<Vec<usize>>(), vec![64, 130]);
+
+        // the path on 70 vertices into the path on 3 vertices, i.e. 2 * 2^34 walks from the middle vertex
+        // and 2^34 walks from each end
+        let ntd = window_ntd(70, 1);
+        assert_eq!(ntd.bit_bag(ntd.stingy_ordering()[ntd.stingy_ordering().len() - 2]).unwrap().words(), &[0, 1 << 5]);
+        let path_edges : Vec<(usize, usize)> = (1..70).map(|v| (v - 1, v)).collect();
+        let path = graph_from_edges(70, &path_edges);
+        let target = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        assert_eq!(diaz_serna_thilikos_algorithm(&path, &ntd, &target), 4 << 34);
+    }
+
+    #[test]
+    fn test_bag_indexer(){
+        // the bag {0, 3, 5} in base 4, the mapping 0 -> 1, 3 -> 2, 5 -> 3 is 1 + 2 * 4 + 3 * 16
+        let bag = BitBag::from_bag(&Bag::from([Vertex::new(5), Vertex::new(0), Vertex::new(3)]));
+        let indexer = BagIndexer::new(bag, 4);
+        let f = 1 + 2 * 4 + 3 * 16;
+
+        assert_eq!(indexer.position(Vertex::new(3)), Some(1));
+        assert_eq!(indexer.position(Vertex::new(4)), None);
+        assert_eq!(indexer.insert_position(Vertex::new(4)), 2);
+        assert_eq!(indexer.image(f, Vertex::new(5)), 3);
+        assert_eq!(indexer.image_at(f, 0), 1);
+
+        // extending by 4 -> 0 shifts the image of 5 and reducing by 3 removes its image
+        assert_eq!(indexer.extend(f, Vertex::new(4), 0), 1 + 2 * 4 + 3 * 64);
+        assert_eq!(indexer.reduce(f, Vertex::new(3)), 1 + 3 * 4);
+
+        // the introduce node 7 adds 3 to the bag {1} of node 6, the forget node 8 removes 1 again
+        let ntd = ntd_test_example();
+        let indexer_6 = BagIndexer::of_node(&ntd, 6, 4);
+        let indexer_7 = BagIndexer::of_node(&ntd, 7, 4);
+        assert_eq!(indexer_6.insert_position(Vertex::new(3)), indexer_7.position(Vertex::new(3)).unwrap());
+        for f in 0..4{
+            for a in 0..4{
+                let f_prime = indexer_6.extend(f, Vertex::new(3), a);
+                assert_eq!(indexer_7.image(f_prime, Vertex::new(1)), f);
+                assert_eq!(indexer_7.image(f_prime, Vertex::new(3)), a);
+                assert_eq!(indexer_7.reduce(f_prime, Vertex::new(3)), f);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mapping_chunks(){
+        // mappings of 3 vertices into 4 vertices in chunks of 16 mappings, where the image of significance 2 is fixed
+        let space = MappingSpace::new(3, 4, 20);
+        assert_eq!(space.len(), 64);
+        assert_eq!(space.chunk_size(), 16);
+        assert_eq!(space.chunk_count(), 4);
+
+        let chunk = space.chunk(2);
+        assert_eq!((chunk.start(), chunk.end()), (32, 48));
+        assert_eq!(chunk.prefix(), &[2]);
+
+        // the chunks cover all mappings in order and the images are those of apply
+        let mut next = 0;
+        for chunk in space.chunks(){
+            chunk.for_each(|f, images| {
+                assert_eq!(f, next);
+                assert_eq!(images.to_vec(), (0..3).map(|s| apply(4, f, s)).collect::<Vec<_>>());
+                next += 1;
+            });
+        }
+        assert_eq!(next, 64);
+        assert_eq!(space.chunks().map(|chunk| chunk.count(|images| images[0] == images[1])).sum::<u64>(), 16);
+
+        // the indexer of node 7 with bag {1, 3} in chunks of single mappings
+        let ntd = ntd_test_example();
+        let chunks = BagIndexer::of_node(&ntd, 7, 4).chunks(0);
+        assert_eq!((chunks.chunk_count(), chunks.chunk_size()), (16, 1));
+
+        // no mappings into the empty graph except for the empty mapping
+        assert_eq!(MappingSpace::new(2, 0, 8).chunk_count(), 0);
+        assert_eq!(MappingSpace::new(0, 0, 8).chunk(0).len(), 1);
+        assert_eq!(MappingSpace::new(5, 1, 8).chunk_count(), 1);
+    }
+
     #[test]
     fn test_nice_tree_decomposition_basic(){
         let ntd = ntd_test_example();
@@ -173,8 +363,127 @@ pub mod nice_tree_decomposition_tests{
 }
 
 #[cfg(test)]
-pub mod tree_decomposition_handler_tests{
+pub mod tree_decomposition_tests{
+    use std::collections::HashMap;
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
     use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::inspect::instance_statistics::NtdStats;
+    use crate::tree_decompositions::nice_tree_decomposition::{Bag, reroot};
+    use crate::tree_decompositions::tree_decomposition::{JoinTree, NiceOptions, TreeDecomposition, TreeDecompositionError};
+    use crate::tree_decompositions::tree_structure::{TreeStructure, Vertex};
+    use crate::unit_tests::graph_from_edges;
+
+    /// Creates a tree decomposition from a list of bags and a list of (parent, child) pairs.
+    fn tree_decomposition(bags : &[&[usize]], edges : &[(u64, u64)], number_of_vertices : u32) -> TreeDecomposition{
+        let mut tree_structure = TreeStructure::new(bags.len() as u64);
+        for &(p, q) in edges{
+            tree_structure.add_child(p, q);
+        }
+        let bags : HashMap<u64, Bag> = bags.iter().enumerate()
+            .map(|(p, bag)| (p as u64, bag.iter().map(|&v| Vertex::new(v)).collect()))
+            .collect();
+        TreeDecomposition::new(tree_structure, bags, number_of_vertices)
+    }
+
+    #[test]
+    fn test_nice_tree_decomposition_round_trip(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        let td = TreeDecomposition::from(&ntd);
+        assert_eq!(td.width(), ntd.width());
+        assert_eq!(td.validate(&from_graph), Ok(()));
+
+        let converted = td.to_nice_tree_decomposition();
+        assert_eq!(converted.width(), ntd.width());
+        assert_eq!(diaz_serna_thilikos_algorithm(&from_graph, &converted, &to_graph), diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph));
+    }
+
+    #[test]
+    fn test_to_nice_tree_decomposition(){
+        // a spider with center 2, the root has three children
+        let graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (2, 4), (4, 4)]);
+        let td = tree_decomposition(&[&[1, 2], &[0, 1], &[2, 3], &[2, 4]], &[(0, 1), (0, 2), (0, 3)], 5);
+
+        assert_eq!(td.width(), 1);
+        assert_eq!(td.validate(&graph), Ok(()));
+
+        let ntd = td.to_nice_tree_decomposition();
+        assert_eq!(ntd.width(), 1);
+        assert_eq!(ntd.vertex_count(), 5);
+        assert!(ntd.bag(ntd.root()).unwrap().is_empty());
+
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        assert_eq!(diaz_serna_thilikos_algorithm(&graph, &ntd, &to_graph), simple_brute_force(&graph, &to_graph));
+    }
+
+    #[test]
+    fn test_join_trees(){
+        // a star with center 0 and six leaves, the root has six children
+        let graph = graph_from_edges(7, &[(0, 1), (0, 2), (0, 3), (0, 4), (0, 5), (0, 6)]);
+        let td = tree_decomposition(&[&[0], &[0, 1], &[0, 2], &[0, 3], &[0, 4], &[0, 5], &[0, 6]],
+                                    &[(0, 1), (0, 2), (0, 3), (0, 4), (0, 5), (0, 6)], 7);
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        let caterpillar = td.to_nice_tree_decomposition_with(&NiceOptions { join_tree: JoinTree::Caterpillar });
+        let balanced = td.to_nice_tree_decomposition_with(&NiceOptions { join_tree: JoinTree::Balanced });
+        assert_eq!(caterpillar, td.to_nice_tree_decomposition());
+
+        let caterpillar_stats = NtdStats::compute(&caterpillar);
+        let balanced_stats = NtdStats::compute(&balanced);
+        assert_eq!(caterpillar_stats.join_nodes, 5);
+        assert_eq!(balanced_stats.join_nodes, 5);
+        assert_eq!(caterpillar_stats.nodes, balanced_stats.nodes);
+        assert_eq!(balanced_stats.width, 1);
+        assert!(balanced_stats.depth < caterpillar_stats.depth);
+
+        let hom_number = simple_brute_force(&graph, &to_graph);
+        assert_eq!(diaz_serna_thilikos_algorithm(&graph, &caterpillar, &to_graph), hom_number);
+        assert_eq!(diaz_serna_thilikos_algorithm(&graph, &balanced, &to_graph), hom_number);
+    }
+
+    #[test]
+    fn test_reroot(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let hom_number = diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph);
+
+        for p in 0..ntd.node_count(){
+            let rerooted = reroot(&ntd, p);
+
+            assert!(rerooted.bag(rerooted.root()).unwrap().is_empty());
+            assert_eq!(rerooted.width(), ntd.width());
+            assert_eq!(rerooted.vertex_count(), ntd.vertex_count());
+            assert_eq!(TreeDecomposition::from(&rerooted).validate(&from_graph), Ok(()));
+            assert_eq!(diaz_serna_thilikos_algorithm(&from_graph, &rerooted, &to_graph), hom_number);
+        }
+    }
+
+    #[test]
+    fn test_invalid_tree_decompositions(){
+        let graph = graph_from_edges(3, &[(0, 1), (1, 2)]);
+
+        let td = tree_decomposition(&[&[0, 1], &[2]], &[(0, 1)], 3);
+        assert_eq!(td.validate(&graph), Err(TreeDecompositionError::EdgeNotCovered(1, 2)));
+
+        let td = tree_decomposition(&[&[0, 1], &[0, 2], &[1, 2]], &[(0, 1), (1, 2)], 3);
+        assert_eq!(td.validate(&graph), Err(TreeDecompositionError::VertexNotConnected(1)));
+
+        let td = tree_decomposition(&[&[0, 1], &[1]], &[(0, 1)], 3);
+        assert_eq!(td.validate(&graph), Err(TreeDecompositionError::VertexNotCovered(2)));
+
+        let td = tree_decomposition(&[&[0, 1], &[1, 2]], &[], 3);
+        assert_eq!(td.validate(&graph), Err(TreeDecompositionError::Disconnected));
+    }
+}
+
+#[cfg(test)]
+pub mod tree_decomposition_handler_tests{
+    use crate::file_handler::tree_decomposition_handler::{import_ntd, load_ntd, NtdParseError, parse_ntd, read_ntd, write_ntd};
     use crate::unit_tests::ntd_test_example;
 
     #[test]
@@ -182,11 +491,64 @@ pub mod tree_decomposition_handler_tests{
         let ntd = ntd_test_example();
         assert_eq!(import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_9.ntd").unwrap(), ntd);
     }
+
+    #[test]
+    pub fn test_read_ntd_from_string() {
+        let content = std::fs::read_to_string("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_9.ntd").unwrap();
+        assert_eq!(read_ntd(content.as_bytes()).unwrap(), ntd_test_example());
+    }
+
+    #[test]
+    pub fn test_write_ntd() {
+        let ntd = ntd_test_example();
+        let mut content = vec![];
+        write_ntd(&ntd, &mut content).unwrap();
+        assert!(content.starts_with(b"s 10 2 4\n"));
+        assert_eq!(read_ntd(content.as_slice()).unwrap(), ntd);
+    }
+
+    #[test]
+    pub fn test_parse_ntd_whitespace_and_comments() {
+        let content = std::fs::read_to_string("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_9.ntd").unwrap();
+
+        // tabs, multiple spaces, Windows line endings, blank lines and comments
+        let messy : String = content.lines()
+            .map(|line| format!("  {}\t\r\n\nc a comment\r\n# another comment\n", line.replace(' ', " \t  ")))
+            .collect();
+        assert_eq!(parse_ntd(messy.as_bytes()).unwrap(), ntd_test_example());
+    }
+
+    #[test]
+    pub fn test_parse_ntd_errors() {
+        let line_of = |content : &str| match parse_ntd(content.as_bytes()) {
+            Err(NtdParseError::MalformedLine { line, .. }) => Some(line),
+            _ => None,
+        };
+
+        assert_eq!(line_of("s 2 1 1\nn 1 l 1\nn 2 x\na 2 1"), Some(3));
+        assert_eq!(line_of("s 2 1 1\nn 1 l 2\nn 2 f\na 2 1"), Some(2));
+        assert_eq!(line_of("s 2 1 1\nn 1 l 1\nn 2 f\na 2 3"), Some(4));
+        assert_eq!(line_of("s 2 1 1\nn 1 l 1\nn 2 f\na 2 1\na 2 1"), Some(5));
+        assert_eq!(line_of("\nn 1 l 1\ns 2 1 1"), Some(2));
+        assert_eq!(line_of("s 2 one 1"), Some(1));
+        assert_eq!(line_of("s 2 1 1\nx 1 2"), Some(2));
+        assert!(matches!(parse_ntd("c only a comment\n".as_bytes()), Err(NtdParseError::MissingStartLine)));
+        assert!(matches!(parse_ntd("s 2 1 1\nn 2 f".as_bytes()), Err(NtdParseError::MissingNode(1))));
+
+        // a typo in the file, where the adjacency line "a 7 6" is written as a second start line
+        let error = load_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_4.ntd").unwrap_err();
+        assert!(matches!(error, NtdParseError::MalformedLine { line: 33, .. }));
+        assert_eq!(error.to_string(), "line 33: duplicate start line in \"s 7 6\"");
+
+        assert!(matches!(load_ntd("data/does_not_exist.ntd"), Err(NtdParseError::Io(_))));
+    }
 }
 
 #[cfg(test)]
 pub mod graph_handler_tests{
-    use crate::file_handler::graph_handler::{import_dimacs, import_metis};
+    use crate::file_handler::graph_handler::{import_dimacs, import_metis, parse_graph6, read_dimacs, read_metis, read_metis_multigraph, to_graph6, write_dimacs, write_metis};
+    use crate::graph_generation::graph_generation_algorithms::equal_graphs;
+    use crate::unit_tests::graph_from_edges;
     use crate::tree_decompositions::tree_structure::Vertex;
 
     #[test]
@@ -230,6 +592,126 @@ pub mod graph_handler_tests{
             assert!(g.has_edge(Vertex::new(a), Vertex::new(b)));
         }
     }
+
+    #[test]
+    pub fn test_parse_graph6()
+    {
+        // the star with center 4 and a tree on 5 vertices as produced by geng
+        let star = parse_graph6("D?{").unwrap();
+        assert_eq!(star.node_count(), 5);
+        assert_eq!(star.edge_count(), 4);
+        for v in 0..4{
+            assert!(star.has_edge(Vertex::new(v), Vertex::new(4)));
+        }
+
+        let tree = parse_graph6(">>graph6<<DCw\n").unwrap();
+        assert_eq!(tree.edge_count(), 4);
+        assert!(tree.has_edge(Vertex::new(0), Vertex::new(3)));
+        assert!(tree.has_edge(Vertex::new(2), Vertex::new(4)));
+
+        assert_eq!(parse_graph6("@").unwrap().node_count(), 1);
+        assert!(parse_graph6("D?").is_none());
+        assert!(parse_graph6("5 4").is_none());
+    }
+
+    #[test]
+    pub fn test_write_graphs()
+    {
+        let graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 2), (1, 3)]);
+
+        let mut metis = vec![];
+        write_metis(&graph, &mut metis).unwrap();
+        assert_eq!(String::from_utf8(metis.clone()).unwrap(), "5 4\n2\n1 3 4\n2 3\n2\n\n");
+        assert!(equal_graphs(&read_metis(metis.as_slice()).unwrap(), &graph));
+
+        let mut dimacs = vec![];
+        write_dimacs(&graph, &mut dimacs).unwrap();
+        assert!(equal_graphs(&read_dimacs(dimacs.as_slice()).unwrap(), &graph));
+
+        // graph6 cannot represent loops
+        assert!(to_graph6(&graph).is_none());
+        let star = parse_graph6("D?{").unwrap();
+        assert_eq!(to_graph6(&star).unwrap(), "D?{");
+        let large = graph_from_edges(70, &[(0, 69), (5, 6)]);
+        assert!(equal_graphs(&parse_graph6(&to_graph6(&large).unwrap()).unwrap(), &large));
+    }
+
+    #[test]
+    pub fn test_read_graphs_from_string()
+    {
+        // a path on three vertices with a loop at the last vertex
+        let metis = "% comment\n3 3\n2\n1 3\n2 3\n";
+        let g = read_metis(metis.as_bytes()).unwrap();
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(g.edge_count(), 3);
+        assert!(g.has_edge(Vertex::new(0), Vertex::new(1)));
+        assert!(g.has_edge(Vertex::new(1), Vertex::new(2)));
+        assert!(g.has_edge(Vertex::new(2), Vertex::new(2)));
+
+        let multigraph = read_metis_multigraph("2 2\n2 2\n1 1\n".as_bytes()).unwrap();
+        assert_eq!(multigraph.edge_weight(Vertex::new(0), Vertex::new(1)), &2);
+
+        let dimacs = "c comment\np tw 3 2\n1 2\n2 3\n";
+        let g = read_dimacs(dimacs.as_bytes()).unwrap();
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(g.edge_count(), 2);
+        assert!(g.has_edge(Vertex::new(0), Vertex::new(1)));
+        assert!(g.has_edge(Vertex::new(1), Vertex::new(2)));
+
+        // malformed tokens and vertices out of range are rejected instead of panicking
+        assert!(read_metis("2 1\n2 x\n1\n".as_bytes()).is_none());
+        assert!(read_metis("2 1\n3\n1\n".as_bytes()).is_none());
+        assert!(read_metis("2 1\n0\n1\n".as_bytes()).is_none());
+        assert!(read_metis("2 1\n2\n1\n1\n".as_bytes()).is_none());
+        assert!(read_metis("x 1\n".as_bytes()).is_none());
+        assert!(read_metis_multigraph("2 1\n2 x\n1\n".as_bytes()).is_none());
+        assert!(read_dimacs("p tw 2 1\n1 x\n".as_bytes()).is_none());
+        assert!(read_dimacs("p tw 2 1\n1 3\n".as_bytes()).is_none());
+        assert!(read_dimacs("p tw 2\n".as_bytes()).is_none());
+
+        // repeated spaces and empty lines are no malformed tokens
+        assert_eq!(read_metis("2 1\n2 \n1\n".as_bytes()).unwrap().edge_count(), 1);
+        assert_eq!(read_dimacs("p tw 2 1\n\n1  2\n".as_bytes()).unwrap().edge_count(), 1);
+    }
+}
+
+#[cfg(test)]
+pub mod compressed_input_tests{
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use crate::file_handler::graph_handler::{import_dimacs, import_metis};
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::graph_generation::graph_generation_algorithms::equal_graphs;
+
+    /// Writes a gzip compressed copy of the given file into the temporary directory and returns its path.
+    fn compressed_copy(path : &str, name : &str) -> PathBuf{
+        let target = std::env::temp_dir().join(format!("compressed_input_tests_{}", name));
+        let mut encoder = GzEncoder::new(File::create(&target).unwrap(), Compression::default());
+        encoder.write_all(&std::fs::read(path).unwrap()).unwrap();
+        encoder.finish().unwrap();
+        target
+    }
+
+    #[test]
+    pub fn test_import_compressed_graphs(){
+        let metis = "data/metis_graphs/handmade/tiny_01.graph";
+        let compressed = import_metis(compressed_copy(metis, "tiny_01.graph.gz")).unwrap();
+        assert!(equal_graphs(&compressed, &import_metis(metis).unwrap()));
+
+        let dimacs = "data/dimacs_graphs/test_graph.gr";
+        let compressed = import_dimacs(compressed_copy(dimacs, "test_graph.gr.gz")).unwrap();
+        assert!(equal_graphs(&compressed, &import_dimacs(dimacs).unwrap()));
+    }
+
+    #[test]
+    pub fn test_import_compressed_ntd(){
+        let ntd = "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_9.ntd";
+        let compressed = import_ntd(compressed_copy(ntd, "ntd_bench_9.ntd.gz")).unwrap();
+        assert_eq!(compressed, import_ntd(ntd).unwrap());
+    }
 }
 
 #[cfg(test)]
@@ -274,7 +756,14 @@ pub mod brute_force_tests{
 
 #[cfg(test)]
 pub mod diaz_tests{
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use std::time::Duration;
     use crate::diaz_serna_thilikos;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_algorithm, diaz_serna_thilikos_parallel, diaz_serna_thilikos_with_options, diaz_serna_thilikos_with_result, diaz_serna_thilikos_interruptible, diaz_serna_thilikos_anytime, AnytimeCount, DiazOptions, DiazResult, NodeTable, PartialRun, StopCondition};
+    use crate::elimination_orderings::elimination_ordering_methods::tree_decomposition_from_ordering;
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
     use crate::file_handler::graph_handler::import_metis;
     use crate::file_handler::tree_decomposition_handler::import_ntd;
     use crate::tree_decompositions::tree_structure::Vertex;
@@ -288,6 +777,11 @@ pub mod diaz_tests{
 
         let mut dp_data = diaz_serna_thilikos::diaz_algorithm::DPData::new(&from_graph, &to_graph, &ntd);
 
+        // test accessors
+        assert_eq!(dp_data.from_graph().node_count(), from_graph.node_count());
+        assert_eq!(dp_data.to_graph().node_count(), 4);
+        assert_eq!(dp_data.nice_tree_decomposition(), &ntd);
+
         // test empty table
         assert_eq!(dp_data.get(&4, &10) , None);
         assert_eq!(dp_data.get(&9, &3) , None);
@@ -300,6 +794,17 @@ pub mod diaz_tests{
         assert_eq!(dp_data.get(&4, &10) , Some(&5));
         assert_eq!(dp_data.get(&9, &3) , Some(&2));
 
+        // zero entries are not stored and missing entries are 0
+        dp_data.set(9, 3, 0);
+        assert_eq!(dp_data.get(&9, &3) , None);
+        assert_eq!(dp_data.value(&9, &3) , 0);
+
+        dp_data.add(4, 10, 2);
+        dp_data.add(4, 11, 1);
+        assert_eq!(dp_data.value(&4, &10) , 7);
+        assert_eq!(dp_data.take(4) , HashMap::from([(10, 7), (11, 1)]));
+        assert_eq!(dp_data.get(&4, &10) , None);
+
         // Check table_apply
         assert_eq!(dp_data.table_apply(30,1), 3);
         assert_eq!(dp_data.table_apply(28,0), 0);
@@ -345,6 +850,114 @@ pub mod diaz_tests{
 
     }
 
+    #[test]
+    fn test_dpdata_shards() {
+        let from_graph = import_metis("data/metis_graphs/handmade/from_5.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_3.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_7.ntd").unwrap();
+
+        let mut dp_data = diaz_serna_thilikos::diaz_algorithm::DPData::new(&from_graph, &to_graph, &ntd);
+        dp_data.set(4, 10, 5);
+
+        // a shard shares the graphs and bags but not the entries
+        let mut shard = dp_data.shard();
+        assert_eq!(shard.sorted_bag(8), dp_data.sorted_bag(8));
+        assert_eq!(shard.get(&4, &10), None);
+
+        // tables computed elsewhere, e.g. in another thread, are inserted or added
+        shard.insert_table(9, NodeTable::from([(3, 2), (5, 0)]));
+        shard.add_table(9, &NodeTable::from([(3, 1), (6, 4)]));
+        assert_eq!(shard.table_size(9), 2);
+
+        std::thread::scope(|scope| {
+            let shard = scope.spawn(move || { shard.set(11, 1, 7); shard }).join().unwrap();
+            dp_data.merge(shard);
+        });
+        assert_eq!(dp_data.take(9), HashMap::from([(3, 3), (6, 4)]));
+        assert_eq!(dp_data.value(&11, &1), 7);
+        assert_eq!(dp_data.value(&4, &10), 5);
+    }
+
+    #[test]
+    fn test_table_backends(){
+        use crate::tables::table_backends::TableBackend;
+
+        let instances = [
+            ("data/metis_graphs/handmade/from_2.graph", "data/metis_graphs/handmade/to_2.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd", 1280),
+            ("data/metis_graphs/handmade/from_3.graph", "data/metis_graphs/handmade/to_3.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd", 256),
+            ("data/metis_graphs/handmade/from_5.graph", "data/metis_graphs/bench_1.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_7.ntd", 0),
+            ("data/metis_graphs/handmade/from_7.graph", "data/metis_graphs/handmade/to_2.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_6.ntd", 960),
+        ];
+        for (from, to, ntd, hom_number) in instances{
+            let (from_graph, to_graph, ntd) = (import_metis(from).unwrap(), import_metis(to).unwrap(), import_ntd(ntd).unwrap());
+            for backend in [TableBackend::Hash, TableBackend::BTree, TableBackend::Dense]{
+                let options = DiazOptions { backend, ..Default::default() };
+                assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &options), hom_number);
+
+                let options = DiazOptions { backend, image_pruning: true, ..Default::default() };
+                assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &options), hom_number);
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_decompositions(){
+        use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+        use crate::ntd_generation::ntd_generators::window_ntd;
+        use crate::tables::table_backends::TableBackend;
+
+        assert!(!import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap().is_path_decomposition());
+
+        // the square of a path with a loop on 6 vertices in the window decomposition of width 2
+        let ntd = window_ntd(6, 2);
+        assert!(ntd.is_path_decomposition());
+        let from_graph = graph_from_edges(6, &[(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (3, 5), (4, 5), (4, 4)]);
+        let to_graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 4), (1, 3)]);
+        let hom_number = simple_brute_force(&from_graph, &to_graph);
+        assert!(hom_number > 0);
+
+        // the streamed tables agree with the audited tables stored per node
+        for backend in [TableBackend::Hash, TableBackend::BTree, TableBackend::Dense]{
+            for image_pruning in [false, true]{
+                let options = DiazOptions { backend, image_pruning, ..Default::default() };
+                let (result, report) = diaz_serna_thilikos_with_result(&from_graph, &ntd, &to_graph, &options);
+                assert_eq!(result, DiazResult::Count(hom_number));
+                assert_eq!(report.len() as u64, ntd.node_count());
+            }
+        }
+        let audited = DiazOptions { audit: true, ..Default::default() };
+        assert_eq!(diaz_serna_thilikos_with_result(&from_graph, &ntd, &to_graph, &audited).0, DiazResult::Count(hom_number));
+
+        // without loops in the target the looped vertex 4 has no image and the run stops at an empty table
+        let (result, _) = diaz_serna_thilikos_with_result(&from_graph, &ntd, &graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]), &DiazOptions::default());
+        assert!(matches!(result, DiazResult::ZeroAtNode(_)));
+    }
+
+    #[test]
+    fn test_parallel_subtrees(){
+        let instances = [
+            ("data/metis_graphs/handmade/from_2.graph", "data/metis_graphs/handmade/to_2.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd"),
+            ("data/metis_graphs/handmade/from_3.graph", "data/metis_graphs/handmade/to_3.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd"),
+            ("data/metis_graphs/handmade/from_7.graph", "data/metis_graphs/handmade/to_2.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_6.ntd"),
+        ];
+        for (from, to, ntd) in instances{
+            let (from_graph, to_graph, ntd) = (import_metis(from).unwrap(), import_metis(to).unwrap(), import_ntd(ntd).unwrap());
+            let hom_number = diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph);
+            for threads in [1, 2, 3, 8]{
+                assert_eq!(diaz_serna_thilikos_parallel(&from_graph, &ntd, &to_graph, threads), hom_number);
+            }
+        }
+
+        // a tree with several join nodes in its decomposition
+        let from_graph = graph_from_edges(9, &[(0, 1), (0, 2), (0, 3), (1, 4), (1, 5), (2, 6), (3, 7), (3, 8)]);
+        let to_graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (1, 1)]);
+        let ntd = crate::pipeline::counting_pipeline::compute_nice_tree_decomposition(&from_graph);
+        let hom_number = diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph);
+        for threads in [1, 2, 4, 16]{
+            assert_eq!(diaz_serna_thilikos_parallel(&from_graph, &ntd, &to_graph, threads), hom_number);
+        }
+    }
+
     #[test]
     fn test_diaz(){
 
@@ -385,6 +998,154 @@ pub mod diaz_tests{
         assert_eq!(i,960);
 
     }
+
+    #[test]
+    fn test_compact_tables(){
+        let options = DiazOptions { compact_tables: true, ..Default::default() };
+
+        // small counts fit into the compact table
+        for (from, to, ntd, expected) in [("from_2", "to_2", "ntd_bench_8", 1280), ("from_3", "to_3", "ntd_bench_8", 256), ("from_7", "to_2", "ntd_bench_6", 960)]{
+            let from_graph = import_metis(format!("data/metis_graphs/handmade/{}.graph", from)).unwrap();
+            let to_graph = import_metis(format!("data/metis_graphs/handmade/{}.graph", to)).unwrap();
+            let ntd = import_ntd(format!("data/nice_tree_decompositions/benchmark_ntds/handmade/{}.ntd", ntd)).unwrap();
+            assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &options), expected);
+        }
+
+        // 8 isolated vertices into the complete graph with loops on 17 vertices gives 17^8 > u32::MAX
+        // homomorphisms, hence the large entries have to be promoted to 64 bit
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_2.ntd").unwrap();
+        let from_graph = graph_from_edges(8, &[]);
+        let edges : Vec<(usize, usize)> = (0..17).flat_map(|u| (u..17).map(move |v| (u, v))).collect();
+        let to_graph = graph_from_edges(17, &edges);
+        assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &options), 17_u64.pow(8));
+
+        // the compact tables are processed by the general dynamic program, hence they can be audited
+        let audited = DiazOptions { compact_tables: true, audit: true, ..Default::default() };
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &audited), 1280);
+    }
+
+    #[test]
+    fn test_early_exit_at_zero_table(){
+        // a triangle with a pendant path has no homomorphism into a bipartite graph
+        let from_graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)]);
+        let ntd = tree_decomposition_from_ordering(&from_graph, &[4, 3, 0, 1, 2]).to_nice_tree_decomposition();
+        let to_graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+
+        for compact_tables in [false, true]{
+            let options = DiazOptions { compact_tables, ..DiazOptions::default() };
+            let (result, report) = diaz_serna_thilikos_with_result(&from_graph, &ntd, &to_graph, &options);
+
+            let DiazResult::ZeroAtNode(p) = result else { panic!("expected an early exit, got {:?}", result); };
+            assert_eq!(result.count(), 0);
+            assert_eq!(report.last().unwrap().node, p);
+            assert_eq!(report.last().unwrap().table_size, 0);
+            assert!(report.len() < ntd.node_count() as usize);
+        }
+
+        // a non-zero count is computed at the root
+        let (result, _) = diaz_serna_thilikos_with_result(&from_graph, &ntd, &graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]), &DiazOptions::default());
+        assert_eq!(result, DiazResult::Count(6 * 2 * 2));
+    }
+
+    #[test]
+    fn test_overflow_result(){
+        // the path on 70 vertices has 2^70 homomorphisms into an edge with loops at both ends
+        let ntd = crate::ntd_generation::ntd_generators::window_ntd(70, 1);
+        let path_edges : Vec<(usize, usize)> = (1..70).map(|v| (v - 1, v)).collect();
+        let path = graph_from_edges(70, &path_edges);
+        let to_graph = graph_from_edges(2, &[(0, 0), (0, 1), (1, 1)]);
+
+        // the streamed path decomposition and the general dynamic program report the overflow
+        let (result, _) = diaz_serna_thilikos_with_result(&path, &ntd, &to_graph, &DiazOptions::default());
+        assert!(matches!(result, DiazResult::Overflow(_)), "expected an overflow, got {:?}", result);
+        let (result, _) = diaz_serna_thilikos_interruptible(&path, &ntd, &to_graph, &DiazOptions::default(), &StopCondition::default()).unwrap();
+        assert!(matches!(result, DiazResult::Overflow(_)), "expected an overflow, got {:?}", result);
+
+        let short_path = graph_from_edges(63, &path_edges[..62]);
+        let ntd = crate::ntd_generation::ntd_generators::window_ntd(63, 1);
+        assert_eq!(diaz_serna_thilikos_with_result(&short_path, &ntd, &to_graph, &DiazOptions::default()).0, DiazResult::Count(1 << 63));
+    }
+
+    #[test]
+    fn test_neighbourhood_candidates(){
+        // targets with more than 64 vertices, such that the neighbourhoods consist of several words
+        let mut to_graph = crate::graph_generation::graph_generation_algorithms::circulant_graph(70, &[1]);
+        let path = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        let cycle = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let compact = DiazOptions { compact_tables: true, ..Default::default() };
+
+        for (from_graph, hom_number) in [(path, 280), (cycle, 420)]{
+            let ntd = crate::pipeline::counting_pipeline::compute_nice_tree_decomposition(&from_graph);
+            assert_eq!(diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph), hom_number);
+            assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &compact), hom_number);
+        }
+
+        // a loop of the introduced vertex has to be mapped onto a loop
+        to_graph.add_edge(Vertex::new(65), Vertex::new(65), ());
+        let from_graph = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        let ntd = crate::pipeline::counting_pipeline::compute_nice_tree_decomposition(&from_graph);
+        assert_eq!(diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph), 3);
+        assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &compact), 3);
+    }
+
+    #[test]
+    fn test_partial_run(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_5.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_3.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_7.ntd").unwrap();
+        let options = DiazOptions::default();
+
+        // without a stop condition the run is completed
+        let (result, report) = diaz_serna_thilikos_interruptible(&from_graph, &ntd, &to_graph, &options, &StopCondition::default()).unwrap();
+        let (expected_result, expected_report) = diaz_serna_thilikos_with_result(&from_graph, &ntd, &to_graph, &options);
+        assert_eq!(result, expected_result);
+        assert_eq!(report.len(), expected_report.len());
+
+        // a cancelled run and an expired timeout stop before the first node
+        let cancelled = Arc::new(AtomicBool::new(true));
+        for stop in [StopCondition::cancellation(cancelled), StopCondition::timeout(Duration::ZERO)]{
+            let partial_run = diaz_serna_thilikos_interruptible(&from_graph, &ntd, &to_graph, &options, &stop).unwrap_err();
+            assert!(partial_run.completed.is_empty());
+            assert!(partial_run.reports.is_empty());
+            assert!(partial_run.tables.is_empty());
+            assert_eq!(partial_run.stored_entries(), 0);
+        }
+
+        // a flag which is not set does not interrupt the run
+        let stop = StopCondition::cancellation(Arc::new(AtomicBool::new(false)));
+        assert!(diaz_serna_thilikos_interruptible(&from_graph, &ntd, &to_graph, &options, &stop).is_ok());
+    }
+
+    #[test]
+    fn test_anytime_count(){
+        // the star with center 1 and the leaves 0, 2 and 3 into the triangle
+        let from_graph = graph_from_edges(4, &[(0, 1), (1, 2), (1, 3)]);
+        let to_graph = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let ntd = ntd_test_example();
+        let options = DiazOptions::default();
+
+        let count = diaz_serna_thilikos_anytime(&from_graph, &ntd, &to_graph, &options, Duration::from_secs(60));
+        assert_eq!(count, AnytimeCount { lower: 24, upper: 24, partial: None });
+        assert!(count.is_exact());
+
+        // without any completed node every vertex can be mapped anywhere
+        let count = diaz_serna_thilikos_anytime(&from_graph, &ntd, &to_graph, &options, Duration::ZERO);
+        assert_eq!((count.lower, count.upper), (0, 81));
+        assert!(!count.is_exact());
+
+        // after the subtree of node 2, i.e. the edge {0, 1}, the vertices 2 and 3 remain
+        let partial_run = PartialRun { completed: vec![0, 1, 2], reports: vec![], tables: HashMap::from([(2, NodeTable::from([(0, 2), (1, 2), (2, 2)]))]) };
+        assert_eq!(partial_run.subtree_counts(), vec![(2, 6)]);
+        assert_eq!(partial_run.upper_bound(&ntd, 3), 6 * 9);
+
+        // both subtrees of the join node are completed, only vertex 3 remains
+        let partial_run = PartialRun { completed: vec![0, 1, 2, 3, 4, 5], reports: vec![], tables: HashMap::from([(2, NodeTable::from([(0, 2), (1, 2), (2, 2)])), (5, NodeTable::from([(0, 2), (1, 2), (2, 2)]))]) };
+        assert_eq!(partial_run.upper_bound(&ntd, 3), 6 * 6 * 3);
+        assert_eq!(partial_run.upper_bound(&ntd, 1 << 60), u64::MAX);
+    }
 }
 
 #[cfg(test)]
@@ -394,7 +1155,8 @@ pub mod graph_generation_test{
     use petgraph::visit::GetAdjacencyMatrix;
     use crate::file_handler::graph_handler::import_metis;
     use crate::file_handler::tree_decomposition_handler::import_ntd;
-    use crate::graph_generation::graph_generation_algorithms::{equal_graphs, generate_graphs, generate_possible_edges};
+    use crate::tree_decompositions::tree_structure::Vertex;
+    use crate::graph_generation::graph_generation_algorithms::{equal_graphs, generate_connected_graphs, generate_graphs, generate_graphs_containing, generate_graphs_filtered, generate_possible_edges, graph_from_edge_subset, is_connected, connected_components, number_of_components, hypercube_graph, circulant_graph, kneser_graph, generate_trees, canonical_form};
     use crate::unit_tests::compare_edge_lists;
 
     #[test]
@@ -430,6 +1192,83 @@ pub mod graph_generation_test{
         }
     }
 
+    #[test]
+    fn test_generate_graphs_containing()
+    {
+        let gen_graphs = generate_graphs_containing(4, vec![(0,1),(0,3),(0,2),(2,3)], &[(1,0), (2,3)]);
+        assert_eq!(gen_graphs.len(), 4);
+
+        // every generated graph contains the required edges
+        for g in &gen_graphs{
+            assert!(g.has_edge(Vertex::new(0), Vertex::new(1)));
+            assert!(g.has_edge(Vertex::new(2), Vertex::new(3)));
+        }
+
+        // the generated graphs are exactly the supergraphs among all generated graphs
+        let all_graphs = generate_graphs(4, vec![(0,1),(0,3),(0,2),(2,3)]);
+        let supergraphs : Vec<_> = all_graphs.iter()
+            .filter(|g| g.has_edge(Vertex::new(0), Vertex::new(1)) && g.has_edge(Vertex::new(2), Vertex::new(3)))
+            .collect();
+        assert_eq!(supergraphs.len(), gen_graphs.len());
+        for g in supergraphs{
+            assert!(gen_graphs.iter().any(|x| {equal_graphs(x,g)}));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_generate_graphs_containing_impossible_edge()
+    {
+        generate_graphs_containing(4, vec![(0,1),(0,3)], &[(1,2)]);
+    }
+
+    #[test]
+    fn test_generate_graphs_filtered()
+    {
+        // graphs on 4 vertices with at least 3 edges from a universe of 4 edges
+        let gen_graphs = generate_graphs_filtered(4, vec![(0,1),(0,3),(0,2),(2,3)], |g| g.edge_count() >= 3);
+        assert_eq!(gen_graphs.len(), 5);
+
+        // the graph with all edges, the star with center 0 and the paths 1-0-2-3 and 1-0-3-2 are connected
+        let connected = generate_connected_graphs(4, vec![(0,1),(0,3),(0,2),(2,3)]);
+        assert_eq!(connected.len(), 4);
+        assert!(connected.iter().all(is_connected));
+
+        // graphs with an isolated vertex or two components are not connected
+        assert!(is_connected(&import_metis("data/metis_graphs/graph_generation_test/gen_1.graph").unwrap()));
+        assert!(!is_connected(&import_metis("data/metis_graphs/graph_generation_test/gen_2.graph").unwrap()));
+        assert!(!is_connected(&import_metis("data/metis_graphs/graph_generation_test/gen_8.graph").unwrap()));
+    }
+
+    #[test]
+    fn test_connected_components()
+    {
+        let graph = crate::unit_tests::graph_from_edges(6, &[(0, 3), (3, 5), (1, 4), (2, 2)]);
+        assert_eq!(connected_components(&graph), vec![vec![0, 3, 5], vec![1, 4], vec![2]]);
+        assert_eq!(number_of_components(&graph), 3);
+        assert_eq!(number_of_components(&crate::unit_tests::graph_from_edges(0, &[])), 0);
+    }
+
+    #[test]
+    fn test_graph_from_edge_subset()
+    {
+        let universe = vec![(0,1),(0,3),(0,2),(2,3)];
+
+        // bit i of the mask selects universe[i]
+        let graph = graph_from_edge_subset(4, &universe, 0b1010);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.has_edge(Vertex::new(0), Vertex::new(3)));
+        assert!(graph.has_edge(Vertex::new(2), Vertex::new(3)));
+
+        // every graph generated from the universe corresponds to exactly one mask
+        let gen_graphs = generate_graphs(4, universe.clone());
+        for mask in 0..16{
+            let graph = graph_from_edge_subset(4, &universe, mask);
+            assert_eq!(gen_graphs.iter().filter(|x| equal_graphs(x, &graph)).count(), 1);
+        }
+    }
+
     #[test]
     fn test_equal_graphs()
     {
@@ -440,17 +1279,69 @@ pub mod graph_generation_test{
         assert!(equal_graphs(&graph2, &graph2));
     }
 
+    #[test]
+    fn test_target_families()
+    {
+        let cube = hypercube_graph(3);
+        assert_eq!((cube.node_count(), cube.edge_count()), (8, 12));
+        assert!(cube.has_edge(Vertex::new(0b101), Vertex::new(0b100)));
+        assert_eq!(hypercube_graph(0).node_count(), 1);
+
+        let cycle = crate::unit_tests::graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        assert!(equal_graphs(&circulant_graph(5, &[1]), &cycle));
+        assert!(equal_graphs(&circulant_graph(5, &[4, 6]), &cycle));
+        assert_eq!(circulant_graph(8, &[1, 4]).edge_count(), 12);
+        assert_eq!(circulant_graph(3, &[0]).edge_count(), 3);
+
+        // the petersen graph is 3-regular with 15 edges
+        let petersen = kneser_graph(5, 2);
+        assert_eq!((petersen.node_count(), petersen.edge_count()), (10, 15));
+        assert!((0..10).all(|u| petersen.neighbors(Vertex::new(u)).count() == 3));
+        assert_eq!(kneser_graph(4, 2).edge_count(), 3);
+    }
+
+    #[test]
+    fn test_generate_trees()
+    {
+        // the numbers of trees with 1 to 7 vertices up to isomorphism
+        let trees = generate_trees(7);
+        for (n, number_of_trees) in [1, 1, 1, 2, 3, 6, 11].into_iter().enumerate(){
+            assert_eq!(trees.iter().filter(|tree| tree.node_count() == n + 1).count(), number_of_trees);
+        }
+        assert!(trees.iter().all(|tree| is_connected(tree) && tree.edge_count() + 1 == tree.node_count()));
+        assert!(generate_trees(0).is_empty());
+    }
+
+    #[test]
+    fn test_canonical_form()
+    {
+        let path = crate::unit_tests::graph_from_edges(3, &[(0, 1), (1, 2)]);
+        let relabeled = crate::unit_tests::graph_from_edges(3, &[(2, 0), (0, 1)]);
+        assert_eq!(canonical_form(&path), "3: 0-2 1-2");
+        assert_eq!(canonical_form(&relabeled), canonical_form(&path));
+
+        // the 6-cycle and two triangles cannot be distinguished by color refinement
+        let cycle = crate::unit_tests::graph_from_edges(6, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+        let triangles = crate::unit_tests::graph_from_edges(6, &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]);
+        assert_ne!(canonical_form(&cycle), canonical_form(&triangles));
+
+        // loops and isolated vertices are part of the canonical form
+        assert_ne!(canonical_form(&crate::unit_tests::graph_from_edges(2, &[(0, 0)])), canonical_form(&crate::unit_tests::graph_from_edges(2, &[(0, 1)])));
+        assert_eq!(canonical_form(&crate::unit_tests::graph_from_edges(2, &[])), "2:");
+        assert_eq!(canonical_form(&crate::unit_tests::graph_from_edges(0, &[])), "0:");
+    }
+
 
 }
 
 
 #[cfg(test)]
 pub mod algorithm_comparison_test{
-    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
-    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::brute_force::brute_force_homomorphism_counter::{simple_brute_force, simple_brute_force_for_ntd_set_containing};
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_algorithm, diaz_serna_thilikos_for_ntd_set_containing};
     use crate::file_handler::graph_handler::import_metis;
     use crate::file_handler::tree_decomposition_handler::import_ntd;
-    use crate::graph_generation::graph_generation_algorithms::{generate_graphs, generate_possible_edges};
+    use crate::graph_generation::graph_generation_algorithms::{equal_graphs, generate_graphs, generate_possible_edges};
 
     #[test]
     fn compare_brute_force_with_diaz()
@@ -469,19 +1360,42 @@ pub mod algorithm_comparison_test{
         }
     }
 
+    #[test]
+    fn compare_brute_force_with_diaz_containing()
+    {
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let required_edges = [(0,1), (1,2)];
+
+        let brute_force = simple_brute_force_for_ntd_set_containing(&ntd, &to_graph, &required_edges);
+        let diaz = diaz_serna_thilikos_for_ntd_set_containing(&ntd, &to_graph, &required_edges);
+
+        // 9 possible edges minus 2 required edges leave 2^7 graphs
+        assert_eq!(brute_force.len(), 128);
+        assert_eq!(diaz.len(), 128);
+
+        for ((g1, h1), (g2, h2)) in brute_force.iter().zip(diaz.iter()){
+            assert!(equal_graphs(g1, g2));
+            assert_eq!(h1, h2);
+        }
+    }
+
 }
 
 #[cfg(test)]
 pub mod equivalence_class_algorithm_test{
     use std::arch::x86_64::_mm256_div_ps;
     use petgraph::dot::Dot;
-    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
-    use crate::modified_dp::algorithm::{DPData, modified_dp};
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_algorithm, diaz_serna_thilikos_with_report, DiazOptions};
+    use crate::modified_dp::algorithm::{DPData, edges_to_graph, is_connected_edge_set, modified_dp, modified_dp_connected, modified_dp_connected_counts, modified_dp_counts, modified_dp_for_pattern, modified_dp_root_table, modified_dp_with_report};
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::file_handler::tree_decomposition_handler::read_ntd;
     use crate::file_handler::graph_handler::import_metis;
     use crate::file_handler::tree_decomposition_handler::import_ntd;
-    use crate::graph_generation::graph_generation_algorithms::{equal_graphs, generate_graphs, generate_possible_edges};
+    use crate::graph_generation::graph_generation_algorithms::{equal_graphs, generate_graphs, generate_possible_edges, is_connected};
+    use crate::tree_decompositions::nice_tree_decomposition::NodeType;
     use crate::tree_decompositions::tree_structure::Vertex;
-    use crate::unit_tests::compare_edge_lists;
+    use crate::unit_tests::{compare_edge_lists, graph_from_edges};
 
     #[test]
     fn test_dpddata() {
@@ -555,14 +1469,13 @@ pub mod equivalence_class_algorithm_test{
                    dp_data.all_possible_edges().iter().position(|x| *x == (2,3) || *x == (3,2)).unwrap());
 
 
-        // test the possible_edges function
-        let pos_edges = dp_data.possible_edges(7).unwrap();
-        let edges : Vec<(usize, usize)> = pos_edges.iter().map(|x| *dp_data.index_to_edge(x).unwrap()).collect();
+        // test the possible_edge_mask function
+        let mask_edges = |mask : u64| -> Vec<(usize, usize)> { (0..12).filter(|i| mask & (1 << i) != 0).map(|i| *dp_data.index_to_edge(&i).unwrap()).collect() };
+        let edges = mask_edges(dp_data.possible_edge_mask(7).unwrap());
         assert!(compare_edge_lists(&vec![(0,0), (2,2), (3,3), (0,2), (0,3), (2,3)], &edges));
 
-
-        let pos_edges = dp_data.possible_edges(14).unwrap();
-        let edges : Vec<(usize, usize)> = pos_edges.iter().map(|x| *dp_data.index_to_edge(x).unwrap()).collect();
+        assert_eq!(dp_data.possible_edge_mask(14), Some((1 << 12) - 1));
+        let edges = mask_edges(dp_data.possible_edge_mask(14).unwrap());
         assert!(compare_edge_lists(&vec![(0,0), (1,1), (2,2), (3,3), (4,4), (0,1), (1,3), (0,3), (0,2), (2,3), (0,4), (3,4)], &edges));
 
 
@@ -622,4 +1535,3010 @@ pub mod equivalence_class_algorithm_test{
         }
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_modified_dp_counts()
+    {
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        let counts = modified_dp_counts(&ntd, &to_graph);
+
+        // one entry for each subset of the 9 possible edges
+        assert_eq!(counts.len(), 512);
+
+        for (edges, hom_number) in &counts{
+            let graph = edges_to_graph(&ntd, *edges);
+            assert_eq!(diaz_serna_thilikos_algorithm(&graph, &ntd, &to_graph), *hom_number);
+        }
+    }
+
+    #[test]
+    fn test_root_table()
+    {
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        // the root bag is empty, hence every row only contains the empty mapping
+        let root_table = modified_dp_root_table(&ntd, &to_graph);
+        assert!(root_table.root_bag().is_empty());
+        for (edges, hom_number) in modified_dp_counts(&ntd, &to_graph){
+            assert_eq!(root_table.value(edges, 0), hom_number);
+            assert!(root_table.row(edges).is_none_or(|row| row.keys().all(|&f| f == 0)));
+        }
+
+        // a decomposition whose root does not forget the vertex 2
+        let ntd = read_ntd("s 3 2 2\nn 1 l 1\nn 2 i 1 2\nn 3 f 2\na 3 2\na 2 1".as_bytes()).unwrap();
+        let to_graph = graph_from_edges(3, &[(0,1), (1,2), (2,2)]);
+
+        let root_table = modified_dp_root_table(&ntd, &to_graph);
+        assert_eq!(root_table.root_bag(), &[Vertex::new(1)]);
+        for (edges, hom_number) in root_table.counts(){
+            let graph = edges_to_graph(&ntd, edges);
+            assert_eq!(hom_number, simple_brute_force(&graph, &to_graph));
+            // the row is indexed by the images of the vertex 2
+            assert_eq!(hom_number, (0..3).map(|a| root_table.value(edges, a)).sum::<u64>());
+        }
+        assert_eq!(modified_dp_counts(&ntd, &to_graph), root_table.counts());
+    }
+
+    #[test]
+    fn test_modified_dp_for_pattern()
+    {
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        // every graph of $H_\tau$ gets the count of the complete algorithm and of diaz et all
+        let counts = modified_dp_counts(&ntd, &to_graph);
+        for (&edges, &hom_number) in &counts{
+            let graph = edges_to_graph(&ntd, edges);
+            assert_eq!(modified_dp_for_pattern(&graph, &ntd, &to_graph), Some(hom_number));
+            assert_eq!(diaz_serna_thilikos_algorithm(&graph, &ntd, &to_graph), hom_number);
+        }
+
+        // an edge to a vertex which does not occur in the decomposition is not a possible edge
+        let n = ntd.vertex_count() as usize;
+        let mut too_many_vertices = graph_from_edges(n + 1, &[]);
+        too_many_vertices.add_edge(Vertex::new(0), Vertex::new(n), ());
+        assert_eq!(modified_dp_for_pattern(&too_many_vertices, &ntd, &to_graph), None);
+    }
+
+    #[test]
+    fn test_is_connected_edge_set()
+    {
+        let universe = vec![(0,1), (1,2), (2,2), (2,3)];
+
+        assert!(is_connected_edge_set(4, &universe, 0b1011));
+        // the loop does not connect anything
+        assert!(!is_connected_edge_set(4, &universe, 0b0111));
+        assert!(!is_connected_edge_set(4, &universe, 0b1001));
+        assert!(!is_connected_edge_set(2, &universe, 0));
+        assert!(is_connected_edge_set(1, &[], 0));
+        assert!(is_connected_edge_set(0, &[], 0));
+    }
+
+    #[test]
+    fn test_modified_dp_connected()
+    {
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        let all_counts = modified_dp_counts(&ntd, &to_graph);
+        let connected_counts = modified_dp_connected_counts(&ntd, &to_graph);
+
+        // exactly the connected spanning graphs are kept with unchanged counts
+        for (edges, hom_number) in &all_counts{
+            let connected = is_connected(&edges_to_graph(&ntd, *edges));
+            assert_eq!(connected_counts.get(edges), if connected { Some(hom_number) } else { None });
+        }
+
+        let list = modified_dp_connected(&ntd, &to_graph);
+        assert_eq!(list.len(), connected_counts.len());
+        for (graph, hom_number) in &list{
+            assert!(is_connected(graph));
+            assert_eq!(diaz_serna_thilikos_algorithm(graph, &ntd, &to_graph), *hom_number);
+        }
+    }
+
+    #[test]
+    fn test_node_reports()
+    {
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        // one report for each node in the stingy ordering
+        let (hom_number, report) = diaz_serna_thilikos_with_report(&from_graph, &ntd, &to_graph, &DiazOptions::default());
+        assert_eq!(hom_number, 1280);
+        assert_eq!(report.iter().map(|node_report| node_report.node).collect::<Vec<u64>>(), ntd.stingy_ordering());
+
+        // the table of the root only contains the empty mapping and a leaf maps its vertex onto each of the 5 vertices
+        let root_report = report.last().unwrap();
+        assert_eq!(root_report.node, ntd.root());
+        assert_eq!(root_report.table_size, 1);
+        assert_eq!(report[0].node_type, NodeType::Leaf);
+        assert_eq!(report[0].table_size, 5);
+
+        // the root table of the equivalence class algorithm contains the non-zero entries of all graphs
+        let (hom_numbers, report) = modified_dp_with_report(&ntd, &to_graph);
+        assert_eq!(report.len(), ntd.node_count() as usize);
+        assert_eq!(report.last().unwrap().table_size, hom_numbers.iter().filter(|(_, hom_number)| *hom_number != 0).count());
+    }
+}
+
+#[cfg(test)]
+pub mod multigraph_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::{import_metis, import_metis_multigraph};
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::file_handler::tree_decomposition_handler::read_ntd;
+    use crate::multigraph::multigraph_algorithms::{brute_force_multigraph, diaz_serna_thilikos_multigraph, multiplicity, to_multigraph, underlying_graph, MultiGraph};
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::tree_decompositions::nice_tree_decomposition::NodeType;
+    use crate::tree_decompositions::tree_structure::Vertex;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_import_metis_multigraph(){
+        let graph = import_metis_multigraph("data/metis_graphs/multigraph_tests/triple_edge_with_loop.graph").unwrap();
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(multiplicity(&graph, Vertex::new(0), Vertex::new(0)), 1);
+        assert_eq!(multiplicity(&graph, Vertex::new(0), Vertex::new(1)), 3);
+        assert_eq!(multiplicity(&graph, Vertex::new(1), Vertex::new(0)), 3);
+        assert_eq!(multiplicity(&graph, Vertex::new(1), Vertex::new(1)), 0);
+    }
+
+    #[test]
+    fn test_multigraph_hom_numbers(){
+        let from_graph = import_metis_multigraph("data/metis_graphs/multigraph_tests/double_edge.graph").unwrap();
+        let to_graph = import_metis_multigraph("data/metis_graphs/multigraph_tests/triple_edge_with_loop.graph").unwrap();
+        let ntd = import_ntd("data/Experiments/ntds/ntd_path_2.ntd").unwrap();
+
+        // (0,0) -> 1^2, (0,1) -> 3^2, (1,0) -> 3^2, (1,1) -> 0^2
+        assert_eq!(brute_force_multigraph(&from_graph, &to_graph), Some(19));
+        assert_eq!(diaz_serna_thilikos_multigraph(&from_graph, &ntd, &to_graph), Some(19));
+
+        // simple graphs have the same hom numbers as multigraphs with multiplicity one
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        let hom_number = diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph);
+        assert_eq!(diaz_serna_thilikos_multigraph(&to_multigraph(&from_graph), &ntd, &to_multigraph(&to_graph)), Some(hom_number));
+        assert_eq!(brute_force_multigraph(&to_multigraph(&from_graph), &to_multigraph(&to_graph)), Some(hom_number));
+    }
+
+    #[test]
+    fn test_multigraph_overflow(){
+        let ntd = import_ntd("data/Experiments/ntds/ntd_path_2.ntd").unwrap();
+        let edge = |m : u64| {
+            let mut graph = MultiGraph::default();
+            graph.add_node(());
+            graph.add_node(());
+            graph.add_edge(Vertex::new(0), Vertex::new(1), m);
+            graph
+        };
+
+        // both orientations of the edge contribute 2^m
+        assert_eq!(diaz_serna_thilikos_multigraph(&edge(62), &ntd, &edge(2)), Some(1 << 63));
+        assert_eq!(brute_force_multigraph(&edge(62), &edge(2)), Some(1 << 63));
+        assert_eq!(diaz_serna_thilikos_multigraph(&edge(63), &ntd, &edge(2)), None);
+        assert_eq!(brute_force_multigraph(&edge(63), &edge(2)), None);
+        assert_eq!(diaz_serna_thilikos_multigraph(&edge(64), &ntd, &edge(2)), None);
+    }
+
+    #[test]
+    fn test_multigraph_join_and_root_bag(){
+        // three paths leaving the vertex 0 with a loop, which is in the bag of a join node
+        let pattern = to_multigraph(&graph_from_edges(10, &[(0, 0), (0, 1), (1, 2), (2, 3), (0, 4), (4, 5), (5, 6), (0, 7), (7, 8), (8, 9)]));
+        let ntd = compute_nice_tree_decomposition(&underlying_graph(&pattern));
+        assert!(ntd.stingy_ordering().iter().any(|&p| matches!(ntd.node_type(p), Some(NodeType::Join))));
+
+        // every edge and the loop is mapped onto the loop of multiplicity 2
+        let mut target = MultiGraph::default();
+        target.add_node(());
+        target.add_edge(Vertex::new(0), Vertex::new(0), 2);
+        assert_eq!(brute_force_multigraph(&pattern, &target), Some(1 << 10));
+        assert_eq!(diaz_serna_thilikos_multigraph(&pattern, &ntd, &target), Some(1 << 10));
+
+        // the weights of the vertices and the edge of a non-empty root bag are multiplied at the root
+        let from_graph = import_metis_multigraph("data/metis_graphs/multigraph_tests/double_edge.graph").unwrap();
+        let to_graph = import_metis_multigraph("data/metis_graphs/multigraph_tests/triple_edge_with_loop.graph").unwrap();
+        let ntd = read_ntd("s 2 2 2\nn 1 l 1\nn 2 i 1 2\na 2 1\n".as_bytes()).unwrap();
+        assert_eq!(diaz_serna_thilikos_multigraph(&from_graph, &ntd, &to_graph), Some(19));
+    }
+}
+
+#[cfg(test)]
+pub mod loop_semantics_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force_with_loop_semantics;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_with_loop_semantics;
+    use crate::file_handler::graph_handler::{import_metis, import_metis_with_loop_semantics};
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::loop_semantics::loop_semantics_methods::{GraphRole, LoopSemantics};
+    use crate::modified_dp::algorithm::modified_dp_with_loop_semantics;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    #[test]
+    fn test_loop_semantics(){
+        let ntd = import_ntd("data/Experiments/ntds/ntd_path_2.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        // an edge with a loop at one of its endpoints
+        let mut from_graph : Graph = Graph::default();
+        from_graph.add_node(());
+        from_graph.add_node(());
+        from_graph.add_edge(Vertex::new(0), Vertex::new(1), ());
+        from_graph.add_edge(Vertex::new(0), Vertex::new(0), ());
+
+        // the complete graph on 5 vertices has no loops
+        assert_eq!(simple_brute_force_with_loop_semantics(&from_graph, &to_graph, LoopSemantics::Respect), 0);
+        assert_eq!(simple_brute_force_with_loop_semantics(&from_graph, &to_graph, LoopSemantics::Ignore), 20);
+        assert_eq!(simple_brute_force_with_loop_semantics(&from_graph, &to_graph, LoopSemantics::Reflexive), 25);
+
+        for loop_semantics in [LoopSemantics::Respect, LoopSemantics::Ignore, LoopSemantics::Reflexive]{
+            assert_eq!(diaz_serna_thilikos_with_loop_semantics(&from_graph, &ntd, &to_graph, loop_semantics),
+                       simple_brute_force_with_loop_semantics(&from_graph, &to_graph, loop_semantics));
+
+            for (graph, hom_number) in modified_dp_with_loop_semantics(&ntd, &to_graph, loop_semantics){
+                assert_eq!(hom_number, simple_brute_force_with_loop_semantics(&graph, &to_graph, loop_semantics));
+            }
+        }
+
+        // importers normalize loops, reflexive semantics only add loops to targets
+        let graph = import_metis_with_loop_semantics("data/metis_graphs/handmade/to_2.graph", LoopSemantics::Reflexive, GraphRole::Target).unwrap();
+        assert_eq!(graph.edge_count(), 15);
+        let graph = import_metis_with_loop_semantics("data/metis_graphs/handmade/to_2.graph", LoopSemantics::Reflexive, GraphRole::Pattern).unwrap();
+        assert_eq!(graph.edge_count(), 10);
+        let graph = import_metis_with_loop_semantics("data/metis_graphs/handmade/to_2.graph", LoopSemantics::Ignore, GraphRole::Target).unwrap();
+        assert_eq!(graph.edge_count(), 10);
+    }
+}
+
+#[cfg(test)]
+pub mod capacitated_tests{
+    use crate::capacitated::capacitated_algorithms::{capacitated_brute_force, capacitated_diaz_serna_thilikos};
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+
+    #[test]
+    fn test_capacitated_counting(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        // capacities which never restrict equal the usual number of homomorphisms
+        assert_eq!(capacitated_diaz_serna_thilikos(&from_graph, &ntd, &to_graph, &[5,5,5,5,5]), 1280);
+        assert_eq!(capacitated_brute_force(&from_graph, &to_graph, &[5,5,5,5,5]), 1280);
+
+        // capacity one everywhere only allows injective homomorphisms
+        assert_eq!(capacitated_diaz_serna_thilikos(&from_graph, &ntd, &to_graph, &[1,1,1,1,1]), 120);
+
+        for capacities in [[1,1,2,1,1], [0,2,2,1,3], [2,2,2,2,2]]{
+            assert_eq!(capacitated_diaz_serna_thilikos(&from_graph, &ntd, &to_graph, &capacities),
+                       capacitated_brute_force(&from_graph, &to_graph, &capacities));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_capacitated_missing_capacities(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        // the last vertex of the target has no capacity
+        capacitated_diaz_serna_thilikos(&from_graph, &ntd, &to_graph, &[5,5,5,5]);
+    }
+}
+
+#[cfg(test)]
+pub mod induced_subgraph_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::graph_ops::graph_operations::induced_subgraph;
+    use crate::induced_subgraphs::induced_subgraph_algorithm::induced_subgraph_counts;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_induced_subgraph_counts(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_3.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        let counts = induced_subgraph_counts(&from_graph, &ntd, &to_graph);
+        assert_eq!(counts.len(), 32);
+
+        for (vertex_set, hom_number) in counts{
+            let vertices : Vec<usize> = (0..from_graph.node_count()).filter(|v| vertex_set & (1 << v) != 0).collect();
+            let induced = induced_subgraph(&from_graph, &vertices);
+
+            assert_eq!(hom_number, simple_brute_force(&induced, &to_graph));
+        }
+
+        // into a graph without edges only the independent sets have homomorphisms, but every subset is counted
+        let to_graph = graph_from_edges(3, &[]);
+        let counts = induced_subgraph_counts(&from_graph, &ntd, &to_graph);
+        assert_eq!(counts.len(), 32);
+        assert_eq!(counts[&0], 1);
+        assert_eq!(counts[&0b11111], 0);
+    }
+}
+
+#[cfg(test)]
+pub mod quantum_graph_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::modified_dp::algorithm::{edges_to_graph, graph_to_edges};
+    use crate::quantum_graphs::quantum_graph::QuantumGraph;
+    use crate::tree_decompositions::tree_structure::Vertex;
+
+    #[test]
+    fn test_quantum_graph(){
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+
+        let from_2 = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+
+        // graph_to_edges is the inverse of edges_to_graph
+        let edges = graph_to_edges(&ntd, &from_2).unwrap();
+        assert_eq!(graph_to_edges(&ntd, &edges_to_graph(&ntd, edges)), Some(edges));
+
+        let mut quantum_graph = QuantumGraph::new(&ntd);
+        assert!(quantum_graph.add_graph(&from_2, 3));
+        quantum_graph.add_term(0, -2);
+        assert_eq!(quantum_graph.coefficient(edges), 3);
+
+        // 3 * hom(from_2, G) - 2 * hom(empty graph on 5 vertices, G)
+        let expected = 3 * diaz_serna_thilikos_algorithm(&from_2, &ntd, &to_graph) as i128 - 2 * 5_i128.pow(5);
+        assert_eq!(quantum_graph.evaluate(&to_graph), expected);
+
+        // the edge (0,4) is not a possible edge of ntd_bench_8
+        let mut graph = edges_to_graph(&ntd, 0);
+        graph.add_edge(Vertex::new(0), Vertex::new(4), ());
+        assert_eq!(graph_to_edges(&ntd, &graph), None);
+        assert!(!quantum_graph.add_graph(&graph, 1));
+        assert_eq!(quantum_graph.terms().len(), 2);
+    }
+}
+
+#[cfg(test)]
+pub mod color_refinement_tests{
+    use crate::color_refinement::weisfeiler_leman::{color_refinement, feasible_images, indistinguishable_by_color_refinement};
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_with_options, DiazOptions};
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_color_refinement(){
+        // endpoints and inner vertices of a path get different colors
+        let path = graph_from_edges(4, &[(0,1), (1,2), (2,3)]);
+        let colors = color_refinement(&path);
+        assert_eq!(colors[0], colors[3]);
+        assert_eq!(colors[1], colors[2]);
+        assert_ne!(colors[0], colors[1]);
+
+        // the complete graph is colored uniformly
+        let colors = color_refinement(&import_metis("data/metis_graphs/handmade/to_2.graph").unwrap());
+        assert!(colors.iter().all(|&c| c == colors[0]));
+
+        // color refinement cannot distinguish a 6-cycle from two triangles
+        let cycle = graph_from_edges(6, &[(0,1), (1,2), (2,3), (3,4), (4,5), (5,0)]);
+        let triangles = graph_from_edges(6, &[(0,1), (1,2), (2,0), (3,4), (4,5), (5,3)]);
+        assert!(indistinguishable_by_color_refinement(&cycle, &triangles));
+
+        // but it distinguishes a 6-cycle from a path and isomorphic graphs are never distinguished
+        let path = graph_from_edges(6, &[(0,1), (1,2), (2,3), (3,4), (4,5)]);
+        let relabeled_path = graph_from_edges(6, &[(3,1), (1,5), (5,0), (0,4), (4,2)]);
+        assert!(!indistinguishable_by_color_refinement(&cycle, &path));
+        assert!(indistinguishable_by_color_refinement(&path, &relabeled_path));
+    }
+
+    #[test]
+    fn test_image_pruning(){
+        // vertex 0 of the pattern has a loop and the target has only a loop at vertex 0, hence
+        // vertex 1 of the pattern can only be mapped onto the neighbours of 0
+        let pattern = graph_from_edges(3, &[(0,0), (0,1), (1,2)]);
+        let target = graph_from_edges(4, &[(0,0), (0,1), (1,2), (2,3)]);
+
+        let feasible = feasible_images(&pattern, &target);
+        assert_eq!(feasible[0], vec![true, false, false, false]);
+        assert_eq!(feasible[1], vec![true, true, false, false]);
+        assert_eq!(feasible[2], vec![true, true, true, false]);
+
+        // pruning does not change the number of homomorphisms
+        let options = DiazOptions { image_pruning: true, ..Default::default() };
+        for (from, to, ntd, expected) in [("from_2", "to_2", "ntd_bench_8", 1280), ("from_3", "to_3", "ntd_bench_8", 256), ("from_7", "to_2", "ntd_bench_6", 960)]{
+            let from_graph = import_metis(format!("data/metis_graphs/handmade/{}.graph", from)).unwrap();
+            let to_graph = import_metis(format!("data/metis_graphs/handmade/{}.graph", to)).unwrap();
+            let ntd = import_ntd(format!("data/nice_tree_decompositions/benchmark_ntds/handmade/{}.ntd", ntd)).unwrap();
+            assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &options), expected);
+        }
+
+        let from_graph = import_metis("data/metis_graphs/handmade/from_5.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/bench_1.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_7.ntd").unwrap();
+        assert_eq!(diaz_serna_thilikos_with_options(&from_graph, &ntd, &to_graph, &options), 0);
+    }
+}
+
+#[cfg(test)]
+pub mod symmetry_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::{simple_brute_force, simple_brute_force_for_ntd_set};
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::symmetry::symmetry_reduction::{automorphisms, symmetric_brute_force, symmetric_brute_force_for_ntd_set};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_automorphisms(){
+        let cycle = graph_from_edges(6, &[(0,1), (1,2), (2,3), (3,4), (4,5), (5,0)]);
+        assert_eq!(automorphisms(&cycle).len(), 12);
+
+        let clique = graph_from_edges(4, &[(0,1), (0,2), (0,3), (1,2), (1,3), (2,3)]);
+        assert_eq!(automorphisms(&clique).len(), 24);
+
+        let path = graph_from_edges(4, &[(0,1), (1,2), (2,3)]);
+        assert_eq!(automorphisms(&path), vec![vec![0,1,2,3], vec![3,2,1,0]]);
+
+        // a loop breaks the symmetry of the path
+        let path_with_loop = graph_from_edges(4, &[(0,0), (0,1), (1,2), (2,3)]);
+        assert_eq!(automorphisms(&path_with_loop), vec![vec![0,1,2,3]]);
+    }
+
+    #[test]
+    fn test_symmetric_brute_force(){
+        // hom(C_6, K_3) = 2^6 + 2
+        let cycle = graph_from_edges(6, &[(0,1), (1,2), (2,3), (3,4), (4,5), (5,0)]);
+        let triangle = graph_from_edges(3, &[(0,1), (1,2), (2,0)]);
+        assert_eq!(symmetric_brute_force(&cycle, &triangle), 66);
+
+        for (from, to, expected) in [("from_2", "to_2", 1280), ("from_3", "to_3", 256), ("from_7", "to_2", 960)]{
+            let from_graph = import_metis(format!("data/metis_graphs/handmade/{}.graph", from)).unwrap();
+            let to_graph = import_metis(format!("data/metis_graphs/handmade/{}.graph", to)).unwrap();
+            assert_eq!(symmetric_brute_force(&from_graph, &to_graph), expected);
+            assert_eq!(symmetric_brute_force(&from_graph, &to_graph), simple_brute_force(&from_graph, &to_graph));
+        }
+
+        let ntd = import_ntd("data/Experiments/ntds/ntd_path_3.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_3.graph").unwrap();
+        let symmetric_counts : Vec<u64> = symmetric_brute_force_for_ntd_set(&ntd, &to_graph).iter().map(|(_, count)| *count).collect();
+        let simple_counts : Vec<u64> = simple_brute_force_for_ntd_set(&ntd, &to_graph).iter().map(|(_, count)| *count).collect();
+        assert_eq!(symmetric_counts, simple_counts);
+    }
+}
+
+#[cfg(test)]
+pub mod incremental_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::incremental::incremental_counting::{edge_deletion_profile, IncrementalCounter};
+    use crate::tree_decompositions::tree_structure::Vertex;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_incremental_counter(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        let mut counter = IncrementalCounter::new(&from_graph, &ntd, &to_graph);
+        assert_eq!(counter.count(), 1280);
+
+        // remove and insert edges and loops of the target one after another
+        let updates = [(false, 0, 1), (false, 2, 3), (true, 0, 0), (false, 1, 4), (true, 3, 3), (true, 0, 1), (false, 0, 0), (true, 1, 4), (true, 2, 3)];
+        for (insert, a, b) in updates{
+            let hom_number = if insert { counter.insert_edge(a, b) } else { counter.remove_edge(a, b) };
+            assert_eq!(hom_number, diaz_serna_thilikos_algorithm(&from_graph, &ntd, counter.to_graph()));
+        }
+
+        // inserting an existing edge does not change anything
+        assert_eq!(counter.insert_edge(2, 3), counter.count());
+
+        // loops of the pattern are affected by loops of the target
+        let from_graph = graph_from_edges(3, &[(0,0), (0,1), (1,2)]);
+        let ntd = import_ntd("data/Experiments/ntds/ntd_path_3.ntd").unwrap();
+        let mut counter = IncrementalCounter::new(&from_graph, &ntd, &to_graph);
+        assert_eq!(counter.count(), 0);
+
+        for (a, b) in [(0, 0), (3, 3), (0, 3)]{
+            assert_eq!(counter.insert_edge(a, b), diaz_serna_thilikos_algorithm(&from_graph, &ntd, counter.to_graph()));
+        }
+        assert_ne!(counter.count(), 0);
+        assert_eq!(counter.remove_edge(0, 0), diaz_serna_thilikos_algorithm(&from_graph, &ntd, counter.to_graph()));
+    }
+
+    #[test]
+    fn test_edge_deletion_profile(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 4)]);
+
+        let profile = edge_deletion_profile(&from_graph, &ntd, &to_graph);
+        let edges : Vec<(usize, usize)> = profile.iter().map(|&(edge, _)| edge).collect();
+        assert_eq!(edges, vec![(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (4, 4)]);
+
+        for ((a, b), hom_number) in profile{
+            let mut edge_deleted = to_graph.clone();
+            edge_deleted.remove_edge(Vertex::new(a), Vertex::new(b));
+            assert_eq!(hom_number, diaz_serna_thilikos_algorithm(&from_graph, &ntd, &edge_deleted));
+        }
+
+        // the target and the count are restored afterwards
+        let mut counter = IncrementalCounter::new(&from_graph, &ntd, &to_graph);
+        let count = counter.count();
+        counter.deletion_counts();
+        assert_eq!(counter.count(), count);
+        assert_eq!(counter.to_graph().edge_count(), to_graph.edge_count());
+    }
+}
+
+#[cfg(test)]
+pub mod prepared_pattern_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::modified_dp::algorithm::modified_dp_counts;
+    use crate::prepared_pattern::pattern_preparation::PreparedPattern;
+
+    #[test]
+    fn test_prepared_pattern(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let pattern = PreparedPattern::new(from_graph.clone(), ntd.clone());
+
+        assert_eq!(pattern.possible_edges().len(), 9);
+
+        // the same pattern is counted into several targets
+        let to_2 = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let to_3 = import_metis("data/metis_graphs/handmade/to_3.graph").unwrap();
+        let bench_1 = import_metis("data/metis_graphs/bench_1.graph").unwrap();
+        assert_eq!(pattern.count(&to_2), 1280);
+
+        let expected : Vec<u64> = [&to_2, &to_3, &bench_1].iter().map(|to_graph| diaz_serna_thilikos_algorithm(&from_graph, &ntd, to_graph)).collect();
+        assert_eq!(pattern.count_all(&[&to_2, &to_3, &bench_1]), expected);
+
+        // the edges of the pattern are the key of the pattern in the equivalence class algorithm
+        let counts = modified_dp_counts(&ntd, &to_3);
+        assert_eq!(counts.get(&pattern.edges().unwrap()), Some(&pattern.count(&to_3)));
+    }
+}
+
+#[cfg(test)]
+pub mod hom_counts_tests{
+    use std::fs;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::hom_counts::hom_count_results::HomCounts;
+    use crate::modified_dp::algorithm::modified_dp;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_write_csv_and_json(){
+        let counts = HomCounts::new(vec![(graph_from_edges(3, &[(0,1), (1,2), (2,2)]), 42), (graph_from_edges(2, &[]), 7)]);
+        assert_eq!(counts.len(), 2);
+
+        let csv_path = std::env::temp_dir().join(format!("hom_counts_test_{}.csv", std::process::id()));
+        counts.write_csv(&csv_path).unwrap();
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv, "vertices,edges,count\n3,0-1 1-2 2-2,42\n2,,7\n");
+
+        let json_path = std::env::temp_dir().join(format!("hom_counts_test_{}.json", std::process::id()));
+        counts.write_json(&json_path).unwrap();
+        let json = fs::read_to_string(&json_path).unwrap();
+        assert_eq!(json, "[\n  {\"vertices\": 3, \"edges\": [[0,1],[1,2],[2,2]], \"count\": 42},\n  {\"vertices\": 2, \"edges\": [], \"count\": 7}\n]\n");
+
+        // the results of the ntd set algorithms can be exported directly
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_3.graph").unwrap();
+        let counts = HomCounts::from(modified_dp(&ntd, &to_graph));
+        counts.write_csv(&csv_path).unwrap();
+        assert_eq!(fs::read_to_string(&csv_path).unwrap().lines().count(), 1 + counts.len());
+
+        fs::remove_file(csv_path).unwrap();
+        fs::remove_file(json_path).unwrap();
+    }
+}
+
+#[cfg(test)]
+pub mod experiment_record_tests{
+    use std::fs;
+    use std::time::Duration;
+    use crate::cost_estimation::cost_model::{estimate_modified_dp, CostEstimate};
+    use crate::experiments::single_running_time_measurement::{EnvironmentInfo, ExperimentPaths, ExperimentRecord, Feasibility, GraphData, graph_data, measure_running_time, screen_experiment, ScreeningLimits, measure_running_time_parallel, merge_result_shards, read_records_csv, run_running_time_experiment, run_running_time_experiment_shard, Shard, write_records_csv, write_records_json};
+    use crate::modified_dp::algorithm::modified_dp;
+    use crate::hom_counts::hom_count_results::HomCounts;
+    use crate::unit_tests::graph_from_edges;
+
+    fn record_with_durations(durations : &[u64]) -> ExperimentRecord{
+        ExperimentRecord {
+            algorithm: "modified_dp".to_string(),
+            experiment: "test".to_string(),
+            ntd_name: "ntd_path_3.ntd".to_string(),
+            width: 1,
+            v_t: 6,
+            e_tau: 5,
+            v_tau: 3,
+            graph_name: "graph.graph".to_string(),
+            v_g: 4,
+            e_g: 3,
+            durations: durations.iter().map(|&micros| Duration::from_micros(micros)).collect(),
+            counts: HomCounts::new(vec![]),
+        }
+    }
+
+    #[test]
+    fn test_experiment_record_sinks(){
+        let record = record_with_durations(&[30, 10, 20]);
+        assert_eq!(record.average(), Duration::from_micros(20));
+        assert_eq!(record.minimum(), Duration::from_micros(10));
+        assert_eq!(record.maximum(), Duration::from_micros(30));
+
+        let csv_path = std::env::temp_dir().join(format!("experiment_record_test_{}.csv", std::process::id()));
+        let _ = fs::remove_file(&csv_path);
+        write_records_csv(&[record], &csv_path).unwrap();
+        write_records_csv(&[record_with_durations(&[5])], &csv_path).unwrap();
+
+        // the header is only written once
+        assert_eq!(fs::read_to_string(&csv_path).unwrap(),
+                   "schema_version,algorithm,experiment,ntd,width,v_t,e_tau,v_tau,graph,v_g,e_g,durations,average\n\
+                    2,modified_dp,test,ntd_path_3.ntd,1,6,5,3,graph.graph,4,3,30 10 20,20\n\
+                    2,modified_dp,test,ntd_path_3.ntd,1,6,5,3,graph.graph,4,3,5,5\n");
+
+        let records = read_records_csv(&csv_path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].experiment, "test");
+        assert_eq!(records[0].durations, record_with_durations(&[30, 10, 20]).durations);
+        assert_eq!(records[1].average(), Duration::from_micros(5));
+
+        // result files of schema version 1 have no header and one column per run
+        fs::write(&csv_path, "modified_dp,ntd_path_3.ntd,1,6,5,3,graph.graph,4,3,30,10,20,20\n").unwrap();
+        let records = read_records_csv(&csv_path).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].experiment, "");
+        assert_eq!((records[0].graph_name.as_str(), records[0].v_g, records[0].e_g), ("graph.graph", 4, 3));
+        assert_eq!(records[0].durations, record_with_durations(&[30, 10, 20]).durations);
+
+        fs::write(&csv_path, "3,modified_dp\n").unwrap();
+        assert!(read_records_csv(&csv_path).is_err());
+
+        let json_path = std::env::temp_dir().join(format!("experiment_record_test_{}.json", std::process::id()));
+        let mut record = record_with_durations(&[5]);
+        record.graph_name = "C:\\graphs\\\"quoted\"\n.graph".to_string();
+        write_records_json(&[record, record_with_durations(&[])], &json_path).unwrap();
+        let json = fs::read_to_string(&json_path).unwrap();
+        assert!(json.contains("\"durations\": [5], \"average\": 5, \"minimum\": 5, \"maximum\": 5"));
+        assert!(json.contains("\"graph\": \"C:\\\\graphs\\\\\\\"quoted\\\"\\n.graph\""));
+        assert!(json.contains("\"durations\": [], \"average\": 0, \"minimum\": 0, \"maximum\": 0"));
+
+        fs::remove_file(csv_path).unwrap();
+        fs::remove_file(json_path).unwrap();
+    }
+
+    #[test]
+    fn test_graph_data(){
+        // a triangle with a pendant vertex and an isolated vertex
+        let graph = graph_from_edges(5, &[(0,1), (1,2), (0,2), (2,3)]);
+        assert_eq!(graph_data(&graph), GraphData { v_g: 5, e_g: 4, density: 0.4, max_degree: 3, components: 2 });
+
+        assert_eq!(graph_data(&graph_from_edges(0, &[])), GraphData { v_g: 0, e_g: 0, density: 0.0, max_degree: 0, components: 0 });
+    }
+
+    #[test]
+    fn test_screen_experiment(){
+        let matrix_path = std::env::temp_dir().join("screening_test.csv");
+        fs::write(&matrix_path, ",randgraph_4_5.graph,randgraph_512_25778.graph\nntd_path_3.ntd,1,1\n").unwrap();
+
+        let limits = ScreeningLimits { memory_bytes: 1e6, operations: 1e9 };
+        let records = screen_experiment(&matrix_path, estimate_modified_dp, &limits, &ExperimentPaths::default()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].feasibility, Feasibility::Feasible);
+        assert_eq!(records[1].feasibility, Feasibility::Infeasible);
+
+        // estimates between a tenth of the limit and the limit are risky
+        assert_eq!(limits.classify(&CostEstimate { peak_entries: 10.0, operations: 5e8 }), Feasibility::Risky);
+
+        fs::remove_file(matrix_path).unwrap();
+    }
+
+    #[test]
+    fn test_experiment_paths(){
+        let directory = std::env::temp_dir().join("experiment_paths_test");
+        let _ = fs::remove_dir_all(&directory);
+
+        let matrix_path = std::env::temp_dir().join("experiment_paths_test.csv");
+        fs::write(&matrix_path, ",randgraph_4_5.graph\nntd_path_3.ntd,1\n").unwrap();
+
+        // the nested result directory is created
+        let paths = ExperimentPaths::with_result_directory(directory.join("nested"));
+        run_running_time_experiment(&matrix_path, modified_dp, &"modified_dp".to_string(), &paths).unwrap();
+        assert!(directory.join("nested/modified_dp_experiment_paths_test_results.csv").exists());
+        assert!(directory.join("nested/modified_dp_experiment_paths_test_ntd_path_3_randgraph_4_5_counts.csv").exists());
+
+        // missing inputs and malformed matrices are reported with their path
+        let paths = ExperimentPaths { ntd_directory: directory.join("missing"), ..ExperimentPaths::default() };
+        let error = measure_running_time(&matrix_path, modified_dp, &"modified_dp".to_string(), &paths).err().unwrap();
+        assert!(error.to_string().contains("missing"));
+
+        fs::write(&matrix_path, ",randgraph_4_5.graph\nntd_path_3.ntd,x\n").unwrap();
+        let error = measure_running_time(&matrix_path, modified_dp, &"modified_dp".to_string(), &ExperimentPaths::default()).err().unwrap();
+        assert!(error.to_string().contains("is not a number"));
+
+        fs::remove_file(matrix_path).unwrap();
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_environment_sidecar(){
+        let mut info = EnvironmentInfo::collect();
+        assert!(info.cores >= 1);
+        assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+
+        // the sidecar replaces the extension of the results file
+        // non-ascii characters are written as they are, control characters are escaped
+        info.cpu_model = "Ryzen™ 7\tPRO".to_string();
+        let results_path = std::env::temp_dir().join(format!("environment_test_results_{}.csv", std::process::id()));
+        info.write_sidecar(&results_path).unwrap();
+        let sidecar_path = std::env::temp_dir().join(format!("environment_test_results_{}.env.json", std::process::id()));
+        let json = fs::read_to_string(&sidecar_path).unwrap();
+        assert!(json.starts_with("{\n  \"hostname\": "));
+        assert!(json.contains(&format!("\"cores\": {},", info.cores)));
+        assert!(json.contains("\"cpu_model\": \"Ryzen™ 7\\tPRO\","));
+        assert!(json.contains(&format!("\"timestamp\": \"{}\"", info.timestamp)));
+
+        fs::remove_file(sidecar_path).unwrap();
+    }
+
+    #[test]
+    fn test_parallel_experiment(){
+        let matrix_path = std::env::temp_dir().join("parallel_experiment_test.csv");
+        fs::write(&matrix_path, ",randgraph_4_5.graph,randgraph_4_6.graph\nntd_path_3.ntd,1,1\ne_tau_modifying_path_10_0.ntd,0,1\n").unwrap();
+
+        let sequential = measure_running_time(&matrix_path, modified_dp, &"modified_dp".to_string(), &ExperimentPaths::default()).unwrap();
+        let mut reported = 0;
+        let parallel = measure_running_time_parallel(&matrix_path, modified_dp, &"modified_dp".to_string(), &ExperimentPaths::default(), 2, |_| reported += 1).unwrap();
+
+        // the records are returned in the order of the experiment matrix
+        assert_eq!(reported, 3);
+        assert_eq!(parallel.len(), 3);
+        for (a, b) in sequential.iter().zip(&parallel){
+            assert_eq!((&a.ntd_name, &a.graph_name), (&b.ntd_name, &b.graph_name));
+            // the order of the patterns of modified_dp is not fixed
+            let mut counts_a : Vec<u64> = a.counts.entries().iter().map(|(_, count)| *count).collect();
+            let mut counts_b : Vec<u64> = b.counts.entries().iter().map(|(_, count)| *count).collect();
+            counts_a.sort_unstable();
+            counts_b.sort_unstable();
+            assert_eq!(counts_a, counts_b);
+        }
+        assert_eq!(parallel[2].ntd_name, "e_tau_modifying_path_10_0.ntd");
+
+        fs::remove_file(matrix_path).unwrap();
+    }
+
+    #[test]
+    fn test_sharded_experiment(){
+        let directory = std::env::temp_dir().join("sharded_experiment_test");
+        let _ = fs::remove_dir_all(&directory);
+        let matrix_path = std::env::temp_dir().join("sharded_experiment_test.csv");
+        fs::write(&matrix_path, ",randgraph_4_5.graph,randgraph_4_6.graph\nntd_path_3.ntd,1,1\ne_tau_modifying_path_10_0.ntd,0,1\n").unwrap();
+        let paths = ExperimentPaths::with_result_directory(&directory);
+        let alg_name = "modified_dp".to_string();
+
+        assert!(Shard::new(1, 2).contains(3));
+        assert!(!Shard::new(1, 2).contains(2));
+
+        // the first shard measures the cells 0 and 2, the second one the cell 1
+        let first = run_running_time_experiment_shard(&matrix_path, modified_dp, &alg_name, &paths, Shard::new(0, 2)).unwrap();
+        assert_eq!(first, directory.join("modified_dp_sharded_experiment_test_results_shard_0_of_2.csv"));
+        assert_eq!(read_records_csv(&first).unwrap().len(), 2);
+        assert!(directory.join("modified_dp_sharded_experiment_test_results_shard_0_of_2.env.json").exists());
+
+        // a missing shard is reported
+        let error = merge_result_shards(&matrix_path, &alg_name, &paths).err().unwrap();
+        assert!(error.to_string().contains("shard 1 of 2 is missing"));
+
+        run_running_time_experiment_shard(&matrix_path, modified_dp, &alg_name, &paths, Shard::new(1, 2)).unwrap();
+        let merged = merge_result_shards(&matrix_path, &alg_name, &paths).unwrap();
+        let cells : Vec<(&str, &str)> = merged.iter().map(|record| (record.ntd_name.as_str(), record.graph_name.as_str())).collect();
+        assert_eq!(cells, vec![("ntd_path_3.ntd", "randgraph_4_5.graph"), ("ntd_path_3.ntd", "randgraph_4_6.graph"),
+                               ("e_tau_modifying_path_10_0.ntd", "randgraph_4_6.graph")]);
+        assert_eq!(read_records_csv(directory.join("modified_dp_sharded_experiment_test_results.csv")).unwrap().len(), 3);
+
+        // rerunning a shard replaces its results, a cell in two shards is reported
+        run_running_time_experiment_shard(&matrix_path, modified_dp, &alg_name, &paths, Shard::new(1, 2)).unwrap();
+        assert_eq!(merge_result_shards(&matrix_path, &alg_name, &paths).unwrap().len(), 3);
+        fs::copy(&first, directory.join("modified_dp_sharded_experiment_test_results_shard_1_of_2.csv")).unwrap();
+        let error = merge_result_shards(&matrix_path, &alg_name, &paths).err().unwrap();
+        assert!(error.to_string().contains("measured twice"));
+
+        fs::remove_file(matrix_path).unwrap();
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod result_database_tests{
+    use std::time::Duration;
+    use crate::experiments::single_running_time_measurement::ExperimentRecord;
+    use crate::hom_counts::hom_count_results::HomCounts;
+    use crate::result_database::experiment_database::ResultDatabase;
+
+    fn record(graph_name : &str, durations : &[u64]) -> ExperimentRecord{
+        ExperimentRecord {
+            algorithm: "modified_dp".to_string(),
+            experiment: "test".to_string(),
+            ntd_name: "ntd_path_3.ntd".to_string(),
+            width: 1,
+            v_t: 6,
+            e_tau: 5,
+            v_tau: 3,
+            graph_name: graph_name.to_string(),
+            v_g: 4,
+            e_g: 3,
+            durations: durations.iter().map(|&micros| Duration::from_micros(micros)).collect(),
+            counts: HomCounts::new(vec![]),
+        }
+    }
+
+    #[test]
+    fn test_upsert(){
+        let mut database = ResultDatabase::open_in_memory().unwrap();
+        database.upsert_all(&[record("a.graph", &[10, 20]), record("b.graph", &[5])], "2022-01-01T00:00:00").unwrap();
+        assert_eq!(database.number_of_runs().unwrap(), 2);
+
+        // a rerun with the same timestamp replaces the run, a new timestamp adds a run
+        database.upsert(&record("a.graph", &[30, 50]), "2022-01-01T00:00:00").unwrap();
+        assert_eq!(database.number_of_runs().unwrap(), 2);
+        database.upsert(&record("a.graph", &[7]), "2022-01-02T00:00:00").unwrap();
+        assert_eq!(database.number_of_runs().unwrap(), 3);
+
+        let average : i64 = database.connection()
+            .query_row("SELECT average FROM runs WHERE graph = 'a.graph' AND timestamp = '2022-01-01T00:00:00'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(average, 40);
+
+        let fastest : i64 = database.connection()
+            .query_row("SELECT MIN(minimum) FROM runs WHERE graph = 'a.graph'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fastest, 7);
+    }
+}
+
+#[cfg(test)]
+pub mod cost_estimation_tests{
+    use crate::cost_estimation::cost_model::{estimate_brute_force_for_ntd_set, estimate_diaz_serna_thilikos, estimate_diaz_serna_thilikos_for_ntd_set, estimate_modified_dp, table_lifetimes, table_lifetimes_for_ordering};
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::unit_tests::ntd_test_example;
+
+    #[test]
+    fn test_estimates(){
+        // path 0-1-2 with loops: 2 leafs, introduce and forget nodes with bags of size at most 2
+        let ntd = import_ntd("data/Experiments/ntds/ntd_path_3.ntd").unwrap();
+
+        let diaz = estimate_diaz_serna_thilikos(&ntd, 10);
+        assert!(diaz.peak_entries >= 100.0 && diaz.peak_entries <= 200.0);
+        assert_eq!(estimate_diaz_serna_thilikos_for_ntd_set(&ntd, 10).operations, diaz.operations * 32.0);
+
+        // the modified dynamic program stores more entries than a single run of diaz et all
+        let modified = estimate_modified_dp(&ntd, 10);
+        assert!(modified.peak_entries > diaz.peak_entries);
+        assert!(modified.operations < estimate_diaz_serna_thilikos_for_ntd_set(&ntd, 10).operations);
+
+        assert_eq!(estimate_brute_force_for_ntd_set(&ntd, 10).operations, 32.0 * 1000.0);
+    }
+
+    #[test]
+    fn test_table_lifetimes(){
+        let ntd = ntd_test_example();
+
+        // a branch of the join node is retained while the other branch is computed
+        let lifetimes = table_lifetimes(&ntd, 10);
+        assert_eq!(lifetimes.steps.len(), 10);
+        assert_eq!(lifetimes.max_live_tables(), 3);
+        assert_eq!(lifetimes.peak_entries(), 120.0);
+        assert_eq!(lifetimes.steps.last().unwrap().live_tables, 2);
+
+        // interleaving both branches retains more tables
+        let interleaved = table_lifetimes_for_ordering(&ntd, &[0, 3, 1, 4, 2, 5, 6, 7, 8, 9], 10);
+        assert_eq!(interleaved.peak_entries(), 210.0);
+        assert_eq!(interleaved.peak_step().unwrap().node, 4);
+
+        // the path 0-1-2 retains at most the table of a node and of its child
+        let path = import_ntd("data/Experiments/ntds/ntd_path_3.ntd").unwrap();
+        assert_eq!(table_lifetimes(&path, 10).max_live_tables(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_table_lifetimes_invalid_ordering(){
+        table_lifetimes_for_ordering(&ntd_test_example(), &[1, 0, 2, 3, 4, 5, 6, 7, 8, 9], 10);
+    }
+}
+
+#[cfg(test)]
+pub mod consistency_tests{
+    use std::collections::HashMap;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_with_options, DiazOptions};
+    use crate::prepared_pattern::pattern_preparation::PreparedPattern;
+    use crate::symmetry::symmetry_reduction::symmetric_brute_force;
+    use crate::testing::consistency_testing::{assert_consistent_with_brute_force, assert_reference_algorithms_consistent, audit_node_table, brute_force_node_table, ConsistencyConfig, random_nice_tree_decomposition, TestRng};
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
+
+    #[test]
+    fn test_random_nice_tree_decompositions(){
+        let mut rng = TestRng::new(7);
+        for _ in 0..100{
+            let ntd = random_nice_tree_decomposition(&mut rng, 6, 3, 2);
+            assert!(ntd.vertex_count() >= 1 && ntd.vertex_count() <= 6);
+            assert!(ntd.width() <= 2);
+            assert!(ntd.bag(ntd.root()).unwrap().is_empty());
+            assert_eq!(ntd.stingy_ordering().len() as u64, ntd.node_count());
+        }
+    }
+
+    #[test]
+    fn test_reference_algorithms(){
+        for seed in 0..4{
+            assert_reference_algorithms_consistent(&ConsistencyConfig { seed, ..ConsistencyConfig::default() });
+        }
+    }
+
+    #[test]
+    fn test_variants(){
+        let config = ConsistencyConfig { seed: 42, ..ConsistencyConfig::default() };
+        assert_consistent_with_brute_force(|from_graph, _, to_graph| symmetric_brute_force(from_graph, to_graph), &config);
+        assert_consistent_with_brute_force(|from_graph, ntd, to_graph| PreparedPattern::new(from_graph.clone(), ntd.clone()).count(to_graph), &config);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inconsistent_algorithm(){
+        assert_consistent_with_brute_force(|_, _, _| 1, &ConsistencyConfig::default());
+    }
+
+    #[test]
+    fn test_audited_diaz(){
+        // every table of the algorithm of diaz et all is verified by brute force, with and without image pruning
+        for image_pruning in [false, true]{
+            let options = DiazOptions { image_pruning, audit: true, ..DiazOptions::default() };
+            let config = ConsistencyConfig { seed: 3, instances: 20, ..ConsistencyConfig::default() };
+            assert_consistent_with_brute_force(|from_graph, ntd, to_graph| diaz_serna_thilikos_with_options(from_graph, ntd, to_graph, &options), &config);
+        }
+    }
+
+    #[test]
+    fn test_audit_node_table(){
+        let from_graph = graph_from_edges(4, &[(0, 1), (1, 2), (1, 3)]);
+        let ntd = ntd_test_example();
+        let to_graph = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let feasible = vec![vec![true; 3]; 4];
+
+        // the introduce node 1 with bag {0, 1} allows every mapping of an edge of the triangle
+        let table = brute_force_node_table(&from_graph, &ntd, &to_graph, 1, &feasible);
+        assert_eq!(table.len(), 6);
+        assert_eq!(audit_node_table(&from_graph, &ntd, &to_graph, 1, &feasible, table.clone()), Ok(()));
+
+        // the forget node 2 with bag {1} sums over the two images of vertex 0
+        let forget_table = brute_force_node_table(&from_graph, &ntd, &to_graph, 2, &feasible);
+        assert_eq!(forget_table, HashMap::from([(0, 2), (1, 2), (2, 2)]));
+
+        // a shifted index produces entries which are not homomorphisms
+        let shifted = table.iter().map(|(&f, &value)| ((f + 1) % 9, value));
+        let mismatch = audit_node_table(&from_graph, &ntd, &to_graph, 1, &feasible, shifted).unwrap_err();
+        assert_eq!(mismatch.node, 1);
+        assert_ne!(mismatch.expected, mismatch.actual);
+
+        // a missing entry is reported as well
+        let missing = table.iter().filter(|(&f, _)| f != 1).map(|(&f, &value)| (f, value));
+        let mismatch = audit_node_table(&from_graph, &ntd, &to_graph, 1, &feasible, missing).unwrap_err();
+        assert_eq!((mismatch.mapping, mismatch.expected, mismatch.actual), (1, 1, 0));
+    }
+}
+
+#[cfg(test)]
+pub mod relational_structure_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::relational_structures::relational_structure_algorithms::{brute_force_relational, diaz_serna_thilikos_relational, RelationalStructure};
+    use crate::testing::consistency_testing::{random_nice_tree_decomposition, TestRng};
+
+    #[test]
+    fn test_graphs_as_relational_structures(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        let from_structure = RelationalStructure::from_graph(&from_graph);
+        let to_structure = RelationalStructure::from_graph(&to_graph);
+
+        let hom_number = diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph);
+        assert_eq!(brute_force_relational(&from_structure, &to_structure), hom_number);
+        assert_eq!(diaz_serna_thilikos_relational(&from_structure, &ntd, &to_structure), hom_number);
+    }
+
+    #[test]
+    fn test_directed_path(){
+        // the directed path 0 -> 1 -> 2 has 2 homomorphisms into the directed cycle on two vertices
+        // and none into a single directed edge
+        let mut path = RelationalStructure::new(3, &[2]);
+        path.add_tuple(0, vec![0, 1]);
+        path.add_tuple(0, vec![1, 2]);
+
+        let mut cycle = RelationalStructure::new(2, &[2]);
+        cycle.add_tuple(0, vec![0, 1]);
+        cycle.add_tuple(0, vec![1, 0]);
+
+        let mut edge = RelationalStructure::new(2, &[2]);
+        edge.add_tuple(0, vec![0, 1]);
+
+        let ntd = import_ntd("data/Experiments/ntds/ntd_path_3.ntd").unwrap();
+        assert_eq!(brute_force_relational(&path, &cycle), 2);
+        assert_eq!(diaz_serna_thilikos_relational(&path, &ntd, &cycle), 2);
+        assert_eq!(diaz_serna_thilikos_relational(&path, &ntd, &edge), 0);
+    }
+
+    #[test]
+    fn test_random_hypergraphs(){
+        let mut rng = TestRng::new(7);
+
+        for _ in 0..30{
+            let ntd = random_nice_tree_decomposition(&mut rng, 5, 4, 1);
+
+            // a ternary and a binary relation whose tuples are drawn from the bags of the decomposition
+            let mut from_structure = RelationalStructure::new(ntd.vertex_count() as usize, &[3, 2]);
+            for p in 0..ntd.node_count(){
+                let mut bag : Vec<usize> = ntd.bag(p).unwrap().iter().map(|v| v.index()).collect();
+                bag.sort();
+                if bag.is_empty() || !rng.chance(0.3) { continue; }
+                let relation = rng.below(2);
+                let tuple = (0..3 - relation).map(|_| bag[rng.below(bag.len())]).collect();
+                from_structure.add_tuple(relation, tuple);
+            }
+
+            let universe_size = 1 + rng.below(3);
+            let mut to_structure = RelationalStructure::new(universe_size, &[3, 2]);
+            for a in 0..universe_size{
+                for b in 0..universe_size{
+                    if rng.chance(0.7) { to_structure.add_tuple(1, vec![a, b]); }
+                    for c in 0..universe_size{
+                        if rng.chance(0.6) { to_structure.add_tuple(0, vec![a, b, c]); }
+                    }
+                }
+            }
+
+            assert_eq!(diaz_serna_thilikos_relational(&from_structure, &ntd, &to_structure),
+                       brute_force_relational(&from_structure, &to_structure));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod elimination_ordering_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::elimination_orderings::elimination_ordering_methods::{chordalization, is_perfect_elimination_ordering, ordering_from_tree_decomposition, ordering_width, perfect_elimination_ordering, tree_decomposition_from_ordering};
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::tree_decompositions::tree_decomposition::TreeDecomposition;
+    use crate::tree_decompositions::tree_structure::Vertex;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_tree_decomposition_from_ordering(){
+        // a cycle of length 5 with a loop and an isolated vertex
+        let graph = graph_from_edges(6, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (2, 2)]);
+
+        let ordering = [0, 1, 2, 3, 4, 5];
+        assert_eq!(ordering_width(&graph, &ordering), 2);
+
+        let td = tree_decomposition_from_ordering(&graph, &ordering);
+        assert_eq!(td.width(), 2);
+        assert_eq!(td.validate(&graph), Ok(()));
+
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let hom_number = simple_brute_force(&graph, &to_graph);
+        assert_eq!(diaz_serna_thilikos_algorithm(&graph, &td.to_nice_tree_decomposition(), &to_graph), hom_number);
+    }
+
+    #[test]
+    fn test_perfect_elimination_ordering(){
+        let cycle = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(perfect_elimination_ordering(&cycle), None);
+
+        let chordal_graph = chordalization(&cycle, &[0, 1, 2, 3]);
+        assert!(chordal_graph.has_edge(Vertex::new(1), Vertex::new(3)));
+        assert!(is_perfect_elimination_ordering(&chordal_graph, &[0, 1, 2, 3]));
+        assert!(!is_perfect_elimination_ordering(&cycle, &[0, 1, 2, 3]));
+
+        let ordering = perfect_elimination_ordering(&chordal_graph).unwrap();
+        assert!(is_perfect_elimination_ordering(&chordal_graph, &ordering));
+        assert_eq!(ordering_width(&chordal_graph, &ordering), 2);
+    }
+
+    #[test]
+    fn test_ordering_from_tree_decomposition(){
+        let graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        let ordering = ordering_from_tree_decomposition(&TreeDecomposition::from(&ntd));
+        assert!(ordering_width(&graph, &ordering) <= ntd.width());
+
+        let td = tree_decomposition_from_ordering(&graph, &ordering);
+        assert_eq!(td.validate(&graph), Ok(()));
+    }
+}
+
+#[cfg(test)]
+pub mod pipeline_tests{
+    use std::path::PathBuf;
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::file_handler::graph_handler::{import_dimacs, import_metis};
+    use crate::graph_generation::graph_generation_algorithms::equal_graphs;
+    use crate::pipeline::counting_pipeline::{Algorithm, component_graphs, count_from_files, count_graphs, count_patterns_dir, detect_format, detect_line_format, GraphFormat, PipelineError, PipelineOptions};
+
+    const PATTERN: &str = "data/metis_graphs/handmade/from_2.graph";
+    const TARGET: &str = "data/metis_graphs/handmade/to_2.graph";
+
+    #[test]
+    fn test_count_from_files(){
+        let hom_number = simple_brute_force(&import_metis(PATTERN).unwrap(), &import_metis(TARGET).unwrap());
+
+        for algorithm in [Algorithm::BruteForce, Algorithm::DiazSernaThilikos, Algorithm::ModifiedDp, Algorithm::Auto]{
+            let options = PipelineOptions { algorithm, ..PipelineOptions::default() };
+            let result = count_from_files(PATTERN, TARGET, &options).unwrap();
+            assert_eq!(result.count, hom_number);
+            assert_eq!(result.algorithm, algorithm);
+            // the pattern is a tree, which the auto dispatcher counts without a nice tree decomposition
+            assert_eq!(result.width.is_none(), algorithm == Algorithm::BruteForce || algorithm == Algorithm::Auto);
+        }
+
+        let options = PipelineOptions {
+            algorithm: Algorithm::DiazSernaThilikos,
+            ntd_path: Some(PathBuf::from("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd")),
+            ..PipelineOptions::default()
+        };
+        assert_eq!(count_from_files(PATTERN, TARGET, &options).unwrap().count, hom_number);
+    }
+
+    #[test]
+    fn test_detect_format(){
+        assert_eq!(detect_format(PATTERN).unwrap(), GraphFormat::Metis);
+        assert_eq!(detect_format("data/dimacs_graphs/test_graph.gr").unwrap(), GraphFormat::Dimacs);
+
+        let pattern = "data/dimacs_graphs/test_graph.gr";
+        let hom_number = simple_brute_force(&import_dimacs(pattern).unwrap(), &import_metis(TARGET).unwrap());
+        assert_eq!(count_from_files(pattern, TARGET, &PipelineOptions::default()).unwrap().count, hom_number);
+    }
+
+    #[test]
+    fn test_detect_line_format(){
+        assert_eq!(detect_line_format("% comment"), None);
+        assert_eq!(detect_line_format("c comment"), None);
+        assert_eq!(detect_line_format(""), None);
+        assert_eq!(detect_line_format("p tw 7 11"), Some(GraphFormat::Dimacs));
+        assert_eq!(detect_line_format("7 11"), Some(GraphFormat::Metis));
+        assert_eq!(detect_line_format("D?{"), Some(GraphFormat::Graph6));
+
+        assert_eq!("graph6".parse::<GraphFormat>(), Ok(GraphFormat::Graph6));
+        assert_eq!("modified_dp".parse::<Algorithm>(), Ok(Algorithm::ModifiedDp));
+        assert_eq!("auto".parse::<Algorithm>(), Ok(Algorithm::Auto));
+        assert!("fastest".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn test_result_to_json(){
+        let options = PipelineOptions { algorithm: Algorithm::BruteForce, ..PipelineOptions::default() };
+        let json = count_from_files(PATTERN, TARGET, &options).unwrap().to_json();
+        assert!(json.starts_with("{\"count\": 1280, \"algorithm\": \"brute_force\", \"pattern_vertices\": 5, \"target_vertices\": 5, \"width\": null"));
+        assert!(json.ends_with(", \"shortcut\": null}"));
+    }
+
+    #[test]
+    fn test_pipeline_errors(){
+        let result = count_from_files("data/metis_graphs/handmade/missing.graph", TARGET, &PipelineOptions::default());
+        assert!(matches!(result, Err(PipelineError::Io(_, _))));
+
+        // the nice tree decomposition of a path does not cover the edges of the pattern
+        let options = PipelineOptions { algorithm: Algorithm::DiazSernaThilikos, ntd_path: Some(PathBuf::from("data/Experiments/ntds/ntd_path_3.ntd")), ..PipelineOptions::default() };
+        let result = count_from_files(PATTERN, TARGET, &options);
+        assert!(matches!(result, Err(PipelineError::InvalidDecomposition(_))));
+
+        let malformed = std::env::temp_dir().join(format!("malformed_pattern_{}.graph", std::process::id()));
+        std::fs::write(&malformed, "2 1\n2 x\n1\n").unwrap();
+        let result = count_from_files(&malformed, TARGET, &PipelineOptions::default());
+        std::fs::remove_file(&malformed).unwrap();
+        assert!(matches!(result, Err(PipelineError::InvalidFile(_))));
+    }
+
+    #[test]
+    fn test_count_per_component(){
+        use crate::unit_tests::graph_from_edges;
+
+        // a triangle, a path and an isolated vertex with a loop
+        let pattern = graph_from_edges(7, &[(0, 2), (2, 4), (4, 0), (1, 3), (3, 5), (6, 6)]);
+        let target = graph_from_edges(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]);
+
+        let components = component_graphs(&pattern);
+        assert_eq!(components.len(), 3);
+        assert!(equal_graphs(&components[0], &graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)])));
+        assert!(equal_graphs(&components[1], &graph_from_edges(3, &[(0, 1), (1, 2)])));
+        assert!(equal_graphs(&components[2], &graph_from_edges(1, &[(0, 0)])));
+
+        let hom_number = simple_brute_force(&pattern, &target);
+        // the path component is counted by the closed form of the auto dispatcher
+        for algorithm in [Algorithm::DiazSernaThilikos, Algorithm::ModifiedDp, Algorithm::Auto]{
+            let result = count_graphs(&pattern, &target, &PipelineOptions { algorithm, ..PipelineOptions::default() }).unwrap();
+            assert_eq!(result.count, hom_number);
+            assert_eq!(result.width, Some(2));
+        }
+
+        // the empty pattern has exactly one homomorphism
+        let result = count_graphs(&graph_from_edges(0, &[]), &target, &PipelineOptions::default()).unwrap();
+        assert_eq!((result.count, result.width), (1, None));
+    }
+
+    #[test]
+    fn test_count_overflow_of_components(){
+        use crate::unit_tests::graph_from_edges;
+
+        // each path on 40 vertices has 3 * 2^39 homomorphisms into a triangle, both together exceed u64
+        let edges : Vec<(usize, usize)> = (1..80).filter(|&v| v != 40).map(|v| (v - 1, v)).collect();
+        let pattern = graph_from_edges(80, &edges);
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        for algorithm in [Algorithm::DiazSernaThilikos, Algorithm::Auto]{
+            let result = count_graphs(&pattern, &triangle, &PipelineOptions { algorithm, ..PipelineOptions::default() });
+            assert!(matches!(result, Err(PipelineError::CountOverflow)));
+        }
+    }
+
+    #[test]
+    fn test_count_patterns_dir(){
+        let directory = std::env::temp_dir().join(format!("count_patterns_dir_test_{}", std::process::id()));
+        let cache = std::env::temp_dir().join(format!("count_patterns_dir_test_cache_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let _ = std::fs::remove_dir_all(&cache);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        std::fs::copy(PATTERN, directory.join("a.graph")).unwrap();
+        std::fs::copy("data/dimacs_graphs/test_graph.gr", directory.join("b.gr")).unwrap();
+        std::fs::write(directory.join("c.g6"), "???\n").unwrap();
+        std::fs::copy("data/Experiments/ntds/ntd_path_3.ntd", directory.join("d.ntd")).unwrap();
+        // a malformed pattern is reported by its error and the remaining patterns are still counted
+        std::fs::write(directory.join("e.graph"), "2 1\n2 x\n1\n").unwrap();
+        std::fs::copy(PATTERN, directory.join("f.graph")).unwrap();
+
+        let options = PipelineOptions { ntd_cache: Some(cache.clone()), ..PipelineOptions::default() };
+        let reports = count_patterns_dir(&directory, TARGET, &options).unwrap();
+
+        let names : Vec<String> = reports.iter().map(|report| report.path.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(names, vec!["a.graph", "b.gr", "c.g6", "e.graph", "f.graph"]);
+        assert_eq!(reports[0].result.as_ref().unwrap().count, count_from_files(PATTERN, TARGET, &PipelineOptions::default()).unwrap().count);
+        assert!(reports[2].result.is_err());
+        assert!(reports[2].to_json().contains("\"error\""));
+        assert!(matches!(&reports[3].result, Err(PipelineError::InvalidFile(path)) if path.ends_with("e.graph")));
+        assert_eq!(reports[4].result.as_ref().unwrap().count, reports[0].result.as_ref().unwrap().count);
+
+        // quotes, backslashes and line breaks of paths and errors are escaped
+        let report = crate::pipeline::counting_pipeline::PatternReport {
+            path: PathBuf::from("dir\\a\"b.graph"),
+            result: Err(PipelineError::InvalidFile(PathBuf::from("line\nbreak"))),
+        };
+        assert_eq!(report.to_json(), "{\"pattern\": \"dir\\\\a\\\"b.graph\", \"error\": \"could not parse line\\nbreak\"}");
+
+        // the decompositions of the valid patterns have been cached and are used in the second run
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 2);
+        let counts = |reports : &[crate::pipeline::counting_pipeline::PatternReport]| reports.iter().map(|report| report.result.as_ref().ok().map(|result| result.count)).collect::<Vec<_>>();
+        assert_eq!(counts(&count_patterns_dir(&directory, TARGET, &options).unwrap()), counts(&reports));
+
+        assert!(matches!(count_patterns_dir(directory.join("missing"), TARGET, &options), Err(PipelineError::Io(_, _))));
+        std::fs::remove_dir_all(&directory).unwrap();
+        std::fs::remove_dir_all(&cache).unwrap();
+    }
+}
+
+#[cfg(test)]
+pub mod structural_check_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::elimination_orderings::elimination_ordering_methods::{min_degree_ordering, tree_decomposition_from_ordering};
+    use crate::structural_checks::structural_shortcuts::{diaz_serna_thilikos_with_shortcuts, is_bipartite, odd_girth, structural_zero, ZeroReason};
+    use crate::unit_tests::graph_from_edges;
+    use crate::tree_decompositions::tree_structure::Graph;
+
+    /// Returns the cycle on n vertices.
+    fn cycle(n : usize) -> Graph{
+        let edges : Vec<(usize, usize)> = (0..n).map(|i| (i, (i + 1) % n)).collect();
+        graph_from_edges(n, &edges)
+    }
+
+    #[test]
+    fn test_odd_girth(){
+        assert_eq!(odd_girth(&cycle(4)), None);
+        assert_eq!(odd_girth(&cycle(5)), Some(5));
+        assert_eq!(odd_girth(&cycle(7)), Some(7));
+        assert_eq!(odd_girth(&graph_from_edges(2, &[(0, 1), (1, 1)])), Some(1));
+
+        // a 9-cycle with a chord creating a triangle
+        assert_eq!(odd_girth(&graph_from_edges(9, &[(0,1),(1,2),(2,3),(3,4),(4,5),(5,6),(6,7),(7,8),(8,0),(0,2)])), Some(3));
+
+        assert!(is_bipartite(&graph_from_edges(3, &[])));
+        assert!(is_bipartite(&cycle(6)));
+        assert!(!is_bipartite(&cycle(3)));
+    }
+
+    #[test]
+    fn test_structural_zero(){
+        assert_eq!(structural_zero(&cycle(5), &cycle(4)), Some(ZeroReason::OddCycleIntoBipartite));
+        assert_eq!(structural_zero(&cycle(5), &cycle(7)), Some(ZeroReason::OddGirth { pattern: 5, target: 7 }));
+        assert_eq!(structural_zero(&cycle(7), &cycle(5)), None);
+        assert_eq!(structural_zero(&cycle(4), &cycle(3)), None);
+
+        for (from_graph, to_graph) in [(cycle(5), cycle(4)), (cycle(5), cycle(7)), (cycle(7), cycle(5)), (cycle(3), cycle(3))]{
+            let ntd = tree_decomposition_from_ordering(&from_graph, &min_degree_ordering(&from_graph)).to_nice_tree_decomposition();
+            assert_eq!(diaz_serna_thilikos_with_shortcuts(&from_graph, &ntd, &to_graph), simple_brute_force(&from_graph, &to_graph));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod prefilter_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::capacitated::capacitated_algorithms::capacitated_brute_force;
+    use crate::pipeline::counting_pipeline::{count_graphs, PipelineOptions};
+    use crate::prefilter::feasibility_prefilter::{degree_sequence, loop_count, prefilter, Infeasibility};
+    use crate::structural_checks::structural_shortcuts::ZeroReason;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_degree_sequence(){
+        let star = graph_from_edges(4, &[(0, 1), (0, 2), (0, 3), (3, 3)]);
+        assert_eq!(degree_sequence(&star), vec![3, 1, 1, 1]);
+        assert_eq!(loop_count(&star), 1);
+    }
+
+    #[test]
+    fn test_prefilter(){
+        let edge = graph_from_edges(2, &[(0, 1)]);
+        let looped_edge = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        let star = graph_from_edges(4, &[(0, 1), (0, 2), (0, 3)]);
+        let path = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+
+        assert_eq!(prefilter(&edge, &graph_from_edges(0, &[]), false), Some(Infeasibility::EmptyTarget));
+        assert_eq!(prefilter(&graph_from_edges(0, &[]), &graph_from_edges(0, &[]), false), None);
+        assert_eq!(prefilter(&looped_edge, &path, false), Some(Infeasibility::LoopIntoLoopless));
+        assert_eq!(prefilter(&edge, &graph_from_edges(3, &[]), false), Some(Infeasibility::EdgeIntoEdgeless));
+        assert_eq!(prefilter(&edge, &graph_from_edges(1, &[(0, 0)]), false), None);
+
+        // the star maps onto the path, but not injectively
+        assert_eq!(prefilter(&star, &path, false), None);
+        assert_eq!(prefilter(&star, &path, true), Some(Infeasibility::DegreeSequence { position: 0, pattern: 3, target: 2 }));
+        assert_eq!(prefilter(&path, &edge, true), Some(Infeasibility::TooManyVertices { pattern: 4, target: 2 }));
+        assert_eq!(prefilter(&path, &star, true), Some(Infeasibility::DegreeSequence { position: 1, pattern: 2, target: 1 }));
+        assert_eq!(prefilter(&graph_from_edges(4, &[(0, 1), (2, 3)]), &graph_from_edges(4, &[(0, 1)]), true), Some(Infeasibility::TooManyEdges { pattern: 2, target: 1 }));
+        assert_eq!(prefilter(&graph_from_edges(2, &[(0, 0), (1, 1)]), &graph_from_edges(2, &[(0, 0)]), true), Some(Infeasibility::TooManyEdges { pattern: 2, target: 1 }));
+        assert_eq!(prefilter(&path, &path, true), None);
+        assert_eq!(capacitated_brute_force(&star, &path, &[1; 4]), 0);
+    }
+
+    #[test]
+    fn test_prefilter_in_pipeline(){
+        let looped_edge = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        let path = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+
+        let result = count_graphs(&looped_edge, &path, &PipelineOptions::default()).unwrap();
+        assert_eq!(result.count, simple_brute_force(&looped_edge, &path));
+        assert_eq!(result.shortcut, Some(ZeroReason::Infeasible(Infeasibility::LoopIntoLoopless)));
+        assert!(result.to_json().ends_with(", \"shortcut\": \"loop_into_loopless\"}"));
+
+        // a triangle does not map into the bipartite path
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let result = count_graphs(&triangle, &path, &PipelineOptions::default()).unwrap();
+        assert!(result.to_json().ends_with(", \"shortcut\": \"odd_cycle_into_bipartite\"}"));
+    }
+}
+
+#[cfg(test)]
+pub mod ntd_cache_tests{
+    use crate::ntd_cache::decomposition_cache::{default_directory, NtdCache, pattern_hash};
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_pattern_hash(){
+        let path = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        assert_eq!(pattern_hash(&path), pattern_hash(&graph_from_edges(3, &[(2, 1), (1, 0)])));
+        assert_ne!(pattern_hash(&path), pattern_hash(&graph_from_edges(3, &[(0, 1), (0, 2)])));
+        assert_ne!(pattern_hash(&path), pattern_hash(&graph_from_edges(4, &[(0, 1), (1, 2)])));
+        assert_ne!(pattern_hash(&path), pattern_hash(&graph_from_edges(3, &[(0, 1), (1, 2), (2, 2)])));
+    }
+
+    #[test]
+    fn test_get_or_compute(){
+        let cache = NtdCache::new(std::env::temp_dir().join("ntd_cache_test"));
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let _ = std::fs::remove_file(cache.path(&graph));
+
+        assert_eq!(cache.get(&graph), None);
+        let ntd = cache.get_or_compute(&graph, compute_nice_tree_decomposition);
+        assert_eq!(cache.get(&graph), Some(ntd.clone()));
+
+        // the cached decomposition is returned without computing it again
+        assert_eq!(cache.get_or_compute(&graph, |_| panic!("the decomposition has been cached")), ntd);
+    }
+
+    #[test]
+    fn test_inspect_and_clear(){
+        let cache = NtdCache::new(std::env::temp_dir().join("ntd_cache_inspection_test"));
+        let _ = std::fs::remove_dir_all(cache.directory());
+        assert_eq!(cache.entries().unwrap(), vec![]);
+        assert_eq!(cache.clear().unwrap(), 0);
+
+        let path = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        for graph in [&path, &triangle]{
+            cache.insert(graph, &compute_nice_tree_decomposition(graph)).unwrap();
+        }
+        std::fs::write(cache.directory().join("notes.txt"), "not a decomposition").unwrap();
+
+        let mut hashes = vec![pattern_hash(&path), pattern_hash(&triangle)];
+        hashes.sort();
+        let entries = cache.entries().unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.hash).collect::<Vec<_>>(), hashes);
+        assert_eq!(cache.size().unwrap(), entries.iter().map(|entry| std::fs::metadata(&entry.path).unwrap().len()).sum::<u64>());
+
+        assert!(cache.remove(&path).unwrap());
+        assert!(!cache.remove(&path).unwrap());
+        assert!(!cache.contains(&path) && cache.contains(&triangle));
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.directory().join("notes.txt").is_file());
+    }
+
+    #[test]
+    fn test_default_directory(){
+        assert!(default_directory().ends_with("counting_homomorphisms/ntd"));
+        assert_eq!(NtdCache::default().directory(), default_directory());
+    }
+}
+
+#[cfg(test)]
+pub mod chromatic_polynomial_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::chromatic::chromatic_polynomial_methods::chromatic_polynomial;
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_chromatic_polynomial(){
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let polynomial = chromatic_polynomial(&triangle, &compute_nice_tree_decomposition(&triangle));
+        assert_eq!(polynomial.coefficients(), &[0, 2, -3, 1]);
+        assert_eq!(polynomial.falling_factorial_coefficients(), &[0, 0, 0, 1]);
+        assert_eq!(polynomial.to_string(), "x^3 - 3x^2 + 2x");
+        assert_eq!(polynomial.chromatic_number(), Some(3));
+
+        // (x-1)^4 + (x-1)
+        let cycle = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let polynomial = chromatic_polynomial(&cycle, &compute_nice_tree_decomposition(&cycle));
+        assert_eq!(polynomial.coefficients(), &[0, -3, 6, -4, 1]);
+        assert_eq!(polynomial.chromatic_number(), Some(2));
+
+        let independent_set = graph_from_edges(2, &[]);
+        assert_eq!(chromatic_polynomial(&independent_set, &compute_nice_tree_decomposition(&independent_set)).to_string(), "x^2");
+
+        let looped = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        let polynomial = chromatic_polynomial(&looped, &compute_nice_tree_decomposition(&looped));
+        assert_eq!(polynomial.degree(), None);
+        assert_eq!(polynomial.to_string(), "0");
+        assert_eq!(polynomial.chromatic_number(), None);
+    }
+
+    #[test]
+    fn test_evaluate_equals_hom_into_complete_graphs(){
+        let pattern = graph_from_edges(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3), (0, 5)]);
+        let polynomial = chromatic_polynomial(&pattern, &compute_nice_tree_decomposition(&pattern));
+        for x in 0..7{
+            let edges : Vec<(usize, usize)> = (0..x).flat_map(|u| (u + 1..x).map(move |v| (u, v))).collect();
+            assert_eq!(polynomial.evaluate(x as i128), simple_brute_force(&pattern, &graph_from_edges(x, &edges)) as i128);
+        }
+    }
+
+    #[test]
+    fn test_largest_edgeless_pattern(){
+        // 15^15 homomorphisms into K_15 fit into 64 bits
+        let pattern = graph_from_edges(15, &[]);
+        assert_eq!(chromatic_polynomial(&pattern, &compute_nice_tree_decomposition(&pattern)).to_string(), "x^15");
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit into 64 bits")]
+    fn test_chromatic_polynomial_overflow(){
+        // 16^16 = 2^64 homomorphisms into K_16
+        let pattern = graph_from_edges(16, &[]);
+        chromatic_polynomial(&pattern, &compute_nice_tree_decomposition(&pattern));
+    }
+}
+
+#[cfg(test)]
+pub mod graph_operation_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::graph_generation::graph_generation_algorithms::equal_graphs;
+    use crate::graph_ops::graph_operations::{contract_edge, disjoint_union, identify_vertices, induced_subgraph, permute_vertices};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_permute_vertices(){
+        let path = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 3)]);
+        let permuted = permute_vertices(&path, &[2, 0, 3, 1]);
+        assert!(equal_graphs(&permuted, &graph_from_edges(4, &[(2, 0), (0, 3), (3, 1), (1, 1)])));
+
+        let target = graph_from_edges(3, &[(0, 1), (1, 2), (2, 2)]);
+        assert_eq!(simple_brute_force(&permuted, &target), simple_brute_force(&path, &target));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_permute_vertices_requires_permutation(){
+        permute_vertices(&graph_from_edges(3, &[(0, 1)]), &[0, 0, 1]);
+    }
+
+    #[test]
+    fn test_induced_subgraph(){
+        let graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (2, 2)]);
+        assert!(equal_graphs(&induced_subgraph(&graph, &[4, 2, 3]), &graph_from_edges(3, &[(0, 2), (2, 1), (1, 1)])));
+        assert_eq!(induced_subgraph(&graph, &[]).node_count(), 0);
+    }
+
+    #[test]
+    fn test_identify_and_contract(){
+        let cycle = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+
+        // identifying opposite vertices of a 4-cycle gives a path with two edges
+        assert!(equal_graphs(&identify_vertices(&cycle, 2, 0), &graph_from_edges(3, &[(0, 1), (0, 2)])));
+
+        // identifying adjacent vertices creates a loop, contracting them does not
+        assert!(equal_graphs(&identify_vertices(&cycle, 0, 1), &graph_from_edges(3, &[(0, 0), (0, 1), (1, 2), (2, 0)])));
+        assert!(equal_graphs(&contract_edge(&cycle, 0, 1), &graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)])));
+
+        let looped = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        assert!(equal_graphs(&contract_edge(&looped, 1, 0), &graph_from_edges(1, &[(0, 0)])));
+    }
+
+    #[test]
+    fn test_disjoint_union(){
+        let union = disjoint_union(&graph_from_edges(2, &[(0, 1)]), &graph_from_edges(2, &[(0, 0), (0, 1)]));
+        assert!(equal_graphs(&union, &graph_from_edges(4, &[(0, 1), (2, 2), (2, 3)])));
+    }
+}
+
+#[cfg(test)]
+pub mod table_backend_tests{
+    use std::collections::{BTreeMap, HashMap};
+    use crate::integer_functions::integer_functions_methods::Mapping;
+    use crate::tables::table_backends::{CompactTable, DenseTable, MappingTable};
+
+    /// Checks the behaviour shared by all storage layouts and returns the entries in iteration order.
+    fn check_table<T : MappingTable>() -> Vec<(Mapping, u64)>{
+        let mut table = T::default();
+        assert!(table.is_empty());
+
+        table.insert(7, 3);
+        table.insert(2, 5);
+        table.insert(4, 0);
+        table.insert(9, 1);
+        table.insert(9, 0);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get(7), Some(3));
+        assert_eq!(table.get(4), None);
+        assert_eq!(table.get(9), None);
+        assert_eq!(table.get(100), None);
+
+        let entries = table.iter().collect();
+        table.clear();
+        assert!(table.is_empty() && table.iter().next().is_none());
+        entries
+    }
+
+    #[test]
+    fn test_backends(){
+        let mut hash_entries = check_table::<HashMap<Mapping, u64>>();
+        hash_entries.sort();
+        assert_eq!(hash_entries, vec![(2, 5), (7, 3)]);
+
+        // the ordered layouts iterate in the order of the mappings
+        assert_eq!(check_table::<BTreeMap<Mapping, u64>>(), vec![(2, 5), (7, 3)]);
+        assert_eq!(check_table::<DenseTable>(), vec![(2, 5), (7, 3)]);
+
+        let mut compact_entries = check_table::<CompactTable>();
+        compact_entries.sort();
+        assert_eq!(compact_entries, vec![(2, 5), (7, 3)]);
+    }
+
+    #[test]
+    fn test_compact_table_promotion(){
+        let mut table = CompactTable::default();
+        table.insert(3, 1);
+        table.insert(1 << 40, 2);
+        table.insert(5, 1 << 33);
+        assert_eq!((table.len(), table.promoted()), (3, 2));
+        assert_eq!(table.get(1 << 40), Some(2));
+        assert_eq!(table.get(5), Some(1 << 33));
+
+        // an entry moves between the u32 and the u64 pairs with its value, but is never stored twice
+        table.insert(3, 1 << 32);
+        table.insert(5, 7);
+        assert_eq!((table.len(), table.promoted()), (3, 2));
+        assert_eq!((table.get(3), table.get(5)), (Some(1 << 32), Some(7)));
+        table.insert(3, 0);
+        table.insert(1 << 40, 0);
+        assert_eq!((table.len(), table.promoted()), (1, 0));
+        assert_eq!(table.iter().collect::<Vec<_>>(), vec![(5, 7)]);
+    }
+}
+
+#[cfg(test)]
+pub mod parity_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::parity::parity_algorithm::{BitTable, diaz_serna_thilikos_parity};
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_bit_table(){
+        let mut bits = BitTable::new(130);
+        bits.set(3);
+        bits.set(129);
+        bits.flip(64);
+        bits.flip(3);
+        assert_eq!(bits.ones().collect::<Vec<_>>(), vec![64, 129]);
+        assert_eq!(bits.count_ones(), 2);
+        assert!(bits.get(129) && !bits.get(3));
+
+        let mut other = BitTable::new(130);
+        other.set(129);
+        other.set(5);
+        assert_eq!(bits.and(&other).ones().collect::<Vec<_>>(), vec![129]);
+    }
+
+    #[test]
+    fn test_parity(){
+        let instances = [
+            ("data/metis_graphs/handmade/from_2.graph", "data/metis_graphs/handmade/to_2.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd"),
+            ("data/metis_graphs/handmade/from_5.graph", "data/metis_graphs/handmade/to_3.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_7.ntd"),
+            ("data/metis_graphs/handmade/from_7.graph", "data/metis_graphs/handmade/to_2.graph", "data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_6.ntd"),
+        ];
+        for (from, to, ntd) in instances{
+            let (from_graph, to_graph, ntd) = (import_metis(from).unwrap(), import_metis(to).unwrap(), import_ntd(ntd).unwrap());
+            assert_eq!(diaz_serna_thilikos_parity(&from_graph, &ntd, &to_graph), diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph) % 2 == 1);
+        }
+
+        // odd and even counts with loops in both graphs
+        let targets = [graph_from_edges(3, &[(0, 1), (1, 2), (2, 2)]), graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0), (0, 0), (1, 1)])];
+        let patterns = [graph_from_edges(3, &[(0, 1), (1, 2)]), graph_from_edges(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]), graph_from_edges(2, &[])];
+        for to_graph in &targets{
+            for from_graph in &patterns{
+                let ntd = compute_nice_tree_decomposition(from_graph);
+                assert_eq!(diaz_serna_thilikos_parity(from_graph, &ntd, to_graph), simple_brute_force(from_graph, to_graph) % 2 == 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod rooted_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::rooted::rooted_homomorphisms::{hom_distribution, retraction_count, RootedGraph, rooted_hom_count, rooted_hom_profile};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_rooted_hom_count(){
+        // paths of length 2 between the roots are common neighbours
+        let path = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        let ntd = compute_nice_tree_decomposition(&path);
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+
+        let from_graph = RootedGraph::new(path.clone(), vec![0, 2]);
+        assert_eq!(rooted_hom_count(&from_graph, &ntd, &RootedGraph::new(triangle.clone(), vec![1, 1])), 2);
+        assert_eq!(rooted_hom_count(&from_graph, &ntd, &RootedGraph::new(triangle.clone(), vec![0, 2])), 1);
+
+        // a root given twice has to be mapped onto both images
+        let from_graph = RootedGraph::new(path.clone(), vec![1, 1]);
+        assert_eq!(rooted_hom_count(&from_graph, &ntd, &RootedGraph::new(triangle.clone(), vec![0, 0])), 4);
+        assert_eq!(rooted_hom_count(&from_graph, &ntd, &RootedGraph::new(triangle.clone(), vec![0, 1])), 0);
+
+        // without roots all homomorphisms are counted
+        let from_graph = RootedGraph::new(path, vec![]);
+        assert_eq!(rooted_hom_count(&from_graph, &ntd, &RootedGraph::new(triangle, vec![])), 12);
+    }
+
+    #[test]
+    fn test_rooted_hom_profile(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        for roots in [vec![], vec![2], vec![0, 4], vec![3, 1, 3]]{
+            let from_graph = RootedGraph::new(from_graph.clone(), roots.clone());
+            let profile = rooted_hom_profile(&from_graph, &ntd, &to_graph);
+
+            // the profile sums up to the number of all homomorphisms
+            assert_eq!(profile.values().sum::<u64>(), diaz_serna_thilikos_algorithm(from_graph.graph(), &ntd, &to_graph));
+
+            // every entry is the number of homomorphisms with pinned roots
+            for (images, &hom_number) in &profile{
+                assert_eq!(images.len(), roots.len());
+                assert_eq!(rooted_hom_count(&from_graph, &ntd, &RootedGraph::new(to_graph.clone(), images.clone())), hom_number);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hom_distribution(){
+        // the images of an endpoint of an edge are distributed like the degrees
+        let edge = graph_from_edges(2, &[(0, 1)]);
+        let path = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 3)]);
+        assert_eq!(hom_distribution(&edge, &compute_nice_tree_decomposition(&edge), &path, 0), vec![1, 2, 2, 2]);
+
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        for r in 0..from_graph.node_count(){
+            let distribution = hom_distribution(&from_graph, &ntd, &to_graph, r);
+            assert_eq!(distribution.iter().sum::<u64>(), 1280);
+            for (a, &hom_number) in distribution.iter().enumerate(){
+                let rooted = RootedGraph::new(from_graph.clone(), vec![r]);
+                assert_eq!(rooted_hom_count(&rooted, &ntd, &RootedGraph::new(to_graph.clone(), vec![a])), hom_number);
+            }
+        }
+    }
+
+    #[test]
+    fn test_retraction_count(){
+        // the 4-cycle retracts onto each of its edges in exactly one way, the 5-cycle onto none
+        let cycle = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let ntd = compute_nice_tree_decomposition(&cycle);
+        assert_eq!(retraction_count(&cycle, &ntd, &[0, 1]), 1);
+        assert_eq!(retraction_count(&cycle, &ntd, &[3, 0]), 1);
+        assert_eq!(retraction_count(&cycle, &ntd, &[0, 1, 2, 3]), 1);
+        assert_eq!(retraction_count(&cycle, &ntd, &[0, 2]), 0);
+        let odd_cycle = graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        assert_eq!(retraction_count(&odd_cycle, &compute_nice_tree_decomposition(&odd_cycle), &[0, 1]), 0);
+
+        // the pendant vertex 3 of a triangle can be folded onto both other neighbours of 0
+        let graph = graph_from_edges(5, &[(0, 1), (1, 2), (2, 0), (0, 3), (3, 4)]);
+        let ntd = compute_nice_tree_decomposition(&graph);
+        assert_eq!(retraction_count(&graph, &ntd, &[0, 1, 2]), 2 * 2);
+
+        // a single vertex is a retract iff it has a loop
+        let path = graph_from_edges(3, &[(0, 1), (1, 2), (2, 2)]);
+        let ntd = compute_nice_tree_decomposition(&path);
+        assert_eq!(retraction_count(&path, &ntd, &[0]), 0);
+        assert_eq!(retraction_count(&path, &ntd, &[2]), 1);
+    }
+
+    #[test]
+    fn test_vertex_deletion_profile(){
+        use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+        use crate::graph_ops::graph_operations::induced_subgraph;
+        use crate::rooted::rooted_homomorphisms::vertex_deletion_profile;
+
+        let target = graph_from_edges(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 4)]);
+        let patterns = [
+            graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]),
+            graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 3)]),
+            graph_from_edges(3, &[(0, 1)]),
+            graph_from_edges(0, &[]),
+        ];
+        for pattern in &patterns{
+            let profile = vertex_deletion_profile(pattern, &target);
+            assert_eq!(profile.len(), 5);
+            for (v, hom_number) in profile.into_iter().enumerate(){
+                let others : Vec<usize> = (0..5).filter(|&u| u != v).collect();
+                assert_eq!(hom_number, simple_brute_force(pattern, &induced_subgraph(&target, &others)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod hom_matrix_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::hom_matrix::homomorphism_matrix::{hom_matrix, hom_matrix_with_threads};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_hom_matrix(){
+        let patterns = vec![
+            graph_from_edges(0, &[]),
+            graph_from_edges(3, &[(0, 1), (1, 2)]),
+            graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]),
+            // disconnected pattern with a loop
+            graph_from_edges(4, &[(0, 1), (2, 2)]),
+        ];
+        let targets = vec![
+            graph_from_edges(0, &[]),
+            graph_from_edges(2, &[(0, 1)]),
+            graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]),
+            graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0), (1, 1)]),
+        ];
+
+        let matrix = hom_matrix(&patterns, &targets);
+        assert_eq!(matrix.len(), patterns.len());
+        for (i, pattern) in patterns.iter().enumerate(){
+            for (j, target) in targets.iter().enumerate(){
+                assert_eq!(matrix[i][j], simple_brute_force(pattern, target), "pattern {} target {}", i, j);
+            }
+        }
+
+        for threads in [2, 3, 100]{
+            assert_eq!(hom_matrix_with_threads(&patterns, &targets, threads), matrix);
+        }
+
+        assert_eq!(hom_matrix(&patterns, &[]), vec![Vec::<u64>::new(); patterns.len()]);
+        assert!(hom_matrix_with_threads(&[], &targets, 4).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit into 64 bits")]
+    fn test_hom_matrix_overflow_of_components(){
+        // two paths on 40 vertices, each with 2^40 homomorphisms into an edge with loops at both ends
+        let edges : Vec<(usize, usize)> = (1..80).filter(|&v| v != 40).map(|v| (v - 1, v)).collect();
+        let pattern = graph_from_edges(80, &edges);
+        hom_matrix(&[pattern], &[graph_from_edges(2, &[(0, 0), (0, 1), (1, 1)])]);
+    }
+}
+
+#[cfg(test)]
+pub mod parallelism_tests{
+    use crate::parallelism::parallel_execution::{count_range, join, map_range, with_threads};
+
+    #[test]
+    fn test_parallel_execution(){
+        // results are identical with and without the parallel feature
+        assert_eq!(map_range(5, |i| i * i), vec![0, 1, 4, 9, 16]);
+        assert_eq!(count_range(100, |i| i % 3 == 0), 34);
+        assert_eq!(join(|| 1 + 1, || "b"), (2, "b"));
+        assert_eq!(with_threads(2, || map_range(1000, |i| i as u64).iter().sum::<u64>()), 499500);
+        assert!(map_range(0, |i| i).is_empty());
+    }
+}
+
+#[cfg(test)]
+pub mod graph_printer_tests{
+    use crate::graph_display::graph_printer::AdjacencyList;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_adjacency_list(){
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 2)]);
+        assert_eq!(AdjacencyList(&graph).to_string(), "4 vertices, 3 edges\n0: 1\n1: 0 2\n2: 1 2\n3:");
+        assert_eq!(AdjacencyList(&graph_from_edges(0, &[])).to_string(), "0 vertices, 0 edges");
+    }
+}
+
+#[cfg(test)]
+pub mod instance_statistics_tests{
+    use crate::cost_estimation::cost_model::estimate_modified_dp;
+    use crate::inspect::instance_statistics::{graph_statistics, Instance, NtdStats, read_instance};
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
+
+    #[test]
+    fn test_graph_statistics(){
+        let statistics = graph_statistics(&graph_from_edges(5, &[(0, 1), (1, 2), (2, 2), (3, 4)]));
+        assert_eq!((statistics.vertices, statistics.edges, statistics.loops, statistics.components), (5, 3, 1, 2));
+        assert_eq!((statistics.min_degree, statistics.max_degree), (Some(1), Some(2)));
+        assert_eq!(statistics.average_degree, 1.2);
+
+        let statistics = graph_statistics(&graph_from_edges(0, &[]));
+        assert_eq!((statistics.min_degree, statistics.components, statistics.average_degree), (None, 0, 0.0));
+        assert!(statistics.to_string().contains("min degree: -"));
+    }
+
+    #[test]
+    fn test_ntd_statistics(){
+        let ntd = ntd_test_example();
+        let expected = NtdStats { nodes: 10, width: 1, vertices: 4, leaves: 2, introduce_nodes: 3, forget_nodes: 4, join_nodes: 1, possible_edges: 7, depth: 6, estimated_cost: None };
+        assert_eq!(NtdStats::compute(&ntd), expected);
+
+        let stats = NtdStats::compute_for_target(&ntd, 5);
+        assert_eq!(stats.estimated_cost, Some(estimate_modified_dp(&ntd, 5)));
+        assert!(stats.to_string().contains("estimated operations"));
+        assert!(!expected.to_string().contains("estimated operations"));
+    }
+
+    #[test]
+    fn test_read_instance(){
+        assert!(matches!(read_instance("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_9.ntd"), Ok(Instance::Ntd(_))));
+        assert!(matches!(read_instance("data/metis_graphs/handmade/from_2.graph"), Ok(Instance::Graph(_))));
+        assert!(read_instance("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_4.ntd").is_err());
+    }
+}
+
+#[cfg(test)]
+pub mod regression_suite_tests{
+    use std::io::Cursor;
+    use std::path::PathBuf;
+    use crate::pipeline::counting_pipeline::Algorithm;
+    use crate::regression::regression_suite::{Manifest, ManifestEntry, RegressionError, RegressionMode, run_regression};
+
+    #[test]
+    fn test_golden_manifest(){
+        let report = run_regression("data/regression/manifest.txt", RegressionMode::Check).unwrap();
+        assert!(report.is_success(), "{}", report);
+        assert_eq!(report.instances, 10);
+    }
+
+    #[test]
+    fn test_parse_manifest(){
+        let manifest = Manifest::parse(Cursor::new("# comment\n\na.graph b.graph 12\n a.graph b.graph a.ntd ? \n")).unwrap();
+        assert_eq!(manifest.entries, vec![
+            ManifestEntry { pattern: PathBuf::from("a.graph"), target: PathBuf::from("b.graph"), ntd: None, expected: Some(12) },
+            ManifestEntry { pattern: PathBuf::from("a.graph"), target: PathBuf::from("b.graph"), ntd: Some(PathBuf::from("a.ntd")), expected: None },
+        ]);
+
+        let mut written = vec![];
+        manifest.write(&mut written).unwrap();
+        assert_eq!(Manifest::parse(Cursor::new(written)).unwrap(), manifest);
+
+        assert!(matches!(Manifest::parse(Cursor::new("a.graph b.graph\n")), Err(RegressionError::MalformedLine { line: 1, .. })));
+        assert!(matches!(Manifest::parse(Cursor::new("# comment\na.graph b.graph twelve\n")), Err(RegressionError::MalformedLine { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_update_manifest(){
+        let directory = std::env::current_dir().unwrap().join("data/metis_graphs/handmade");
+        let manifest_path = std::env::temp_dir().join("regression_suite_test_manifest.txt");
+        let entry = |expected| ManifestEntry { pattern: directory.join("from_2.graph"), target: directory.join("to_3.graph"), ntd: None, expected };
+
+        Manifest { entries: vec![entry(Some(1))] }.save(&manifest_path).unwrap();
+        let report = run_regression(&manifest_path, RegressionMode::Check).unwrap();
+        assert_eq!(report.mismatches.len(), 3);
+        assert_eq!(report.mismatches[1].algorithm, Algorithm::DiazSernaThilikos);
+
+        let report = run_regression(&manifest_path, RegressionMode::Update).unwrap();
+        assert!(report.is_success());
+        assert_eq!(report.updated, 1);
+
+        let report = run_regression(&manifest_path, RegressionMode::Check).unwrap();
+        assert!(report.is_success());
+        assert_eq!(report.updated, 0);
+        assert_ne!(Manifest::load(&manifest_path).unwrap().entries[0], entry(Some(1)));
+    }
+}
+
+#[cfg(test)]
+pub mod implicit_target_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::graph_generation::graph_generation_algorithms::circulant_graph;
+    use crate::implicit_target::implicit_target_counting::{count_into, diaz_serna_thilikos_implicit, AdjacencyOracle, Complement, ImplicitGraph, LexicographicProduct, Power, TensorProduct};
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_circulant_target(){
+        // the circulant graph on 9 vertices with jumps 1 and 3
+        let circulant = ImplicitGraph::new(9, |a, b| matches!((9 + a - b) % 9, 1 | 3 | 6 | 8));
+        let to_graph = circulant.to_matrix_graph();
+        assert_eq!(to_graph.edge_count(), 18);
+
+        for from_graph in [graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]),
+                           graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (0, 2)]),
+                           graph_from_edges(4, &[(0, 1), (2, 3)])]{
+            let ntd = compute_nice_tree_decomposition(&from_graph);
+            assert_eq!(count_into(&from_graph, &ntd, &circulant), diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph));
+        }
+    }
+
+    #[test]
+    fn test_loops_and_empty_target(){
+        let from_graph = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        let ntd = compute_nice_tree_decomposition(&from_graph);
+
+        // the complete graph with loops at the even vertices
+        assert_eq!(diaz_serna_thilikos_implicit(&from_graph, &ntd, 4, |a, b| a != b || a % 2 == 0), 8);
+        assert_eq!(diaz_serna_thilikos_implicit(&from_graph, &ntd, 4, |a, b| a != b), 0);
+        assert_eq!(diaz_serna_thilikos_implicit(&from_graph, &ntd, 0, |_, _| true), 0);
+    }
+
+    #[test]
+    fn test_adapters(){
+        let cycle = circulant_graph(6, &[1]);
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let edge = graph_from_edges(2, &[(0, 1)]);
+
+        // the square of the 6-cycle is the circulant graph with jumps 1 and 2
+        assert_eq!(Power::new(&cycle, 2).to_matrix_graph().edge_count(), circulant_graph(6, &[1, 2]).edge_count());
+        assert!(Power::new(&cycle, 2).has_edge(0, 2) && !Power::new(&cycle, 2).has_edge(0, 3));
+        assert_eq!(Power::new(&cycle, 3).to_matrix_graph().edge_count(), 15);
+
+        // the complement of the 6-cycle contains two triangles and the perfect matching of opposite vertices
+        assert_eq!(Complement::new(&cycle).to_matrix_graph().edge_count(), 9);
+        assert!(!Complement::new(&triangle).has_edge(0, 0));
+
+        // K_2[complement of K_2] is the 4-cycle
+        let two_vertices = Complement::new(&edge);
+        assert_eq!(LexicographicProduct::new(&edge, &two_vertices).to_matrix_graph().edge_count(), 4);
+
+        for from_graph in [graph_from_edges(3, &[(0, 1), (1, 2)]),
+                           graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]),
+                           triangle.clone()]{
+            let ntd = compute_nice_tree_decomposition(&from_graph);
+
+            // the counts of the tensor product are the products of the counts of its factors
+            let tensor = TensorProduct::new(&cycle, &triangle);
+            assert_eq!(count_into(&from_graph, &ntd, &tensor), count_into(&from_graph, &ntd, &cycle) * count_into(&from_graph, &ntd, &triangle));
+
+            // the adapters count like their materialized graphs
+            let power = Power::new(&cycle, 2);
+            let complement = Complement::new(&cycle);
+            let product = LexicographicProduct::new(&triangle, &edge);
+            assert_eq!(count_into(&from_graph, &ntd, &power), diaz_serna_thilikos_algorithm(&from_graph, &ntd, &power.to_matrix_graph()));
+            assert_eq!(count_into(&from_graph, &ntd, &complement), diaz_serna_thilikos_algorithm(&from_graph, &ntd, &complement.to_matrix_graph()));
+            assert_eq!(count_into(&from_graph, &ntd, &product), diaz_serna_thilikos_algorithm(&from_graph, &ntd, &product.to_matrix_graph()));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod hom_kernels_tests{
+    use crate::kernels::hom_kernels::{FeatureScaling, HomKernel, normalize_gram_matrix, PatternFamily};
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
+
+    #[test]
+    fn test_gram_matrix(){
+        let graphs = vec![
+            graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]),
+            graph_from_edges(3, &[(1, 2), (2, 0), (0, 1)]),
+            graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]),
+        ];
+
+        // the trees with 1 and 2 vertices count vertices and twice the edges
+        let kernel = HomKernel::new(PatternFamily::TreesUpTo(2), FeatureScaling::Counts);
+        assert_eq!(kernel.feature_vectors(&graphs), vec![vec![3.0, 6.0], vec![3.0, 6.0], vec![4.0, 6.0]]);
+
+        let gram_matrix = kernel.gram_matrix(&graphs);
+        assert_eq!(gram_matrix, vec![vec![45.0, 45.0, 48.0], vec![45.0, 45.0, 48.0], vec![48.0, 48.0, 52.0]]);
+        assert_eq!(kernel.cross_gram_matrix(&graphs[2..], &graphs), vec![gram_matrix[2].clone()]);
+
+        let normalized = normalize_gram_matrix(&gram_matrix);
+        assert!((0..3).all(|i| (normalized[i][i] - 1.0).abs() < 1e-12));
+        assert!((normalized[0][1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_feature_scaling(){
+        let graphs = vec![graph_from_edges(2, &[(0, 1)]), graph_from_edges(0, &[])];
+        let edge = PatternFamily::Patterns(vec![graph_from_edges(2, &[(0, 1)])]);
+
+        assert_eq!(HomKernel::new(edge.clone(), FeatureScaling::Densities).feature_vectors(&graphs), vec![vec![0.5], vec![0.0]]);
+        assert_eq!(HomKernel::new(edge, FeatureScaling::Logarithmic).feature_vectors(&graphs), vec![vec![2f64.ln_1p()], vec![0.0]]);
+    }
+
+    #[test]
+    fn test_ntd_universe(){
+        let ntd = ntd_test_example();
+        let graphs = vec![graph_from_edges(3, &[(0, 1), (1, 2), (2, 2)])];
+        let kernel = HomKernel::new(PatternFamily::NtdUniverse(Box::new(ntd.clone())), FeatureScaling::Counts);
+
+        let features = kernel.feature_vectors(&graphs);
+        assert_eq!(features[0].len(), 1 << ntd.all_possible_edges().len());
+        // the graph without edges is mapped arbitrarily
+        assert_eq!(features[0][0], 81.0);
+    }
+}
+
+#[cfg(test)]
+pub mod pattern_family_export_tests{
+    use std::fs;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::graph_generation::graph_generation_algorithms::{equal_graphs, graph_from_edge_subset};
+    use crate::pattern_export::pattern_family_export::{export_graphs, export_ntd_universe};
+    use crate::pipeline::counting_pipeline::GraphFormat;
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
+
+    #[test]
+    fn test_export_ntd_universe(){
+        let ntd = ntd_test_example();
+        let directory = std::env::temp_dir().join("pattern_family_export_test_universe");
+        let _ = fs::remove_dir_all(&directory);
+
+        let rows = export_ntd_universe(&ntd, &directory, GraphFormat::Metis, false).unwrap();
+        assert_eq!(rows.len(), 1 << ntd.all_possible_edges().len());
+
+        // the id is the edge set of the pattern
+        let row = &rows[5];
+        assert_eq!(row.id, 5);
+        let expected = graph_from_edge_subset(4, ntd.all_possible_edges(), 5);
+        assert!(equal_graphs(&import_metis(directory.join(&row.file)).unwrap(), &expected));
+        assert_eq!((row.vertices, row.edges), (4, 2));
+
+        let manifest = fs::read_to_string(directory.join("manifest.csv")).unwrap();
+        assert_eq!(manifest.lines().count(), rows.len() + 1);
+        assert!(manifest.starts_with("id,file,canonical_form,vertices,edges\n0,pattern_0.graph,4:,4,0\n"));
+
+        // deduplication keeps one pattern per isomorphism class
+        let deduplicated = export_ntd_universe(&ntd, &directory, GraphFormat::Dimacs, true).unwrap();
+        assert!(deduplicated.len() < rows.len());
+        assert_eq!(deduplicated[0].id, 0);
+        let mut forms : Vec<&String> = rows.iter().map(|row| &row.canonical_form).collect();
+        forms.sort();
+        forms.dedup();
+        assert_eq!(forms.len(), deduplicated.len());
+    }
+
+    #[test]
+    fn test_export_graph6(){
+        let directory = std::env::temp_dir().join("pattern_family_export_test_graph6");
+        let _ = fs::remove_dir_all(&directory);
+
+        let graphs = vec![graph_from_edges(3, &[(0, 1)]), graph_from_edges(3, &[(1, 2)])];
+        let rows = export_graphs(&graphs, &directory, GraphFormat::Graph6, true).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(fs::read_to_string(directory.join(&rows[0].file)).unwrap(), "B_\n");
+
+        assert!(export_graphs(&[graph_from_edges(1, &[(0, 0)])], &directory, GraphFormat::Graph6, false).is_err());
+    }
+}
+
+#[cfg(test)]
+pub mod image_size_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::graph_generation::graph_generation_algorithms::circulant_graph;
+    use crate::image_size::image_size_counting::{hom_counts_by_image_size, surjective_hom_count};
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_hom_counts_by_image_size(){
+        let path = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let ntd = compute_nice_tree_decomposition(&path);
+
+        // the 12 homomorphisms of the path into the triangle either map both ends onto the same vertex or not
+        assert_eq!(hom_counts_by_image_size(&path, &ntd, &triangle), vec![0, 0, 6, 6]);
+        assert_eq!(surjective_hom_count(&path, &ntd, &triangle), 6);
+
+        // only the constant mapping onto the looped vertex has an image of size 1
+        let looped = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        assert_eq!(hom_counts_by_image_size(&path, &ntd, &looped), vec![0, 1, 4]);
+
+        // a pattern with fewer vertices than the target is never surjective
+        let edge = graph_from_edges(2, &[(0, 1)]);
+        assert_eq!(surjective_hom_count(&edge, &compute_nice_tree_decomposition(&edge), &triangle), 0);
+
+        // the counts sum up to the number of homomorphisms
+        let cycle = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let to_graph = circulant_graph(6, &[1, 2]);
+        let ntd = compute_nice_tree_decomposition(&cycle);
+        let counts = hom_counts_by_image_size(&cycle, &ntd, &to_graph);
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts[1], 0);
+        assert_eq!(counts.iter().sum::<u64>(), diaz_serna_thilikos_algorithm(&cycle, &ntd, &to_graph));
+    }
+}
+
+#[cfg(test)]
+pub mod pattern_reduction_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::graph_generation::graph_generation_algorithms::circulant_graph;
+    use crate::multigraph::multigraph_algorithms::multiplicity;
+    use crate::pattern_reduction::pattern_reductions::{count_with_reductions, regular_degree, remove_isolated_vertices, remove_pendant_trees, suppress_degree_two_vertices, RemovedVertex};
+    use crate::tree_decompositions::tree_structure::Vertex;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_remove_pendant_trees(){
+        // a triangle with a pendant path of length 2 and an isolated vertex with a loop
+        let graph = graph_from_edges(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (5, 5)]);
+        let reduction = remove_pendant_trees(&graph);
+        assert_eq!(reduction.pattern.node_count(), 3);
+        assert_eq!(reduction.pattern.edge_count(), 3);
+        assert_eq!(reduction.removed, vec![RemovedVertex::Pendant, RemovedVertex::Pendant, RemovedVertex::IsolatedLoop]);
+
+        // the 5-cycle with loops is 3-regular
+        let mut to_graph = circulant_graph(5, &[1]);
+        for a in 0..5{
+            to_graph.add_edge(Vertex::new(a), Vertex::new(a), ());
+        }
+        assert_eq!(regular_degree(&to_graph), Some(3));
+        assert_eq!(reduction.correction_factor(&to_graph), Some(3 * 3 * 5));
+
+        // pendant vertices have no factor on irregular targets, isolated vertices always have one
+        let path = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        assert_eq!(regular_degree(&path), None);
+        assert_eq!(reduction.correction_factor(&path), None);
+        let isolated = remove_isolated_vertices(&graph_from_edges(3, &[(0, 1)]));
+        assert_eq!(isolated.removed, vec![RemovedVertex::Isolated]);
+        assert_eq!(isolated.correction_factor(&path), Some(3));
+    }
+
+    #[test]
+    fn test_suppress_degree_two_vertices(){
+        // the 6-cycle becomes a single vertex with a loop
+        let series = suppress_degree_two_vertices(&circulant_graph(6, &[1])).unwrap();
+        assert_eq!((series.kernel.node_count(), series.length), (1, 6));
+        assert_eq!(multiplicity(&series.kernel, Vertex::new(0), Vertex::new(0)), 1);
+
+        // K_{2,3} consists of three paths of length 2 between the vertices of degree 3
+        let theta = graph_from_edges(5, &[(0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4)]);
+        let series = suppress_degree_two_vertices(&theta).unwrap();
+        assert_eq!((series.kernel.node_count(), series.length), (2, 2));
+        assert_eq!(multiplicity(&series.kernel, Vertex::new(0), Vertex::new(1)), 3);
+
+        // a star with one subdivided edge has paths of lengths 1 and 2
+        assert!(suppress_degree_two_vertices(&graph_from_edges(5, &[(0, 1), (0, 2), (0, 3), (3, 4)])).is_none());
+    }
+
+    #[test]
+    fn test_count_with_reductions(){
+        let patterns = [graph_from_edges(4, &[(0, 1), (0, 2), (0, 3)]),
+                        graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]),
+                        graph_from_edges(5, &[(0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4)]),
+                        graph_from_edges(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (5, 5)]),
+                        graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]),
+                        graph_from_edges(2, &[])];
+        let targets = [circulant_graph(6, &[1, 2]),
+                       graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (1, 1)]),
+                       graph_from_edges(3, &[(0, 1), (1, 2), (2, 0), (0, 0), (1, 1), (2, 2)])];
+
+        for from_graph in &patterns{
+            for to_graph in &targets{
+                assert_eq!(count_with_reductions(from_graph, to_graph), simple_brute_force(from_graph, to_graph));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod target_partition_tests{
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::graph_generation::graph_generation_algorithms::circulant_graph;
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::target_partition::target_partitioning::{count_by_chunks, partition_target, pattern_center};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_pattern_center(){
+        assert_eq!(pattern_center(&graph_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4)])), Some((2, 2)));
+        assert_eq!(pattern_center(&graph_from_edges(4, &[(0, 1), (0, 2), (0, 3)])), Some((0, 1)));
+        assert_eq!(pattern_center(&graph_from_edges(1, &[(0, 0)])), Some((0, 0)));
+        assert_eq!(pattern_center(&graph_from_edges(3, &[(0, 1)])), None);
+    }
+
+    #[test]
+    fn test_partition_target(){
+        // the path 0 - 1 - .. - 7 split into 3 chunks with radius 1
+        let path = graph_from_edges(8, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 7)]);
+        let chunks = partition_target(&path, 3, 1);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].owned, vec![0, 1]);
+        assert_eq!(chunks[0].vertices, vec![0, 1, 2]);
+        assert_eq!(chunks[1].owned, vec![2, 3, 4]);
+        assert_eq!(chunks[1].vertices, vec![1, 2, 3, 4, 5]);
+        assert_eq!(chunks[1].owned_positions(), vec![1, 2, 3]);
+        assert_eq!(chunks[2].vertices, vec![4, 5, 6, 7]);
+
+        // more chunks than vertices
+        assert_eq!(partition_target(&graph_from_edges(2, &[(0, 1)]), 5, 0).len(), 2);
+    }
+
+    #[test]
+    fn test_count_by_chunks(){
+        let patterns = [graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]),
+                        graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]),
+                        graph_from_edges(4, &[(0, 1), (0, 2), (0, 3), (1, 1)])];
+        let targets = [circulant_graph(10, &[1, 3]),
+                       graph_from_edges(7, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3), (6, 6), (5, 6)])];
+
+        for from_graph in &patterns{
+            let ntd = compute_nice_tree_decomposition(from_graph);
+            for to_graph in &targets{
+                let expected = diaz_serna_thilikos_algorithm(from_graph, &ntd, to_graph);
+                for chunks in [1, 2, 3, 7]{
+                    assert_eq!(count_by_chunks(from_graph, &ntd, to_graph, chunks), expected);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod sanity_check_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::sanity::sanity_checks::{check_invariants, Invariant};
+    use crate::testing::consistency_testing::{random_instance, ConsistencyConfig, TestRng};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
+
+    #[test]
+    fn test_invariants_hold(){
+        let config = ConsistencyConfig { instances: 20, ..ConsistencyConfig::default() };
+        let mut rng = TestRng::new(config.seed);
+        for _ in 0..config.instances{
+            let instance = random_instance(&mut rng, &config);
+            let violations = check_invariants(diaz_serna_thilikos_algorithm, &instance.from_graph, &instance.nice_tree_decomposition, &instance.to_graph);
+            assert!(violations.is_empty(), "{:?} for {}", violations, instance.describe());
+        }
+    }
+
+    #[test]
+    fn test_invariants_violated(){
+        // the pattern of the example decomposition has two components {0, 1, 2} and {3}
+        let from_graph = graph_from_edges(4, &[(0, 1), (1, 2)]);
+        let to_graph = graph_from_edges(3, &[(0, 1), (1, 2), (2, 2)]);
+        let ntd = ntd_test_example();
+
+        // an algorithm ignoring loops of the target
+        let ignoring_loops = |from : &Graph, _ : &NiceTreeDecomposition, to : &Graph| {
+            let mut loopless = to.clone();
+            for a in 0..loopless.node_count(){
+                if loopless.has_edge(Vertex::new(a), Vertex::new(a)) { loopless.remove_edge(Vertex::new(a), Vertex::new(a)); }
+            }
+            simple_brute_force(from, &loopless)
+        };
+        let violations = check_invariants(ignoring_loops, &from_graph, &ntd, &to_graph);
+        let invariants : Vec<Invariant> = violations.iter().map(|violation| violation.invariant).collect();
+        assert_eq!(invariants, vec![Invariant::LoopedVertex, Invariant::CompleteWithLoops]);
+        assert_eq!(violations[0].to_string(), "hom(H, K_1 with loop) = 1 violated: expected 1 homomorphisms but got 0");
+
+        // an algorithm counting one homomorphism too many
+        let off_by_one = |from : &Graph, _ : &NiceTreeDecomposition, to : &Graph| simple_brute_force(from, to) + 1;
+        let invariants : Vec<Invariant> = check_invariants(off_by_one, &from_graph, &ntd, &to_graph).iter().map(|violation| violation.invariant).collect();
+        assert_eq!(invariants, vec![Invariant::LoopedVertex, Invariant::SingleVertex, Invariant::CompleteWithLoops, Invariant::DisjointUnion]);
+    }
+}
+
+#[cfg(test)]
+pub mod ntd_generation_tests{
+    use crate::file_handler::tree_decomposition_handler::{import_ntd, write_ntd};
+    use crate::ntd_generation::ntd_generators::{export_family, NtdFamily};
+    use crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition;
+
+    fn ntd_text(ntd : &NiceTreeDecomposition) -> String{
+        let mut text = vec![];
+        write_ntd(ntd, &mut text).unwrap();
+        String::from_utf8(text).unwrap()
+    }
+
+    #[test]
+    fn test_generated_families_match_data(){
+        // the generated families reproduce the decompositions of the experiments
+        let mut members = NtdFamily::ETauPaths { vertices: 10 }.generate();
+        members.extend(NtdFamily::Paths { max_vertices: 3 }.generate());
+        members.extend(NtdFamily::Complete { max_vertices: 5 }.generate().into_iter().skip(1));
+        for member in members{
+            let expected = import_ntd(format!("data/Experiments/ntds/{}", member.name)).unwrap();
+            // the python scripts declared a bag size of 2 for all decompositions, hence only the nodes are compared
+            let nodes = |ntd : &NiceTreeDecomposition| ntd_text(ntd).lines().skip(1).collect::<Vec<_>>().join("\n");
+            assert_eq!(nodes(&member.ntd), nodes(&expected), "{}", member.name);
+            assert_eq!(member.ntd.vertex_count(), expected.vertex_count());
+        }
+    }
+
+    #[test]
+    fn test_family_parameters(){
+        // fixed |E_τ| = 7 with 4 to 7 vertices
+        let fixed_e_tau = NtdFamily::FixedETau { possible_edges: 7 }.generate();
+        assert_eq!(fixed_e_tau.iter().map(|member| member.ntd.vertex_count()).collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+        assert!(fixed_e_tau.iter().all(|member| member.ntd.all_possible_edges().len() == 7));
+
+        // fixed width 2, where every further vertex adds itself and two edges
+        let fixed_width = NtdFamily::FixedWidth { width: 2, max_vertices: 6 }.generate();
+        assert_eq!(fixed_width.iter().map(|member| member.name.as_str()).collect::<Vec<_>>(),
+                   vec!["ntd_window_3_2.ntd", "ntd_window_4_2.ntd", "ntd_window_5_2.ntd", "ntd_window_6_2.ntd"]);
+        assert!(fixed_width.iter().all(|member| member.ntd.width() == 2));
+        assert_eq!(fixed_width.iter().map(|member| member.ntd.all_possible_edges().len()).collect::<Vec<_>>(), vec![6, 9, 12, 15]);
+
+        // the written files are imported again
+        let directory = std::env::temp_dir().join("ntd_generation_test");
+        let paths = export_family(&NtdFamily::FixedWidth { width: 2, max_vertices: 6 }, &directory).unwrap();
+        assert_eq!(paths.len(), 4);
+        for (path, member) in paths.iter().zip(&fixed_width){
+            assert_eq!(ntd_text(&import_ntd(path).unwrap()), ntd_text(&member.ntd));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod walk_count_tests{
+    use num_bigint::BigUint;
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::walks::walk_counts::{count_walks, path_hom_count, path_length};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_path_length(){
+        assert_eq!(path_length(&graph_from_edges(1, &[])), Some(0));
+        assert_eq!(path_length(&graph_from_edges(4, &[(2, 0), (0, 3), (3, 1)])), Some(3));
+        assert_eq!(path_length(&graph_from_edges(0, &[])), None);
+        // a star, a loop and a path together with a triangle
+        assert_eq!(path_length(&graph_from_edges(4, &[(0, 1), (0, 2), (0, 3)])), None);
+        assert_eq!(path_length(&graph_from_edges(2, &[(0, 1), (1, 1)])), None);
+        assert_eq!(path_length(&graph_from_edges(5, &[(0, 1), (2, 3), (3, 4), (4, 2)])), None);
+    }
+
+    #[test]
+    fn test_path_hom_count(){
+        let target = graph_from_edges(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]);
+        for k in 1..=6{
+            let path = graph_from_edges(k, &(1..k).map(|v| (v - 1, v)).collect::<Vec<_>>());
+            assert_eq!(path_hom_count(&path, &target), Some(BigUint::from(simple_brute_force(&path, &target))));
+        }
+        assert_eq!(path_hom_count(&graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]), &target), None);
+
+        // the walks of length 100 in the triangle exceed the range of u64
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(count_walks(&triangle, 100), BigUint::from(3u8) * BigUint::from(2u8).pow(100));
+        assert_eq!(count_walks(&graph_from_edges(0, &[]), 5), BigUint::from(0u8));
+
+        // a cycle has 2 continuations of each walk, short walks are counted by the vector iteration and
+        // long walks by repeated squaring
+        let cycle_edges : Vec<(usize, usize)> = (0..20).map(|a| (a, (a + 1) % 20)).collect();
+        let cycle = graph_from_edges(20, &cycle_edges);
+        for length in [0, 1, 5, 10000]{
+            assert_eq!(count_walks(&cycle, length), BigUint::from(20u8) * BigUint::from(2u8).pow(length as u32));
+        }
+        let looped_edge = graph_from_edges(2, &[(0, 0), (0, 1)]);
+        assert_eq!(count_walks(&looped_edge, 3), BigUint::from(8u8));
+    }
+}
+
+#[cfg(test)]
+pub mod fingerprint_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::fingerprint::hom_fingerprints::{fingerprint_patterns, hom_fingerprint, ntd_fingerprint, MAX_PATTERN_VERTICES};
+    use crate::graph_ops::graph_operations::permute_vertices;
+    use crate::modified_dp::algorithm::edges_to_graph;
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
+
+    #[test]
+    fn test_hom_fingerprint(){
+        // 1 + 1 + 2 + 6 connected graphs with up to 4 vertices
+        let patterns = fingerprint_patterns(4);
+        assert_eq!(patterns.len(), 10);
+
+        let target = graph_from_edges(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 4)]);
+        let fingerprint = hom_fingerprint(&target, 4);
+        let expected : Vec<u64> = patterns.iter().map(|pattern| simple_brute_force(pattern, &target)).collect();
+        assert_eq!(fingerprint, expected);
+
+        // isomorphic targets have the same fingerprint, while C_6 and two triangles are distinguished by the triangle
+        assert_eq!(hom_fingerprint(&permute_vertices(&target, &[3, 0, 4, 1, 2]), 4), fingerprint);
+        let cycle = graph_from_edges(6, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+        let triangles = graph_from_edges(6, &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]);
+        assert_eq!(hom_fingerprint(&cycle, 2), hom_fingerprint(&triangles, 2));
+        assert_ne!(hom_fingerprint(&cycle, 3), hom_fingerprint(&triangles, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "limited to 10 vertices")]
+    fn test_fingerprint_pattern_limit(){
+        fingerprint_patterns(MAX_PATTERN_VERTICES + 1);
+    }
+
+    #[test]
+    fn test_ntd_fingerprint(){
+        let ntd = ntd_test_example();
+        let target = graph_from_edges(3, &[(0, 1), (1, 2), (2, 2)]);
+        let fingerprint = ntd_fingerprint(&ntd, &target);
+        assert_eq!(fingerprint.len(), 1 << ntd.all_possible_edges().len());
+        for (edges, &hom_number) in fingerprint.iter().enumerate(){
+            assert_eq!(hom_number, simple_brute_force(&edges_to_graph(&ntd, edges as u64), &target));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod report_tests{
+    use crate::pipeline::counting_pipeline::PipelineOptions;
+    use crate::report::hom_report::{Report, ReportFormat};
+    use crate::unit_tests::{graph_from_edges, ntd_test_example};
+
+    #[test]
+    fn test_pair_report(){
+        let pattern = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        let target = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let report = Report::for_pair("path", &pattern, "<triangle>", &target, &PipelineOptions::default()).unwrap();
+        assert_eq!(report.counts, vec![("path".to_string(), 12)]);
+        assert_eq!(report.graphs.len(), 2);
+
+        let markdown = report.render(ReportFormat::Markdown);
+        assert!(markdown.starts_with("# Homomorphisms from path into <triangle>\n"));
+        assert!(markdown.contains("| path | 12 |"));
+        assert!(markdown.contains("| algorithm | diaz_serna_thilikos |"));
+        assert!(markdown.contains("```text\n3 vertices, 3 edges\n0: 1 2"));
+
+        let html = report.render(ReportFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2>Graph &lt;triangle&gt;</h2>"));
+        assert!(html.contains("<tr><td>path</td><td>12</td></tr>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert_eq!(html.matches("<line").count(), 2 + 3);
+    }
+
+    #[test]
+    fn test_ntd_set_report(){
+        let ntd = ntd_test_example();
+        let target = graph_from_edges(2, &[(0, 1), (1, 1)]);
+        let report = Report::for_ntd_set(&ntd, "target", &target);
+
+        assert_eq!(report.counts.len(), 1 << ntd.all_possible_edges().len());
+        assert_eq!(report.counts[0], ("no edges".to_string(), 16));
+        assert_eq!(report.ntd.as_ref().unwrap().possible_edges, ntd.all_possible_edges().len());
+
+        // the loop of the target is drawn as circle besides the two vertices
+        let html = report.to_html();
+        assert_eq!(html.matches("<circle").count(), 3);
+        assert!(report.to_markdown().contains("## Nice tree decomposition"));
+
+        assert_eq!("html".parse::<ReportFormat>(), Ok(ReportFormat::Html));
+        assert_eq!("md".parse::<ReportFormat>(), Ok(ReportFormat::Markdown));
+        assert!("pdf".parse::<ReportFormat>().is_err());
+    }
+}
+
+#[cfg(test)]
+pub mod lattice_tests{
+    use std::collections::HashMap;
+    use crate::lattice::edge_subset_lattice::{lattice_arcs, lattice_to_dot, lattice_to_json, LatticeFormat};
+    use crate::modified_dp::algorithm::modified_dp_counts;
+    use crate::ntd_generation::ntd_generators::window_ntd;
+    use crate::report::hom_report::describe_edges;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_lattice_arcs(){
+        // every subset of 3 edges has an arc for each of its missing edges
+        let counts : HashMap<u64, u64> = (0..8).map(|subset| (subset, 0)).collect();
+        let arcs = lattice_arcs(3, &counts);
+        assert_eq!(arcs.len(), 3 * 4);
+        assert!(arcs.contains(&(0b001, 0b101, 2)));
+        assert!(arcs.iter().all(|&(subset, superset, i)| superset == subset | 1 << i && subset & (1 << i) == 0));
+
+        // subsets which are not contained in the counts are skipped
+        let counts : HashMap<u64, u64> = [(0, 0), (0b010, 0)].into_iter().collect();
+        assert_eq!(lattice_arcs(3, &counts), vec![(0, 0b010, 1)]);
+    }
+
+    #[test]
+    fn test_lattice_export(){
+        // the path 0 - 1 with loops, i.e. the possible edges (0,0), (1,1) and (0,1) in some order
+        let ntd = window_ntd(2, 1);
+        let target = graph_from_edges(2, &[(0, 1)]);
+        let counts = modified_dp_counts(&ntd, &target);
+
+        let dot = lattice_to_dot(&ntd, &counts);
+        assert!(dot.starts_with("digraph lattice {"));
+        assert!(dot.contains("    0 [label=\"no edges\\n4\"];"));
+        let full_label = format!("    7 [label=\"{}\\n0\"];", describe_edges(ntd.all_possible_edges(), 7));
+        assert!(dot.contains(&full_label));
+        assert_eq!(dot.matches(" -> ").count(), 12);
+        assert_eq!(dot.matches("rank=same").count(), 4);
+
+        let json = lattice_to_json(&ntd, &counts);
+        assert!(json.starts_with("{\"possible_edges\": ["));
+        assert!(json.contains("{\"id\": 0, \"edges\": [], \"count\": 4}"));
+        assert!(json.contains("{\"id\": 7, \"edges\": [0, 1, 2], \"count\": 0}"));
+        assert_eq!(json.matches("\"from\"").count(), 12);
+
+        assert_eq!("dot".parse::<LatticeFormat>(), Ok(LatticeFormat::Dot));
+        assert!("svg".parse::<LatticeFormat>().is_err());
+    }
+}
+
+#[cfg(test)]
+pub mod quotient_tests{
+    use crate::color_refinement::weisfeiler_leman::color_refinement;
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::quotient::target_quotient::{quotient, quotient_hom_count, refine_by_twins, twin_partition, weighted_hom_count, QuotientError};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_twin_quotient(){
+        // K_{2,3} is the blow-up of an edge
+        let target = graph_from_edges(5, &[(0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4)]);
+        assert_eq!(twin_partition(&target), vec![0, 0, 1, 1, 1]);
+
+        let quotient_of_target = quotient(&target, &[7, 7, 3, 3, 3]).unwrap();
+        assert_eq!(quotient_of_target.weights, vec![2, 3]);
+        assert_eq!(quotient_of_target.classes, vec![0, 0, 1, 1, 1]);
+        assert_eq!(quotient_of_target.graph.node_count(), 2);
+        assert_eq!(quotient_of_target.graph.edge_count(), 1);
+
+        assert_eq!(quotient(&target, &[0, 0, 0, 1, 1]).err(), Some(QuotientError::NotTwins(0, 2)));
+        assert_eq!(quotient(&target, &[0, 0]).err(), Some(QuotientError::LengthMismatch { expected: 5, actual: 2 }));
+
+        // color refinement puts all vertices of the cycle C_4 into one class, which is split into the two sides
+        let cycle = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(refine_by_twins(&cycle, &color_refinement(&cycle)), vec![0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_quotient_hom_count(){
+        let patterns = [
+            graph_from_edges(1, &[]),
+            graph_from_edges(3, &[(0, 1), (1, 2)]),
+            graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]),
+            graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]),
+            graph_from_edges(5, &[(0, 1), (0, 2), (0, 3), (3, 4), (4, 4)]),
+            graph_from_edges(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)]),
+            // a vertex with a loop in the bag of a join node
+            graph_from_edges(10, &[(0, 0), (0, 1), (1, 2), (2, 3), (0, 4), (4, 5), (5, 6), (0, 7), (7, 8), (8, 9)]),
+        ];
+        let targets = [
+            graph_from_edges(5, &[(0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4)]),
+            // a looped twin class {0, 1} next to the twins {2, 3} and the single vertex 4
+            graph_from_edges(5, &[(0, 0), (0, 1), (1, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 4), (3, 4)]),
+            // without twins the quotient is the target itself
+            graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 3)]),
+        ];
+
+        for pattern in &patterns{
+            let ntd = compute_nice_tree_decomposition(pattern);
+            for target in &targets{
+                assert_eq!(quotient_hom_count(pattern, &ntd, target), diaz_serna_thilikos_algorithm(pattern, &ntd, target));
+            }
+        }
+
+        // every class of the trivial partition has weight 1
+        let target = &targets[2];
+        let trivial = quotient(target, &[0, 1, 2, 3]).unwrap();
+        let ntd = compute_nice_tree_decomposition(&patterns[2]);
+        assert_eq!(weighted_hom_count(&patterns[2], &ntd, &trivial), diaz_serna_thilikos_algorithm(&patterns[2], &ntd, target));
+    }
+}
+
+#[cfg(test)]
+pub mod interval_counting_tests{
+    use crate::bounds::interval_counting::{interval_hom_count, Interval, PruningOptions};
+    use crate::diaz_serna_thilikos::diaz_algorithm::diaz_serna_thilikos_algorithm;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_interval_arithmetic(){
+        let a = Interval { lower: 2, upper: 5 };
+        assert_eq!(a.add(&Interval::exact(1)), Interval { lower: 3, upper: 6 });
+        assert_eq!(a.mul(&Interval { lower: 0, upper: 3 }), Interval { lower: 0, upper: 15 });
+        assert!(a.contains(5) && !a.contains(1) && !a.is_exact());
+
+        // saturation keeps both bounds valid
+        let large = Interval { lower: u64::MAX / 2, upper: u64::MAX / 2 + 1 };
+        let product = large.mul(&Interval::exact(3));
+        assert!(product.is_unbounded());
+        assert_eq!(product.lower, u64::MAX);
+    }
+
+    #[test]
+    fn test_interval_hom_count(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let hom_number = diaz_serna_thilikos_algorithm(&from_graph, &ntd, &to_graph);
+
+        // without pruning the count is exact
+        let exact = interval_hom_count(&from_graph, &ntd, &to_graph, &PruningOptions::default());
+        assert_eq!(exact.bounds, Interval::exact(hom_number));
+        assert_eq!(exact.dropped_entries, 0);
+
+        for options in [PruningOptions { drop_below: 2, max_entries: None }, PruningOptions { drop_below: 0, max_entries: Some(3) },
+                        PruningOptions { drop_below: 5, max_entries: Some(1) }]{
+            let pruned = interval_hom_count(&from_graph, &ntd, &to_graph, &options);
+            assert!(pruned.bounds.contains(hom_number), "{:?} does not contain {}", pruned.bounds, hom_number);
+            assert!(pruned.dropped_entries > 0);
+        }
+
+        // 2^64 homomorphisms from a path into the edge with loops exceed u64
+        let edges : Vec<(usize, usize)> = (0..63).map(|v| (v, v + 1)).collect();
+        let path = graph_from_edges(64, &edges);
+        let looped_edge = graph_from_edges(2, &[(0, 0), (0, 1), (1, 1)]);
+        let bounds = interval_hom_count(&path, &compute_nice_tree_decomposition(&path), &looped_edge, &PruningOptions::default()).bounds;
+        assert_eq!(bounds.lower, u64::MAX);
+        assert!(bounds.is_unbounded());
+    }
+}
+
+#[cfg(test)]
+pub mod log_counting_tests{
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::log_space::log_counting::log_hom_count;
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_log_hom_count(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        let count = log_hom_count(&from_graph, &ntd, &to_graph);
+        assert!(count.contains(1280), "{} does not contain 1280", count);
+        assert!(count.error > 0.0 && count.error < 1e-12);
+
+        let patterns = [graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]), graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 3)])];
+        let target = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (3, 3)]);
+        for pattern in &patterns{
+            let count = log_hom_count(pattern, &compute_nice_tree_decomposition(pattern), &target);
+            assert!(count.contains(simple_brute_force(pattern, &target)));
+        }
+
+        // no homomorphism from a triangle into a bipartite graph
+        let square = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let count = log_hom_count(&patterns[0], &compute_nice_tree_decomposition(&patterns[0]), &square);
+        assert!(count.is_zero());
+        assert_eq!(count.to_string(), "0");
+    }
+
+    #[test]
+    fn test_log_hom_count_beyond_u64(){
+        // 10 * 9^62 walks with 62 edges in K_10, which is about 10^60.2
+        let edges : Vec<(usize, usize)> = (0..62).map(|v| (v, v + 1)).collect();
+        let path = graph_from_edges(63, &edges);
+        let complete_edges : Vec<(usize, usize)> = (0..10).flat_map(|u| (u + 1..10).map(move |v| (u, v))).collect();
+        let complete = graph_from_edges(10, &complete_edges);
+
+        let count = log_hom_count(&path, &compute_nice_tree_decomposition(&path), &complete);
+        let expected = 1.0 + 62.0 * 9f64.log10();
+        assert!((count.log10 - expected).abs() <= count.error, "{} differs from 10^{}", count, expected);
+        assert!(count.value() > 1e60);
+    }
+}
+
+#[cfg(test)]
+pub mod ablation_tests {
+    use std::time::Duration;
+    use crate::ablation::optimization_ablation::{AblationRecord, run_ablation, speedups, Toggles, write_ablation_csv};
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_with_result, DiazOptions, DiazResult};
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::ntd_generation::ntd_generators::complete_ntd;
+    use crate::pipeline::counting_pipeline::compute_nice_tree_decomposition;
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_toggles(){
+        let all = Toggles::all();
+        assert_eq!(all.len(), 16);
+        assert!(all.iter().enumerate().all(|(i, toggles)| toggles.index() == i));
+        assert_eq!(all[0].name(), "none");
+        assert_eq!(all[15].name(), "dense_tables+bitset_adjacency+zero_pruning+wl_pruning");
+
+        // all optimizations switched on are the usual optimizations of the algorithm
+        let options = all[15].options();
+        assert!(options.image_pruning && !options.scalar_adjacency && !options.keep_zero_tables);
+        assert!(all[0].options().scalar_adjacency && all[0].options().keep_zero_tables);
+    }
+
+    #[test]
+    fn test_run_ablation(){
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+
+        let records = run_ablation(&from_graph, &ntd, &to_graph, 2);
+        assert_eq!(records.len(), 16);
+        assert!(records.iter().all(|record| record.count == 1280 && record.durations.len() == 2));
+        assert_eq!(speedups(&records).len(), 4);
+
+        // a triangle has no homomorphism into a square, with and without the early exit
+        let triangle = graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let square = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let ntd = compute_nice_tree_decomposition(&triangle);
+        assert_eq!(simple_brute_force(&triangle, &square), 0);
+        assert!(run_ablation(&triangle, &ntd, &square, 1).iter().all(|record| record.count == 0));
+
+        let options = DiazOptions { keep_zero_tables: true, ..DiazOptions::default() };
+        assert_eq!(diaz_serna_thilikos_with_result(&triangle, &ntd, &square, &options).0, DiazResult::Count(0));
+        let options = DiazOptions::default();
+        assert!(matches!(diaz_serna_thilikos_with_result(&triangle, &ntd, &square, &options).0, DiazResult::ZeroAtNode(_)));
+
+        // the toggles also apply to the streaming of path decompositions and to the compact tables
+        let ntd = complete_ntd(3);
+        for compact_tables in [false, true]{
+            let options = DiazOptions { compact_tables, keep_zero_tables: true, ..DiazOptions::default() };
+            let (result, report) = diaz_serna_thilikos_with_result(&triangle, &ntd, &square, &options);
+            assert_eq!(result, DiazResult::Count(0));
+            assert_eq!(report.len(), ntd.stingy_ordering().len());
+
+            let options = DiazOptions { compact_tables, scalar_adjacency: true, ..DiazOptions::default() };
+            assert_eq!(diaz_serna_thilikos_with_result(&square, &complete_ntd(4), &triangle, &options).0.count(), 18);
+        }
+    }
+
+    #[test]
+    fn test_speedups_and_csv(){
+        let record = |index : usize, micros : u64| AblationRecord { toggles: Toggles::from_index(index), count: 1, durations: vec![Duration::from_micros(micros)] };
+        // dense tables halve the running time, all other optimizations have no effect
+        let records : Vec<AblationRecord> = (0..16).map(|index| record(index, if index & 1 != 0 { 100 } else { 200 })).collect();
+
+        let speedups = speedups(&records);
+        assert_eq!(speedups[0].0, "dense_tables");
+        assert!((speedups[0].1 - 2.0).abs() < 1e-9);
+        assert!(speedups[1..].iter().all(|(_, speedup)| (speedup - 1.0).abs() < 1e-9));
+
+        let path = std::env::temp_dir().join(format!("ablation_test_{}.csv", std::process::id()));
+        write_ablation_csv(&records, &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines : Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 17);
+        assert_eq!(lines[1], "false,false,false,false,none,1,200,200,1.000");
+        assert_eq!(lines[2], "true,false,false,false,dense_tables,1,100,100,2.000");
+    }
+}
+
+#[cfg(test)]
+pub mod tree_counting_tests {
+    use num_bigint::BigUint;
+    use crate::brute_force::brute_force_homomorphism_counter::simple_brute_force;
+    use crate::pipeline::counting_pipeline::{Algorithm, count_graphs, PipelineOptions};
+    use crate::trees::tree_counting::{is_forest, labeled_tree_hom_count, tree_hom_count};
+    use crate::unit_tests::graph_from_edges;
+
+    #[test]
+    fn test_is_forest(){
+        assert!(is_forest(&graph_from_edges(0, &[])));
+        assert!(is_forest(&graph_from_edges(6, &[(0, 1), (1, 2), (1, 3), (4, 5)])));
+        assert!(!is_forest(&graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)])));
+        assert!(!is_forest(&graph_from_edges(2, &[(0, 1), (1, 1)])));
+    }
+
+    #[test]
+    fn test_tree_hom_count(){
+        // a star, a spider and a forest with an isolated vertex
+        let patterns = [
+            graph_from_edges(4, &[(0, 1), (0, 2), (0, 3)]),
+            graph_from_edges(6, &[(2, 0), (0, 1), (2, 3), (3, 4), (2, 5)]),
+            graph_from_edges(5, &[(0, 3), (3, 1), (4, 3)]),
+        ];
+        let targets = [
+            graph_from_edges(5, &[(0, 1), (1, 2), (1, 3), (3, 4)]),
+            graph_from_edges(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]),
+        ];
+        for pattern in &patterns{
+            for target in &targets{
+                assert_eq!(tree_hom_count(pattern, target), Some(BigUint::from(simple_brute_force(pattern, target))));
+            }
+        }
+        assert_eq!(tree_hom_count(&graph_from_edges(3, &[(0, 1), (1, 2), (2, 0)]), &targets[0]), None);
+    }
+
+    #[test]
+    fn test_labeled_tree_hom_count(){
+        // a root labeled 0 with two leaves labeled 1 into a root labeled 0 with leaves labeled 1, 1 and 2
+        let pattern = graph_from_edges(3, &[(0, 1), (0, 2)]);
+        let target = graph_from_edges(4, &[(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(labeled_tree_hom_count(&pattern, &[0, 1, 1], &target, &[0, 1, 1, 2]), Some(BigUint::from(4u8)));
+        assert_eq!(labeled_tree_hom_count(&pattern, &[1, 2, 2], &target, &[0, 1, 1, 2]), Some(BigUint::from(0u8)));
+        assert_eq!(labeled_tree_hom_count(&pattern, &[0, 0, 0], &target, &[0, 0, 0, 0]), tree_hom_count(&pattern, &target));
+    }
+
+    #[test]
+    fn test_auto_dispatch_of_trees(){
+        let pattern = graph_from_edges(7, &[(0, 1), (1, 2), (1, 3), (4, 5), (5, 6), (6, 4)]);
+        let target = graph_from_edges(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]);
+        let options = PipelineOptions { algorithm: Algorithm::Auto, ..PipelineOptions::default() };
+
+        // the tree is counted without a nice tree decomposition, the triangle with one of width 2
+        let result = count_graphs(&pattern, &target, &options).unwrap();
+        assert_eq!(result.count, simple_brute_force(&pattern, &target));
+        assert_eq!(result.width, Some(2));
+
+        let tree = graph_from_edges(4, &[(0, 1), (1, 2), (1, 3)]);
+        let result = count_graphs(&tree, &target, &options).unwrap();
+        assert_eq!((result.count, result.width), (simple_brute_force(&tree, &target), None));
+    }
+}
+
+#[cfg(test)]
+pub mod dp_snapshot_tests {
+    use std::collections::HashSet;
+    use crate::file_handler::graph_handler::import_metis;
+    use crate::file_handler::tree_decomposition_handler::import_ntd;
+    use crate::rooted::rooted_homomorphisms::hom_distribution;
+    use crate::testing::consistency_testing::TestRng;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+    use crate::warm_start::dp_snapshots::{DpSnapshot, load_snapshot, parse_snapshot, save_snapshot, SnapshotError};
+
+    fn example() -> (Graph, crate::tree_decompositions::nice_tree_decomposition::NiceTreeDecomposition, Graph) {
+        let from_graph = import_metis("data/metis_graphs/handmade/from_2.graph").unwrap();
+        let to_graph = import_metis("data/metis_graphs/handmade/to_2.graph").unwrap();
+        let ntd = import_ntd("data/nice_tree_decompositions/benchmark_ntds/handmade/ntd_bench_8.ntd").unwrap();
+        (from_graph, ntd, to_graph)
+    }
+
+    fn is_homomorphism(from_graph : &Graph, to_graph : &Graph, images : &[usize]) -> bool {
+        let n = from_graph.node_count();
+        (0..n).flat_map(|u| (0..n).map(move |v| (u, v)))
+            .filter(|&(u, v)| from_graph.has_edge(Vertex::new(u), Vertex::new(v)))
+            .all(|(u, v)| to_graph.has_edge(Vertex::new(images[u]), Vertex::new(images[v])))
+    }
+
+    #[test]
+    fn test_snapshot_queries(){
+        let (from_graph, ntd, to_graph) = example();
+        let snapshot = DpSnapshot::compute(&from_graph, &ntd, &to_graph);
+        assert!(snapshot.is_complete(&ntd));
+        assert_eq!(snapshot.count(&ntd), 1280);
+        assert_eq!(snapshot.root_only(&ntd).count(&ntd), 1280);
+        assert!(!snapshot.root_only(&ntd).is_complete(&ntd));
+
+        for v in 0..from_graph.node_count(){
+            assert_eq!(snapshot.anchored_counts(&ntd, v), hom_distribution(&from_graph, &ntd, &to_graph, v));
+        }
+
+        let mut homomorphisms = HashSet::new();
+        assert!(snapshot.for_each_homomorphism(&ntd, |images| {
+            assert!(is_homomorphism(&from_graph, &to_graph, images));
+            homomorphisms.insert(images.to_vec())
+        }));
+        assert_eq!(homomorphisms.len(), 1280);
+
+        // the enumeration stops as soon as visit returns false
+        let mut visited = 0;
+        assert!(!snapshot.for_each_homomorphism(&ntd, |_| { visited += 1; visited < 10 }));
+        assert_eq!(visited, 10);
+
+        let mut rng = TestRng::new(7);
+        for _ in 0..20{
+            assert!(homomorphisms.contains(&snapshot.sample(&ntd, &mut rng).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_resume_from_frontier(){
+        let (from_graph, ntd, to_graph) = example();
+        let snapshot = DpSnapshot::compute(&from_graph, &ntd, &to_graph);
+
+        // the table of a node in the middle of the stingy ordering is the frontier of an interrupted run
+        let stingy_ordering = ntd.stingy_ordering();
+        let q = stingy_ordering[stingy_ordering.len() / 2];
+        let frontier = DpSnapshot { target_vertices: to_graph.node_count(), tables: [(q, snapshot.tables[&q].clone())].into_iter().collect() };
+
+        let resumed = frontier.resume(&from_graph, &ntd, &to_graph);
+        assert_eq!(resumed.count(&ntd), 1280);
+        assert_eq!(resumed.tables[&ntd.root()], snapshot.tables[&ntd.root()]);
+    }
+
+    #[test]
+    fn test_save_and_load_snapshot(){
+        let (from_graph, ntd, to_graph) = example();
+        let snapshot = DpSnapshot::compute(&from_graph, &ntd, &to_graph);
+
+        let path = std::env::temp_dir().join("dp_snapshot_test.dps");
+        save_snapshot(&snapshot, &path).unwrap();
+        let loaded = load_snapshot(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, snapshot);
+        assert_eq!(loaded.count(&ntd), 1280);
+
+        let parsed = parse_snapshot("c comment\ns 3\nt 2\ne 0 4\ne 5 1\n".as_bytes()).unwrap();
+        assert_eq!(parsed.target_vertices, 3);
+        assert_eq!(parsed.tables[&1].len(), 2);
+
+        assert!(matches!(parse_snapshot("t 1\n".as_bytes()), Err(SnapshotError::MalformedLine { line: 1, .. })));
+        assert!(matches!(parse_snapshot("s 3\ne 0 1\n".as_bytes()), Err(SnapshotError::MalformedLine { line: 2, .. })));
+        assert!(matches!(parse_snapshot("s 3\nt 1\ne 0\n".as_bytes()), Err(SnapshotError::MalformedLine { line: 3, .. })));
+        assert!(matches!(parse_snapshot("c only a comment\n".as_bytes()), Err(SnapshotError::MissingStartLine)));
+    }
+}
+
@@ -0,0 +1,89 @@
+/// A module containing the closed form for the number of homomorphisms from a path into a target G. A
+/// homomorphism from the path P_k with k vertices is exactly a walk with k-1 edges in G, hence
+/// $hom(P_k, G) = 1^T A^{k-1} 1$ for the adjacency matrix A of G, where a loop of G is a diagonal entry of A.
+/// For short paths the vector $A^{i} 1$ of the numbers of walks ending at each vertex is updated k-1 times in
+/// O(|V(G)| + |E(G)|) operations each, for long paths the power is computed by repeated squaring in
+/// O(|V(G)|^3 log k) operations. Both work over BigUint instead of tables of size |V(G)|^2 per node of a nice
+/// tree decomposition and do not overflow for long paths.
+pub mod walk_counts {
+    use num_bigint::BigUint;
+    use crate::tree_decompositions::tree_structure::{Graph, Vertex};
+
+    /// Returns the number of edges of the pattern if it is a path without loops, i.e. k-1 for P_k, and None
+    /// otherwise. A single vertex is the path P_1 with 0 edges.
+    pub fn path_length(graph : &Graph) -> Option<usize> {
+        let n = graph.node_count();
+        if n == 0 || graph.edge_count() != n - 1 { return None; }
+
+        let has_edge = |u : usize, v : usize| graph.has_edge(Vertex::new(u), Vertex::new(v));
+        let degrees : Vec<usize> = (0..n).map(|u| (0..n).filter(|&v| has_edge(u, v)).count()).collect();
+        if (0..n).any(|u| has_edge(u, u)) || degrees.iter().any(|&degree| degree > 2) { return None; }
+
+        // with n-1 edges and maximal degree 2 the pattern is a path iff it is connected, i.e. not a path
+        // together with cycles, which is checked by walking along the path from one of its ends
+        let start = (0..n).find(|&u| degrees[u] <= 1)?;
+        let (mut previous, mut current, mut visited) = (None, start, 1);
+        while let Some(next) = (0..n).find(|&v| has_edge(current, v) && Some(v) != previous) {
+            (previous, current, visited) = (Some(current), next, visited + 1);
+        }
+        if visited == n { Some(n - 1) } else { None }
+    }
+
+    /// Returns the number of walks with the given number of edges in the graph, i.e. $1^T A^{length} 1$. The
+    /// walks with 0 edges are the vertices. The vector iteration is used unless repeated squaring needs fewer
+    /// operations.
+    pub fn count_walks(graph : &Graph, length : usize) -> BigUint {
+        let n = graph.node_count();
+        let neighbours : Vec<Vec<usize>> = (0..n)
+            .map(|a| (0..n).filter(|&b| graph.has_edge(Vertex::new(a), Vertex::new(b))).collect())
+            .collect();
+
+        let iteration_cost = length.saturating_mul(n + neighbours.iter().map(Vec::len).sum::<usize>());
+        let squaring_cost = n.saturating_pow(3).saturating_mul((usize::BITS - length.leading_zeros()) as usize);
+        if iteration_cost <= squaring_cost { count_walks_by_iteration(&neighbours, length) } else { count_walks_by_squaring(graph, length) }
+    }
+
+    /// Returns $1^T A^{length} 1$ by updating the numbers of walks ending at each vertex once per edge of the walks.
+    fn count_walks_by_iteration(neighbours : &[Vec<usize>], length : usize) -> BigUint {
+        let mut walks = vec![BigUint::from(1u8); neighbours.len()];
+        for _ in 0..length{
+            walks = neighbours.iter().map(|neighbours_a| neighbours_a.iter().map(|&b| &walks[b]).sum()).collect();
+        }
+        walks.into_iter().sum()
+    }
+
+    /// Returns $1^T A^{length} 1$ by computing the power of the adjacency matrix by repeated squaring.
+    fn count_walks_by_squaring(graph : &Graph, length : usize) -> BigUint {
+        let n = graph.node_count();
+        let adjacency : Vec<Vec<BigUint>> = (0..n)
+            .map(|a| (0..n).map(|b| BigUint::from(graph.has_edge(Vertex::new(a), Vertex::new(b)) as u8)).collect())
+            .collect();
+
+        // the power A^length by repeated squaring, starting with the identity
+        let mut power : Vec<Vec<BigUint>> = (0..n).map(|a| (0..n).map(|b| BigUint::from((a == b) as u8)).collect()).collect();
+        let mut square = adjacency;
+        let mut exponent = length;
+        while exponent > 0 {
+            if exponent & 1 == 1 { power = multiply(&power, &square); }
+            exponent >>= 1;
+            if exponent > 0 { square = multiply(&square, &square); }
+        }
+
+        power.iter().flatten().sum()
+    }
+
+    /// Returns hom(from_graph, to_graph) by the closed form if "from_graph" is a path, None otherwise.
+    pub fn path_hom_count(from_graph : &Graph, to_graph : &Graph) -> Option<BigUint> {
+        path_length(from_graph).map(|length| count_walks(to_graph, length))
+    }
+
+    /// Returns the product of the two square matrices.
+    fn multiply(a : &[Vec<BigUint>], b : &[Vec<BigUint>]) -> Vec<Vec<BigUint>> {
+        let n = a.len();
+        (0..n)
+            .map(|i| (0..n).map(|j| (0..n).filter(|&k| a[i][k] != BigUint::ZERO && b[k][j] != BigUint::ZERO)
+                .map(|k| &a[i][k] * &b[k][j])
+                .sum()).collect())
+            .collect()
+    }
+}
@@ -0,0 +1,370 @@
+/// A module containing snapshots of the tables of the algorithm of diaz et all, which can be written to a file
+/// and loaded again, such that follow-up queries on the same pattern, decomposition and target are answered
+/// without running the dynamic program again. With the tables of all nodes, the number of homomorphisms, the
+/// anchored counts of each vertex, uniformly random homomorphisms and the enumeration of all homomorphisms are
+/// obtained by descending from the root, i.e. counting, sampling and enumeration share one preprocessing phase.
+/// The tables of an interrupted run form a frontier snapshot, from which the run is resumed.
+///
+/// A snapshot file starts with the line "s target_vertices", each table with the line "t node", followed by one
+/// line "e mapping value" per non-zero entry. Nodes are numbered from 1 as in the .ntd format, comment lines
+/// start with "c".
+pub mod dp_snapshots {
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::fs::File;
+    use std::io;
+    use std::io::{BufRead, BufWriter, Write};
+    use std::path::Path;
+    use crate::diaz_serna_thilikos::diaz_algorithm::{diaz_serna_thilikos_all_tables, NodeTable, PartialRun};
+    use crate::file_handler::open_reader;
+    use crate::integer_functions::integer_functions_methods::{BagIndexer, Mapping};
+    use crate::testing::consistency_testing::TestRng;
+    use crate::tree_decompositions::nice_tree_decomposition::{NiceTreeDecomposition, NodeType};
+    use crate::tree_decompositions::tree_structure::{Graph, TreeNode, Vertex};
+
+    /// The tables of a run of the algorithm of diaz et all for a fixed pattern, nice tree decomposition and
+    /// target, which are not stored in the snapshot and have to be passed to the queries again.
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct DpSnapshot {
+        /// the number of vertices of the target
+        pub target_vertices: usize,
+        /// the non-zero entries of the tables of the stored nodes
+        pub tables: HashMap<TreeNode, NodeTable>,
+    }
+
+    /// The reason why a snapshot file could not be parsed. Line numbers start with 1.
+    #[derive(Debug)]
+    pub enum SnapshotError {
+        /// the file could not be read
+        Io(io::Error),
+        /// the line does not follow the snapshot format
+        MalformedLine { line: usize, content: String, reason: String },
+        /// the file does not contain a start line "s target_vertices"
+        MissingStartLine,
+    }
+
+    impl fmt::Display for SnapshotError {
+        fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SnapshotError::Io(error) => write!(f, "could not read the snapshot: {}", error),
+                SnapshotError::MalformedLine { line, content, reason } => write!(f, "line {}: {} in \"{}\"", line, reason, content),
+                SnapshotError::MissingStartLine => write!(f, "the start line \"s target_vertices\" is missing"),
+            }
+        }
+    }
+
+    impl std::error::Error for SnapshotError {}
+
+    impl From<io::Error> for SnapshotError {
+        fn from(error : io::Error) -> SnapshotError { SnapshotError::Io(error) }
+    }
+
+    impl DpSnapshot {
+        /// Runs the algorithm of diaz et all and keeps the tables of all nodes.
+        pub fn compute(from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> DpSnapshot {
+            let feasible = vec![vec![true; to_graph.node_count()]; from_graph.node_count()];
+            let tables = diaz_serna_thilikos_all_tables(from_graph, ntd, to_graph, &feasible, HashMap::new());
+            DpSnapshot { target_vertices: to_graph.node_count(), tables }
+        }
+
+        /// Returns the frontier snapshot of an interrupted run into a target with the given number of vertices,
+        /// i.e. the tables of the roots of its completed subtrees.
+        pub fn from_partial_run(partial : &PartialRun, target_vertices : usize) -> DpSnapshot {
+            DpSnapshot { target_vertices, tables: partial.tables.clone() }
+        }
+
+        /// Completes the run from the stored tables, whose subtrees are not processed again. The result contains
+        /// the stored tables and the tables of all processed nodes, hence it is complete iff no node has been skipped.
+        pub fn resume(self, from_graph : &Graph, ntd : &NiceTreeDecomposition, to_graph : &Graph) -> DpSnapshot {
+            assert_eq!(self.target_vertices, to_graph.node_count(), "The snapshot belongs to another target!");
+            let feasible = vec![vec![true; to_graph.node_count()]; from_graph.node_count()];
+            let tables = diaz_serna_thilikos_all_tables(from_graph, ntd, to_graph, &feasible, self.tables);
+            DpSnapshot { target_vertices: self.target_vertices, tables }
+        }
+
+        /// Returns the snapshot containing only the table of the root, which suffices for the count.
+        pub fn root_only(&self, ntd : &NiceTreeDecomposition) -> DpSnapshot {
+            let tables = self.tables.get(&ntd.root()).map(|table| (ntd.root(), table.clone())).into_iter().collect();
+            DpSnapshot { target_vertices: self.target_vertices, tables }
+        }
+
+        /// Returns true if the snapshot contains the table of every node, which is required by anchored_counts,
+        /// sample and for_each_homomorphism.
+        pub fn is_complete(&self, ntd : &NiceTreeDecomposition) -> bool {
+            ntd.stingy_ordering().iter().all(|p| self.tables.contains_key(p))
+        }
+
+        /// Returns the number of homomorphisms, i.e. the sum of the table of the root.
+        /// Panics if the snapshot does not contain the table of the root.
+        pub fn count(&self, ntd : &NiceTreeDecomposition) -> u64 {
+            self.table(ntd.root()).values().sum()
+        }
+
+        /// Returns the vector c with c[a] = the number of homomorphisms which map the vertex v onto a. The number of
+        /// extensions of each mapping of a node to the vertices outside of its subtree is computed top-down from the
+        /// root until the first node containing v, where the extensions inside and outside of the subtree are
+        /// multiplied. Panics if the snapshot is not complete or v is not a vertex of the decomposition.
+        pub fn anchored_counts(&self, ntd : &NiceTreeDecomposition, v : usize) -> Vec<u64> {
+            let n = self.target_vertices as Mapping;
+            let vertex = Vertex::new(v);
+            let mut outside : HashMap<TreeNode, HashMap<Mapping, u64>> = HashMap::new();
+            outside.insert(ntd.root(), self.table(ntd.root()).keys().map(|&f| (f, 1)).collect());
+
+            // the stingy ordering in reverse visits every parent before its children
+            for p in ntd.stingy_ordering().into_iter().rev(){
+                let outside_p = outside.remove(&p).unwrap_or_default();
+
+                if ntd.bag(p).unwrap().contains(&vertex) {
+                    let indexer = BagIndexer::of_node(ntd, p, n);
+                    let mut counts = vec![0; self.target_vertices];
+                    for (f, value) in self.table(p){
+                        counts[indexer.image(*f, vertex) as usize] += value * outside_p.get(f).copied().unwrap_or(0);
+                    }
+                    return counts;
+                }
+
+                match ntd.node_type(p){
+                    Some(NodeType::Introduce) => {
+                        let q = *ntd.unique_child(p).unwrap();
+                        let introduced_vertex = *ntd.unique_vertex(p).unwrap();
+                        let indexer_p = BagIndexer::of_node(ntd, p, n);
+                        let outside_q = outside.entry(q).or_default();
+                        for (f, value) in outside_p{
+                            *outside_q.entry(indexer_p.reduce(f, introduced_vertex)).or_insert(0) += value;
+                        }
+                    }
+                    Some(NodeType::Forget) => {
+                        let q = *ntd.unique_child(p).unwrap();
+                        let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+                        let indexer_q = BagIndexer::of_node(ntd, q, n);
+                        let outside_q = self.table(q).keys()
+                            .filter_map(|&g| outside_p.get(&indexer_q.reduce(g, forgotten_vertex)).map(|&value| (g, value)))
+                            .collect();
+                        outside.insert(q, outside_q);
+                    }
+                    Some(NodeType::Join) => {
+                        let children = ntd.children(p).unwrap();
+                        for (&q, &other) in [(&children[0], &children[1]), (&children[1], &children[0])]{
+                            let table_other = self.table(other);
+                            let outside_q = outside_p.iter()
+                                .map(|(&f, &value)| (f, value * table_other.get(&f).copied().unwrap_or(0)))
+                                .collect();
+                            outside.insert(q, outside_q);
+                        }
+                    }
+                    Some(NodeType::Leaf) | None => {}
+                }
+            }
+            panic!("The vertex {} is not contained in any bag!", v);
+        }
+
+        /// Returns a uniformly random homomorphism as the vector of the images of the vertices, or None if there
+        /// is no homomorphism. Starting with a mapping of the root chosen with probability proportional to its
+        /// entry, the image of each forgotten vertex is chosen proportional to the entries of the child.
+        /// Panics if the snapshot is not complete.
+        pub fn sample(&self, ntd : &NiceTreeDecomposition, rng : &mut TestRng) -> Option<Vec<usize>> {
+            let n = self.target_vertices as Mapping;
+            let mut root_entries : Vec<(Mapping, u64)> = self.table(ntd.root()).iter().map(|(&f, &value)| (f, value)).collect();
+            root_entries.sort_unstable();
+            let f_root = choose(rng, root_entries)?;
+
+            let mut images = self.root_images(ntd, f_root);
+            let mut pending = vec![(ntd.root(), f_root)];
+            while let Some((p, f)) = pending.pop() {
+                match ntd.node_type(p){
+                    Some(NodeType::Introduce) => {
+                        let q = *ntd.unique_child(p).unwrap();
+                        pending.push((q, BagIndexer::of_node(ntd, p, n).reduce(f, *ntd.unique_vertex(p).unwrap())));
+                    }
+                    Some(NodeType::Forget) => {
+                        let q = *ntd.unique_child(p).unwrap();
+                        let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+                        // the mappings of p are extended by the image of the forgotten vertex
+                        let indexer_p = BagIndexer::of_node(ntd, p, n);
+                        let table_q = self.table(q);
+                        let extensions = (0..n).filter_map(|a| table_q.get(&indexer_p.extend(f, forgotten_vertex, a)).map(|&value| (a, value))).collect();
+
+                        let a = choose(rng, extensions).expect("The tables of the snapshot are inconsistent!");
+                        images[forgotten_vertex.index()] = a as usize;
+                        pending.push((q, indexer_p.extend(f, forgotten_vertex, a)));
+                    }
+                    Some(NodeType::Join) => pending.extend(ntd.children(p).unwrap().iter().map(|&q| (q, f))),
+                    Some(NodeType::Leaf) | None => {}
+                }
+            }
+            Some(images)
+        }
+
+        /// Calls visit with the vector of the images of the vertices of every homomorphism until visit returns
+        /// false. Returns true if all homomorphisms have been visited. Since only non-zero entries are followed,
+        /// every branch of the descent ends in a homomorphism. Panics if the snapshot is not complete.
+        pub fn for_each_homomorphism<F>(&self, ntd : &NiceTreeDecomposition, mut visit : F) -> bool
+            where F: FnMut(&[usize]) -> bool
+        {
+            let mut root_entries : Vec<Mapping> = self.table(ntd.root()).keys().copied().collect();
+            root_entries.sort_unstable();
+
+            for f_root in root_entries{
+                let mut images = self.root_images(ntd, f_root);
+                if !self.descend(ntd, &mut vec![(ntd.root(), f_root)], &mut images, &mut visit) { return false; }
+            }
+            true
+        }
+
+        /// Visits all extensions of the images to the subtrees of the pending pairs of nodes and mappings, where the
+        /// pending pairs are unchanged afterwards. Returns false if visit returned false.
+        fn descend<F>(&self, ntd : &NiceTreeDecomposition, pending : &mut Vec<(TreeNode, Mapping)>, images : &mut Vec<usize>, visit : &mut F) -> bool
+            where F: FnMut(&[usize]) -> bool
+        {
+            let Some((p, f)) = pending.pop() else { return visit(images); };
+            let n = self.target_vertices as Mapping;
+
+            let complete = match ntd.node_type(p){
+                Some(NodeType::Introduce) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    pending.push((q, BagIndexer::of_node(ntd, p, n).reduce(f, *ntd.unique_vertex(p).unwrap())));
+                    let complete = self.descend(ntd, pending, images, visit);
+                    pending.pop();
+                    complete
+                }
+                Some(NodeType::Forget) => {
+                    let q = *ntd.unique_child(p).unwrap();
+                    let forgotten_vertex = *ntd.unique_vertex(p).unwrap();
+                    let indexer_p = BagIndexer::of_node(ntd, p, n);
+                    let table_q = self.table(q);
+
+                    let mut complete = true;
+                    for a in 0..n{
+                        let g = indexer_p.extend(f, forgotten_vertex, a);
+                        if !table_q.contains_key(&g) { continue; }
+                        images[forgotten_vertex.index()] = a as usize;
+                        pending.push((q, g));
+                        complete = self.descend(ntd, pending, images, visit);
+                        pending.pop();
+                        if !complete { break; }
+                    }
+                    complete
+                }
+                Some(NodeType::Join) => {
+                    let children = ntd.children(p).unwrap();
+                    pending.extend(children.iter().map(|&q| (q, f)));
+                    let complete = self.descend(ntd, pending, images, visit);
+                    pending.truncate(pending.len() - children.len());
+                    complete
+                }
+                Some(NodeType::Leaf) | None => self.descend(ntd, pending, images, visit),
+            };
+
+            pending.push((p, f));
+            complete
+        }
+
+        /// Returns the images of all vertices, where only the vertices of the bag of the root are mapped by f.
+        fn root_images(&self, ntd : &NiceTreeDecomposition, f : Mapping) -> Vec<usize> {
+            let indexer = BagIndexer::of_node(ntd, ntd.root(), self.target_vertices as Mapping);
+            let mut images = vec![0; ntd.vertex_count() as usize];
+            for v in ntd.bit_bag(ntd.root()).unwrap().iter(){
+                images[v.index()] = indexer.image(f, v) as usize;
+            }
+            images
+        }
+
+        fn table(&self, p : TreeNode) -> &NodeTable {
+            self.tables.get(&p).unwrap_or_else(|| panic!("The snapshot does not contain the table of node {}!", p))
+        }
+    }
+
+    /// Returns one of the items chosen with probability proportional to its weight, or None if all weights are 0.
+    fn choose<T>(rng : &mut TestRng, items : Vec<(T, u64)>) -> Option<T> {
+        let total : u64 = items.iter().map(|(_, weight)| weight).sum();
+        if total == 0 { return None; }
+
+        // rejection of the incomplete last range of random numbers avoids a bias towards small numbers
+        let zone = u64::MAX - u64::MAX % total;
+        let mut r = rng.next_u64();
+        while r >= zone { r = rng.next_u64(); }
+        r %= total;
+
+        for (item, weight) in items{
+            if r < weight { return Some(item); }
+            r -= weight;
+        }
+        unreachable!("The random number is smaller than the total weight!")
+    }
+
+    /// Writes the snapshot into the given file, such that load_snapshot returns it again.
+    pub fn save_snapshot<P>(snapshot : &DpSnapshot, filename : P) -> io::Result<()>
+        where P: AsRef<Path>
+    {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        write_snapshot(snapshot, &mut writer)?;
+        writer.flush()
+    }
+
+    /// Writes the snapshot in the snapshot format into the given writer, ordered by the nodes and the mappings.
+    pub fn write_snapshot<W>(snapshot : &DpSnapshot, writer : &mut W) -> io::Result<()>
+        where W: Write
+    {
+        writeln!(writer, "s {}", snapshot.target_vertices)?;
+
+        let mut nodes : Vec<&TreeNode> = snapshot.tables.keys().collect();
+        nodes.sort_unstable();
+        for p in nodes{
+            writeln!(writer, "t {}", p + 1)?;
+            let mut entries : Vec<(&Mapping, &u64)> = snapshot.tables[p].iter().filter(|(_, &value)| value != 0).collect();
+            entries.sort_unstable();
+            for (f, value) in entries{
+                writeln!(writer, "e {} {}", f, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a snapshot from the given file, files ending with .gz are decompressed.
+    pub fn load_snapshot<P>(filename : P) -> Result<DpSnapshot, SnapshotError>
+        where P: AsRef<Path>
+    {
+        parse_snapshot(open_reader(filename)?)
+    }
+
+    /// Parses a snapshot in the snapshot format from the given reader.
+    pub fn parse_snapshot<R>(reader : R) -> Result<DpSnapshot, SnapshotError>
+        where R: BufRead
+    {
+        let mut target_vertices = None;
+        let mut tables : HashMap<TreeNode, NodeTable> = HashMap::new();
+        let mut current = None;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let malformed = |reason : &str| SnapshotError::MalformedLine { line: index + 1, content: line.trim().to_string(), reason: reason.to_string() };
+            let args : Vec<&str> = line.split_whitespace().collect();
+            let numbers : Option<Vec<u64>> = args.iter().skip(1).map(|arg| arg.parse::<u64>().ok()).collect();
+
+            match (args.first().copied(), numbers.as_deref()) {
+                (None | Some("c"), _) => {}
+                (Some("s"), Some(&[vertices])) => {
+                    if target_vertices.is_some() { return Err(malformed("duplicate start line")); }
+                    target_vertices = Some(vertices as usize);
+                }
+                (Some("t"), Some(&[node])) if node >= 1 => {
+                    if target_vertices.is_none() { return Err(malformed("table line before the start line")); }
+                    if tables.insert(node - 1, NodeTable::new()).is_some() { return Err(malformed("duplicate table")); }
+                    current = Some(node - 1);
+                }
+                (Some("e"), Some(&[f, value])) => {
+                    let Some(p) = current else { return Err(malformed("entry line before the first table line")); };
+                    if tables.get_mut(&p).unwrap().insert(f, value).is_some() { return Err(malformed("duplicate entry")); }
+                }
+                (Some("s" | "t" | "e"), _) => return Err(malformed("expected the numbers of the line type")),
+                _ => return Err(malformed("unknown line type")),
+            }
+        }
+
+        let target_vertices = target_vertices.ok_or(SnapshotError::MissingStartLine)?;
+        for table in tables.values_mut(){
+            table.retain(|_, value| *value != 0);
+        }
+        Ok(DpSnapshot { target_vertices, tables })
+    }
+}